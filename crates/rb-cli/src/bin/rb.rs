@@ -6,8 +6,9 @@ use rb_cli::error_display::{
     format_rubies_dir_not_found,
 };
 use rb_cli::help_formatter::print_custom_help;
+use rb_cli::output::{Output, StderrOutput, enable_plain_output};
 use rb_cli::runtime_helpers::CommandContext;
-use rb_cli::{Cli, Commands, init_logger};
+use rb_cli::{Cli, Commands, disable_colors, init_logger};
 use rb_core::butler::ButlerError;
 
 /// Centralized error handler that transforms technical errors into friendly messages
@@ -20,23 +21,34 @@ fn handle_command_error(error: ButlerError, context: &CommandContext) -> ! {
                 .config
                 .ruby_version
                 .as_ref()
-                .map(|v| (v.get().clone(), v.source.to_string()));
+                .map(|v| (v.get().join(" or "), v.source.to_string()));
             format_no_suitable_ruby(rubies_dir, source, version_info)
         }
         ButlerError::CommandNotFound(command) => format_command_not_found(command),
         ButlerError::RubiesDirectoryNotFound(path) => format_rubies_dir_not_found(path),
-        ButlerError::General(msg) => format_general_error(msg),
+        ButlerError::BundlerFailed(msg) | ButlerError::General(msg) => format_general_error(msg),
     };
 
-    eprintln!("{}", message);
+    StderrOutput.line(&message);
     std::process::exit(error_exit_code(&error));
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    // Skip logging for bash completion (must be silent)
-    if !matches!(cli.command, Some(Commands::BashComplete { .. })) {
+    if cli.color_disabled() {
+        disable_colors();
+    }
+
+    if cli.plain_output() {
+        enable_plain_output();
+    }
+
+    // Skip logging for completion (must be silent)
+    if !matches!(
+        cli.command,
+        Some(Commands::BashComplete { .. } | Commands::CompleteJson { .. })
+    ) {
         init_logger(cli.effective_log_level());
     }
 
@@ -74,6 +86,7 @@ fn main() {
     let mut context = CommandContext {
         config: tracked_config,
         project_file: cli_parsed.project_file.clone(),
+        config_file: cli_parsed.config_file.clone(),
     };
 
     let result = dispatch_command(command, &mut context);
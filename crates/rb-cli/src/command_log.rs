@@ -0,0 +1,135 @@
+use log::{debug, warn};
+use rb_core::butler::ButlerRuntime;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One line of the command log, describing a single `rb exec` or `rb run`
+///
+/// Only the names of `--env` variables are kept; their values may well be secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandLogEntry {
+    /// Seconds since the Unix epoch when the command started
+    pub timestamp: u64,
+    /// The Butler command that ran it: `exec` or `run`
+    pub command: &'static str,
+    /// The project script, for `rb run`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    pub program: String,
+    pub args: Vec<String>,
+    /// Version of the selected Ruby, if one was selected
+    pub ruby: Option<String>,
+    /// Names of the variables passed with `--env`
+    pub env: Vec<String>,
+}
+
+impl CommandLogEntry {
+    pub fn new(
+        butler_runtime: &ButlerRuntime,
+        command: &'static str,
+        script: Option<&str>,
+        program_args: &[String],
+        env: &[(String, String)],
+    ) -> Self {
+        let (program, args) = match program_args.split_first() {
+            Some((program, args)) => (program.clone(), args.to_vec()),
+            None => (String::new(), Vec::new()),
+        };
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+            command,
+            script: script.map(str::to_string),
+            program,
+            args,
+            ruby: butler_runtime
+                .selected_ruby()
+                .ok()
+                .map(|ruby| ruby.version.to_string()),
+            env: env.iter().map(|(name, _)| name.clone()).collect(),
+        }
+    }
+}
+
+/// Append `entry` to the log at `path` as one JSON line, creating the file and its directory
+pub fn append(path: &Path, entry: &CommandLogEntry) -> io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// Record `entry` when a command log is configured; a log that cannot be written only warns
+pub fn record(path: Option<&Path>, entry: &CommandLogEntry) {
+    let Some(path) = path else {
+        return;
+    };
+    debug!(
+        "Recording {} in command log {}",
+        entry.program,
+        path.display()
+    );
+    if let Err(e) = append(path, entry) {
+        warn!("Unable to write command log {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_tests::RubySandbox;
+
+    #[test]
+    fn test_append_writes_one_json_line_per_entry() {
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let work = tempfile::TempDir::new().unwrap();
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(work.path().join("gems")),
+            false,
+            work.path().to_path_buf(),
+        )
+        .unwrap();
+        let log = work.path().join("logs/commands.jsonl");
+
+        let args = vec!["rake".to_string(), "db:migrate".to_string()];
+        let env = vec![("API_TOKEN".to_string(), "s3cret".to_string())];
+        append(
+            &log,
+            &CommandLogEntry::new(&butler, "exec", None, &args, &env),
+        )
+        .unwrap();
+        append(
+            &log,
+            &CommandLogEntry::new(&butler, "run", Some("migrate"), &args, &[]),
+        )
+        .unwrap();
+
+        let content = std::fs::read_to_string(&log).unwrap();
+        assert!(!content.contains("s3cret"));
+        let entries: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["command"], "exec");
+        assert_eq!(entries[0]["program"], "rake");
+        assert_eq!(entries[0]["args"], serde_json::json!(["db:migrate"]));
+        assert_eq!(entries[0]["ruby"], "3.3.0");
+        assert_eq!(entries[0]["env"], serde_json::json!(["API_TOKEN"]));
+        assert!(entries[0]["timestamp"].as_u64().unwrap() > 0);
+        assert!(entries[0].get("script").is_none());
+        assert_eq!(entries[1]["script"], "migrate");
+    }
+}
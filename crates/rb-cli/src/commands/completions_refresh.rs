@@ -0,0 +1,49 @@
+use crate::output::Output;
+use colored::*;
+use log::debug;
+use rb_core::butler::ButlerError;
+use rb_core::project::RbprojectDetector;
+use rb_core::ruby::RubyRuntimeDetector;
+use std::path::{Path, PathBuf};
+
+/// Completions refresh command - rebuilds the Ruby and script lists completion offers
+///
+/// Completion reads discovery live rather than from a persisted cache, so there is
+/// nothing stale to drop; rediscovering reports exactly what the next Tab will see.
+pub fn completions_refresh_command(
+    rubies_dir: &Path,
+    additional_rubies_dirs: &[PathBuf],
+    current_dir: &Path,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    debug!(
+        "Refreshing completion data from {} and {}",
+        rubies_dir.display(),
+        current_dir.display()
+    );
+
+    let rubies = RubyRuntimeDetector::discover_in(rubies_dir, additional_rubies_dirs)
+        .unwrap_or_else(|e| {
+            debug!("Ruby discovery for completions failed: {}", e);
+            Vec::new()
+        });
+
+    let scripts = match RbprojectDetector::discover(current_dir) {
+        Ok(Some(project)) => project.script_names().len(),
+        Ok(None) => 0,
+        Err(e) => {
+            debug!("Project discovery for completions failed: {}", e);
+            0
+        }
+    };
+
+    out.success(&format!(
+        "Completion data refreshed: {} {}, {} {}",
+        rubies.len().to_string().cyan(),
+        if rubies.len() == 1 { "Ruby" } else { "Rubies" },
+        scripts.to_string().cyan(),
+        if scripts == 1 { "script" } else { "scripts" },
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use colored::*;
+use log::info;
+use rb_core::bundler::DependencyNode;
+use rb_core::butler::{ButlerError, ButlerRuntime};
+use std::collections::HashSet;
+
+use crate::DepsFormat;
+use crate::output::Output;
+
+/// Deps command - shows the dependency tree locked in Gemfile.lock
+///
+/// With `group`, only the gems the Gemfile declares in that group are listed at the top.
+pub fn deps_command(
+    butler_runtime: &ButlerRuntime,
+    format: DepsFormat,
+    group: Option<&str>,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    let bundler_runtime = butler_runtime.bundler_runtime().ok_or_else(|| {
+        ButlerError::General(
+            "Bundler environment not detected.\n\nNo Gemfile found in the current directory or its ancestors.\nThe dependency tree requires a bundler-managed project.".to_string(),
+        )
+    })?;
+
+    let lockfile = bundler_runtime.lockfile_path();
+    info!("Reading dependency tree from {}", lockfile.display());
+    let locked = bundler_runtime.locked_dependencies().map_err(|e| {
+        ButlerError::General(format!(
+            "Unable to read {}: {}\n\nRun 'rb sync' to create the lockfile first.",
+            lockfile.display(),
+            e
+        ))
+    })?;
+    let mut tree = locked.tree();
+    if let Some(group) = group {
+        let groups = bundler_runtime.gem_groups();
+        tree.retain(|node| groups.is_in_group(&node.name, group));
+    }
+
+    match format {
+        DepsFormat::Json => {
+            let json = serde_json::to_string_pretty(&tree).map_err(|e| {
+                ButlerError::General(format!("Unable to render the dependency tree: {}", e))
+            })?;
+            out.line(&json);
+        }
+        DepsFormat::Tree | DepsFormat::Plain => {
+            out.line(&"📦 Dependency Tree".green().bold().to_string());
+            out.line(&lockfile.display().to_string().bright_black().to_string());
+            out.blank();
+            if tree.is_empty() {
+                let empty = match group {
+                    Some(group) => format!("No gems declared in the {} group.", group),
+                    None => "No dependencies recorded.".to_string(),
+                };
+                out.line(&format!("  {}", empty.bright_black()));
+            }
+            let mut expanded = HashSet::new();
+            for node in &tree {
+                render_node(node, 1, &mut expanded, out);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Print `node` and its dependencies indented by depth
+///
+/// A gem whose dependencies were already listed is marked `(*)` and not expanded again.
+fn render_node<'a>(
+    node: &'a DependencyNode,
+    depth: usize,
+    expanded: &mut HashSet<&'a str>,
+    out: &mut dyn Output,
+) {
+    let version = node
+        .version
+        .as_deref()
+        .map(|version| format!(" ({})", version).bright_black().to_string())
+        .unwrap_or_default();
+    let repeated = !node.dependencies.is_empty() && !expanded.insert(&node.name);
+    out.line(&format!(
+        "{}{}{}{}",
+        "  ".repeat(depth),
+        node.name.cyan(),
+        version,
+        if repeated { " (*)" } else { "" }
+    ));
+
+    if !repeated {
+        for dependency in &node.dependencies {
+            render_node(dependency, depth + 1, expanded, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::CapturedOutput;
+    use rb_tests::{BundlerSandbox, RubySandbox};
+
+    const LOCKFILE: &str = "\
+GEM
+  remote: https://rubygems.org/
+  specs:
+    rack (3.0.8)
+    rack-test (2.1.0)
+      rack (>= 1.3)
+    rails (7.1.3)
+      rack (>= 2.2.4)
+      rack-test (>= 0.6.3)
+
+PLATFORMS
+  ruby
+
+DEPENDENCIES
+  rack-test
+  rails (~> 7.1)
+";
+
+    fn deps(format: DepsFormat) -> CapturedOutput {
+        deps_in_group(format, None)
+    }
+
+    fn deps_in_group(format: DepsFormat, group: Option<&str>) -> CapturedOutput {
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = sandbox.add_bundler_project("app", false).unwrap();
+        std::fs::write(project.join("Gemfile.lock"), LOCKFILE).unwrap();
+        std::fs::write(
+            project.join("Gemfile"),
+            "source \"https://rubygems.org\"\n\ngem \"rails\", \"~> 7.1\"\n\ngroup :test do\n  gem \"rack-test\"\nend\n",
+        )
+        .unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(sandbox.root().join("gems")),
+            false,
+            project,
+        )
+        .unwrap();
+
+        colored::control::set_override(false);
+        let mut out = CapturedOutput::new();
+        deps_command(&butler, format, group, &mut out).expect("deps should succeed");
+        out
+    }
+
+    #[test]
+    fn test_tree_lists_top_level_and_nested_dependencies() {
+        let out = deps(DepsFormat::Tree);
+
+        let tree: Vec<&str> = out.lines().iter().skip(3).map(String::as_str).collect();
+        assert_eq!(
+            tree,
+            [
+                "  rack-test (2.1.0)",
+                "    rack (3.0.8)",
+                "  rails (7.1.3)",
+                "    rack (3.0.8)",
+                "    rack-test (2.1.0) (*)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_keeps_only_gems_declared_in_it() {
+        let out = deps_in_group(DepsFormat::Tree, Some("test"));
+        let tree: Vec<&str> = out.lines().iter().skip(3).map(String::as_str).collect();
+        assert_eq!(tree, ["  rack-test (2.1.0)", "    rack (3.0.8)"]);
+
+        let out = deps_in_group(DepsFormat::Json, Some("default"));
+        let tree: serde_json::Value = serde_json::from_str(&out.text()).unwrap();
+        assert_eq!(tree.as_array().unwrap().len(), 1);
+        assert_eq!(tree[0]["name"], "rails");
+
+        let out = deps_in_group(DepsFormat::Tree, Some("production"));
+        assert!(out.contains("No gems declared in the production group."));
+    }
+
+    #[test]
+    fn test_json_nests_dependencies() {
+        let out = deps(DepsFormat::Json);
+
+        let tree: serde_json::Value = serde_json::from_str(&out.text()).unwrap();
+        assert_eq!(tree[0]["name"], "rack-test");
+        assert_eq!(tree[1]["name"], "rails");
+        assert_eq!(tree[1]["version"], "7.1.3");
+        assert_eq!(tree[1]["dependencies"][1]["name"], "rack-test");
+        assert_eq!(
+            tree[1]["dependencies"][1]["dependencies"][0]["name"],
+            "rack"
+        );
+    }
+
+    #[test]
+    fn test_requires_bundler_project() {
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let project = rubies.add_dir("plain").unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            None,
+            false,
+            project,
+        )
+        .unwrap();
+
+        let error =
+            deps_command(&butler, DepsFormat::Tree, None, &mut CapturedOutput::new()).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Bundler environment not detected")
+        );
+    }
+}
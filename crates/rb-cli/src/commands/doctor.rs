@@ -0,0 +1,578 @@
+use colored::*;
+use log::{debug, info};
+use rb_core::butler::{ButlerError, ButlerRuntime};
+use rb_core::ruby::RubyRuntimeDetector;
+
+use crate::DoctorFormat;
+
+/// Outcome of a single diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// A single diagnostic finding presented by the doctor command
+#[derive(Debug, Clone)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl DoctorCheck {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+        }
+    }
+}
+
+/// Run every diagnostic check against the composed environment
+pub fn collect_checks(
+    butler_runtime: &ButlerRuntime,
+    existing_path: Option<String>,
+) -> Vec<DoctorCheck> {
+    vec![
+        check_selected_ruby(butler_runtime),
+        check_broken_installations(butler_runtime),
+        check_rubies_dir_writable(butler_runtime),
+        check_path_shadowing(butler_runtime, existing_path),
+        check_gem_home(butler_runtime),
+        check_bundle(butler_runtime),
+        check_bundler_version(butler_runtime),
+    ]
+}
+
+fn check_selected_ruby(butler_runtime: &ButlerRuntime) -> DoctorCheck {
+    match butler_runtime.selected_ruby() {
+        Ok(ruby) => {
+            let executable = ruby.ruby_executable_path();
+            if executable.exists() {
+                DoctorCheck::pass(
+                    "Ruby installation",
+                    format!("{} at {}", ruby.version_name(), ruby.root.display()),
+                )
+            } else {
+                DoctorCheck::fail(
+                    "Ruby installation",
+                    format!("Ruby executable missing: {}", executable.display()),
+                )
+            }
+        }
+        Err(e) => DoctorCheck::fail("Ruby installation", e.to_string()),
+    }
+}
+
+fn check_broken_installations(butler_runtime: &ButlerRuntime) -> DoctorCheck {
+    let mut surveyed = Vec::new();
+    let mut broken = Vec::new();
+    let rubies_dirs =
+        std::iter::once(butler_runtime.rubies_dir()).chain(butler_runtime.additional_rubies_dirs());
+    for rubies_dir in rubies_dirs {
+        match RubyRuntimeDetector::discover_all(rubies_dir) {
+            Ok(discovery) => {
+                surveyed.push(rubies_dir.display().to_string());
+                broken.extend(discovery.broken_installations);
+            }
+            Err(e) => debug!("Unable to survey {}: {}", rubies_dir.display(), e),
+        }
+    }
+
+    if surveyed.is_empty() {
+        return DoctorCheck::pass("Ruby directories", "No rubies directory to inspect");
+    }
+    if broken.is_empty() {
+        return DoctorCheck::pass(
+            "Ruby directories",
+            format!("No broken installations in {}", surveyed.join(", ")),
+        );
+    }
+
+    let listing: Vec<String> = broken.iter().map(|p| p.display().to_string()).collect();
+    DoctorCheck::warn(
+        "Ruby directories",
+        format!(
+            "Ignoring {} installation(s) without an executable bin/ruby: {}",
+            broken.len(),
+            listing.join(", ")
+        ),
+    )
+}
+
+fn check_rubies_dir_writable(butler_runtime: &ButlerRuntime) -> DoctorCheck {
+    let rubies_dir = butler_runtime.rubies_dir();
+    if !rubies_dir.is_dir() {
+        return DoctorCheck::pass("Rubies directory", "No rubies directory to inspect");
+    }
+
+    if RubyRuntimeDetector::is_read_only(rubies_dir) {
+        DoctorCheck::warn(
+            "Rubies directory",
+            format!(
+                "{} is read-only, so Rubies cannot be installed there; point RB_RUBIES_DIR at a writable directory to install more",
+                rubies_dir.display()
+            ),
+        )
+    } else {
+        DoctorCheck::pass(
+            "Rubies directory",
+            format!("{} is writable", rubies_dir.display()),
+        )
+    }
+}
+
+fn check_path_shadowing(
+    butler_runtime: &ButlerRuntime,
+    existing_path: Option<String>,
+) -> DoctorCheck {
+    match butler_runtime.shadowing_ruby(existing_path) {
+        Some(shadow) => DoctorCheck::warn(
+            "PATH precedence",
+            format!(
+                "{} resolves before the selected Ruby; commands may run the wrong ruby",
+                shadow.display()
+            ),
+        ),
+        None => DoctorCheck::pass("PATH precedence", "Selected Ruby resolves first in PATH"),
+    }
+}
+
+fn check_gem_home(butler_runtime: &ButlerRuntime) -> DoctorCheck {
+    let Some(gem_home) = butler_runtime.gem_home() else {
+        return DoctorCheck::pass("Gem home", "No gem home in use");
+    };
+
+    match butler_runtime.ensure_gem_home_writable() {
+        Ok(()) => DoctorCheck::pass("Gem home", format!("{} is writable", gem_home.display())),
+        Err(e) => DoctorCheck::fail("Gem home", e.to_string()),
+    }
+}
+
+fn check_bundle(butler_runtime: &ButlerRuntime) -> DoctorCheck {
+    let Some(bundler_runtime) = butler_runtime.bundler_runtime() else {
+        return DoctorCheck::pass("Bundle dependencies", "No Gemfile in this project");
+    };
+
+    let gemfile = bundler_runtime.gemfile_path();
+    match bundler_runtime.is_synced(butler_runtime) {
+        Ok(true) => DoctorCheck::pass(
+            "Bundle dependencies",
+            format!("Dependencies of {} are satisfied", gemfile.display()),
+        ),
+        Ok(false) => DoctorCheck::warn(
+            "Bundle dependencies",
+            format!(
+                "Dependencies of {} are not satisfied or the lockfile is stale; run 'rb sync' to install them",
+                gemfile.display()
+            ),
+        ),
+        Err(e) => DoctorCheck::warn(
+            "Bundle dependencies",
+            format!("Unable to run bundle check: {}", e),
+        ),
+    }
+}
+
+fn check_bundler_version(butler_runtime: &ButlerRuntime) -> DoctorCheck {
+    let Some(bundler_runtime) = butler_runtime.bundler_runtime() else {
+        return DoctorCheck::pass("Bundler version", "No Gemfile in this project");
+    };
+
+    match bundler_runtime.bundler_version_mismatch(butler_runtime) {
+        Ok(None) => DoctorCheck::pass(
+            "Bundler version",
+            "Installed bundler matches BUNDLED WITH in the lockfile",
+        ),
+        Ok(Some((locked, installed))) => DoctorCheck::warn(
+            "Bundler version",
+            bundler_mismatch_message(&locked, &installed),
+        ),
+        Err(e) => DoctorCheck::warn(
+            "Bundler version",
+            format!("Unable to determine the installed bundler version: {}", e),
+        ),
+    }
+}
+
+/// Describe a lockfile bundled with another bundler than the one installed
+pub(crate) fn bundler_mismatch_message(locked: &str, installed: &str) -> String {
+    format!(
+        "Gemfile.lock was bundled with bundler {} but {} is installed; run 'rb exec gem install bundler -v {}' to match",
+        locked, installed, locked
+    )
+}
+
+/// Render warnings and failures as GitHub Actions workflow commands
+///
+/// Passing checks produce no line. Messages are escaped so multi-line or `%`-bearing
+/// text stays a single annotation.
+pub fn github_annotations(checks: &[DoctorCheck]) -> Vec<String> {
+    fn escape(text: &str) -> String {
+        text.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    }
+
+    checks
+        .iter()
+        .filter_map(|check| {
+            let level = match check.status {
+                CheckStatus::Pass => return None,
+                CheckStatus::Warn => "warning",
+                CheckStatus::Fail => "error",
+            };
+            Some(format!(
+                "::{}::{}: {}",
+                level,
+                escape(check.name),
+                escape(&check.message)
+            ))
+        })
+        .collect()
+}
+
+/// Doctor command - examines the environment for common problems
+pub fn doctor_command(
+    butler_runtime: &ButlerRuntime,
+    format: DoctorFormat,
+) -> Result<(), ButlerError> {
+    info!("Examining the Ruby environment for irregularities");
+
+    let checks = collect_checks(butler_runtime, std::env::var("PATH").ok());
+    let failures = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+
+    if format == DoctorFormat::Github {
+        for annotation in github_annotations(&checks) {
+            println!("{}", annotation);
+        }
+        if failures > 0 {
+            return Err(ButlerError::General(format!(
+                "The examination revealed {} issue(s) requiring your attention.",
+                failures
+            )));
+        }
+        return Ok(());
+    }
+
+    println!("{}", "🩺 Environment Examination".to_string().bold());
+    println!();
+
+    for check in &checks {
+        debug!("Check '{}' finished with {:?}", check.name, check.status);
+        let marker = match check.status {
+            CheckStatus::Pass => "✅".green(),
+            CheckStatus::Warn => "⚠️ ".yellow(),
+            CheckStatus::Fail => "❌".red(),
+        };
+        println!(
+            "{} {}: {}",
+            marker,
+            check.name.bright_blue().bold(),
+            check.message.bright_black()
+        );
+    }
+
+    println!();
+    if failures > 0 {
+        return Err(ButlerError::General(format!(
+            "The examination revealed {} issue(s) requiring your attention.",
+            failures
+        )));
+    }
+
+    println!(
+        "{}",
+        "Your environment is in distinguished order.".bright_black()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_core::gems::GemRuntime;
+    use rb_core::ruby::{RubyRuntime, RubyType};
+    use rb_tests::RubySandbox;
+    use semver::Version;
+
+    #[cfg(unix)]
+    fn write_executable(path: &std::path::Path) {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "#!/bin/sh\necho ruby\n").unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doctor_warns_when_conflicting_ruby_precedes_selected_ruby() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        let ruby_dir = sandbox.add_ruby_dir("3.2.5").unwrap();
+        let ruby = RubyRuntime::new(RubyType::CRuby, Version::parse("3.2.5").unwrap(), &ruby_dir);
+        write_executable(&ruby.ruby_executable_path());
+
+        let gem_runtime = GemRuntime::for_base_dir(&sandbox.gem_base_dir(), &ruby.version);
+        write_executable(&gem_runtime.gem_bin.join("ruby"));
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+        let checks = collect_checks(&butler, None);
+
+        let shadowing = checks
+            .iter()
+            .find(|c| c.name == "PATH precedence")
+            .expect("PATH precedence check should be present");
+        assert_eq!(shadowing.status, CheckStatus::Warn);
+        assert!(
+            shadowing
+                .message
+                .contains("resolves before the selected Ruby")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doctor_passes_when_selected_ruby_resolves_first() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        let ruby_dir = sandbox.add_ruby_dir("3.2.5").unwrap();
+        let ruby = RubyRuntime::new(RubyType::CRuby, Version::parse("3.2.5").unwrap(), &ruby_dir);
+        write_executable(&ruby.ruby_executable_path());
+
+        let butler = ButlerRuntime::new(ruby, None);
+        let checks = collect_checks(&butler, None);
+
+        assert!(checks.iter().all(|c| c.status == CheckStatus::Pass));
+    }
+
+    #[test]
+    fn doctor_warns_about_broken_ruby_installations() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        sandbox.add_ruby_dir("3.3.0").unwrap();
+        sandbox.add_broken_ruby_dir("3.4.0").unwrap();
+
+        let butler =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None).unwrap();
+        let checks = collect_checks(&butler, None);
+
+        let directories = checks
+            .iter()
+            .find(|c| c.name == "Ruby directories")
+            .expect("Ruby directories check should be present");
+        assert_eq!(directories.status, CheckStatus::Warn);
+        assert!(directories.message.contains("ruby-3.4.0"));
+        assert_eq!(butler.selected_ruby().unwrap().version.to_string(), "3.3.0");
+    }
+
+    #[test]
+    fn doctor_surveys_additional_rubies_dirs() {
+        use rb_core::butler::SelectionOptions;
+
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        sandbox.add_ruby_dir("3.3.0").unwrap();
+        let opt_rubies = RubySandbox::new().expect("Failed to create sandbox");
+        opt_rubies.add_broken_ruby_dir("3.4.0").unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_options(
+            sandbox.root().to_path_buf(),
+            None,
+            None,
+            true,
+            sandbox.root().to_path_buf(),
+            &SelectionOptions {
+                additional_rubies_dirs: vec![opt_rubies.root().to_path_buf()],
+                ..SelectionOptions::default()
+            },
+        )
+        .unwrap();
+        let checks = collect_checks(&butler, None);
+
+        let directories = checks
+            .iter()
+            .find(|c| c.name == "Ruby directories")
+            .expect("Ruby directories check should be present");
+        assert_eq!(directories.status, CheckStatus::Warn);
+        assert!(directories.message.contains("ruby-3.4.0"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doctor_warns_about_read_only_rubies_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        sandbox.add_ruby_dir("3.3.0").unwrap();
+        let butler =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None).unwrap();
+
+        let rubies_check = |butler: &ButlerRuntime| {
+            collect_checks(butler, None)
+                .into_iter()
+                .find(|c| c.name == "Rubies directory")
+                .expect("Rubies directory check should be present")
+        };
+        assert_eq!(rubies_check(&butler).status, CheckStatus::Pass);
+
+        let permissions = |mode| std::fs::Permissions::from_mode(mode);
+        std::fs::set_permissions(sandbox.root(), permissions(0o555)).unwrap();
+        let check = rubies_check(&butler);
+        std::fs::set_permissions(sandbox.root(), permissions(0o755)).unwrap();
+
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.message.contains("read-only"), "{}", check.message);
+        assert!(check.message.contains("RB_RUBIES_DIR"), "{}", check.message);
+    }
+
+    #[test]
+    fn doctor_fails_when_gem_home_cannot_be_created() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        let ruby_dir = sandbox.add_ruby_dir("3.2.5").unwrap();
+        let ruby = RubyRuntime::new(RubyType::CRuby, Version::parse("3.2.5").unwrap(), &ruby_dir);
+        let blocker = sandbox.add_file("gem-blocker", "").unwrap();
+        let gem_runtime = GemRuntime::for_base_dir(&blocker, &ruby.version);
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+        let checks = collect_checks(&butler, None);
+
+        let gem_home = checks
+            .iter()
+            .find(|c| c.name == "Gem home")
+            .expect("Gem home check should be present");
+        assert_eq!(gem_home.status, CheckStatus::Fail);
+
+        let annotations = github_annotations(&checks);
+        assert!(
+            annotations
+                .iter()
+                .any(|line| line.starts_with("::error::Gem home: ")),
+            "Expected a Gem home error annotation, got: {:?}",
+            annotations
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doctor_warns_when_bundle_check_fails() {
+        use rb_tests::BundlerSandbox;
+        use std::os::unix::fs::PermissionsExt;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let bundler_sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = bundler_sandbox
+            .add_bundler_project("app", false)
+            .expect("Failed to create project");
+
+        let failing_bundle = bundler_sandbox.root().join("failing-bundle");
+        std::fs::write(&failing_bundle, "#!/bin/sh\nexit 1\n").unwrap();
+        std::fs::set_permissions(&failing_bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            None,
+            false,
+            project,
+        )
+        .unwrap()
+        .with_bundler_bin(failing_bundle.to_string_lossy());
+        let checks = collect_checks(&butler, None);
+
+        let bundle = checks
+            .iter()
+            .find(|c| c.name == "Bundle dependencies")
+            .expect("Bundle dependencies check should be present");
+        assert_eq!(bundle.status, CheckStatus::Warn);
+        assert!(bundle.message.contains("rb sync"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn doctor_warns_when_bundler_differs_from_lockfile() {
+        use rb_tests::BundlerSandbox;
+        use std::os::unix::fs::PermissionsExt;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let bundler_sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = bundler_sandbox
+            .add_bundler_project("app", false)
+            .expect("Failed to create project");
+        std::fs::write(
+            project.join("Gemfile.lock"),
+            "GEM\n  specs:\n\nBUNDLED WITH\n   2.5.16\n",
+        )
+        .unwrap();
+
+        let stub_bundle = bundler_sandbox.root().join("stub-bundle");
+        std::fs::write(&stub_bundle, "#!/bin/sh\necho 'Bundler version 2.4.22'\n").unwrap();
+        std::fs::set_permissions(&stub_bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            None,
+            false,
+            project.clone(),
+        )
+        .unwrap()
+        .with_bundler_bin(stub_bundle.to_string_lossy());
+        let checks = collect_checks(&butler, None);
+
+        let version = checks
+            .iter()
+            .find(|c| c.name == "Bundler version")
+            .expect("Bundler version check should be present");
+        assert_eq!(version.status, CheckStatus::Warn);
+        assert!(version.message.contains("2.5.16"));
+        assert!(version.message.contains("2.4.22"));
+
+        std::fs::write(
+            project.join("Gemfile.lock"),
+            "GEM\n  specs:\n\nBUNDLED WITH\n   2.4.22\n",
+        )
+        .unwrap();
+        let checks = collect_checks(&butler, None);
+        let version = checks.iter().find(|c| c.name == "Bundler version").unwrap();
+        assert_eq!(version.status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn github_annotations_cover_only_warnings_and_failures() {
+        let checks = vec![
+            DoctorCheck::pass("Ruby installation", "ruby-3.3.0"),
+            DoctorCheck::warn("PATH precedence", "shadowed"),
+            DoctorCheck::fail("Gem home", "100% broken\nsecond line"),
+        ];
+
+        assert_eq!(
+            github_annotations(&checks),
+            vec![
+                "::warning::PATH precedence: shadowed".to_string(),
+                "::error::Gem home: 100%25 broken%0Asecond line".to_string(),
+            ]
+        );
+    }
+}
@@ -1,19 +1,219 @@
+use crate::command_log::{self, CommandLogEntry};
 use colored::*;
-use log::{debug, info};
+use log::{debug, info, warn};
 use rb_core::butler::{ButlerError, ButlerRuntime, Command};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+
+/// Wait before retrying a program that hit a held bundler lock, multiplied by the attempt
+const LOCK_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// What bundler prints when another process is holding its lock
+const LOCK_ERROR_MARKERS: &[&str] = &["Resource temporarily unavailable", "Errno::EAGAIN"];
+
+/// Trailing stderr bytes kept to look for a lock error once a retried program exits
+const LOCK_ERROR_TAIL: usize = 8 * 1024;
+
+/// Options refining how `rb exec` prepares the child process
+#[derive(Debug, Clone, Default)]
+pub struct ExecOptions {
+    /// Start from a minimal environment instead of inheriting the current one
+    pub clean_env: bool,
+    /// Print the composed PATH and exit without running anything
+    pub print_path: bool,
+    /// Extra variables applied on top of the composed environment
+    pub env: Vec<(String, String)>,
+    /// File receiving the program's stdout (and stderr unless captured separately)
+    pub capture: Option<PathBuf>,
+    /// File receiving the program's stderr when kept apart from `capture`
+    pub capture_stderr: Option<PathBuf>,
+    /// Reruns granted when the program fails because bundler's lock is held
+    pub lock_retries: u32,
+    /// Create a missing gem home before running
+    pub create_gem_home: bool,
+    /// Run the program through the user's login shell (`$SHELL -lc`)
+    pub login: bool,
+    /// Refuse programs that only the inherited PATH provides unless `allow_system` is set
+    pub refuse_system: bool,
+    /// Let programs come from the inherited PATH, running them outside `bundle exec`
+    pub allow_system: bool,
+    /// Run the program from the bundler project root rather than the current directory
+    pub from_bundler_root: bool,
+    /// JSON-lines file recording each execution
+    pub command_log: Option<PathBuf>,
+    /// Print the wall-clock duration of the program to stderr
+    pub measure: bool,
+}
 
-pub fn exec_command(butler: ButlerRuntime, program_args: Vec<String>) -> Result<(), ButlerError> {
-    if program_args.is_empty() {
-        return Err(ButlerError::General(
-            "No program specified for execution.\nProper usage: rb exec <program> [arguments...]\nFor example: rb exec gem list\n             rb exec bundle install".to_string()
-        ));
+/// Create (or truncate) a capture file, naming it in the error
+fn create_capture_file(path: &Path) -> Result<File, ButlerError> {
+    File::create(path).map_err(|e| {
+        ButlerError::General(format!(
+            "Unable to create capture file {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+pub fn exec_command(
+    butler: ButlerRuntime,
+    program_args: Vec<String>,
+    options: &ExecOptions,
+) -> Result<(), ButlerError> {
+    if options.print_path {
+        debug!("Printing composed PATH instead of executing");
+        println!("{}", butler.build_path(std::env::var("PATH").ok()));
+        return Ok(());
     }
 
-    let program = &program_args[0];
-    let args = if program_args.len() > 1 {
-        &program_args[1..]
+    command_log::record(
+        options.command_log.as_deref(),
+        &CommandLogEntry::new(&butler, "exec", None, &program_args, &options.env),
+    );
+    let status = measured(options.measure, || {
+        run_program(&butler, &program_args, options)
+    })?;
+    exit_with_status(status)
+}
+
+/// Run `f`, then print its wall-clock duration to stderr when `measure` is set and it succeeded
+pub(crate) fn measured<T, E>(measure: bool, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    let started = Instant::now();
+    let result = f();
+    if measure && result.is_ok() {
+        eprintln!("{}", format_duration(started.elapsed()));
+    }
+    result
+}
+
+/// The line `--measure` prints, e.g. `⏱ 2.34s`
+fn format_duration(elapsed: Duration) -> String {
+    format!("⏱ {:.2}s", elapsed.as_secs_f64())
+}
+
+/// Leave with the program's exit code, or 1 when a signal ended it
+pub(crate) fn exit_with_status(status: ExitStatus) -> ! {
+    if let Some(code) = status.code() {
+        debug!("Program concluded with exit code: {}", code);
+        std::process::exit(code);
     } else {
-        &[]
+        debug!("Program was terminated by system signal");
+        std::process::exit(1);
+    }
+}
+
+/// Run a program in the composed environment and hand back its exit status
+///
+/// Synchronizes bundler first when needed, just like `rb exec`, but leaves it to
+/// the caller to decide what the status means instead of exiting the process.
+/// A run failing on a held bundler lock is repeated up to `lock_retries` times;
+/// captured runs are not, as their stderr never passes through Butler. While retries
+/// are enabled the whole program runs again, and its stderr is relayed through a pipe
+/// instead of reaching the terminal directly; with `lock_retries` 0 it is inherited.
+pub(crate) fn run_program(
+    butler: &ButlerRuntime,
+    program_args: &[String],
+    options: &ExecOptions,
+) -> Result<ExitStatus, ButlerError> {
+    if options.lock_retries == 0 || options.capture.is_some() {
+        return prepare_program(butler, program_args, options)?.status_with_validation(butler);
+    }
+
+    prepare_environment(butler, program_args, options)?;
+
+    let mut attempt = 0;
+    loop {
+        let mut cmd = build_program(butler, program_args, options)?;
+        cmd.stderr(Stdio::piped());
+        let (status, stderr) = wait_relaying_stderr(cmd.execute_with_validation(butler)?)?;
+
+        if status.success() || attempt >= options.lock_retries || !is_bundler_lock_error(&stderr) {
+            return Ok(status);
+        }
+
+        attempt += 1;
+        let backoff = LOCK_RETRY_BACKOFF * attempt;
+        eprintln!(
+            "{} {}",
+            "🔁".bright_blue(),
+            format!(
+                "Retry {}/{}: bundler's lock is held elsewhere, trying again in {}ms",
+                attempt,
+                options.lock_retries,
+                backoff.as_millis()
+            )
+            .dimmed()
+        );
+        std::thread::sleep(backoff);
+    }
+}
+
+/// Whether a program's stderr shows it failed because bundler's lock was held
+pub(crate) fn is_bundler_lock_error(stderr: &str) -> bool {
+    LOCK_ERROR_MARKERS
+        .iter()
+        .any(|marker| stderr.contains(marker))
+}
+
+/// Pass the child's stderr through to ours while keeping its tail, then wait for it
+fn wait_relaying_stderr(mut child: Child) -> Result<(ExitStatus, String), ButlerError> {
+    let io_error =
+        |e: std::io::Error| ButlerError::General(format!("Unable to relay program output: {}", e));
+
+    let mut seen = Vec::new();
+    if let Some(mut stderr) = child.stderr.take() {
+        let mut buffer = [0u8; 4096];
+        loop {
+            let read = stderr.read(&mut buffer).map_err(io_error)?;
+            if read == 0 {
+                break;
+            }
+            let _ = std::io::stderr().write_all(&buffer[..read]);
+            seen.extend_from_slice(&buffer[..read]);
+            if seen.len() > LOCK_ERROR_TAIL {
+                seen.drain(..seen.len() - LOCK_ERROR_TAIL);
+            }
+        }
+    }
+
+    let status = child.wait().map_err(io_error)?;
+    Ok((status, String::from_utf8_lossy(&seen).into_owned()))
+}
+
+/// Synchronize bundler if needed and build the command `run_program` would run
+pub(crate) fn prepare_program(
+    butler: &ButlerRuntime,
+    program_args: &[String],
+    options: &ExecOptions,
+) -> Result<Command, ButlerError> {
+    prepare_environment(butler, program_args, options)?;
+    build_program(butler, program_args, options)
+}
+
+/// Get the gem home and bundler ready for `program_args`
+fn prepare_environment(
+    butler: &ButlerRuntime,
+    program_args: &[String],
+    options: &ExecOptions,
+) -> Result<(), ButlerError> {
+    if options.create_gem_home
+        && let Err(e) = butler.create_gem_home()
+    {
+        warn!("{}", e);
+    }
+    synchronize_bundler(butler, program_args)
+}
+
+/// Bring the bundler environment up to date before running `program_args`
+fn synchronize_bundler(butler: &ButlerRuntime, program_args: &[String]) -> Result<(), ButlerError> {
+    let Some(program) = program_args.first() else {
+        return Err(ButlerError::General(
+            "No program specified for execution.\nProper usage: rb exec <program> [arguments...]\nFor example: rb exec gem list\n             rb exec bundle install".to_string()
+        ));
     };
 
     info!(
@@ -22,7 +222,7 @@ pub fn exec_command(butler: ButlerRuntime, program_args: Vec<String>) -> Result<
     );
 
     if let Some(bundler_runtime) = butler.bundler_runtime() {
-        match bundler_runtime.check_sync(&butler) {
+        match bundler_runtime.check_sync(butler) {
             Ok(false) => {
                 println!(
                     "{} {}",
@@ -30,7 +230,7 @@ pub fn exec_command(butler: ButlerRuntime, program_args: Vec<String>) -> Result<
                     "Bundler environment requires synchronization. Preparing now...".dimmed()
                 );
 
-                match bundler_runtime.synchronize(&butler, |line| {
+                match bundler_runtime.synchronize(butler, |line| {
                     println!("{}", line.dimmed());
                 }) {
                     Ok(_) => {
@@ -42,7 +242,7 @@ pub fn exec_command(butler: ButlerRuntime, program_args: Vec<String>) -> Result<
                         );
                     }
                     Err(e) => {
-                        return Err(ButlerError::General(format!(
+                        return Err(ButlerError::BundlerFailed(format!(
                             "Failed to prepare bundler environment: {}",
                             e
                         )));
@@ -58,25 +258,112 @@ pub fn exec_command(butler: ButlerRuntime, program_args: Vec<String>) -> Result<
         }
     }
 
+    Ok(())
+}
+
+/// Build the command for `program_args` with the environment and capture `options` ask for
+fn build_program(
+    butler: &ButlerRuntime,
+    program_args: &[String],
+    options: &ExecOptions,
+) -> Result<Command, ButlerError> {
+    let (program, args) = program_args
+        .split_first()
+        .ok_or_else(|| ButlerError::General("No program specified for execution.".to_string()))?;
+
     debug!("Program: {}", program);
     debug!("Arguments: {:?}", args);
 
-    let mut cmd = Command::new(program);
-    cmd.args(args);
+    let mut cmd = if options.login {
+        let shell = login_shell();
+        let line = shell_command_line(program_args);
+        debug!("Running through login shell {}: {}", shell, line);
+        let mut cmd = Command::new(shell);
+        cmd.args(["-lc", &line]);
+        cmd
+    } else {
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        cmd
+    };
+
+    if options.refuse_system || options.allow_system {
+        cmd.allow_system(options.allow_system);
+    }
+
+    if options.from_bundler_root
+        && let Some(bundler_runtime) = butler.bundler_runtime()
+    {
+        debug!(
+            "Running from bundler root {}",
+            bundler_runtime.root.display()
+        );
+        cmd.current_dir(&bundler_runtime.root);
+    }
+
+    if options.clean_env {
+        debug!("Starting from a clean environment");
+        cmd.env_clear();
+    }
+
+    for (key, value) in &options.env {
+        debug!("Setting {} for this run", key);
+        cmd.env(key, value);
+    }
+
+    if let Some(capture) = &options.capture {
+        debug!("Capturing program output in {}", capture.display());
+        let stdout = create_capture_file(capture)?;
+        let stderr = match &options.capture_stderr {
+            Some(path) => create_capture_file(path)?,
+            None => stdout.try_clone().map_err(|e| {
+                ButlerError::General(format!(
+                    "Unable to share capture file {}: {}",
+                    capture.display(),
+                    e
+                ))
+            })?,
+        };
+        cmd.stdout(stdout).stderr(stderr);
+    } else {
+        cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+    }
+
+    // Interactive programs such as pry or a Rails console read the terminal directly
+    cmd.stdin(Stdio::inherit());
 
     debug!("Commencing program execution...");
 
-    match cmd.status_with_validation(&butler) {
-        Ok(status) => {
-            if let Some(code) = status.code() {
-                debug!("Program concluded with exit code: {}", code);
-                std::process::exit(code);
-            } else {
-                debug!("Program was terminated by system signal");
-                std::process::exit(1);
-            }
-        }
-        Err(e) => Err(e),
+    Ok(cmd)
+}
+
+/// The shell `--login` runs programs through: `$SHELL`, or `/bin/sh` when unset
+fn login_shell() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+/// Join `program_args` into one POSIX shell line that reproduces them word for word
+fn shell_command_line(program_args: &[String]) -> String {
+    program_args
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Quote `arg` for a POSIX shell, leaving plainly safe words untouched
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
     }
 }
 
@@ -184,4 +471,246 @@ mod tests {
         let bundle_app_config = env_vars.get("BUNDLE_APP_CONFIG").unwrap();
         assert!(bundle_app_config.contains(".rb"));
     }
+
+    #[test]
+    fn test_format_duration_uses_two_decimals() {
+        assert_eq!(format_duration(Duration::from_millis(2340)), "⏱ 2.34s");
+        assert_eq!(format_duration(Duration::ZERO), "⏱ 0.00s");
+    }
+
+    #[test]
+    fn test_shell_command_line_quotes_only_what_needs_it() {
+        let args = ["rspec", "--tag", "it's slow", "", "a b", "$HOME"].map(String::from);
+
+        assert_eq!(
+            shell_command_line(&args),
+            r#"rspec --tag 'it'\''s slow' '' 'a b' '$HOME'"#
+        );
+    }
+
+    #[test]
+    fn test_recognizes_bundler_lock_errors() {
+        assert!(is_bundler_lock_error(
+            "Errno::EAGAIN: Resource temporarily unavailable @ rb_file_flock"
+        ));
+        assert!(!is_bundler_lock_error("Could not find gem 'rails'"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retries_program_after_bundler_lock_error() {
+        use rb_tests::BundlerSandbox;
+        use std::os::unix::fs::PermissionsExt;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = sandbox.add_bundler_project("app", true).unwrap();
+
+        // Fails its first `exec` the way a contended bundler lock does, then runs normally
+        let bundle = sandbox.root().join("bundle-stub");
+        let dir = project.display();
+        std::fs::write(
+            &bundle,
+            format!(
+                "#!/bin/sh\nif [ \"$1\" = exec ]; then\n  echo attempt >> {dir}/attempts\n  if [ ! -f {dir}/locked-once ]; then\n    touch {dir}/locked-once\n    echo 'Errno::EAGAIN: Resource temporarily unavailable' >&2\n    exit 1\n  fi\n  shift; exec \"$@\"\nfi\nexit 0\n"
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(sandbox.root().join("gems")),
+            false,
+            project.clone(),
+        )
+        .unwrap()
+        .with_bundler_bin(bundle.to_string_lossy());
+
+        let options = ExecOptions {
+            lock_retries: 2,
+            ..ExecOptions::default()
+        };
+        let args = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("touch {}/ran", dir),
+        ];
+
+        let status = run_program(&butler, &args, &options).unwrap();
+
+        assert!(status.success(), "Retry should have succeeded");
+        assert!(project.join("ran").exists());
+        let attempts = std::fs::read_to_string(project.join("attempts")).unwrap();
+        assert_eq!(attempts.lines().count(), 2);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_program_inherits_stderr_by_default() {
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let dir = rubies.add_dir("work").unwrap();
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            None,
+            true,
+            dir.clone(),
+        )
+        .unwrap();
+
+        // The child reports what its stderr descriptor points at, for comparison with ours
+        let seen = dir.join("stderr");
+        let args = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("readlink /proc/$$/fd/2 > {}", seen.display()),
+        ];
+        let status = run_program(&butler, &args, &ExecOptions::default()).unwrap();
+
+        assert!(status.success());
+        let child_stderr = std::fs::read_to_string(&seen).unwrap();
+        let own_stderr = std::fs::read_link("/proc/self/fd/2").unwrap();
+        assert_eq!(PathBuf::from(child_stderr.trim()), own_stderr);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allow_system_runs_system_command_outside_bundle_exec() {
+        use rb_tests::BundlerSandbox;
+        use std::os::unix::fs::PermissionsExt;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = sandbox.add_bundler_project("app", true).unwrap();
+
+        // Stands in for bundler refusing a command that is not part of the bundle
+        let bundle = sandbox.root().join("bundle-stub");
+        std::fs::write(
+            &bundle,
+            "#!/bin/sh\nif [ \"$1\" = exec ]; then\n  echo \"bundler: command not found: $2\" >&2\n  exit 127\nfi\nexit 0\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(sandbox.root().join("gems")),
+            false,
+            project.clone(),
+        )
+        .unwrap()
+        .with_bundler_bin(bundle.to_string_lossy());
+
+        // `sh` lives only on the inherited PATH, never in Butler's bin directories
+        let args = vec!["sh".to_string(), "-c".to_string(), "exit 0".to_string()];
+        let run = |allow_system: bool| {
+            let options = ExecOptions {
+                allow_system,
+                capture: Some(sandbox.root().join("output.log")),
+                ..ExecOptions::default()
+            };
+            run_program(&butler, &args, &options).unwrap()
+        };
+
+        assert_eq!(run(false).code(), Some(127));
+        assert!(run(true).success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_no_bundle_exec_runs_directly_with_bundler_environment() {
+        use rb_core::butler::SelectionOptions;
+        use rb_tests::BundlerSandbox;
+        use std::os::unix::fs::PermissionsExt;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = sandbox.add_bundler_project("app", true).unwrap();
+
+        // Fails any `bundle exec`, so success means the command ran directly
+        let bundle = sandbox.root().join("bundle-stub");
+        std::fs::write(
+            &bundle,
+            "#!/bin/sh\n[ \"$1\" = exec ] && exit 127\nexit 0\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_options(
+            rubies.root().to_path_buf(),
+            None,
+            Some(sandbox.root().join("gems")),
+            false,
+            project.clone(),
+            &SelectionOptions {
+                no_bundle_exec: true,
+                ..SelectionOptions::default()
+            },
+        )
+        .unwrap()
+        .with_bundler_bin(bundle.to_string_lossy());
+        assert!(!butler.uses_bundle_exec());
+
+        let seen = project.join("bundle_gemfile");
+        let args = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("printf %s \"$BUNDLE_GEMFILE\" > {}", seen.display()),
+        ];
+        let status = run_program(&butler, &args, &ExecOptions::default()).unwrap();
+
+        assert!(status.success(), "Command should run outside bundle exec");
+        let gemfile = std::fs::read_to_string(&seen).unwrap();
+        assert_eq!(PathBuf::from(gemfile), project.join("Gemfile"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_from_bundler_root_runs_program_in_project_root() {
+        use rb_tests::BundlerSandbox;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = sandbox.add_bundler_project("app", false).unwrap();
+        let nested = project.join("lib").join("tasks");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(sandbox.root().join("gems")),
+            false,
+            nested,
+        )
+        .unwrap()
+        .with_bundler_bin("true");
+
+        let seen = sandbox.root().join("cwd");
+        let args = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("pwd > {}", seen.display()),
+        ];
+        let options = ExecOptions {
+            from_bundler_root: true,
+            allow_system: true,
+            ..ExecOptions::default()
+        };
+        let status = run_program(&butler, &args, &options).unwrap();
+
+        assert!(status.success());
+        let cwd = std::fs::read_to_string(&seen).unwrap();
+        assert_eq!(
+            PathBuf::from(cwd.trim()).canonicalize().unwrap(),
+            project.canonicalize().unwrap()
+        );
+    }
 }
@@ -0,0 +1,119 @@
+use colored::*;
+use log::{debug, warn};
+use rb_core::butler::{ButlerError, ButlerRuntime, Command};
+
+/// Arguments handed to `gem` for a cleanup, optionally as a dry run
+fn cleanup_args(dry_run: bool) -> Vec<&'static str> {
+    let mut args = vec!["cleanup"];
+    if dry_run {
+        args.push("--dry-run");
+    }
+    args
+}
+
+/// Gem clean command - removes outdated gem versions from the user gem home
+pub fn gem_clean_command(butler_runtime: &ButlerRuntime, dry_run: bool) -> Result<(), ButlerError> {
+    if let Some(bundler_runtime) = butler_runtime.bundler_runtime() {
+        warn!(
+            "Refusing gem cleanup inside bundler project {}",
+            bundler_runtime.root.display()
+        );
+        return Err(ButlerError::General(
+            "Gem cleanup is unavailable in bundler isolation.\n\nThis project's gems are managed by bundler, so user gems are not active here.\nUse 'rb exec bundle clean' for the bundle, or run 'rb -B gem-clean' to tidy the user gem home.".to_string(),
+        ));
+    }
+
+    let args = cleanup_args(dry_run);
+    debug!("Running gem {}", args.join(" "));
+    println!(
+        "{} {}",
+        "🧹 Cleaning outdated gem versions".green().bold(),
+        if dry_run {
+            "(dry run)".bright_black().to_string()
+        } else {
+            String::new()
+        }
+    );
+
+    let status = Command::new("gem")
+        .args(&args)
+        .status_with_validation(butler_runtime)
+        .map_err(|e| ButlerError::General(format!("Unable to run gem cleanup: {}", e)))?;
+
+    if !status.success() {
+        return Err(ButlerError::General(format!(
+            "gem cleanup failed (exit code: {})",
+            status.code().unwrap_or(-1)
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_tests::{BundlerSandbox, RubySandbox};
+
+    #[test]
+    fn test_cleanup_args_forward_dry_run() {
+        assert_eq!(cleanup_args(false), vec!["cleanup"]);
+        assert_eq!(cleanup_args(true), vec!["cleanup", "--dry-run"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_gem_clean_runs_gem_cleanup_from_selected_ruby() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        let ruby_dir = rubies.add_ruby_dir("3.3.0").unwrap();
+        let work = tempfile::TempDir::new().unwrap();
+        let log = work.path().join("gem.log");
+
+        let gem = ruby_dir.join("bin").join("gem");
+        std::fs::write(
+            &gem,
+            format!("#!/bin/sh\necho \"$@\" >> '{}'\n", log.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&gem, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(work.path().join("gems")),
+            false,
+            work.path().to_path_buf(),
+        )
+        .unwrap();
+
+        gem_clean_command(&butler, false).unwrap();
+        gem_clean_command(&butler, true).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&log).unwrap(),
+            "cleanup\ncleanup --dry-run\n"
+        );
+    }
+
+    #[test]
+    fn test_gem_clean_refuses_in_bundler_project() {
+        let rubies = RubySandbox::new().expect("Failed to create sandbox");
+        rubies.add_ruby_dir("3.3.0").unwrap();
+        let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+        let project = sandbox.add_bundler_project("app", true).unwrap();
+
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            rubies.root().to_path_buf(),
+            None,
+            Some(sandbox.root().join("gems")),
+            false,
+            project,
+        )
+        .unwrap();
+
+        let error = gem_clean_command(&butler, false).unwrap_err();
+        assert!(error.to_string().contains("bundler isolation"));
+    }
+}
@@ -1,70 +1,139 @@
-use crate::config::TrackedConfig;
+use crate::ConfigFormat;
+use crate::config::{ConfigValue, TrackedConfig};
+use crate::output::Output;
 use colored::Colorize;
 use rb_core::butler::ButlerError;
 
-/// Display current configuration with sources
-pub fn config_command(config: &TrackedConfig) -> Result<(), ButlerError> {
-    println!("{}", "🎩 Current Configuration".bright_cyan().bold());
-    println!();
+/// Print where a value came from, followed by the lower-priority values it overrides
+fn show_source<T>(out: &mut dyn Output, value: &ConfigValue<T>) {
+    out.line(&format!(
+        "  {} {}",
+        "Source:".dimmed(),
+        format!("{}", value.source).yellow()
+    ));
+    for (source, shadowed) in &value.shadowed {
+        out.line(&format!(
+            "  {} {} {}",
+            "Overrides:".dimmed(),
+            shadowed.dimmed().strikethrough(),
+            format!("({})", source).dimmed()
+        ));
+    }
+}
+
+/// Display current configuration with sources, or as the `RB_*` variables reproducing it
+pub fn config_command(
+    config: &TrackedConfig,
+    format: ConfigFormat,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    if format == ConfigFormat::Env {
+        for (name, value) in config.to_env_vars() {
+            out.line(&format!("{}={}", name, value));
+        }
+        return Ok(());
+    }
+
+    out.line(&"🎩 Current Configuration".bright_cyan().bold().to_string());
+    out.blank();
 
-    println!(
+    out.line(&format!(
         "{} {}",
         "Rubies Directory:".bright_white().bold(),
         config.rubies_dir.get().display()
-    );
-    println!(
-        "  {} {}",
-        "Source:".dimmed(),
-        format!("{}", config.rubies_dir.source).yellow()
-    );
-    println!();
+    ));
+    show_source(out, &config.rubies_dir);
+    out.blank();
+
+    let additional_dirs = config.additional_rubies_dirs.get();
+    out.line(&format!(
+        "{} {}",
+        "Additional Rubies Directories:".bright_white().bold(),
+        if additional_dirs.is_empty() {
+            "none".dimmed().to_string()
+        } else {
+            additional_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    ));
+    show_source(out, &config.additional_rubies_dirs);
+    out.blank();
 
     if let Some(ref version) = config.ruby_version {
-        println!(
+        out.line(&format!(
             "{} {}",
             "Ruby Version:".bright_white().bold(),
-            version.get()
-        );
-        println!(
-            "  {} {}",
-            "Source:".dimmed(),
-            format!("{}", version.source).yellow()
-        );
+            version.get().join(", ")
+        ));
+        show_source(out, version);
         if version.is_unresolved() {
-            println!(
+            out.line(&format!(
                 "  {} {}",
                 "Note:".dimmed(),
                 "Will be resolved to latest available Ruby".cyan()
-            );
+            ));
         }
     } else {
-        println!(
+        out.line(&format!(
             "{} {}",
             "Ruby Version:".bright_white().bold(),
             "latest".dimmed()
-        );
-        println!("  {} {}", "Source:".dimmed(), "default".yellow());
-        println!(
+        ));
+        out.line(&format!("  {} {}", "Source:".dimmed(), "default".yellow()));
+        out.line(&format!(
             "  {} {}",
             "Note:".dimmed(),
             "Will use latest available Ruby".cyan()
-        );
+        ));
     }
-    println!();
+    out.blank();
 
-    println!(
+    out.line(&format!(
         "{} {}",
         "Gem Home:".bright_white().bold(),
         config.gem_home.get().display()
-    );
-    println!(
-        "  {} {}",
-        "Source:".dimmed(),
-        format!("{}", config.gem_home.source).yellow()
-    );
-    println!();
+    ));
+    show_source(out, &config.gem_home);
+    out.blank();
+
+    if let Some(ref gem_bases) = config.gem_bases {
+        out.line(
+            &"Gem Bases by Ruby Version:"
+                .bright_white()
+                .bold()
+                .to_string(),
+        );
+        for (pattern, base) in gem_bases.get() {
+            out.line(&format!("  {} {}", pattern.cyan(), base.display()));
+        }
+        show_source(out, gem_bases);
+        out.blank();
+    }
+
+    if let Some(ref gemset) = config.gemset {
+        out.line(&format!(
+            "{} {}",
+            "Gemset:".bright_white().bold(),
+            gemset.get()
+        ));
+        show_source(out, gemset);
+        out.blank();
+    }
+
+    if let Some(ref default_ruby) = config.default_ruby {
+        out.line(&format!(
+            "{} {}",
+            "Default Ruby:".bright_white().bold(),
+            default_ruby.get()
+        ));
+        show_source(out, default_ruby);
+        out.blank();
+    }
 
-    println!(
+    out.line(&format!(
         "{} {}",
         "No Bundler:".bright_white().bold(),
         if *config.no_bundler.get() {
@@ -72,31 +141,228 @@ pub fn config_command(config: &TrackedConfig) -> Result<(), ButlerError> {
         } else {
             "no".dimmed()
         }
-    );
-    println!(
-        "  {} {}",
-        "Source:".dimmed(),
-        format!("{}", config.no_bundler.source).yellow()
-    );
-    println!();
+    ));
+    show_source(out, &config.no_bundler);
+    out.blank();
 
-    println!(
+    out.line(&format!(
         "{} {}",
         "Working Directory:".bright_white().bold(),
         config.work_dir.get().display()
-    );
-    println!(
-        "  {} {}",
-        "Source:".dimmed(),
-        format!("{}", config.work_dir.source).yellow()
-    );
-    println!();
+    ));
+    show_source(out, &config.work_dir);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Include Prereleases:".bright_white().bold(),
+        if *config.include_prereleases.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.include_prereleases);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Interactive Select:".bright_white().bold(),
+        if *config.interactive_select.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.interactive_select);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Auto Install Missing:".bright_white().bold(),
+        if *config.auto_install_missing.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.auto_install_missing);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Keep Binstubs:".bright_white().bold(),
+        if *config.keep_binstubs.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.keep_binstubs);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Inherit GEM_PATH:".bright_white().bold(),
+        if *config.inherit_gem_path.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.inherit_gem_path);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Include site_ruby:".bright_white().bold(),
+        if *config.include_site_ruby.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.include_site_ruby);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Skip bundle exec:".bright_white().bold(),
+        if *config.no_bundle_exec.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.no_bundle_exec);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Bundler Executable:".bright_white().bold(),
+        config.bundler_bin.get()
+    ));
+    show_source(out, &config.bundler_bin);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Exec Lock Retries:".bright_white().bold(),
+        config.exec_lock_retries.get()
+    ));
+    show_source(out, &config.exec_lock_retries);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Exec From Bundler Root:".bright_white().bold(),
+        if *config.exec_from_bundler_root.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.exec_from_bundler_root);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Shared Scripts:".bright_white().bold(),
+        if *config.shared_scripts.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.shared_scripts);
+    out.blank();
 
-    println!("{}", "Configuration sources (in priority order):".dimmed());
-    println!("  {} CLI arguments", "1.".dimmed());
-    println!("  {} Configuration file", "2.".dimmed());
-    println!("  {} Environment variables", "3.".dimmed());
-    println!("  {} Built-in defaults", "4.".dimmed());
+    out.line(&format!(
+        "{} {}",
+        "Allow System Ruby:".bright_white().bold(),
+        if *config.allow_system_ruby.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.allow_system_ruby);
+    out.blank();
+
+    out.line(&format!(
+        "{} {}",
+        "Create Gem Home:".bright_white().bold(),
+        if *config.create_gem_home.get() {
+            "yes".green()
+        } else {
+            "no".dimmed()
+        }
+    ));
+    show_source(out, &config.create_gem_home);
+    out.blank();
+
+    if let Some(ref command_log) = config.command_log {
+        out.line(&format!(
+            "{} {}",
+            "Command Log:".bright_white().bold(),
+            command_log.get().display()
+        ));
+        show_source(out, command_log);
+        out.blank();
+    }
+
+    out.line(
+        &"Configuration sources (in priority order):"
+            .dimmed()
+            .to_string(),
+    );
+    out.line(&format!("  {} CLI arguments", "1.".dimmed()));
+    out.line(&format!("  {} Configuration file", "2.".dimmed()));
+    out.line(&format!("  {} Environment variables", "3.".dimmed()));
+    out.line(&format!("  {} Built-in defaults", "4.".dimmed()));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RbConfig;
+    use crate::output::CapturedOutput;
+
+    #[test]
+    fn test_reports_file_value_shadowed_by_cli() {
+        let cli_config = RbConfig {
+            ruby_version: vec!["3.3.0".to_string()],
+            ..Default::default()
+        };
+        let file_config = RbConfig {
+            ruby_version: vec!["3.2.0".to_string()],
+            exec_lock_retries: Some(5),
+            ..Default::default()
+        };
+        let config = TrackedConfig::from_merged_with_env(&cli_config, &file_config, |_| None);
+        let mut out = CapturedOutput::new();
+
+        config_command(&config, ConfigFormat::Human, &mut out).unwrap();
+
+        let lines = out.lines();
+        let version = lines
+            .iter()
+            .position(|line| line.contains("Ruby Version:"))
+            .unwrap();
+        assert!(lines[version].contains("3.3.0"));
+        assert!(lines[version + 1].contains("CLI argument"));
+        let overridden = &lines[version + 2];
+        assert!(overridden.contains("Overrides:"), "got {}", overridden);
+        assert!(overridden.contains("3.2.0") && overridden.contains("config file"));
+
+        // A value only the file sets overrides nothing
+        let retries = lines
+            .iter()
+            .position(|line| line.contains("Exec Lock Retries:"))
+            .unwrap();
+        assert!(lines[retries + 1].contains("config file"));
+        assert!(!lines[retries + 2].contains("Overrides:"));
+    }
+}
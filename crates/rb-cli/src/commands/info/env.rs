@@ -1,25 +1,78 @@
+use crate::output::Output;
+use crate::watch::{WatchFilter, watch_and_rerun};
 use colored::*;
 use log::{debug, info, warn};
 use rb_core::bundler::BundlerRuntime;
 use rb_core::butler::{ButlerError, ButlerRuntime};
 use rb_core::project::{ProjectRuntime, RbprojectDetector};
 use rb_core::ruby::RubyType;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Files in the working directory whose changes can alter the composed environment
+const ENVIRONMENT_FILES: &[&str] = &[
+    ".ruby-version",
+    "Gemfile",
+    "Gemfile.lock",
+    "rbproject.kdl",
+    "rbproject.toml",
+    "gem.kdl",
+    "gem.toml",
+];
 
 pub fn environment_command(
     butler_runtime: &ButlerRuntime,
     project_file: Option<PathBuf>,
+    explain: bool,
+    out: &mut dyn Output,
 ) -> Result<(), ButlerError> {
     info!("Presenting current Ruby environment from the working directory");
-    present_current_environment(butler_runtime, project_file)
+    present_current_environment(butler_runtime, project_file, explain, out)
+}
+
+/// Present the environment, then redraw it whenever a file shaping it changes
+///
+/// `render` composes the environment afresh each time, so a new `.ruby-version` selects
+/// a new Ruby. A failing render is reported and watching continues. Returns on Ctrl-C.
+pub fn watch_environment(
+    current_dir: &Path,
+    out: &mut dyn Output,
+    mut render: impl FnMut(&mut dyn Output) -> Result<(), ButlerError>,
+) -> Result<(), ButlerError> {
+    let filter = WatchFilter::new(current_dir).only_files(ENVIRONMENT_FILES);
+    redraw(out, &mut render);
+    watch_and_rerun(&filter, || redraw(out, &mut render))
+}
+
+/// Clear the screen and render the environment once more
+fn redraw(
+    out: &mut dyn Output,
+    render: &mut impl FnMut(&mut dyn Output) -> Result<(), ButlerError>,
+) {
+    out.clear();
+    if let Err(e) = render(out) {
+        out.error(&e.to_string());
+    }
+    out.blank();
+    out.line(
+        &"👀 Watching for changes (Ctrl-C to stop)"
+            .dimmed()
+            .to_string(),
+    );
 }
 
 fn present_current_environment(
     butler_runtime: &ButlerRuntime,
     project_file: Option<PathBuf>,
+    explain: bool,
+    out: &mut dyn Output,
 ) -> Result<(), ButlerError> {
-    println!("{}", "🌍 Your Current Ruby Environment".to_string().bold());
-    println!();
+    out.line(
+        &"🌍 Your Current Ruby Environment"
+            .to_string()
+            .bold()
+            .to_string(),
+    );
+    out.blank();
 
     let current_dir = butler_runtime.current_dir();
     debug!("Current working directory: {}", current_dir.display());
@@ -74,6 +127,8 @@ fn present_current_environment(
         bundler_runtime,
         project_runtime.as_ref(),
         butler_runtime,
+        explain,
+        out,
     );
 
     Ok(())
@@ -85,6 +140,8 @@ fn present_environment_details(
     bundler_runtime: Option<&BundlerRuntime>,
     project_runtime: Option<&ProjectRuntime>,
     butler: &ButlerRuntime,
+    explain: bool,
+    out: &mut dyn Output,
 ) {
     let label_width = [
         "Installation",
@@ -94,8 +151,12 @@ fn present_environment_details(
         "Bundler root",
         "Gemfile",
         "Vendor directory",
+        "Gem cache",
         "App config",
         "Synchronized",
+        "Bundle path",
+        "Without groups",
+        "Install jobs",
     ]
     .iter()
     .map(|s| s.len())
@@ -105,30 +166,49 @@ fn present_environment_details(
     // Present Ruby Environment
     let ruby_type = match ruby.kind {
         RubyType::CRuby => "💎 CRuby".green(),
+        RubyType::JRuby => "☕ JRuby".green(),
+        RubyType::TruffleRuby => "🐷 TruffleRuby".green(),
     };
-    println!("{} {}", ruby_type, format!("({})", ruby.version).cyan());
+    out.line(&format!(
+        "{} {}",
+        ruby_type,
+        format!("({})", ruby.version).cyan()
+    ));
 
-    println!(
+    out.line(&format!(
         "    {:<width$}: {}",
         "Installation".bright_blue().bold(),
         ruby.root.display().to_string().bright_black(),
         width = label_width
-    );
+    ));
+
+    if explain {
+        let reason = butler
+            .selection_reason()
+            .map(|reason| reason.to_string())
+            .unwrap_or_else(|| "provided directly".to_string());
+        out.line(&format!(
+            "    {:<width$}: {}",
+            "Selection".bright_blue().bold(),
+            reason.bright_black(),
+            width = label_width
+        ));
+    }
 
     if let Some(gem_rt) = gem_runtime {
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Gem home".bright_blue().bold(),
             gem_rt.gem_home.display().to_string().bright_black(),
             width = label_width
-        );
+        ));
     } else {
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Gem home".bright_blue().bold(),
             "Not available".yellow(),
             width = label_width
-        );
+        ));
     }
 
     let gem_dirs = butler.gem_dirs();
@@ -137,12 +217,12 @@ fn present_environment_details(
             .iter()
             .map(|d| d.display().to_string())
             .collect::<Vec<_>>();
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Gem libraries".bright_blue().bold(),
             gem_paths.join(", ").bright_black(),
             width = label_width
-        );
+        ));
     }
 
     let bin_dirs = butler.bin_dirs();
@@ -151,34 +231,34 @@ fn present_environment_details(
             .iter()
             .map(|d| d.display().to_string())
             .collect::<Vec<_>>();
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Executable paths".bright_blue().bold(),
             bin_paths.join(", ").bright_black(),
             width = label_width
-        );
+        ));
     }
 
     // Present Bundler Environment (if detected)
     if let Some(bundler) = bundler_runtime {
-        println!();
-        println!("{}", "📦 Bundler Environment".green().bold());
+        out.blank();
+        out.line(&"📦 Bundler Environment".green().bold().to_string());
 
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Bundler root".bright_blue().bold(),
             bundler.root.display().to_string().bright_black(),
             width = label_width
-        );
+        ));
 
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Gemfile".bright_blue().bold(),
             bundler.gemfile_path().display().to_string().bright_black(),
             width = label_width
-        );
+        ));
 
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "App config".bright_blue().bold(),
             bundler
@@ -187,22 +267,44 @@ fn present_environment_details(
                 .to_string()
                 .bright_black(),
             width = label_width
-        );
+        ));
 
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Vendor directory".bright_blue().bold(),
             bundler.vendor_dir().display().to_string().bright_black(),
             width = label_width
-        );
+        ));
+
+        let gem_cache = match bundler.gem_cache() {
+            Some(cache) => format!(
+                "{} {}",
+                cache.dir.display().to_string().bright_black(),
+                format!(
+                    "({} {}, offline install possible)",
+                    cache.gem_count,
+                    if cache.gem_count == 1 { "gem" } else { "gems" }
+                )
+                .cyan()
+            ),
+            None => "None (bundle cache packages gems for offline installs)"
+                .bright_black()
+                .to_string(),
+        };
+        out.line(&format!(
+            "    {:<width$}: {}",
+            "Gem cache".bright_blue().bold(),
+            gem_cache,
+            width = label_width
+        ));
 
         if let Some(version) = bundler.ruby_version() {
-            println!(
+            out.line(&format!(
                 "    {:<width$}: {}",
                 "Required Ruby".bright_blue().bold(),
                 format!("{}", version).bright_black(),
                 width = label_width
-            );
+            ));
         }
 
         let configured = if bundler.is_configured() {
@@ -210,12 +312,12 @@ fn present_environment_details(
         } else {
             "No".yellow()
         };
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Configured".bright_blue().bold(),
             configured,
             width = label_width
-        );
+        ));
 
         // Check synchronization status
         let sync_status = if !bundler.is_configured() {
@@ -227,44 +329,81 @@ fn present_environment_details(
                 Err(_) => "❓ Unknown".bright_black(),
             }
         };
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Synchronized".bright_blue().bold(),
             sync_status,
             width = label_width
-        );
+        ));
+
+        let settings = bundler.settings();
+        if let Some(path) = &settings.path {
+            out.line(&format!(
+                "    {:<width$}: {}",
+                "Bundle path".bright_blue().bold(),
+                path.bright_black(),
+                width = label_width
+            ));
+        }
+        if !settings.without.is_empty() {
+            out.line(&format!(
+                "    {:<width$}: {}",
+                "Without groups".bright_blue().bold(),
+                settings.without.join(", ").bright_black(),
+                width = label_width
+            ));
+        }
+        if let Some(jobs) = settings.jobs {
+            out.line(&format!(
+                "    {:<width$}: {}",
+                "Install jobs".bright_blue().bold(),
+                jobs.to_string().bright_black(),
+                width = label_width
+            ));
+        }
+        for checkout in bundler.local_override_dirs() {
+            out.line(&format!(
+                "    {:<width$}: {}",
+                "Local checkout".bright_blue().bold(),
+                checkout.display().to_string().bright_black(),
+                width = label_width
+            ));
+        }
     } else {
-        println!();
-        println!("{}", "📦 Bundler Environment".bright_black());
-        println!("    {}", "Bundler environment not detected".bright_black());
+        out.blank();
+        out.line(&"📦 Bundler Environment".bright_black().to_string());
+        out.line(&format!(
+            "    {}",
+            "Bundler environment not detected".bright_black()
+        ));
     }
 
     // Present Project Environment (if detected)
     if let Some(project) = project_runtime {
-        println!();
-        println!("{}", "📋 Project".green().bold());
+        out.blank();
+        out.line(&"📋 Project".green().bold().to_string());
 
         // Display project name if available
         if let Some(name) = &project.metadata.name {
-            println!(
+            out.line(&format!(
                 "    {:<width$}: {}",
                 "Name".bright_blue().bold(),
                 name.bright_black(),
                 width = label_width
-            );
+            ));
         }
 
         // Display project description if available
         if let Some(description) = &project.metadata.description {
-            println!(
+            out.line(&format!(
                 "    {:<width$}: {}",
                 "Description".bright_blue().bold(),
                 description.bright_black(),
                 width = label_width
-            );
+            ));
         }
 
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Project file".bright_blue().bold(),
             project
@@ -273,18 +412,21 @@ fn present_environment_details(
                 .to_string()
                 .bright_black(),
             width = label_width
-        );
+        ));
 
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Scripts loaded".bright_blue().bold(),
             format!("{}", project.scripts.len()).bright_black(),
             width = label_width
-        );
+        ));
 
         if !project.scripts.is_empty() {
-            println!();
-            println!("    {}", "Available Scripts:".bright_blue().bold());
+            out.blank();
+            out.line(&format!(
+                "    {}",
+                "Available Scripts:".bright_blue().bold()
+            ));
 
             // Get sorted script names for consistent display
             let script_names = project.script_names();
@@ -293,36 +435,39 @@ fn present_environment_details(
                 let command = script.command();
 
                 // Always show: name → command
-                println!(
+                out.line(&format!(
                     "      {} {} {}",
                     name.cyan().bold(),
                     "→".bright_black(),
                     command.to_string().bright_black()
-                );
+                ));
 
                 // Optionally show description on next line with more indent
                 if let Some(description) = script.description() {
-                    println!("        {}", description.bright_black().italic());
+                    out.line(&format!("        {}", description.bright_black().italic()));
                 }
             }
         }
     } else {
-        println!();
-        println!("{}", "📋 Project Scripts".bright_black());
-        println!("    {}", "No project config detected".bright_black());
+        out.blank();
+        out.line(&"📋 Project Scripts".bright_black().to_string());
+        out.line(&format!(
+            "    {}",
+            "No project config detected".bright_black()
+        ));
     }
 
     // Present environment summary
-    println!();
-    println!("{}", "🎯 Environment Summary".green().bold());
+    out.blank();
+    out.line(&"🎯 Environment Summary".green().bold().to_string());
 
     let ruby_version_text = format!("{} {}", ruby_type_as_str(&ruby.kind), ruby.version);
-    println!(
+    out.line(&format!(
         "    {:<width$}: {}",
         "Active Ruby".bright_blue().bold(),
         ruby_version_text.bright_black(),
         width = label_width
-    );
+    ));
 
     if let Some(bundler) = bundler_runtime {
         let project_name = bundler
@@ -330,12 +475,12 @@ fn present_environment_details(
             .file_name()
             .unwrap_or_default()
             .to_string_lossy();
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Bundler project".bright_blue().bold(),
             project_name.bright_black(),
             width = label_width
-        );
+        ));
 
         if let Some(req_version) = bundler.ruby_version() {
             let matches = if ruby.version == req_version {
@@ -343,31 +488,48 @@ fn present_environment_details(
             } else {
                 "⚠️  Mismatch".yellow()
             };
-            println!(
+            out.line(&format!(
                 "    {:<width$}: {}",
                 "Version match".bright_blue().bold(),
                 matches,
                 width = label_width
-            );
+            ));
         }
     }
 
-    println!();
-    println!(
-        "{}",
-        "Environment ready for distinguished Ruby development.".bright_black()
+    if let Some(shadow) = butler.shadowing_ruby(std::env::var("PATH").ok()) {
+        out.line(&format!(
+            "    {:<width$}: {}",
+            "PATH warning".bright_blue().bold(),
+            format!(
+                "⚠️  {} shadows the selected Ruby (see rb doctor)",
+                shadow.display()
+            )
+            .yellow(),
+            width = label_width
+        ));
+    }
+
+    out.blank();
+    out.line(
+        &"Environment ready for distinguished Ruby development."
+            .bright_black()
+            .to_string(),
     );
 }
 
 fn ruby_type_as_str(ruby_type: &RubyType) -> &'static str {
     match ruby_type {
         RubyType::CRuby => "CRuby",
+        RubyType::JRuby => "JRuby",
+        RubyType::TruffleRuby => "TruffleRuby",
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::CapturedOutput;
     use rb_core::butler::ButlerRuntime;
     use rb_tests::RubySandbox;
 
@@ -385,7 +547,7 @@ mod tests {
                 .expect("Failed to create butler runtime with test Ruby");
 
         // This will handle the environment presentation gracefully
-        let _ = environment_command(&butler_runtime, None);
+        let _ = environment_command(&butler_runtime, None, false, &mut CapturedOutput::new());
     }
 
     #[test]
@@ -405,9 +567,18 @@ mod tests {
         let gem_runtime = GemRuntime::for_base_dir(&ruby_sandbox.gem_base_dir(), &ruby.version);
         let butler = ButlerRuntime::new(ruby.clone(), Some(gem_runtime.clone()));
 
-        // Test with no bundler environment
-        // Note: This test outputs to stdout - that's expected behavior
-        present_environment_details(&ruby, Some(&gem_runtime), None, None, &butler);
+        let mut out = CapturedOutput::new();
+        present_environment_details(
+            &ruby,
+            Some(&gem_runtime),
+            None,
+            None,
+            &butler,
+            false,
+            &mut out,
+        );
+
+        assert!(out.contains("Bundler environment not detected"));
 
         Ok(())
     }
@@ -433,14 +604,123 @@ mod tests {
         let gem_runtime = GemRuntime::for_base_dir(&ruby_sandbox.gem_base_dir(), &ruby.version);
         let butler = ButlerRuntime::new(ruby.clone(), Some(gem_runtime.clone()));
 
-        // Test with bundler environment
+        let mut out = CapturedOutput::new();
+        present_environment_details(
+            &ruby,
+            Some(&gem_runtime),
+            Some(&bundler_runtime),
+            None,
+            &butler,
+            false,
+            &mut out,
+        );
+
+        assert!(out.contains("Bundler Environment"));
+        assert!(!out.contains("Bundler environment not detected"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn present_environment_details_shows_bundler_settings() -> std::io::Result<()> {
+        use rb_core::gems::GemRuntime;
+        use rb_tests::{BundlerSandbox, RubySandbox};
+
+        let ruby_sandbox = RubySandbox::new()?;
+        let ruby_dir = ruby_sandbox.add_ruby_dir("3.2.5")?;
+        let ruby = rb_core::ruby::RubyRuntime::new(
+            rb_core::ruby::RubyType::CRuby,
+            semver::Version::parse("3.2.5").unwrap(),
+            &ruby_dir,
+        );
+
+        let bundler_sandbox = BundlerSandbox::new()?;
+        let project_dir = bundler_sandbox.add_bundler_project("settings-app", true)?;
+        let bundler_runtime = BundlerRuntime::new(&project_dir, ruby.version.clone());
+        std::fs::write(
+            bundler_runtime.config_file(),
+            "---\nBUNDLE_PATH: \"vendor/gems\"\nBUNDLE_WITHOUT: \"development:test\"\nBUNDLE_JOBS: \"8\"\n",
+        )?;
+
+        let gem_runtime = GemRuntime::for_base_dir(&ruby_sandbox.gem_base_dir(), &ruby.version);
+        let butler = ButlerRuntime::new(ruby.clone(), Some(gem_runtime.clone()));
+
+        let mut out = CapturedOutput::new();
         present_environment_details(
             &ruby,
             Some(&gem_runtime),
             Some(&bundler_runtime),
             None,
             &butler,
+            false,
+            &mut out,
+        );
+
+        let line = |label: &str| {
+            out.lines()
+                .iter()
+                .find(|line| line.contains(label))
+                .cloned()
+                .unwrap_or_default()
+        };
+        assert!(line("Bundle path").contains("vendor/gems"));
+        assert!(line("Without groups").contains("development, test"));
+        assert!(line("Install jobs").contains('8'));
+
+        Ok(())
+    }
+
+    #[test]
+    fn present_environment_details_reports_gem_cache() -> std::io::Result<()> {
+        use rb_core::gems::GemRuntime;
+        use rb_tests::{BundlerSandbox, RubySandbox};
+
+        let ruby_sandbox = RubySandbox::new()?;
+        let ruby_dir = ruby_sandbox.add_ruby_dir("3.2.5")?;
+        let ruby = rb_core::ruby::RubyRuntime::new(
+            rb_core::ruby::RubyType::CRuby,
+            semver::Version::parse("3.2.5").unwrap(),
+            &ruby_dir,
+        );
+
+        let bundler_sandbox = BundlerSandbox::new()?;
+        let project_dir = bundler_sandbox.add_bundler_project("cached-app", true)?;
+        let bundler_runtime = BundlerRuntime::new(&project_dir, ruby.version.clone());
+        let gem_runtime = GemRuntime::for_base_dir(&ruby_sandbox.gem_base_dir(), &ruby.version);
+        let butler = ButlerRuntime::new(ruby.clone(), Some(gem_runtime.clone()));
+
+        let present = || {
+            let mut out = CapturedOutput::new();
+            present_environment_details(
+                &ruby,
+                Some(&gem_runtime),
+                Some(&bundler_runtime),
+                None,
+                &butler,
+                false,
+                &mut out,
+            );
+            out.lines()
+                .iter()
+                .find(|line| line.contains("Gem cache"))
+                .cloned()
+                .unwrap_or_default()
+        };
+        assert!(present().contains("None"));
+
+        let cache_dir = project_dir.join("vendor").join("cache");
+        std::fs::create_dir_all(&cache_dir)?;
+        for name in ["rake-13.2.1.gem", "rack-3.1.8.gem", "json-2.9.1.gem"] {
+            std::fs::write(cache_dir.join(name), "")?;
+        }
+
+        let line = present();
+        assert!(
+            line.contains(&cache_dir.display().to_string()),
+            "line: {}",
+            line
         );
+        assert!(line.contains("3 gems"), "line: {}", line);
 
         Ok(())
     }
@@ -467,6 +747,9 @@ mod tests {
             ScriptDefinition::Detailed {
                 command: "rspec".to_string(),
                 description: Some("Run the test suite".to_string()),
+                enabled: true,
+                runner: true,
+                timeout: None,
             },
         );
         scripts.insert(
@@ -482,14 +765,79 @@ mod tests {
         let gem_runtime = GemRuntime::for_base_dir(&ruby_sandbox.gem_base_dir(), &ruby.version);
         let butler = ButlerRuntime::new(ruby.clone(), Some(gem_runtime.clone()));
 
-        // Test with project environment
+        let mut out = CapturedOutput::new();
         present_environment_details(
             &ruby,
             Some(&gem_runtime),
             None,
             Some(&project_runtime),
             &butler,
+            true,
+            &mut out,
+        );
+
+        assert!(out.contains("lint:fix"));
+        assert!(out.contains("Run the test suite"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn change_event_rediscovers_and_redraws_environment() -> std::io::Result<()> {
+        use crate::watch::{WatchEvent, watch_loop};
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let sandbox = RubySandbox::new()?;
+        sandbox.add_ruby_dir("3.2.5")?;
+        sandbox.add_ruby_dir("3.3.6")?;
+        let work_dir = sandbox.add_dir("work")?;
+        std::fs::write(work_dir.join("Gemfile"), "source 'https://rubygems.org'\n")?;
+        std::fs::write(work_dir.join(".ruby-version"), "3.2.5\n")?;
+
+        let filter = WatchFilter::new(&work_dir).only_files(ENVIRONMENT_FILES);
+        let version_file = filter.root().join(".ruby-version");
+        let mut compositions = 0;
+        let mut render = |out: &mut dyn Output| {
+            compositions += 1;
+            let runtime = ButlerRuntime::discover_and_compose_with_current_dir(
+                sandbox.root().to_path_buf(),
+                None,
+                None,
+                false,
+                work_dir.clone(),
+            )?;
+            environment_command(&runtime, None, false, out)
+        };
+
+        let mut out = CapturedOutput::new();
+        redraw(&mut out, &mut render);
+        std::fs::write(&version_file, "3.3.6\n")?;
+
+        let (tx, rx) = mpsc::channel();
+        let feeder = std::thread::spawn(move || {
+            tx.send(WatchEvent::Changed(vec![version_file])).unwrap();
+            std::thread::sleep(Duration::from_millis(300));
+            tx.send(WatchEvent::Stop).unwrap();
+        });
+        watch_loop(&rx, &filter, Duration::from_millis(20), || {
+            redraw(&mut out, &mut render)
+        });
+        feeder.join().unwrap();
+
+        assert_eq!(compositions, 2);
+        let text = out.text();
+        let renders: Vec<&str> = text
+            .split("Your Current Ruby Environment")
+            .skip(1)
+            .collect();
+        assert_eq!(renders.len(), 2, "output: {}", text);
+        assert!(
+            renders[0].contains("3.2.5") && !renders[0].contains("3.3.6"),
+            "output: {}",
+            text
         );
+        assert!(renders[1].contains("3.3.6"), "output: {}", text);
 
         Ok(())
     }
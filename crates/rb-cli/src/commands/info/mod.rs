@@ -6,8 +6,9 @@ pub mod runtime;
 use rb_core::butler::{ButlerError, ButlerRuntime};
 use std::path::PathBuf;
 
-use crate::InfoCommands;
 use crate::config::TrackedConfig;
+use crate::output::StdoutOutput;
+use crate::{ConfigFormat, InfoCommands};
 
 pub fn info_command(
     command: &InfoCommands,
@@ -15,10 +16,27 @@ pub fn info_command(
     project_file: Option<PathBuf>,
 ) -> Result<(), ButlerError> {
     match command {
-        InfoCommands::Runtime => runtime::runtime_command(butler_runtime),
-        InfoCommands::Env => env::environment_command(butler_runtime, project_file),
-        InfoCommands::Project => project::project_command(butler_runtime, project_file),
-        InfoCommands::Config => {
+        InfoCommands::Runtime {
+            only,
+            since,
+            sort,
+            reverse,
+            default: _,
+        } => runtime::runtime_command(
+            butler_runtime,
+            only.as_deref(),
+            since.as_deref(),
+            *sort,
+            *reverse,
+            &mut StdoutOutput,
+        ),
+        InfoCommands::Env { explain, .. } => {
+            env::environment_command(butler_runtime, project_file, *explain, &mut StdoutOutput)
+        }
+        InfoCommands::Project => {
+            project::project_command(butler_runtime, project_file, &mut StdoutOutput)
+        }
+        InfoCommands::Config { .. } => {
             // Config command doesn't actually need the runtime, but we have it available
             // For now, return an error - this will be handled specially in dispatch
             Err(ButlerError::General(
@@ -28,6 +46,9 @@ pub fn info_command(
     }
 }
 
-pub fn info_config_command(config: &TrackedConfig) -> Result<(), ButlerError> {
-    config::config_command(config)
+pub fn info_config_command(
+    config: &TrackedConfig,
+    format: ConfigFormat,
+) -> Result<(), ButlerError> {
+    config::config_command(config, format, &mut StdoutOutput)
 }
@@ -4,21 +4,25 @@ use rb_core::butler::{ButlerError, ButlerRuntime};
 use rb_core::project::{ProjectRuntime, RbprojectDetector};
 use std::path::PathBuf;
 
+use crate::output::Output;
+
 pub fn project_command(
     butler_runtime: &ButlerRuntime,
     project_file: Option<PathBuf>,
+    out: &mut dyn Output,
 ) -> Result<(), ButlerError> {
     info!("Inspecting project configuration");
-    present_project_info(butler_runtime, project_file)?;
+    present_project_info(butler_runtime, project_file, out)?;
     Ok(())
 }
 
 fn present_project_info(
     butler_runtime: &ButlerRuntime,
     project_file: Option<PathBuf>,
+    out: &mut dyn Output,
 ) -> Result<(), ButlerError> {
-    println!("{}", "📁 Project Configuration".to_string().bold());
-    println!();
+    out.line(&"📁 Project Configuration".bold().to_string());
+    out.blank();
 
     let project_runtime = if let Some(path) = project_file {
         debug!(
@@ -36,66 +40,96 @@ fn present_project_info(
 
     match project_runtime {
         Some(project_runtime) => {
-            println!(
+            out.line(&format!(
                 "  {} {}",
                 "Project File:".bold(),
                 project_runtime.rbproject_path().display()
-            );
-            println!();
+            ));
+            out.blank();
 
             if let Some(name) = &project_runtime.metadata.name {
-                println!("  {} {}", "Name:".bold(), name.cyan());
+                out.line(&format!("  {} {}", "Name:".bold(), name.cyan()));
             }
 
             if let Some(description) = &project_runtime.metadata.description {
-                println!("  {} {}", "Description:".bold(), description.dimmed());
+                out.line(&format!(
+                    "  {} {}",
+                    "Description:".bold(),
+                    description.dimmed()
+                ));
+            }
+
+            let metadata = &project_runtime.metadata;
+            if let Some(version) = metadata.version() {
+                out.line(&format!("  {} {}", "Version:".bold(), version));
+            }
+            if let Some(homepage) = metadata.homepage() {
+                out.line(&format!("  {} {}", "Homepage:".bold(), homepage.cyan()));
+            }
+            if let Some(license) = metadata.license() {
+                out.line(&format!("  {} {}", "License:".bold(), license));
+            }
+            if !metadata.authors().is_empty() {
+                out.line(&format!(
+                    "  {} {}",
+                    "Authors:".bold(),
+                    metadata.authors().join(", ")
+                ));
             }
 
             if !project_runtime.scripts.is_empty() {
-                println!();
-                println!("  {}", "Scripts:".bold());
+                out.blank();
+                out.line(&format!("  {}", "Scripts:".bold()));
                 for (name, script) in &project_runtime.scripts {
                     if let Some(desc) = script.description() {
-                        println!(
+                        out.line(&format!(
                             "    {} → {} {}",
                             name.cyan(),
                             script.command().dimmed(),
                             format!("({})", desc).bright_black()
-                        );
+                        ));
                     } else {
-                        println!("    {} → {}", name.cyan(), script.command().dimmed());
+                        out.line(&format!(
+                            "    {} → {}",
+                            name.cyan(),
+                            script.command().dimmed()
+                        ));
                     }
                 }
             }
         }
         None => {
-            println!(
+            out.line(&format!(
                 "  {}",
                 "No rbproject.toml found in current directory or parents".dimmed()
-            );
-            println!();
-            println!("  {} Run {} to create one.", "Tip:".bold(), "rb new".cyan());
+            ));
+            out.blank();
+            out.line(&format!(
+                "  {} Run {} to create one.",
+                "Tip:".bold(),
+                "rb new".cyan()
+            ));
         }
     }
 
-    println!();
+    out.blank();
 
-    println!("{}", "🔧 Effective Configuration".to_string().bold());
-    println!();
-    println!(
+    out.line(&"🔧 Effective Configuration".bold().to_string());
+    out.blank();
+    out.line(&format!(
         "  {} {}",
         "Rubies Directory:".bold(),
         butler_runtime.rubies_dir().display()
-    );
+    ));
 
     if let Some(gem_base) = butler_runtime.gem_base_dir() {
-        println!("  {} {}", "Gem Home:".bold(), gem_base.display());
+        out.line(&format!("  {} {}", "Gem Home:".bold(), gem_base.display()));
     }
 
     if let Some(requested) = butler_runtime.requested_ruby_version() {
-        println!("  {} {}", "Requested Ruby:".bold(), requested);
+        out.line(&format!("  {} {}", "Requested Ruby:".bold(), requested));
     }
 
-    println!();
+    out.blank();
     Ok(())
 }
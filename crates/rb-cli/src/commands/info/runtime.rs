@@ -1,25 +1,199 @@
+use crate::RuntimeSort;
+use crate::config::writer;
+use crate::output::Output;
 use colored::*;
 use log::{debug, info};
 use rb_core::butler::{ButlerError, ButlerRuntime};
-use rb_core::ruby::RubyType;
-use semver::Version;
+use rb_core::ruby::{RubyRuntime, RubyType};
+use semver::{Version, VersionReq};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Narrows the survey to matching Rubies: a dotted prefix (`3.3`) or a semver requirement (`>=3.2`)
+#[derive(Debug)]
+enum VersionFilter {
+    Prefix(Vec<u64>),
+    Requirement(VersionReq),
+}
+
+impl VersionFilter {
+    fn parse(pattern: &str) -> Result<Self, ButlerError> {
+        let pattern = pattern.trim();
+        let components: Option<Vec<u64>> = pattern
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect();
+
+        if let Some(components) = components.filter(|c| c.len() <= 3) {
+            return Ok(VersionFilter::Prefix(components));
+        }
+
+        VersionReq::parse(pattern)
+            .map(VersionFilter::Requirement)
+            .map_err(|e| {
+                ButlerError::General(format!(
+                    "Invalid version filter '{}': {}\n\nUse a prefix such as 3.3 or a requirement such as '>=3.2'.",
+                    pattern, e
+                ))
+            })
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        match self {
+            VersionFilter::Prefix(components) => components
+                .iter()
+                .zip([version.major, version.minor, version.patch])
+                .all(|(wanted, actual)| *wanted == actual),
+            VersionFilter::Requirement(req) => req.matches(version),
+        }
+    }
+}
+
+impl std::fmt::Display for VersionFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionFilter::Prefix(components) => {
+                let parts: Vec<String> = components.iter().map(u64::to_string).collect();
+                write!(f, "{}", parts.join("."))
+            }
+            VersionFilter::Requirement(req) => write!(f, "{}", req),
+        }
+    }
+}
 
-pub fn runtime_command(butler_runtime: &ButlerRuntime) -> Result<(), ButlerError> {
+/// Parse a window such as `30m`, `12h`, `7d` or `2w`
+fn parse_since(text: &str) -> Result<Duration, ButlerError> {
+    let text = text.trim();
+    let invalid = || {
+        ButlerError::General(format!(
+            "Invalid duration '{}'\n\nUse a number followed by m, h, d or w, for example 7d.",
+            text
+        ))
+    };
+
+    let unit_start = text.char_indices().last().map_or(0, |(index, _)| index);
+    let (amount, unit) = text.split_at(unit_start);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let seconds_per_unit = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Duration::from_secs(amount.saturating_mul(seconds_per_unit)))
+}
+
+/// Whether the Ruby's installation directory changed within `window` of `now`
+fn installed_within(ruby: &RubyRuntime, window: Duration, now: SystemTime) -> bool {
+    match ruby.installed_at() {
+        Some(installed_at) => now
+            .duration_since(installed_at)
+            .map_or(true, |age| age <= window),
+        None => {
+            debug!("No modification time for {}", ruby.root.display());
+            false
+        }
+    }
+}
+
+/// Put `rubies` in `sort` order, flipped when `reverse` is set
+fn sort_rubies(rubies: &mut [&RubyRuntime], sort: RuntimeSort, reverse: bool) {
+    match sort {
+        RuntimeSort::Version => rubies.sort_by(|a, b| b.latest_order().cmp(&a.latest_order())),
+        RuntimeSort::Name => rubies.sort_by(|a, b| a.root.file_name().cmp(&b.root.file_name())),
+        // Rubies without a modification time sort last
+        RuntimeSort::Mtime => rubies.sort_by_key(|ruby| std::cmp::Reverse(ruby.installed_at())),
+    }
+    if reverse {
+        rubies.reverse();
+    }
+}
+
+pub fn runtime_command(
+    butler_runtime: &ButlerRuntime,
+    only: Option<&str>,
+    since: Option<&str>,
+    sort: RuntimeSort,
+    reverse: bool,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
     info!(
         "Surveying Ruby installations in distinguished directory: {}",
         butler_runtime.rubies_dir().display()
     );
-    present_ruby_installations(butler_runtime)?;
+    let filter = only.map(VersionFilter::parse).transpose()?;
+    let since = since.map(parse_since).transpose()?;
+    present_ruby_installations(butler_runtime, filter.as_ref(), since, sort, reverse, out)?;
+    Ok(())
+}
+
+/// Save an installed Ruby as `default-ruby` in the config file at `config_path`
+pub fn set_default_ruby_command(
+    butler_runtime: &ButlerRuntime,
+    version: &str,
+    config_path: Option<PathBuf>,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    let wanted = Version::parse(version.trim()).map_err(|e| {
+        ButlerError::General(format!(
+            "Invalid Ruby version '{}': {}\n\nGive a full version such as 3.3.0.",
+            version, e
+        ))
+    })?;
+
+    if !butler_runtime
+        .ruby_installations()
+        .iter()
+        .any(|ruby| ruby.version == wanted)
+    {
+        return Err(ButlerError::NoSuitableRuby(format!(
+            "Ruby {} is not installed in {}",
+            wanted,
+            butler_runtime.rubies_dir().display()
+        )));
+    }
+
+    let config_path = config_path.ok_or_else(|| {
+        ButlerError::General(
+            "Unable to determine where to write the configuration.\n\nSet RB_CONFIG or pass --config.".to_string(),
+        )
+    })?;
+    debug!(
+        "Saving default Ruby {} to {}",
+        wanted,
+        config_path.display()
+    );
+    // Not `ruby-version`: that setting is a request and would override every project's
+    // .ruby-version and Gemfile, while a default only applies when nothing selects a Ruby
+    writer::set_string(&config_path, "default-ruby", &wanted.to_string()).map_err(|e| {
+        ButlerError::General(format!("Unable to update {}: {}", config_path.display(), e))
+    })?;
+
+    out.line(&format!(
+        "{} {} {}",
+        "✓".green().bold(),
+        format!("Ruby {} is now the default", wanted).bold(),
+        format!("(saved to {})", config_path.display()).bright_black()
+    ));
     Ok(())
 }
 
-fn present_ruby_installations(butler_runtime: &ButlerRuntime) -> Result<(), ButlerError> {
+fn present_ruby_installations(
+    butler_runtime: &ButlerRuntime,
+    filter: Option<&VersionFilter>,
+    since: Option<Duration>,
+    sort: RuntimeSort,
+    reverse: bool,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
     let rubies_dir = butler_runtime.rubies_dir();
     let ruby_installations = butler_runtime.ruby_installations();
-    let requested_ruby_version = butler_runtime.requested_ruby_version();
+    let requested_ruby_version = butler_runtime.resolved_ruby_version();
 
-    println!("{}", "💎 Ruby Environment Survey".to_string().bold());
-    println!();
+    out.line(&"💎 Ruby Environment Survey".to_string().bold().to_string());
+    out.blank();
 
     debug!("Surveying directory: {}", rubies_dir.display());
     debug!("Found {} Ruby installations", ruby_installations.len());
@@ -30,12 +204,36 @@ fn present_ruby_installations(butler_runtime: &ButlerRuntime) -> Result<(), Butl
         ));
     }
 
+    let now = SystemTime::now();
+    let mut surveyed: Vec<_> = ruby_installations
+        .iter()
+        .filter(|ruby| filter.is_none_or(|f| f.matches(&ruby.version)))
+        .filter(|ruby| since.is_none_or(|window| installed_within(ruby, window, now)))
+        .collect();
+    sort_rubies(&mut surveyed, sort, reverse);
+
+    if surveyed.is_empty() {
+        let reason = match (filter, since) {
+            (Some(filter), None) => format!("No Ruby installations match '{}'", filter),
+            (Some(filter), Some(_)) => format!(
+                "No Ruby installations match '{}' within the requested window",
+                filter
+            ),
+            (None, _) => {
+                "No Ruby installations were installed within the requested window".to_string()
+            }
+        };
+        return Err(ButlerError::NoSuitableRuby(reason));
+    }
+
     // Collect all ruby display data first for proper alignment calculation
     let mut ruby_display_data = Vec::new();
 
-    for ruby in ruby_installations {
+    for ruby in surveyed {
         let ruby_type = match ruby.kind {
             RubyType::CRuby => "CRuby",
+            RubyType::JRuby => "JRuby",
+            RubyType::TruffleRuby => "TruffleRuby",
         };
         let ruby_header = format!("{} ({})", ruby_type, ruby.version);
 
@@ -137,71 +335,71 @@ fn present_ruby_installations(butler_runtime: &ButlerRuntime) -> Result<(), Butl
         let version_start = ruby_header.find('(').unwrap_or(0);
         let version = ruby_header[version_start..].cyan();
 
-        println!("{} {}", ruby_type, version);
+        out.line(&format!("{} {}", ruby_type, version));
 
         // Present installation location with proper alignment
-        println!(
+        out.line(&format!(
             "    {:<width$}: {}",
             "Installation".bright_blue().bold(),
             ruby_path.bright_black(),
             width = label_width
-        );
+        ));
 
         // Present gem home with appropriate dignity
         if let Some(gem_home) = gem_home {
-            println!(
+            out.line(&format!(
                 "    {:<width$}: {}",
                 "Gem home".bright_blue().bold(),
                 gem_home.bright_black(),
                 width = label_width
-            );
+            ));
         } else {
-            println!(
+            out.line(&format!(
                 "    {:<width$}: {}",
                 "Gem home".bright_blue().bold(),
                 "Not available".yellow(),
                 width = label_width
-            );
+            ));
         }
 
         // Present gem libraries with proper ceremony
         if !gem_paths.is_empty() {
-            println!(
+            out.line(&format!(
                 "    {:<width$}:",
                 "Gem libraries".bright_blue().bold(),
                 width = label_width
-            );
+            ));
             for gem_path in gem_paths {
-                println!(
+                out.line(&format!(
                     "    {:<width$}  {}",
                     "",
                     gem_path.cyan(),
                     width = label_width
-                );
+                ));
             }
         }
 
         // Present executable paths with proper ceremony
         if !bin_paths.is_empty() {
-            println!(
+            out.line(&format!(
                 "    {:<width$}:",
                 "Executable paths".bright_blue().bold(),
                 width = label_width
-            );
+            ));
             for bin_path in bin_paths {
-                println!(
+                out.line(&format!(
                     "    {:<width$}  {}",
                     "",
                     bin_path.green(),
                     width = label_width
-                );
+                ));
             }
         }
 
-        println!(); // Maintain dignified spacing between entries
+        out.blank(); // Maintain dignified spacing between entries
     }
 
-    println!();
+    out.blank();
 
     // Handle Ruby selection with appropriate ceremony
     if let Some(version_str) = requested_ruby_version {
@@ -226,14 +424,14 @@ fn present_ruby_installations(butler_runtime: &ButlerRuntime) -> Result<(), Butl
                     ruby_type_as_str(&ruby.kind),
                     ruby.version
                 );
-                println!(
+                out.line(&format!(
                     "{}: {} {} {} {}",
                     "Environment Selected".bold(),
                     "(as requested)".bright_blue(),
                     ruby_type_as_str(&ruby.kind).green(),
                     format!("({})", ruby.version).cyan(),
                     format!("residing at {}", ruby.root.display()).bright_black()
-                );
+                ));
             }
             None => {
                 return Err(ButlerError::NoSuitableRuby(format!(
@@ -244,40 +442,40 @@ fn present_ruby_installations(butler_runtime: &ButlerRuntime) -> Result<(), Butl
         }
     } else {
         // Present the finest Ruby with appropriate recognition
-        if let Some(latest) = ruby_installations.iter().max_by_key(|r| &r.version) {
+        if let Some(latest) = ruby_installations.iter().max_by_key(|r| r.latest_order()) {
             info!(
                 "Presenting your finest Ruby installation: {} {}",
                 ruby_type_as_str(&latest.kind),
                 latest.version
             );
-            println!(
+            out.line(&format!(
                 "{}: {} {} {} {}",
                 "Environment Ready".bold(),
                 "(latest available)".bright_blue(),
                 ruby_type_as_str(&latest.kind).green(),
                 format!("({})", latest.version).cyan(),
                 format!("residing at {}", latest.root.display()).bright_black()
-            );
+            ));
         }
     }
 
-    println!();
+    out.blank();
 
     if let Some(requested) = requested_ruby_version {
-        println!(
-            "{}",
-            format!(
+        out.line(
+            &format!(
                 "Environment ready for distinguished Ruby development with version {}.",
                 requested
             )
             .dimmed()
+            .to_string(),
         );
     } else {
-        println!(
-            "{}",
-            "Environment ready for distinguished Ruby development."
+        out.line(
+            &"Environment ready for distinguished Ruby development."
                 .to_string()
                 .dimmed()
+                .to_string(),
         );
     }
 
@@ -287,10 +485,14 @@ fn present_ruby_installations(butler_runtime: &ButlerRuntime) -> Result<(), Butl
 fn ruby_type_as_str(ruby_type: &RubyType) -> &'static str {
     match ruby_type {
         RubyType::CRuby => "CRuby",
+        RubyType::JRuby => "JRuby",
+        RubyType::TruffleRuby => "TruffleRuby",
     }
 }
 #[cfg(test)]
 mod tests {
+    use crate::RuntimeSort;
+    use crate::output::CapturedOutput;
     use rb_core::butler::ButlerRuntime;
     use rb_tests::RubySandbox;
 
@@ -307,6 +509,294 @@ mod tests {
             .expect("Failed to create butler runtime");
 
         // This test just verifies the function can be called without panicking
-        let _ = super::runtime_command(&butler_runtime);
+        let _ = super::runtime_command(
+            &butler_runtime,
+            None,
+            None,
+            RuntimeSort::Version,
+            false,
+            &mut CapturedOutput::new(),
+        );
+    }
+
+    #[test]
+    fn test_runtime_command_output_can_be_captured() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        let ruby_dir = sandbox
+            .add_ruby_dir("3.2.5")
+            .expect("Failed to create ruby-3.2.5");
+        sandbox
+            .add_ruby_dir("3.4.1")
+            .expect("Failed to create ruby-3.4.1");
+
+        let butler_runtime =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None)
+                .expect("Failed to create butler runtime");
+
+        let mut out = CapturedOutput::new();
+        super::runtime_command(
+            &butler_runtime,
+            None,
+            None,
+            RuntimeSort::Version,
+            false,
+            &mut out,
+        )
+        .expect("Survey should succeed");
+
+        assert!(out.lines()[0].contains("Ruby Environment Survey"));
+        assert!(out.contains(&ruby_dir.display().to_string()));
+        assert!(out.contains("(3.2.5)"));
+        assert!(out.contains("Environment Ready"));
+        assert!(out.contains("(3.4.1)"));
+    }
+
+    fn filtered(pattern: &str) -> Vec<String> {
+        let filter = super::VersionFilter::parse(pattern).expect("valid filter");
+        ["3.1.4", "3.2.5", "3.3.0", "3.3.6", "3.4.1"]
+            .iter()
+            .map(|v| semver::Version::parse(v).unwrap())
+            .filter(|v| filter.matches(v))
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_only_filter_matches_prefix() {
+        assert_eq!(filtered("3.3"), vec!["3.3.0", "3.3.6"]);
+        assert_eq!(filtered("3.2.5"), vec!["3.2.5"]);
+        assert_eq!(filtered("3").len(), 5);
+        assert!(filtered("3.5").is_empty());
+    }
+
+    #[test]
+    fn test_only_filter_matches_requirement() {
+        assert_eq!(filtered(">=3.3"), vec!["3.3.0", "3.3.6", "3.4.1"]);
+        assert_eq!(filtered(">=3.2, <3.3"), vec!["3.2.5"]);
+        assert_eq!(filtered("~3.3.1"), vec!["3.3.6"]);
+    }
+
+    #[test]
+    fn test_only_filter_rejects_invalid_pattern() {
+        assert!(super::VersionFilter::parse(">=three").is_err());
+    }
+
+    #[test]
+    fn test_runtime_command_errors_when_filter_matches_nothing() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        sandbox
+            .add_ruby_dir("3.2.5")
+            .expect("Failed to create ruby-3.2.5");
+
+        let butler_runtime =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None)
+                .expect("Failed to create butler runtime");
+
+        assert!(
+            super::runtime_command(
+                &butler_runtime,
+                Some("3.4"),
+                None,
+                RuntimeSort::Version,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_err()
+        );
+        assert!(
+            super::runtime_command(
+                &butler_runtime,
+                Some("3.2"),
+                None,
+                RuntimeSort::Version,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_since_accepts_units() {
+        use std::time::Duration;
+
+        assert_eq!(
+            super::parse_since("30m").unwrap(),
+            Duration::from_secs(1800)
+        );
+        assert_eq!(
+            super::parse_since("12h").unwrap(),
+            Duration::from_secs(43200)
+        );
+        assert_eq!(
+            super::parse_since("7d").unwrap(),
+            Duration::from_secs(604800)
+        );
+        assert_eq!(
+            super::parse_since("2w").unwrap(),
+            Duration::from_secs(1209600)
+        );
+        for invalid in ["", "d", "7", "7y", "-1d", "7é", "seven days"] {
+            assert!(
+                super::parse_since(invalid).is_err(),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_since_selects_recently_installed_rubies() {
+        use std::time::{Duration, SystemTime};
+
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        let fresh = sandbox.add_ruby_dir("3.4.1").unwrap();
+        let stale = sandbox.add_ruby_dir("3.2.5").unwrap();
+
+        let month_ago = SystemTime::now() - Duration::from_secs(30 * 24 * 60 * 60);
+        std::fs::File::open(&stale)
+            .and_then(|dir| dir.set_modified(month_ago))
+            .expect("Failed to age installation directory");
+
+        let butler_runtime =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None)
+                .expect("Failed to create butler runtime");
+        let window = super::parse_since("7d").unwrap();
+        let now = SystemTime::now();
+        let recent: Vec<String> = butler_runtime
+            .ruby_installations()
+            .iter()
+            .filter(|ruby| super::installed_within(ruby, window, now))
+            .map(|ruby| ruby.root.display().to_string())
+            .collect();
+
+        assert_eq!(recent, vec![fresh.display().to_string()]);
+        assert!(
+            super::runtime_command(
+                &butler_runtime,
+                None,
+                Some("7d"),
+                RuntimeSort::Version,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_ok()
+        );
+        assert!(
+            super::runtime_command(
+                &butler_runtime,
+                Some("3.2"),
+                Some("7d"),
+                RuntimeSort::Version,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_err()
+        );
+    }
+
+    fn sorted_dirs(
+        butler_runtime: &ButlerRuntime,
+        sort: RuntimeSort,
+        reverse: bool,
+    ) -> Vec<String> {
+        let mut rubies: Vec<_> = butler_runtime.ruby_installations().iter().collect();
+        super::sort_rubies(&mut rubies, sort, reverse);
+        rubies
+            .iter()
+            .map(|ruby| {
+                ruby.root
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_by_version_and_name() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        for version in ["3.2.5", "3.10.0", "2.7.8", "3.9.0"] {
+            sandbox.add_ruby_dir(version).unwrap();
+        }
+        let butler_runtime =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None)
+                .expect("Failed to create butler runtime");
+
+        assert_eq!(
+            sorted_dirs(&butler_runtime, RuntimeSort::Version, false),
+            vec!["ruby-3.10.0", "ruby-3.9.0", "ruby-3.2.5", "ruby-2.7.8"]
+        );
+        assert_eq!(
+            sorted_dirs(&butler_runtime, RuntimeSort::Version, true),
+            vec!["ruby-2.7.8", "ruby-3.2.5", "ruby-3.9.0", "ruby-3.10.0"]
+        );
+        assert_eq!(
+            sorted_dirs(&butler_runtime, RuntimeSort::Name, false),
+            vec!["ruby-2.7.8", "ruby-3.10.0", "ruby-3.2.5", "ruby-3.9.0"]
+        );
+        assert_eq!(
+            sorted_dirs(&butler_runtime, RuntimeSort::Name, true),
+            vec!["ruby-3.9.0", "ruby-3.2.5", "ruby-3.10.0", "ruby-2.7.8"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sort_by_installation_time() {
+        use std::time::{Duration, SystemTime};
+
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        let now = SystemTime::now();
+        for (version, days_ago) in [("3.4.1", 20), ("3.2.5", 1), ("3.3.6", 10)] {
+            let dir = sandbox.add_ruby_dir(version).unwrap();
+            std::fs::File::open(&dir)
+                .and_then(|dir| dir.set_modified(now - Duration::from_secs(days_ago * 86400)))
+                .expect("Failed to age installation directory");
+        }
+        let butler_runtime =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None)
+                .expect("Failed to create butler runtime");
+
+        assert_eq!(
+            sorted_dirs(&butler_runtime, RuntimeSort::Mtime, false),
+            vec!["ruby-3.2.5", "ruby-3.3.6", "ruby-3.4.1"]
+        );
+        assert_eq!(
+            sorted_dirs(&butler_runtime, RuntimeSort::Mtime, true),
+            vec!["ruby-3.4.1", "ruby-3.3.6", "ruby-3.2.5"]
+        );
+    }
+
+    #[test]
+    fn test_runtime_command_lists_in_requested_order() {
+        let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+        sandbox.add_ruby_dir("3.2.5").unwrap();
+        sandbox.add_ruby_dir("3.4.1").unwrap();
+        let butler_runtime =
+            ButlerRuntime::discover_and_compose(sandbox.root().to_path_buf(), None)
+                .expect("Failed to create butler runtime");
+
+        let position = |reverse: bool, version: &str| {
+            let mut out = CapturedOutput::new();
+            super::runtime_command(
+                &butler_runtime,
+                None,
+                None,
+                RuntimeSort::Version,
+                reverse,
+                &mut out,
+            )
+            .expect("Survey should succeed");
+            out.lines()
+                .iter()
+                .position(|line| line.contains(version))
+                .unwrap()
+        };
+
+        assert!(position(false, "(3.4.1)") < position(false, "(3.2.5)"));
+        assert!(position(true, "(3.2.5)") < position(true, "(3.4.1)"));
     }
 }
@@ -1,17 +1,31 @@
+pub mod completions_refresh;
+pub mod deps;
+pub mod doctor;
 pub mod exec;
+pub mod gem_clean;
 pub mod help;
 pub mod info;
 pub mod new;
+pub mod pin;
+pub mod platform;
 pub mod run;
 pub mod shell_integration;
+pub mod subshell;
 pub mod sync;
 pub mod version;
 
-pub use exec::exec_command;
+pub use completions_refresh::completions_refresh_command;
+pub use deps::deps_command;
+pub use doctor::doctor_command;
+pub use exec::{ExecOptions, exec_command};
+pub use gem_clean::gem_clean_command;
 pub use help::help_command;
 pub use info::info_command;
-pub use new::init_command as new_command;
-pub use run::run_command;
+pub use new::{check_command as check_project_command, init_command as new_command};
+pub use pin::{pin_command, unpin_command};
+pub use platform::platform_command;
+pub use run::{RunOptions, resolve_script_reference, run_command};
 pub use shell_integration::shell_integration_command;
-pub use sync::sync_command;
+pub use subshell::subshell_command;
+pub use sync::{SyncOptions, sync_command};
 pub use version::version_command;
@@ -1,30 +1,83 @@
-use rb_core::project::create_default_project;
+use rb_core::project::{ProjectRuntime, RbprojectDetector, project_template, write_project_file};
 use std::path::Path;
 
-/// Initialize a new rbproject.toml in the current directory
-pub fn init_command(current_dir: &Path) -> Result<(), String> {
-    create_default_project(current_dir)?;
-
-    println!("✨ Splendid! A new rbproject.toml has been created with appropriate ceremony.");
-    println!();
-    println!("📝 This template includes:");
-    println!("   • Project metadata (name and description)");
-    println!("   • A sample script (ruby-version) to demonstrate usage");
-    println!();
-    println!("🎯 You may now:");
-    println!("   • Edit rbproject.toml to add your own scripts");
-    println!("   • Run 'rb run' to list available scripts");
-    println!("   • Execute scripts with: rb run <script-name>");
-    println!();
-    println!("For comprehensive examples, please consult:");
-    println!("   https://github.com/RubyElders/ruby-butler/blob/main/examples/rbproject.toml");
+use crate::output::Output;
+
+/// Initialize a new project file (rbproject.toml by default) in the current directory
+///
+/// With `dry_run` the template is printed as-is and nothing touches the disk; `force`
+/// replaces an existing file of the same name instead of declining.
+pub fn init_command(
+    current_dir: &Path,
+    filename: &str,
+    dry_run: bool,
+    force: bool,
+    out: &mut dyn Output,
+) -> Result<(), String> {
+    if dry_run {
+        print!("{}", project_template(filename)?);
+        return Ok(());
+    }
+
+    write_project_file(current_dir, filename, force)?;
+
+    out.line(&format!(
+        "✨ Splendid! A new {} has been created with appropriate ceremony.",
+        filename
+    ));
+    out.blank();
+    out.line("📝 This template includes:");
+    out.line("   • Project metadata (name and description)");
+    out.line("   • A sample script (ruby-version) to demonstrate usage");
+    out.blank();
+    out.line("🎯 You may now:");
+    out.line(&format!("   • Edit {} to add your own scripts", filename));
+    out.line("   • Run 'rb run' to list available scripts");
+    out.line("   • Execute scripts with: rb run <script-name>");
+    out.blank();
+    out.line("For comprehensive examples, please consult:");
+    out.line("   https://github.com/RubyElders/ruby-butler/blob/main/examples/rbproject.toml");
 
     Ok(())
 }
 
+/// Verify that the current directory has a project file that parses, changing nothing
+///
+/// Looks for the project file names in their usual precedence, so the file checked is the
+/// one `rb run` would load from this directory.
+pub fn check_command(current_dir: &Path, out: &mut dyn Output) -> Result<(), String> {
+    let project_path = RbprojectDetector::PROJECT_FILENAMES
+        .iter()
+        .map(|filename| current_dir.join(filename))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            format!(
+                "No project file found in {} (looked for {}).\n\nRun 'rb new' to create one.",
+                current_dir.display(),
+                RbprojectDetector::PROJECT_FILENAMES.join(", ")
+            )
+        })?;
+
+    let project = ProjectRuntime::from_file(&project_path).map_err(|e| {
+        format!(
+            "{} is not a valid project file: {}",
+            project_path.display(),
+            e
+        )
+    })?;
+
+    out.success(&format!(
+        "{} is present and valid ({} script(s))",
+        project_path.display(),
+        project.scripts.len()
+    ));
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::CapturedOutput;
     use std::fs;
 
     #[test]
@@ -32,7 +85,13 @@ mod tests {
         let temp_dir = std::env::temp_dir().join(format!("rb-init-test-{}", std::process::id()));
         fs::create_dir_all(&temp_dir).unwrap();
 
-        let result = init_command(&temp_dir);
+        let result = init_command(
+            &temp_dir,
+            "rbproject.toml",
+            false,
+            false,
+            &mut CapturedOutput::new(),
+        );
 
         assert!(result.is_ok());
         let project_file = temp_dir.join("rbproject.toml");
@@ -58,7 +117,13 @@ mod tests {
         // Create existing file
         fs::write(&project_file, "existing content").unwrap();
 
-        let result = init_command(&temp_dir);
+        let result = init_command(
+            &temp_dir,
+            "rbproject.toml",
+            false,
+            false,
+            &mut CapturedOutput::new(),
+        );
         assert!(result.is_err());
         let error = result.unwrap_err();
         assert!(error.contains("already graces this directory"));
@@ -73,7 +138,13 @@ mod tests {
             std::env::temp_dir().join(format!("rb-init-test-valid-{}", std::process::id()));
         fs::create_dir_all(&temp_dir).unwrap();
 
-        let result = init_command(&temp_dir);
+        let result = init_command(
+            &temp_dir,
+            "rbproject.toml",
+            false,
+            false,
+            &mut CapturedOutput::new(),
+        );
 
         assert!(result.is_ok());
         let project_file = temp_dir.join("rbproject.toml");
@@ -86,4 +157,131 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_init_creates_chosen_gem_toml() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let result = init_command(
+            temp_dir.path(),
+            "gem.toml",
+            false,
+            false,
+            &mut CapturedOutput::new(),
+        );
+
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join("gem.toml").exists());
+        assert!(!temp_dir.path().join("rbproject.toml").exists());
+    }
+
+    #[test]
+    fn test_init_dry_run_writes_nothing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        assert!(
+            init_command(
+                temp_dir.path(),
+                "rbproject.kdl",
+                true,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_ok()
+        );
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        // An existing file is left untouched as well
+        let project_file = temp_dir.path().join("rbproject.kdl");
+        fs::write(&project_file, "existing content").unwrap();
+        assert!(
+            init_command(
+                temp_dir.path(),
+                "rbproject.kdl",
+                true,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_ok()
+        );
+        assert_eq!(
+            fs::read_to_string(&project_file).unwrap(),
+            "existing content"
+        );
+    }
+
+    #[test]
+    fn test_init_force_overwrites_existing_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_file = temp_dir.path().join("rbproject.toml");
+        fs::write(&project_file, "existing content").unwrap();
+
+        assert!(
+            init_command(
+                temp_dir.path(),
+                "rbproject.toml",
+                false,
+                false,
+                &mut CapturedOutput::new()
+            )
+            .is_err()
+        );
+        assert_eq!(
+            fs::read_to_string(&project_file).unwrap(),
+            "existing content"
+        );
+
+        assert!(
+            init_command(
+                temp_dir.path(),
+                "rbproject.toml",
+                false,
+                true,
+                &mut CapturedOutput::new()
+            )
+            .is_ok()
+        );
+        let content = fs::read_to_string(&project_file).unwrap();
+        assert!(content.contains("[scripts]"));
+    }
+
+    #[test]
+    fn test_check_accepts_valid_project_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        init_command(
+            temp_dir.path(),
+            "rbproject.toml",
+            false,
+            false,
+            &mut CapturedOutput::new(),
+        )
+        .unwrap();
+        let before = fs::read_to_string(temp_dir.path().join("rbproject.toml")).unwrap();
+
+        assert!(check_command(temp_dir.path(), &mut CapturedOutput::new()).is_ok());
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("rbproject.toml")).unwrap(),
+            before
+        );
+    }
+
+    #[test]
+    fn test_check_reports_missing_project_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let error = check_command(temp_dir.path(), &mut CapturedOutput::new()).unwrap_err();
+
+        assert!(error.contains("No project file found"));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_check_reports_invalid_project_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("gem.toml"), "[scripts\ntest = ").unwrap();
+
+        let error = check_command(temp_dir.path(), &mut CapturedOutput::new()).unwrap_err();
+
+        assert!(error.contains("gem.toml is not a valid project file"));
+    }
 }
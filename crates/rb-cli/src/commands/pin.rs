@@ -0,0 +1,73 @@
+use crate::output::Output;
+use colored::*;
+use rb_core::butler::ButlerError;
+use rb_core::ruby::PinFileDetector;
+use std::path::Path;
+
+/// Pin command - makes `version` the Ruby for this project, ahead of any detection
+pub fn pin_command(
+    project_dir: &Path,
+    version: &str,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    let pin_file = PinFileDetector::write(project_dir, version).map_err(|e| {
+        ButlerError::General(format!("Unable to pin Ruby {}: {}", version.trim(), e))
+    })?;
+
+    out.success(&format!(
+        "Ruby {} pinned for this project in {}",
+        version.trim().cyan(),
+        pin_file.display()
+    ));
+    Ok(())
+}
+
+/// Unpin command - hands Ruby selection back to detection
+pub fn unpin_command(project_dir: &Path, out: &mut dyn Output) -> Result<(), ButlerError> {
+    let removed = PinFileDetector::remove(project_dir)
+        .map_err(|e| ButlerError::General(format!("Unable to remove the Ruby pin: {}", e)))?;
+
+    if removed {
+        out.success("Ruby pin removed; selection follows the project's version files again");
+    } else {
+        out.line("No Ruby pin is set for this project");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::CapturedOutput;
+
+    #[test]
+    fn test_pin_then_unpin() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut out = CapturedOutput::new();
+
+        pin_command(temp_dir.path(), "3.2.5", &mut out).unwrap();
+        assert!(out.contains("3.2.5"));
+        assert_eq!(
+            std::fs::read_to_string(PinFileDetector::path(temp_dir.path())).unwrap(),
+            "3.2.5\n"
+        );
+
+        unpin_command(temp_dir.path(), &mut out).unwrap();
+        assert!(out.contains("Ruby pin removed"));
+        assert!(!PinFileDetector::path(temp_dir.path()).exists());
+
+        unpin_command(temp_dir.path(), &mut out).unwrap();
+        assert!(out.contains("No Ruby pin is set"));
+    }
+
+    #[test]
+    fn test_pin_rejects_invalid_version() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut out = CapturedOutput::new();
+
+        let result = pin_command(temp_dir.path(), "three", &mut out);
+
+        assert!(result.is_err());
+        assert!(out.lines().is_empty());
+    }
+}
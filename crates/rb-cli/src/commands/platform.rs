@@ -0,0 +1,67 @@
+use colored::*;
+use log::{debug, info};
+use rb_core::butler::{ButlerError, ButlerRuntime};
+
+use crate::PlatformCommands;
+use crate::output::Output;
+
+/// Platform command - manages the platforms recorded in Gemfile.lock
+pub fn platform_command(
+    butler_runtime: &ButlerRuntime,
+    command: &PlatformCommands,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    let bundler_runtime = butler_runtime.bundler_runtime().ok_or_else(|| {
+        ButlerError::General(
+            "Bundler environment not detected.\n\nNo Gemfile found in the current directory or its ancestors.\nPlatform management requires a bundler-managed project.".to_string(),
+        )
+    })?;
+
+    match command {
+        PlatformCommands::List => {
+            info!(
+                "Listing platforms locked in {}",
+                bundler_runtime.lockfile_path().display()
+            );
+
+            let platforms = bundler_runtime.locked_platforms().map_err(|e| {
+                ButlerError::General(format!(
+                    "Unable to read {}: {}\n\nRun 'rb sync' to create the lockfile first.",
+                    bundler_runtime.lockfile_path().display(),
+                    e
+                ))
+            })?;
+
+            out.line(&"🖥️  Locked Platforms".green().bold().to_string());
+            out.blank();
+            if platforms.is_empty() {
+                out.line(&format!("  {}", "No platforms recorded.".bright_black()));
+            }
+            for platform in platforms {
+                out.line(&format!("  {}", platform.cyan()));
+            }
+        }
+        PlatformCommands::Add { platform } => {
+            debug!("Adding platform {}", platform);
+            bundler_runtime
+                .add_platform(butler_runtime, platform)
+                .map_err(|e| ButlerError::BundlerFailed(e.to_string()))?;
+            out.success(&format!(
+                "Platform {} added to Gemfile.lock",
+                platform.cyan().bold()
+            ));
+        }
+        PlatformCommands::Remove { platform } => {
+            debug!("Removing platform {}", platform);
+            bundler_runtime
+                .remove_platform(butler_runtime, platform)
+                .map_err(|e| ButlerError::BundlerFailed(e.to_string()))?;
+            out.success(&format!(
+                "Platform {} removed from Gemfile.lock",
+                platform.cyan().bold()
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -2,103 +2,226 @@ use colored::*;
 use log::{debug, info, warn};
 use rb_core::butler::{ButlerError, ButlerRuntime};
 use rb_core::project::{ProjectRuntime, RbprojectDetector};
+use std::io::{BufRead, BufReader, Read};
 use std::path::PathBuf;
+use std::process::{Child, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
-use super::exec::exec_command;
+use super::exec::{ExecOptions, exit_with_status, measured, prepare_program, run_program};
+use crate::command_log::{self, CommandLogEntry};
+use crate::interrupt;
+use crate::output::{Output, StderrOutput, StdoutOutput};
+use crate::run_summary::{self, RunStep, SUMMARY_ENV};
+use crate::watch::{WatchFilter, watch_and_rerun};
 
-fn list_available_scripts(
-    butler_runtime: ButlerRuntime,
-    project_file: Option<PathBuf>,
-) -> Result<(), ButlerError> {
-    info!("Listing available project scripts");
+/// Options refining how `rb run` treats a script
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    /// Extra variables applied on top of the composed environment
+    pub env: Vec<(String, String)>,
+    /// Show the script dependency tree instead of running
+    pub graph: bool,
+    /// Rerun the script whenever project files change
+    pub watch: bool,
+    /// Fall back to `[tool.rb.scripts]` in a shared file when no project file exists
+    pub shared_scripts: bool,
+    /// Create a missing gem home before running the script
+    pub create_gem_home: bool,
+    /// Prefix each line the script prints with `[script]`
+    pub prefix: bool,
+    /// JSON-lines file recording each execution
+    pub command_log: Option<PathBuf>,
+    /// Print the wall-clock duration of each script run to stderr
+    pub measure: bool,
+    /// Run every script of a batch even after one fails, reporting the failures at the end
+    pub keep_going: bool,
+}
 
-    let current_dir = butler_runtime.current_dir();
-    let project_runtime = if let Some(path) = project_file {
+/// Variable telling scripts started by a prefixed run to prefix their own output too
+const PREFIX_ENV: &str = "RB_RUN_PREFIX";
+
+/// Load the project configuration from an explicit path or by autodetection
+pub(crate) fn load_project(
+    butler_runtime: &ButlerRuntime,
+    project_file: Option<PathBuf>,
+    shared_scripts: bool,
+) -> Result<Option<ProjectRuntime>, ButlerError> {
+    if let Some(path) = project_file {
         debug!(
             "Loading project config from specified path: {}",
             path.display()
         );
-        match ProjectRuntime::from_file(&path) {
-            Ok(project) => Some(project),
-            Err(e) => {
-                return Err(ButlerError::General(format!(
-                    "The specified project configuration could not be loaded from {}:\n{}",
-                    path.display(),
-                    e
-                )));
-            }
-        }
+        return match ProjectRuntime::from_file(&path) {
+            Ok(project) => Ok(Some(project)),
+            Err(e) => Err(ButlerError::General(format!(
+                "The specified project configuration could not be loaded from {}:\n{}",
+                path.display(),
+                e
+            ))),
+        };
+    }
+
+    // Auto-detect project file
+    let discovered = if shared_scripts {
+        RbprojectDetector::discover_with_shared_scripts(butler_runtime.current_dir())
     } else {
-        // Auto-detect project file
-        match RbprojectDetector::discover(current_dir) {
-            Ok(Some(project)) => {
-                debug!(
-                    "Discovered {} with {} scripts",
-                    project.config_filename,
-                    project.scripts.len()
-                );
-                Some(project)
-            }
-            Ok(None) => None,
-            Err(e) => {
-                warn!("Error detecting project config: {}", e);
-                None
-            }
+        RbprojectDetector::discover(butler_runtime.current_dir())
+    };
+    match discovered {
+        Ok(Some(project)) => {
+            debug!(
+                "Discovered {} with {} scripts",
+                project.config_filename,
+                project.scripts.len()
+            );
+            Ok(Some(project))
+        }
+        Ok(None) => Ok(None),
+        Err(e) => {
+            warn!("Error detecting project config: {}", e);
+            Ok(None)
         }
+    }
+}
+
+/// Load the project configuration, explaining how to create one when none is found
+fn require_project(
+    butler_runtime: &ButlerRuntime,
+    project_file: Option<PathBuf>,
+    shared_scripts: bool,
+    script_name: &str,
+) -> Result<ProjectRuntime, ButlerError> {
+    load_project(butler_runtime, project_file, shared_scripts)?.ok_or_else(|| {
+        ButlerError::General(format!(
+            "No project configuration detected in the current directory hierarchy.\n\nTo use project scripts, create one of these files (in priority order): rbproject.kdl, rbproject.toml, gem.kdl, gem.toml\n\nOr specify a custom location with: rb -P path/to/rbproject.toml run {}",
+            script_name
+        ))
+    })
+}
+
+/// The program arguments running `script_name`, with trailing `args` placed as it asks
+fn script_args(
+    project: &ProjectRuntime,
+    script_name: &str,
+    args: Vec<String>,
+) -> Result<Vec<String>, ButlerError> {
+    if !project.has_script(script_name) {
+        return Err(ButlerError::General(format!(
+            "The script '{}' is not defined in your project configuration",
+            script_name
+        )));
+    }
+
+    if project.is_script_disabled(script_name) {
+        return Err(ButlerError::General(format!(
+            "The script '{}' is disabled in your project configuration",
+            script_name
+        )));
+    }
+
+    let command_str = project.get_script_invocation(script_name).unwrap();
+    info!("Executing script: {} → {}", script_name, command_str);
+
+    let command_parts = parse_command(&command_str);
+
+    if command_parts.is_empty() {
+        return Err(ButlerError::General(format!(
+            "The script '{}' has an empty command",
+            script_name
+        )));
+    }
+
+    Ok(substitute_args(command_parts, args))
+}
+
+/// Replace a leading `@script` in `rb exec` arguments with that project script's command
+///
+/// The remaining arguments are passed to the script as `rb run` would pass them.
+pub fn resolve_script_reference(
+    butler_runtime: &ButlerRuntime,
+    program_args: Vec<String>,
+    project_file: Option<PathBuf>,
+    shared_scripts: bool,
+) -> Result<Vec<String>, ButlerError> {
+    let Some(script_name) = program_args
+        .first()
+        .and_then(|first| first.strip_prefix('@'))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+    else {
+        return Ok(program_args);
     };
 
-    let project = match project_runtime {
+    debug!("Resolving '@{}' to its project script", script_name);
+    let project = require_project(butler_runtime, project_file, shared_scripts, &script_name)?;
+    script_args(&project, &script_name, program_args[1..].to_vec())
+}
+
+fn list_available_scripts(
+    butler_runtime: ButlerRuntime,
+    project_file: Option<PathBuf>,
+    shared_scripts: bool,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    info!("Listing available project scripts");
+
+    let project = match load_project(&butler_runtime, project_file, shared_scripts)? {
         Some(p) => p,
         None => {
             return Err(ButlerError::General(
-                "No project configuration detected in the current directory hierarchy.\n\nTo define project scripts, create one of these files (in priority order):\n  gem.kdl, gem.toml, rbproject.kdl, rbproject.toml\n\nOr specify a custom location: rb -P path/to/gem.kdl run".to_string()
+                "No project configuration detected in the current directory hierarchy.\n\nTo define project scripts, create one of these files (in priority order):\n  rbproject.kdl, rbproject.toml, gem.kdl, gem.toml\n\nOr specify a custom location: rb -P path/to/gem.kdl run".to_string()
             ));
         }
     };
 
-    println!("{}", "🎯 Run Project Scripts".green().bold());
-    println!();
+    out.line(&"🎯 Run Project Scripts".green().bold().to_string());
+    out.blank();
 
     if let Some(name) = &project.metadata.name {
-        println!("{}", name);
+        out.line(&name.to_string());
     }
 
     if let Some(description) = &project.metadata.description {
-        println!("{}", description.bright_black());
+        out.line(&description.bright_black().to_string());
     }
 
     if project.metadata.name.is_some() || project.metadata.description.is_some() {
-        println!();
+        out.blank();
     }
 
     // Usage section
-    println!("{}", "Usage:".green().bold());
-    println!("  rb run <SCRIPT> [ARGS]...");
-    println!();
+    out.line(&"Usage:".green().bold().to_string());
+    out.line("  rb run <SCRIPT> [ARGS]...");
+    out.blank();
 
     let available_scripts = project.script_names();
     let script_count = available_scripts.len();
 
     if available_scripts.is_empty() {
-        println!("{}", "Scripts:".green().bold());
-        println!("  {}", "No scripts defined.".bright_black());
-        println!();
-        println!(
+        out.line(&"Scripts:".green().bold().to_string());
+        out.line(&format!("  {}", "No scripts defined.".bright_black()));
+        out.blank();
+        out.line(&format!(
             "To define scripts, add them to {}:",
             project.config_filename.cyan()
-        );
-        println!();
-        println!("  {}", "[scripts]".bright_black());
-        println!("  {} = {}", "test".cyan(), "\"rspec\"".bright_black());
-        println!(
+        ));
+        out.blank();
+        out.line(&format!("  {}", "[scripts]".bright_black()));
+        out.line(&format!(
+            "  {} = {}",
+            "test".cyan(),
+            "\"rspec\"".bright_black()
+        ));
+        out.line(&format!(
             "  {} = {{ command = {}, description = {} }}",
             "lint".cyan(),
             "\"rubocop\"".bright_black(),
             "\"Check code quality\"".bright_black()
-        );
+        ));
     } else {
         // Scripts section - formatted like Clap's Commands section
-        println!("{}", "Scripts:".green().bold());
+        out.line(&"Scripts:".green().bold().to_string());
 
         let max_name_width = available_scripts.iter().map(|s| s.len()).max().unwrap_or(0);
 
@@ -108,26 +231,26 @@ fn list_available_scripts(
 
             if let Some(description) = script.description() {
                 // Show: name  description
-                println!(
+                out.line(&format!(
                     "  {:<width$}  {}",
                     name.cyan().bold(),
                     description.bright_black(),
                     width = max_name_width
-                );
+                ));
             } else {
                 // Show: name  command
-                println!(
+                out.line(&format!(
                     "  {:<width$}  {}",
                     name.cyan().bold(),
                     command.bright_black(),
                     width = max_name_width
-                );
+                ));
             }
         }
 
-        println!();
-        println!("{}", "Details:".green().bold());
-        println!(
+        out.blank();
+        out.line(&"Details:".green().bold().to_string());
+        out.line(&format!(
             "  {}: {}",
             "Project".bright_black(),
             project
@@ -135,12 +258,91 @@ fn list_available_scripts(
                 .display()
                 .to_string()
                 .bright_black()
-        );
-        println!(
+        ));
+        out.line(&format!(
             "  {}: {}",
             "Scripts".bright_black(),
             script_count.to_string().bright_black()
-        );
+        ));
+    }
+
+    Ok(())
+}
+
+/// Render the script call tree as indented lines, two spaces per level
+///
+/// Roots are scripts no other script invokes; when every script takes part in a
+/// cycle, all scripts are shown as roots. Repeated visits along a path are marked.
+fn render_script_graph(project: &ProjectRuntime) -> Vec<String> {
+    fn visit<'a>(
+        project: &'a ProjectRuntime,
+        name: &'a str,
+        depth: usize,
+        path: &mut Vec<&'a str>,
+        lines: &mut Vec<String>,
+    ) {
+        let indent = "  ".repeat(depth);
+        if path.contains(&name) {
+            lines.push(format!("{}{} (cycle)", indent, name));
+            return;
+        }
+
+        lines.push(format!("{}{}", indent, name));
+        path.push(name);
+        for child in project.script_references(name) {
+            visit(project, child, depth + 1, path, lines);
+        }
+        path.pop();
+    }
+
+    let names = project.script_names();
+    let referenced: Vec<&str> = names
+        .iter()
+        .flat_map(|name| project.script_references(name))
+        .collect();
+
+    let mut roots: Vec<&str> = names
+        .iter()
+        .copied()
+        .filter(|name| !referenced.contains(name))
+        .collect();
+    if roots.is_empty() {
+        roots = names;
+    }
+
+    let mut lines = Vec::new();
+    for root in roots {
+        visit(project, root, 0, &mut Vec::new(), &mut lines);
+    }
+    lines
+}
+
+fn show_script_graph(
+    butler_runtime: ButlerRuntime,
+    project_file: Option<PathBuf>,
+    shared_scripts: bool,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    info!("Tracing project script dependencies");
+
+    let project = match load_project(&butler_runtime, project_file, shared_scripts)? {
+        Some(p) => p,
+        None => {
+            return Err(ButlerError::General(
+                "No project configuration detected in the current directory hierarchy.\n\nTo define project scripts, create one of these files (in priority order):\n  rbproject.kdl, rbproject.toml, gem.kdl, gem.toml\n\nOr specify a custom location: rb -P path/to/gem.kdl run --graph".to_string()
+            ));
+        }
+    };
+
+    out.line(&"🎯 Script Dependency Graph".green().bold().to_string());
+    out.blank();
+
+    let lines = render_script_graph(&project);
+    if lines.is_empty() {
+        out.line(&format!("  {}", "No scripts defined.".bright_black()));
+    }
+    for line in lines {
+        out.line(&format!("  {}", line.cyan()));
     }
 
     Ok(())
@@ -151,9 +353,24 @@ pub fn run_command(
     script_name: Option<String>,
     args: Vec<String>,
     project_file: Option<PathBuf>,
+    options: &RunOptions,
 ) -> Result<(), ButlerError> {
+    if options.graph {
+        return show_script_graph(
+            butler_runtime,
+            project_file,
+            options.shared_scripts,
+            &mut StdoutOutput,
+        );
+    }
+
     if script_name.is_none() {
-        return list_available_scripts(butler_runtime, project_file);
+        return list_available_scripts(
+            butler_runtime,
+            project_file,
+            options.shared_scripts,
+            &mut StdoutOutput,
+        );
     }
 
     let script_name = script_name.unwrap();
@@ -162,108 +379,511 @@ pub fn run_command(
         script_name
     );
 
-    let current_dir = butler_runtime.current_dir();
-    let project_runtime = if let Some(path) = project_file {
-        debug!(
-            "Loading project config from specified path: {}",
-            path.display()
+    let project = require_project(
+        &butler_runtime,
+        project_file,
+        options.shared_scripts,
+        &script_name,
+    )?;
+    let (script_names, args) = batch_scripts(&project, script_name, args)?;
+    if script_names.len() > 1 && options.watch {
+        return Err(ButlerError::General(
+            "--watch reruns a single script; name just one script to watch".to_string(),
+        ));
+    }
+
+    // Resolve every script up front, so a typo fails before anything runs
+    let mut batch = Vec::with_capacity(script_names.len());
+    let mut args = Some(args);
+    for name in script_names {
+        let full_args = script_args(&project, &name, args.take().unwrap_or_default())?;
+        let timeout = project
+            .get_script(&name)
+            .and_then(|script| script.timeout());
+        batch.push((name, full_args, timeout));
+    }
+
+    info!("Delegating to exec command for {} script(s)", batch.len());
+
+    // Delegate to exec for consistent behavior (auto bundle exec, env composition)
+    let mut exec_options = ExecOptions {
+        env: options.env.clone(),
+        create_gem_home: options.create_gem_home,
+        measure: options.measure,
+        ..ExecOptions::default()
+    };
+    if options.prefix {
+        // Scripts invoking `rb run` then label their lines within ours
+        exec_options
+            .env
+            .push((PREFIX_ENV.to_string(), "true".to_string()));
+    }
+
+    if options.watch {
+        let (script_name, full_args, timeout) = &batch[0];
+        log_script(&butler_runtime, script_name, full_args, options);
+        return watch_script(
+            &butler_runtime,
+            &project,
+            script_name,
+            full_args,
+            &exec_options,
+            *timeout,
+            options.prefix,
         );
-        match ProjectRuntime::from_file(&path) {
-            Ok(project) => Some(project),
-            Err(e) => {
-                return Err(ButlerError::General(format!(
-                    "The specified project configuration could not be loaded from {}:\n{}",
-                    path.display(),
-                    e
-                )));
-            }
+    }
+
+    // The outermost run collects the steps of nested `rb run` calls for its summary
+    let outer_summary = std::env::var_os(SUMMARY_ENV).map(PathBuf::from);
+    let own_summary = match outer_summary {
+        Some(_) => None,
+        None => Some(run_summary::create_summary().map_err(|e| {
+            ButlerError::General(format!("Unable to create the run summary file: {}", e))
+        })?),
+    };
+    if let Some(summary) = &own_summary {
+        exec_options.env.push((
+            SUMMARY_ENV.to_string(),
+            summary.path().display().to_string(),
+        ));
+    }
+
+    let mut out = StdoutOutput;
+    let mut steps = Vec::new();
+    let mut failed = Vec::new();
+    let mut outcome = None;
+    for (script_name, full_args, timeout) in &batch {
+        log_script(&butler_runtime, script_name, full_args, options);
+        let result = measured(options.measure, || {
+            run_script(
+                &butler_runtime,
+                script_name,
+                full_args,
+                &exec_options,
+                *timeout,
+                options.prefix.then_some(&mut out as &mut dyn Output),
+            )
+        });
+
+        let step = RunStep::new(
+            script_name.as_str(),
+            result.as_ref().ok().and_then(|status| status.code()),
+        );
+        if let Some(path) = &outer_summary {
+            run_summary::record_or_warn(path, &step);
+        } else if let Some(summary) = &own_summary {
+            steps.extend(run_summary::take(summary.path()));
+            steps.push(step.clone());
         }
-    } else {
-        match RbprojectDetector::discover(current_dir) {
-            Ok(Some(project)) => {
-                debug!(
-                    "Discovered {} with {} scripts",
-                    project.config_filename,
-                    project.scripts.len()
-                );
-                Some(project)
+
+        // The first failure decides the exit status; otherwise the last script does
+        if step.passed() {
+            if failed.is_empty() {
+                outcome = Some(result);
             }
-            Ok(None) => None,
-            Err(e) => {
-                warn!("Error detecting project config: {}", e);
-                None
+        } else {
+            failed.push(script_name.as_str());
+            if failed.len() == 1 {
+                outcome = Some(result);
+            }
+            if !options.keep_going {
+                break;
             }
         }
-    };
+    }
 
-    let project = match project_runtime {
-        Some(p) => p,
-        None => {
-            return Err(ButlerError::General(format!(
-                "No project configuration detected in the current directory hierarchy.\n\nTo use project scripts, create one of these files: rbproject.toml, rb.toml, rb.kdl, gem.toml, gem.kdl\n\nOr specify a custom location with: rb -P path/to/rb.toml run {}",
-                script_name
-            )));
-        }
-    };
+    // Exiting below skips destructors, so remove the summary file now
+    drop(own_summary);
+
+    // Reports go to stderr, keeping the scripts' piped stdout clean
+    let mut report = StderrOutput;
+    if steps.len() > 1 {
+        run_summary::present(&steps, &mut report);
+    }
+    if options.keep_going && batch.len() > 1 && !failed.is_empty() {
+        report.blank();
+        report.error(&format!(
+            "{} of {} scripts failed: {}",
+            failed.len(),
+            batch.len(),
+            failed.join(", ")
+        ));
+    }
+
+    match outcome {
+        Some(result) => exit_with_status(result?),
+        None => Ok(()),
+    }
+}
+
+/// Split `rb run a b c` into the scripts to run and the arguments for a single script
+///
+/// Arguments naming defined scripts directly after the first script join the batch;
+/// anything after them is passed to the script, which only works when there is one.
+fn batch_scripts(
+    project: &ProjectRuntime,
+    first: String,
+    args: Vec<String>,
+) -> Result<(Vec<String>, Vec<String>), ButlerError> {
+    let named = args
+        .iter()
+        .take_while(|arg| project.has_script(arg))
+        .count();
+    if named == 0 {
+        return Ok((vec![first], args));
+    }
 
-    if !project.has_script(&script_name) {
+    let mut scripts = vec![first];
+    let mut args = args.into_iter();
+    scripts.extend(args.by_ref().take(named));
+    let rest: Vec<String> = args.collect();
+    if !rest.is_empty() {
         return Err(ButlerError::General(format!(
-            "The script '{}' is not defined in your project configuration",
-            script_name
+            "Arguments ({}) can only be passed when running a single script, but {} were named: {}",
+            rest.join(" "),
+            scripts.len(),
+            scripts.join(", ")
         )));
     }
 
-    let command_str = project.get_script_command(&script_name).unwrap();
+    debug!("Running a batch of scripts: {:?}", scripts);
+    Ok((scripts, Vec::new()))
+}
 
-    info!("Executing script: {} → {}", script_name, command_str);
+/// Record a script execution in the command log, when one is configured
+fn log_script(
+    butler_runtime: &ButlerRuntime,
+    script_name: &str,
+    full_args: &[String],
+    options: &RunOptions,
+) {
+    command_log::record(
+        options.command_log.as_deref(),
+        &CommandLogEntry::new(
+            butler_runtime,
+            "run",
+            Some(script_name),
+            full_args,
+            &options.env,
+        ),
+    );
+}
 
-    let command_parts = parse_command(command_str);
+/// How often the monitoring thread checks whether a timed script has finished
+const TIMEOUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
-    if command_parts.is_empty() {
-        return Err(ButlerError::General(format!(
-            "The script '{}' has an empty command",
-            script_name
-        )));
+/// Run the script's program, terminating it when it outlives `timeout`
+///
+/// With a `prefixed` output handler, every line the script writes to stdout or
+/// stderr is relayed there as `[script] line` instead of reaching the terminal directly.
+fn run_script(
+    butler_runtime: &ButlerRuntime,
+    script_name: &str,
+    full_args: &[String],
+    exec_options: &ExecOptions,
+    timeout: Option<Duration>,
+    prefixed: Option<&mut dyn Output>,
+) -> Result<ExitStatus, ButlerError> {
+    if timeout.is_none() && prefixed.is_none() {
+        return run_program(butler_runtime, full_args, exec_options);
     }
 
-    let mut full_args = command_parts;
-    full_args.extend(args);
+    if let Some(timeout) = timeout {
+        debug!(
+            "Running '{}' with a timeout of {}s",
+            script_name,
+            timeout.as_secs()
+        );
+    }
+    let mut cmd = prepare_program(butler_runtime, full_args, exec_options)?;
+    if timeout.is_some() {
+        cmd.own_process_group();
+    }
+    if prefixed.is_some() {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+    let mut child = cmd.execute_with_validation(butler_runtime)?;
+    let lines = relay_lines(&mut child);
 
-    info!("Delegating to exec command with args: {:?}", full_args);
+    let waiter = std::thread::spawn(move || match timeout {
+        Some(timeout) => wait_with_timeout(child, timeout),
+        None => child.wait().map(Some),
+    });
 
-    // Delegate to exec_command for consistent behavior (auto bundle exec, env composition)
-    exec_command(butler_runtime, full_args)
+    if let Some(out) = prefixed {
+        for line in lines {
+            out.line(&format!("[{}] {}", script_name, line));
+        }
+    }
+
+    let waited = waiter
+        .join()
+        .unwrap_or_else(|_| Err(std::io::Error::other("waiting thread panicked")));
+    match waited {
+        Ok(Some(status)) => Ok(status),
+        Ok(None) => Err(ButlerError::General(format!(
+            "The script '{}' exceeded its timeout of {}s and was terminated",
+            script_name,
+            timeout.map_or(0, |timeout| timeout.as_secs())
+        ))),
+        Err(e) => Err(ButlerError::General(format!(
+            "Unable to wait for the script '{}': {}",
+            script_name, e
+        ))),
+    }
+}
+
+/// Collect the lines of the child's piped stdout and stderr, in the order they arrive
+///
+/// The receiver runs dry once both streams close; without piped streams it is empty.
+fn relay_lines(child: &mut Child) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    let streams = [
+        child
+            .stdout
+            .take()
+            .map(|stream| Box::new(stream) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|stream| Box::new(stream) as Box<dyn Read + Send>),
+    ];
+
+    for stream in streams.into_iter().flatten() {
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).split(b'\n') {
+                let Ok(line) = line else { break };
+                let line = String::from_utf8_lossy(&line);
+                if sender
+                    .send(line.trim_end_matches('\r').to_string())
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+    }
+    receiver
+}
+
+/// Wait for `child` on a monitoring thread, killing it once `timeout` passes
+///
+/// The child leads its own process group, so the kill reaches every process it
+/// started, and Ctrl-C (which the terminal no longer delivers to that group) is
+/// passed on to it. Returns the exit status, or `None` when the child had to be terminated.
+fn wait_with_timeout(mut child: Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let interrupts = interrupt::subscribe()
+        .inspect_err(|e| warn!("Unable to listen for Ctrl-C: {}", e))
+        .ok();
+    let monitor = std::thread::spawn(move || {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(Some(status));
+            }
+            if interrupts
+                .as_ref()
+                .is_some_and(|interrupts| interrupts.try_recv().is_ok())
+            {
+                forward_interrupt(&child);
+            }
+            if Instant::now() >= deadline {
+                warn!("Timeout of {}s reached, terminating", timeout.as_secs());
+                kill_process_tree(&mut child)?;
+                child.wait()?;
+                return Ok(None);
+            }
+            std::thread::sleep(TIMEOUT_POLL_INTERVAL);
+        }
+    });
+
+    monitor
+        .join()
+        .unwrap_or_else(|_| Err(std::io::Error::other("monitoring thread panicked")))
+}
+
+/// Send SIGINT to the child's process group, as the terminal would have
+#[cfg(unix)]
+fn forward_interrupt(child: &Child) {
+    if let Err(e) = signal_process_group(child, libc::SIGINT) {
+        debug!("Unable to pass Ctrl-C on to the script: {}", e);
+    }
+}
+
+/// The console already delivers Ctrl-C to every process attached to it
+#[cfg(windows)]
+fn forward_interrupt(_child: &Child) {}
+
+/// Kill the child together with everything in its process group
+#[cfg(unix)]
+fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+    signal_process_group(child, libc::SIGKILL)
+}
+
+/// Kill the child together with every process it started
+#[cfg(windows)]
+fn kill_process_tree(child: &mut Child) -> std::io::Result<()> {
+    let killed_tree = std::process::Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &child.id().to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if killed_tree { Ok(()) } else { child.kill() }
+}
+
+/// Deliver `signal` to every process in the group led by `child`
+#[cfg(unix)]
+fn signal_process_group(child: &Child, signal: libc::c_int) -> std::io::Result<()> {
+    // A negative pid addresses the whole process group led by that process
+    let group = -(child.id() as libc::pid_t);
+    // SAFETY: kill(2) only takes plain integers and touches no memory of ours
+    if unsafe { libc::kill(group, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+/// Run the script once, then again after every settled change below the project root
+fn watch_script(
+    butler_runtime: &ButlerRuntime,
+    project: &ProjectRuntime,
+    script_name: &str,
+    full_args: &[String],
+    exec_options: &ExecOptions,
+    timeout: Option<Duration>,
+    prefix: bool,
+) -> Result<(), ButlerError> {
+    let report = |status: std::process::ExitStatus| match status.code() {
+        Some(0) => StdoutOutput.success(&format!("'{}' succeeded", script_name)),
+        Some(code) => {
+            StdoutOutput.error(&format!("'{}' failed with exit code {}", script_name, code))
+        }
+        None => StdoutOutput.error(&format!("'{}' was terminated by a signal", script_name)),
+    };
+
+    let run = || {
+        let mut out = StdoutOutput;
+        measured(exec_options.measure, || {
+            run_script(
+                butler_runtime,
+                script_name,
+                full_args,
+                exec_options,
+                timeout,
+                prefix.then_some(&mut out as &mut dyn Output),
+            )
+        })
+    };
+    report(run()?);
+
+    let filter = WatchFilter::new(&project.root).excluding(butler_runtime.gem_dirs());
+    StdoutOutput.line(&format!(
+        "{} Watching {} for changes (Ctrl-C to stop)",
+        "👀".bright_blue(),
+        project.root.display().to_string().cyan()
+    ));
+
+    watch_and_rerun(&filter, || {
+        StdoutOutput.blank();
+        StdoutOutput.line(&format!(
+            "{} Change detected, rerunning '{}'",
+            "🔄".bright_blue(),
+            script_name.cyan()
+        ));
+        match run() {
+            Ok(status) => report(status),
+            Err(e) => warn!("Unable to rerun '{}': {}", script_name, e),
+        }
+    })
+}
+
+/// Placeholder in a script command standing for the arguments given after the script name
+const ARGS_PLACEHOLDER: &str = "${args}";
+
+/// Place trailing arguments where the command asks for them with `${args}`
+///
+/// A word that is exactly `${args}` expands to the arguments as separate words, so
+/// values containing spaces or quotes reach the program untouched; a placeholder
+/// embedded in a larger word is replaced by the arguments joined with spaces.
+/// Without any placeholder the arguments are appended, as before.
+fn substitute_args(command_parts: Vec<String>, args: Vec<String>) -> Vec<String> {
+    if !command_parts
+        .iter()
+        .any(|part| part.contains(ARGS_PLACEHOLDER))
+    {
+        let mut full_args = command_parts;
+        full_args.extend(args);
+        return full_args;
+    }
+
+    debug!("Substituting {} into script command", ARGS_PLACEHOLDER);
+    let mut full_args = Vec::with_capacity(command_parts.len() + args.len());
+    for part in command_parts {
+        if part == ARGS_PLACEHOLDER {
+            full_args.extend(args.iter().cloned());
+        } else if part.contains(ARGS_PLACEHOLDER) {
+            full_args.push(part.replace(ARGS_PLACEHOLDER, &args.join(" ")));
+        } else {
+            full_args.push(part);
+        }
+    }
+    full_args
 }
 
 /// Parse a command string into program and arguments
-/// This is a simple whitespace-based parser that respects quotes
-fn parse_command(command: &str) -> Vec<String> {
+///
+/// Splits on whitespace with shell-like quoting: single quotes keep their content
+/// literally, double quotes group words, and a backslash escapes a following quote
+/// or whitespace character. Other backslashes are kept as-is so Windows and UNC
+/// paths survive. Quoted empty strings are preserved as empty arguments.
+pub(crate) fn parse_command(command: &str) -> Vec<String> {
     let mut parts = Vec::new();
     let mut current = String::new();
+    let mut has_token = false;
     let mut in_double_quotes = false;
     let mut in_single_quotes = false;
+    let mut chars = command.chars().peekable();
 
-    for ch in command.chars() {
+    while let Some(ch) = chars.next() {
         match ch {
             '"' if !in_single_quotes => {
                 in_double_quotes = !in_double_quotes;
+                has_token = true;
             }
             '\'' if !in_double_quotes => {
                 in_single_quotes = !in_single_quotes;
+                has_token = true;
+            }
+            '\\' if !in_single_quotes => {
+                let escapable = |next: char| {
+                    next == '"' || (!in_double_quotes && (next == '\'' || next.is_whitespace()))
+                };
+                match chars.peek() {
+                    Some(&next) if escapable(next) => {
+                        current.push(next);
+                        chars.next();
+                    }
+                    _ => current.push(ch),
+                }
+                has_token = true;
             }
-            ' ' if !in_double_quotes && !in_single_quotes => {
-                if !current.is_empty() {
-                    parts.push(current.clone());
-                    current.clear();
+            c if c.is_whitespace() && !in_double_quotes && !in_single_quotes => {
+                if has_token {
+                    parts.push(std::mem::take(&mut current));
+                    has_token = false;
                 }
             }
             _ => {
                 current.push(ch);
+                has_token = true;
             }
         }
     }
 
-    if !current.is_empty() {
+    if has_token {
         parts.push(current);
     }
 
@@ -273,6 +893,147 @@ fn parse_command(command: &str) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rb_core::project::{ProjectMetadata, ScriptDefinition};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_script_graph_shows_two_level_dependency() {
+        let mut scripts = HashMap::new();
+        scripts.insert(
+            "build".to_string(),
+            ScriptDefinition::Detailed {
+                command: "rb run test".to_string(),
+                description: Some("Build everything".to_string()),
+                enabled: true,
+                runner: true,
+                timeout: None,
+            },
+        );
+        scripts.insert(
+            "test".to_string(),
+            ScriptDefinition::Simple("rb run lint && rspec".to_string()),
+        );
+        scripts.insert(
+            "lint".to_string(),
+            ScriptDefinition::Simple("rubocop".to_string()),
+        );
+        let project = ProjectRuntime::new(
+            "/tmp",
+            "rbproject.toml",
+            ProjectMetadata::default(),
+            scripts,
+        );
+
+        assert_eq!(
+            render_script_graph(&project),
+            vec!["build", "  test", "    lint"]
+        );
+    }
+
+    #[test]
+    fn test_script_graph_marks_cycles() {
+        let mut scripts = HashMap::new();
+        scripts.insert(
+            "a".to_string(),
+            ScriptDefinition::Simple("rb run b".to_string()),
+        );
+        scripts.insert(
+            "b".to_string(),
+            ScriptDefinition::Simple("rb run a".to_string()),
+        );
+        let project = ProjectRuntime::new(
+            "/tmp",
+            "rbproject.toml",
+            ProjectMetadata::default(),
+            scripts,
+        );
+
+        assert_eq!(
+            render_script_graph(&project),
+            vec!["a", "  b", "    a (cycle)", "b", "  a", "    b (cycle)"]
+        );
+    }
+
+    #[test]
+    fn test_batch_scripts_splits_script_names_from_arguments() {
+        let scripts = ["lint", "test"]
+            .iter()
+            .map(|name| (name.to_string(), ScriptDefinition::Simple(name.to_string())))
+            .collect();
+        let project = ProjectRuntime::new(
+            "/tmp",
+            "rbproject.toml",
+            ProjectMetadata::default(),
+            scripts,
+        );
+
+        assert_eq!(
+            batch_scripts(&project, "test".to_string(), words(&["spec/a_spec.rb"])).unwrap(),
+            (words(&["test"]), words(&["spec/a_spec.rb"]))
+        );
+        assert_eq!(
+            batch_scripts(&project, "lint".to_string(), words(&["test"])).unwrap(),
+            (words(&["lint", "test"]), Vec::new())
+        );
+        assert!(
+            batch_scripts(
+                &project,
+                "lint".to_string(),
+                words(&["test", "--fail-fast"])
+            )
+            .is_err()
+        );
+    }
+
+    fn words(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_substitute_args_in_middle_of_command() {
+        assert_eq!(
+            substitute_args(
+                parse_command("bundle exec rspec ${args} --format progress"),
+                words(&["--fail-fast", "spec/my models"]),
+            ),
+            words(&[
+                "bundle",
+                "exec",
+                "rspec",
+                "--fail-fast",
+                "spec/my models",
+                "--format",
+                "progress"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_substitute_args_appends_without_placeholder() {
+        assert_eq!(
+            substitute_args(parse_command("rspec"), words(&["--fail-fast"])),
+            words(&["rspec", "--fail-fast"])
+        );
+    }
+
+    #[test]
+    fn test_substitute_args_removes_placeholder_when_no_args() {
+        assert_eq!(
+            substitute_args(parse_command("rspec ${args} spec"), Vec::new()),
+            words(&["rspec", "spec"])
+        );
+    }
+
+    #[test]
+    fn test_substitute_args_within_a_word() {
+        assert_eq!(
+            substitute_args(
+                parse_command("ruby -e \"p ARGV\" --opts=${args}"),
+                words(&["a", "b"]),
+            ),
+            words(&["ruby", "-e", "p ARGV", "--opts=a b"])
+        );
+    }
 
     #[test]
     fn test_parse_command_simple() {
@@ -337,4 +1098,245 @@ mod tests {
     fn test_parse_command_only_spaces() {
         assert_eq!(parse_command("   "), Vec::<String>::new());
     }
+
+    #[test]
+    fn test_parse_command_with_escaped_quotes() {
+        assert_eq!(
+            parse_command(r#"ruby -e "puts \"hi\"""#),
+            vec![
+                "ruby".to_string(),
+                "-e".to_string(),
+                "puts \"hi\"".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_with_escaped_space() {
+        assert_eq!(
+            parse_command(r"cat my\ file.txt"),
+            vec!["cat".to_string(), "my file.txt".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_keeps_windows_paths() {
+        assert_eq!(
+            parse_command(r"ruby C:\scripts\setup.rb"),
+            vec!["ruby".to_string(), r"C:\scripts\setup.rb".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_keeps_unc_paths() {
+        assert_eq!(
+            parse_command(r"ruby \\server\share\x.rb"),
+            vec!["ruby".to_string(), r"\\server\share\x.rb".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_keeps_empty_quoted_argument() {
+        assert_eq!(
+            parse_command("echo \"\" done"),
+            vec!["echo".to_string(), String::new(), "done".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_with_tabs() {
+        assert_eq!(
+            parse_command("ruby\t-v"),
+            vec!["ruby".to_string(), "-v".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_from_toml_script() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("rbproject.toml");
+        std::fs::write(
+            &project_path,
+            r#"
+[scripts]
+greet = 'echo "hello world"'
+quote = 'ruby -e "puts \"it works\""'
+spaced = { command = "ls 'my dir' other\\ dir" }
+"#,
+        )
+        .unwrap();
+
+        let project = ProjectRuntime::from_file(&project_path).unwrap();
+
+        assert_eq!(
+            parse_command(project.get_script_command("greet").unwrap()),
+            vec!["echo".to_string(), "hello world".to_string()]
+        );
+        assert_eq!(
+            parse_command(project.get_script_command("quote").unwrap()),
+            vec![
+                "ruby".to_string(),
+                "-e".to_string(),
+                "puts \"it works\"".to_string()
+            ]
+        );
+        assert_eq!(
+            parse_command(project.get_script_command("spaced").unwrap()),
+            vec![
+                "ls".to_string(),
+                "my dir".to_string(),
+                "other dir".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_command_from_kdl_script() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_path = temp_dir.path().join("rbproject.kdl");
+        std::fs::write(
+            &project_path,
+            r#"
+scripts {
+    greet "echo \"hello world\""
+    quote {
+        command "ruby -e 'puts \"it works\"'"
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let project = ProjectRuntime::from_file(&project_path).unwrap();
+
+        assert_eq!(
+            parse_command(project.get_script_command("greet").unwrap()),
+            vec!["echo".to_string(), "hello world".to_string()]
+        );
+        assert_eq!(
+            parse_command(project.get_script_command("quote").unwrap()),
+            vec![
+                "ruby".to_string(),
+                "-e".to_string(),
+                "puts \"it works\"".to_string()
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    fn sandboxed_butler() -> (rb_tests::RubySandbox, ButlerRuntime) {
+        let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+        sandbox.add_ruby_dir("3.2.5").unwrap();
+        let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+            sandbox.root().to_path_buf(),
+            None,
+            None,
+            true,
+            sandbox.root().to_path_buf(),
+        )
+        .expect("Failed to create butler runtime");
+        (sandbox, butler)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timed_script_completing_in_time_reports_its_status() {
+        let (_sandbox, butler) = sandboxed_butler();
+
+        let status = run_script(
+            &butler,
+            "quick",
+            &words(&["sh", "-c", "exit 3"]),
+            &ExecOptions::default(),
+            Some(Duration::from_secs(10)),
+            None,
+        )
+        .expect("Script should finish within its timeout");
+
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sleeping_script_is_killed_at_timeout() {
+        let (_sandbox, butler) = sandboxed_butler();
+
+        let started = Instant::now();
+        let result = run_script(
+            &butler,
+            "server",
+            &words(&["sleep", "30"]),
+            &ExecOptions::default(),
+            Some(Duration::from_secs(1)),
+            None,
+        );
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        match result {
+            Err(ButlerError::General(message)) => {
+                assert!(message.contains("'server'"), "got {}", message);
+                assert!(message.contains("timeout of 1s"), "got {}", message);
+            }
+            other => panic!("Expected a timeout error, got {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_processes_the_script_started() {
+        use crate::output::CapturedOutput;
+
+        let (_sandbox, butler) = sandboxed_butler();
+        let mut out = CapturedOutput::new();
+
+        // The shell forks `sleep`, which holds the relayed output open until it is killed too
+        let started = Instant::now();
+        let result = run_script(
+            &butler,
+            "server",
+            &words(&["sh", "-c", "echo started; sleep 30; true"]),
+            &ExecOptions::default(),
+            Some(Duration::from_secs(1)),
+            Some(&mut out),
+        );
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(result.is_err());
+        assert!(
+            out.text().contains("[server] started"),
+            "got {}",
+            out.text()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prefixed_script_output_carries_script_name() {
+        use crate::output::CapturedOutput;
+
+        let (_sandbox, butler) = sandboxed_butler();
+        let mut out = CapturedOutput::new();
+
+        let status = run_script(
+            &butler,
+            "build",
+            &words(&[
+                "sh",
+                "-c",
+                "echo compiling; echo 'warning: slow' >&2; echo done",
+            ]),
+            &ExecOptions::default(),
+            None,
+            Some(&mut out),
+        )
+        .expect("Script should run");
+
+        assert!(status.success());
+        let mut lines = out.lines().to_vec();
+        lines.sort();
+        assert_eq!(
+            lines,
+            ["[build] compiling", "[build] done", "[build] warning: slow"]
+        );
+    }
 }
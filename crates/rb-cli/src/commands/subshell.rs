@@ -0,0 +1,66 @@
+use colored::*;
+use log::{debug, info};
+use rb_core::butler::{ButlerError, ButlerRuntime};
+
+/// Environment variable marking a Butler subshell, set to the selected Ruby's name
+/// so prompts can show which environment is active
+pub const SUBSHELL_ENV_VAR: &str = "RB_SUBSHELL";
+
+/// Determine which shell to spawn: `$SHELL` on Unix, `%COMSPEC%` on Windows
+fn resolve_shell() -> String {
+    #[cfg(windows)]
+    let (var, fallback) = ("COMSPEC", "cmd.exe");
+    #[cfg(not(windows))]
+    let (var, fallback) = ("SHELL", "/bin/sh");
+
+    std::env::var(var)
+        .ok()
+        .filter(|shell| !shell.trim().is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// Subshell command - enters an interactive shell with the composed environment applied
+pub fn subshell_command(butler_runtime: &ButlerRuntime) -> Result<(), ButlerError> {
+    let ruby = butler_runtime.selected_ruby()?;
+    let shell = resolve_shell();
+
+    info!(
+        "Opening {} within the {} environment",
+        shell,
+        ruby.version_name()
+    );
+
+    if let Ok(outer) = std::env::var(SUBSHELL_ENV_VAR) {
+        debug!("Already inside a Butler subshell for {}", outer);
+    }
+
+    let env_vars = butler_runtime.env_vars(std::env::var("PATH").ok());
+    debug!("Applying {} environment variables", env_vars.len());
+
+    println!(
+        "{} {}",
+        "🎩 Butler Notice:".bright_blue().bold(),
+        format!(
+            "Entering a subshell prepared with {}. Type 'exit' to return.",
+            ruby.version_name()
+        )
+        .dimmed()
+    );
+
+    let status = std::process::Command::new(&shell)
+        .envs(&env_vars)
+        .env(SUBSHELL_ENV_VAR, ruby.version_name())
+        .status()
+        .map_err(|e| ButlerError::General(format!("Failed to start shell '{}': {}", shell, e)))?;
+
+    debug!("Subshell concluded with {:?}", status.code());
+
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code) => std::process::exit(code),
+        None => {
+            debug!("Subshell was terminated by system signal");
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,9 +1,197 @@
 use log::debug;
-use rb_core::bundler::SyncResult;
-use rb_core::butler::{ButlerError, ButlerRuntime};
+use rb_core::bundler::{BundlerError, DEPLOYMENT_WITHOUT_GROUPS, SyncResult};
+use rb_core::butler::{ButlerError, ButlerRuntime, Command};
+use rb_core::project::ProjectHooks;
+use std::path::{Path, PathBuf};
 
-pub fn sync_command(butler_runtime: ButlerRuntime) -> Result<(), ButlerError> {
-    debug!("Starting sync command");
+use super::doctor::bundler_mismatch_message;
+use super::run::{load_project, parse_command};
+use crate::output::Output;
+
+/// Run a project hook through Butler's environment from the project root
+fn run_hook(
+    butler_runtime: &ButlerRuntime,
+    project_root: &Path,
+    name: &str,
+    command: &str,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    let parts = parse_command(command);
+    let Some((program, args)) = parts.split_first() else {
+        return Err(ButlerError::General(format!(
+            "The {} hook has an empty command",
+            name
+        )));
+    };
+
+    out.line(&format!("🪝 Running {} hook: {}", name, command));
+    let status = Command::new(program)
+        .args(args)
+        .current_dir(project_root)
+        .status_with_validation(butler_runtime)
+        .map_err(|e| {
+            ButlerError::General(format!(
+                "The {} hook '{}' could not be run: {}",
+                name, command, e
+            ))
+        })?;
+
+    if !status.success() {
+        return Err(ButlerError::General(format!(
+            "The {} hook '{}' failed (exit code: {})",
+            name,
+            command,
+            status.code().unwrap_or(-1)
+        )));
+    }
+
+    Ok(())
+}
+
+/// Whether bundler's report points at a native extension that failed to build
+fn is_native_extension_failure(details: &str) -> bool {
+    details.contains("extconf.rb failed")
+        || details.contains("native extension")
+        || details.contains("development tools")
+        || details.contains("compiler failed")
+        || details.contains("Makefile")
+}
+
+/// Print guidance for a failed synchronization, tailored to what went wrong
+fn explain_sync_failure(error: &BundlerError, out: &mut dyn Output) {
+    match error {
+        BundlerError::NotInstalled => {
+            out.line("📦 Bundler Not Found");
+            out.blank();
+            out.line("The bundler executable is not available in your Ruby environment.");
+            out.blank();
+            out.line("🚀 Installation:");
+            out.line("  gem install bundler");
+        }
+        BundlerError::CheckFailed(details) => {
+            out.line("🔎 Bundle Check Failed");
+            out.blank();
+            out.line("Butler could not determine whether your dependencies are satisfied.");
+            out.blank();
+            out.line("💡 Solutions:");
+            out.line("  • Ensure the Gemfile is valid Ruby and loads cleanly");
+            out.line("  • Ensure the project directory is writable for bundler's local config");
+            out.blank();
+            out.line(&format!("Details: {}", details));
+        }
+        BundlerError::InstallFailed { details, .. } if is_native_extension_failure(details) => {
+            out.line("🔧 Native Extension Compilation Failed");
+            out.blank();
+            out.line("Some gems in your Gemfile require native extensions to be compiled.");
+            out.line("This requires development tools to be installed on your system.");
+            out.blank();
+            out.line("📋 Required Development Tools:");
+            out.line("  • Build essentials (gcc, make, etc.)");
+            out.line("  • Ruby development headers");
+            out.line("  • Platform-specific libraries");
+            out.blank();
+            out.line("🚀 Installation Commands:");
+            out.line("  Ubuntu/Debian: sudo apt-get install build-essential ruby-dev");
+            out.line("  CentOS/RHEL:   sudo yum groupinstall 'Development Tools' && sudo yum install ruby-devel");
+            out.line("  Alpine Linux:  sudo apk add build-base ruby-dev");
+            out.line("  macOS:         xcode-select --install");
+            out.blank();
+            out.line("💡 Alternative Solutions:");
+            out.line("  • Use pre-compiled gem versions if available");
+            out.line("  • Consider using --platform ruby to force source compilation");
+            out.line("  • Use Docker with a development-ready base image");
+        }
+        BundlerError::InstallFailed { details, .. }
+            if details.contains("permission") || details.contains("Permission") =>
+        {
+            out.line("🔒 Permission Denied");
+            out.blank();
+            out.line("Unable to write to the gem installation directory.");
+            out.blank();
+            out.line("💡 Solutions:");
+            out.line("  • Ensure write permissions to the vendor directory");
+            out.line("  • Check file system permissions");
+            out.line("  • Consider using a user-specific gem directory");
+        }
+        BundlerError::InstallFailed { .. } => {
+            out.line("⚠️  Bundle Installation Error");
+            out.blank();
+            out.line(&format!("Details: {}", error));
+        }
+        BundlerError::LockFailed(details) => {
+            out.line("📄 Lockfile Update Failed");
+            out.blank();
+            out.line("Your gems are installed, but Gemfile.lock could not be brought in line with the Gemfile.");
+            out.blank();
+            out.line("💡 Solutions:");
+            out.line("  • Run 'rb exec bundle lock' to see what bundler cannot resolve locally");
+            out.line("  • Ensure Gemfile.lock is writable");
+            out.blank();
+            out.line(&format!("Details: {}", details));
+        }
+    }
+}
+
+/// Parallel install jobs used when `--jobs` is not given: one per available CPU
+fn default_install_jobs() -> u32 {
+    std::thread::available_parallelism()
+        .map(|n| u32::try_from(n.get()).unwrap_or(u32::MAX))
+        .unwrap_or(1)
+}
+
+/// Options refining how `rb sync` installs the bundle
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Extra attempts after a failed install
+    pub retries: u32,
+    /// Parallel install jobs, one per CPU when not given
+    pub jobs: Option<u32>,
+    /// Directory to vendor gems into for this run, relative to the current directory
+    pub vendor_path: Option<PathBuf>,
+    /// Configure bundler's deployment mode
+    pub deployment: bool,
+    /// Reinstall every gem, redownloading it
+    pub redownload: bool,
+}
+
+pub fn sync_command(
+    butler_runtime: ButlerRuntime,
+    options: &SyncOptions,
+    project_file: Option<PathBuf>,
+    out: &mut dyn Output,
+) -> Result<(), ButlerError> {
+    let SyncOptions {
+        retries,
+        jobs,
+        ref vendor_path,
+        deployment,
+        redownload,
+    } = *options;
+    let jobs = jobs.unwrap_or_else(default_install_jobs).max(1);
+    debug!(
+        "Starting sync command (retries: {}, jobs: {}, deployment: {}, redownload: {})",
+        retries, jobs, deployment, redownload
+    );
+
+    let butler_runtime = match vendor_path {
+        Some(path) => {
+            let path = butler_runtime.current_dir().join(path);
+            debug!("Vendoring gems into {} for this run", path.display());
+            butler_runtime.with_bundler_vendor_dir(path)
+        }
+        None => butler_runtime,
+    }
+    .with_bundler_jobs(jobs);
+    let butler_runtime = if deployment {
+        butler_runtime.with_bundler_deployment()
+    } else {
+        butler_runtime
+    };
+    let butler_runtime = if redownload {
+        butler_runtime.with_bundler_redownload()
+    } else {
+        butler_runtime
+    };
 
     let bundler_runtime = match butler_runtime.bundler_runtime() {
         Some(bundler) => bundler,
@@ -14,104 +202,99 @@ pub fn sync_command(butler_runtime: ButlerRuntime) -> Result<(), ButlerError> {
         }
     };
 
-    println!("🔄 Synchronizing Bundler Environment");
-    println!();
-    println!("📂 Project: {}", bundler_runtime.root.display());
-    println!("📄 Gemfile: {}", bundler_runtime.gemfile_path().display());
-    println!("📦 Vendor:  {}", bundler_runtime.vendor_dir().display());
-    println!();
+    butler_runtime.ensure_gem_home_writable()?;
+
+    // Hooks are optional, so a project file that cannot be loaded must not stop the sync
+    let project = load_project(&butler_runtime, project_file, false).unwrap_or_else(|e| {
+        out.warn(&format!("{}\nSynchronizing without project hooks.", e));
+        out.blank();
+        None
+    });
+    let (project_root, hooks) = match project {
+        Some(project) => (project.root, project.hooks),
+        None => (bundler_runtime.root.clone(), ProjectHooks::default()),
+    };
+
+    if let Some(command) = &hooks.pre_sync {
+        run_hook(&butler_runtime, &project_root, "pre_sync", command, out)?;
+        out.blank();
+    }
+
+    out.line("🔄 Synchronizing Bundler Environment");
+    out.blank();
+    out.line(&format!("📂 Project: {}", bundler_runtime.root.display()));
+    out.line(&format!(
+        "📄 Gemfile: {}",
+        bundler_runtime.gemfile_path().display()
+    ));
+    out.line(&format!(
+        "📦 Vendor:  {}",
+        bundler_runtime.vendor_dir().display()
+    ));
+    out.line(&format!("🧵 Jobs:    {}", jobs));
+    if bundler_runtime.is_deployment() {
+        out.line(&format!(
+            "🚀 Mode:    deployment (frozen, without {})",
+            DEPLOYMENT_WITHOUT_GROUPS
+        ));
+    }
+    if bundler_runtime.is_redownload() {
+        out.line("♻️  Reinstall: redownloading every gem");
+    }
+    out.blank();
 
-    match bundler_runtime.synchronize(&butler_runtime, |line| {
-        println!("{}", line);
+    match bundler_runtime.bundler_version_mismatch(&butler_runtime) {
+        Ok(Some((locked, installed))) => {
+            out.warn(&bundler_mismatch_message(&locked, &installed));
+            out.blank();
+        }
+        Ok(None) => {}
+        Err(e) => debug!("Skipping bundler version comparison: {}", e),
+    }
+
+    match bundler_runtime.synchronize_with_retry(&butler_runtime, retries, |line| {
+        out.line(line);
     }) {
         Ok(SyncResult::AlreadySynced) => {
-            println!("✅ Environment Already Synchronized");
-            println!();
-            println!(
-                "Your bundler environment is meticulously prepared and ready for distinguished service."
-            );
-            println!("All dependencies are satisfied and properly installed.");
+            out.line("✅ Environment Already Synchronized");
+            out.blank();
+            out.line("Your bundler environment is meticulously prepared and ready for distinguished service.");
+            out.line("All dependencies are satisfied and properly installed.");
         }
         Ok(SyncResult::Synchronized) => {
-            println!();
-            println!("✅ Environment Successfully Synchronized");
-            println!();
-            println!(
-                "Your bundler environment has been meticulously prepared with all required dependencies."
-            );
-            println!("The installation is complete and ready for distinguished service.");
+            out.blank();
+            out.line("✅ Environment Successfully Synchronized");
+            out.blank();
+            out.line("Your bundler environment has been meticulously prepared with all required dependencies.");
+            out.line("The installation is complete and ready for distinguished service.");
         }
         Err(e) => {
-            println!();
-            println!("❌ Synchronization Failed");
-            println!();
-
-            let error_msg = e.to_string();
-
-            if error_msg.contains("extconf.rb failed")
-                || error_msg.contains("native extension")
-                || error_msg.contains("development tools")
-                || error_msg.contains("compiler failed")
-                || error_msg.contains("Makefile")
-            {
-                println!("🔧 Native Extension Compilation Failed");
-                println!();
-                println!("Some gems in your Gemfile require native extensions to be compiled.");
-                println!("This requires development tools to be installed on your system.");
-                println!();
-                println!("📋 Required Development Tools:");
-                println!("  • Build essentials (gcc, make, etc.)");
-                println!("  • Ruby development headers");
-                println!("  • Platform-specific libraries");
-                println!();
-                println!("🚀 Installation Commands:");
-                println!("  Ubuntu/Debian: sudo apt-get install build-essential ruby-dev");
-                println!(
-                    "  CentOS/RHEL:   sudo yum groupinstall 'Development Tools' && sudo yum install ruby-devel"
-                );
-                println!("  Alpine Linux:  sudo apk add build-base ruby-dev");
-                println!("  macOS:         xcode-select --install");
-                println!();
-                println!("💡 Alternative Solutions:");
-                println!("  • Use pre-compiled gem versions if available");
-                println!("  • Consider using --platform ruby to force source compilation");
-                println!("  • Use Docker with a development-ready base image");
-            } else if error_msg.contains("not found") && error_msg.contains("bundler") {
-                println!("📦 Bundler Not Found");
-                println!();
-                println!("The bundler executable is not available in your Ruby environment.");
-                println!();
-                println!("🚀 Installation:");
-                println!("  gem install bundler");
-            } else if error_msg.contains("permission") || error_msg.contains("Permission") {
-                println!("🔒 Permission Denied");
-                println!();
-                println!("Unable to write to the gem installation directory.");
-                println!();
-                println!("💡 Solutions:");
-                println!("  • Ensure write permissions to the vendor directory");
-                println!("  • Check file system permissions");
-                println!("  • Consider using a user-specific gem directory");
-            } else {
-                println!("⚠️  Bundle Installation Error");
-                println!();
-                println!("Details: {}", error_msg);
-            }
+            out.blank();
+            out.line("❌ Synchronization Failed");
+            out.blank();
 
-            println!();
-            println!("🔍 For detailed error information, run:");
-            println!("  rb exec bundle install --verbose");
+            explain_sync_failure(&e, out);
 
-            return Err(ButlerError::General(e.to_string()));
+            out.blank();
+            out.line("🔍 For detailed error information, run:");
+            out.line("  rb exec bundle install --verbose");
+
+            return Err(ButlerError::BundlerFailed(e.to_string()));
         }
     }
 
+    if let Some(command) = &hooks.post_sync {
+        out.blank();
+        run_hook(&butler_runtime, &project_root, "post_sync", command, out)?;
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::output::CapturedOutput;
     use rb_tests::BundlerSandbox;
 
     #[test]
@@ -135,7 +318,12 @@ mod tests {
         match result {
             Ok(runtime) => {
                 // If runtime creation succeeded (found Ruby), sync should fail due to no Gemfile
-                let sync_result = sync_command(runtime);
+                let sync_result = sync_command(
+                    runtime,
+                    &SyncOptions::default(),
+                    None,
+                    &mut CapturedOutput::new(),
+                );
                 assert!(
                     sync_result.is_err(),
                     "Expected sync to fail without Gemfile"
@@ -148,4 +336,242 @@ mod tests {
             }
         }
     }
+
+    /// A bundler project on a sandboxed Ruby whose `bundle` is a shell stub
+    ///
+    /// The stub appends each invocation's arguments to a log, then runs the given body.
+    #[cfg(unix)]
+    struct StubbedBundle {
+        rubies: rb_tests::RubySandbox,
+        sandbox: BundlerSandbox,
+        project: PathBuf,
+        bundle: PathBuf,
+    }
+
+    #[cfg(unix)]
+    impl StubbedBundle {
+        fn new(body: &str) -> Self {
+            use std::os::unix::fs::PermissionsExt;
+
+            let rubies = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+            rubies.add_ruby_dir("3.3.0").unwrap();
+            let sandbox = BundlerSandbox::new().expect("Failed to create bundler sandbox");
+            let project = sandbox.add_bundler_project("app", false).unwrap();
+
+            let bundle = sandbox.root().join("bundle-stub");
+            let log = sandbox.root().join("bundle-args.log");
+            std::fs::write(
+                &bundle,
+                format!("#!/bin/sh\necho \"$@\" >> '{}'\n{}", log.display(), body),
+            )
+            .unwrap();
+            std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+            Self {
+                rubies,
+                sandbox,
+                project,
+                bundle,
+            }
+        }
+
+        /// A stub that succeeds at everything, for tests looking only at the arguments
+        fn succeeding() -> Self {
+            Self::new("exit 0\n")
+        }
+
+        fn butler(&self) -> ButlerRuntime {
+            ButlerRuntime::discover_and_compose_with_current_dir(
+                self.rubies.root().to_path_buf(),
+                None,
+                Some(self.sandbox.root().join("gems")),
+                false,
+                self.project.clone(),
+            )
+            .unwrap()
+            .with_bundler_bin(self.bundle.to_string_lossy())
+        }
+
+        fn sync(&self, options: &SyncOptions) -> Result<(), ButlerError> {
+            sync_command(self.butler(), options, None, &mut CapturedOutput::new())
+        }
+
+        /// Arguments of every `bundle` invocation so far, one line each
+        fn invocations(&self) -> String {
+            std::fs::read_to_string(self.sandbox.root().join("bundle-args.log")).unwrap()
+        }
+    }
+
+    #[cfg(unix)]
+    fn sync_with_post_hook(body: &str) -> (StubbedBundle, Result<(), ButlerError>) {
+        let stub = StubbedBundle::new(body);
+        std::fs::write(
+            stub.project.join("rbproject.toml"),
+            "[hooks]\npost_sync = \"touch post-sync-ran\"\n",
+        )
+        .unwrap();
+
+        let result = stub.sync(&SyncOptions::default());
+        (stub, result)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn post_sync_hook_runs_after_successful_sync() {
+        let (stub, result) =
+            sync_with_post_hook("if [ \"$1\" = exec ]; then shift; exec \"$@\"; fi\nexit 0\n");
+
+        assert!(result.is_ok(), "Sync should succeed: {:?}", result);
+        assert!(
+            stub.project.join("post-sync-ran").exists(),
+            "post_sync hook should have run"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn post_sync_hook_skipped_when_sync_fails() {
+        let (stub, result) = sync_with_post_hook("exit 1\n");
+
+        assert!(result.is_err());
+        assert!(
+            !stub.project.join("post-sync-ran").exists(),
+            "post_sync hook must not run after a failed sync"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_warns_about_malformed_project_file() {
+        let stub = StubbedBundle::succeeding();
+        let project_file = stub.project.join("rbproject.toml");
+        std::fs::write(
+            &project_file,
+            "[hooks\npost_sync = \"touch post-sync-ran\"\n",
+        )
+        .unwrap();
+
+        let mut out = CapturedOutput::new();
+        let result = sync_command(
+            stub.butler(),
+            &SyncOptions::default(),
+            Some(project_file),
+            &mut out,
+        );
+
+        assert!(result.is_ok(), "Sync should succeed: {:?}", result);
+        assert!(out.contains("Synchronizing without project hooks"));
+        assert!(!stub.project.join("post-sync-ran").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_reports_missing_bundler() {
+        let stub = StubbedBundle::succeeding();
+        let butler = stub.butler().with_bundler_bin("no-such-bundle-executable");
+
+        let error = sync_command(
+            butler,
+            &SyncOptions::default(),
+            None,
+            &mut CapturedOutput::new(),
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("gem install bundler"),
+            "Unexpected error: {}",
+            error
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_forwards_install_jobs_to_bundle_config() {
+        let stub = StubbedBundle::succeeding();
+
+        stub.sync(&SyncOptions::default())
+            .expect("Sync should succeed");
+        let invocations = stub.invocations();
+        assert!(
+            invocations.contains(&format!(
+                "config set jobs --local {}",
+                default_install_jobs()
+            )),
+            "Default jobs should reach bundle config, got: {}",
+            invocations
+        );
+        assert!(default_install_jobs() >= 1);
+
+        stub.sync(&SyncOptions {
+            jobs: Some(3),
+            ..SyncOptions::default()
+        })
+        .expect("Sync should succeed");
+        let invocations = stub.invocations();
+        assert!(
+            invocations.contains("config set jobs --local 3"),
+            "--jobs should reach bundle config, got: {}",
+            invocations
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_deployment_configures_frozen_vendored_install() {
+        let stub = StubbedBundle::succeeding();
+        let vendor_dir = stub.butler().bundler_runtime().unwrap().vendor_dir();
+        assert_eq!(
+            vendor_dir,
+            stub.project.join(".rb").join("vendor").join("bundler")
+        );
+
+        stub.sync(&SyncOptions {
+            jobs: Some(1),
+            deployment: true,
+            ..SyncOptions::default()
+        })
+        .expect("Sync should succeed");
+
+        let invocations = stub.invocations();
+        let lines: Vec<&str> = invocations.lines().collect();
+        let path_config = format!("config set path --local {}", vendor_dir.display());
+        let position = |line: &str| {
+            lines
+                .iter()
+                .position(|l| *l == line)
+                .unwrap_or_else(|| panic!("Missing '{}' in: {}", line, invocations))
+        };
+
+        let path = position(&path_config);
+        let deployment = position("config set deployment --local true");
+        let without = position("config set without --local development:test");
+        assert!(path < deployment && deployment < without);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sync_redownload_forwards_flag_to_bundle_install() {
+        // bundle check succeeds, so only a redownload leads to an install
+        let stub = StubbedBundle::succeeding();
+
+        stub.sync(&SyncOptions {
+            jobs: Some(1),
+            redownload: true,
+            ..SyncOptions::default()
+        })
+        .expect("Sync should succeed");
+
+        let invocations = stub.invocations();
+        let lines: Vec<&str> = invocations.lines().collect();
+        assert!(
+            lines.contains(&"install --redownload"),
+            "--redownload should reach bundle install, got: {}",
+            invocations
+        );
+        assert!(
+            !lines.contains(&"check"),
+            "A redownload should not consult bundle check, got: {}",
+            invocations
+        );
+    }
 }
@@ -1,7 +1,41 @@
+use crate::config::paths::expand_path;
 use crate::{Cli, resolve_search_dir};
 use clap::CommandFactory;
 use rb_core::ruby::RubyRuntimeDetector;
-use std::path::PathBuf;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// What a completion candidate stands for, letting editors pick icons and ordering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionKind {
+    Command,
+    Flag,
+    Version,
+    Script,
+    Binstub,
+    Directory,
+    File,
+    Shell,
+}
+
+/// A single completion suggestion with the context editors need to present it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompletionCandidate {
+    pub value: String,
+    pub kind: CompletionKind,
+    pub description: Option<String>,
+}
+
+impl CompletionCandidate {
+    fn new(value: impl Into<String>, kind: CompletionKind, description: Option<String>) -> Self {
+        Self {
+            value: value.into(),
+            kind,
+            description,
+        }
+    }
+}
 
 /// Defines how a command should complete its arguments
 #[derive(Debug, Clone, PartialEq)]
@@ -22,33 +56,27 @@ fn get_completion_behavior(command: &str) -> CompletionBehavior {
     }
 }
 
-/// Expand tilde (~) to home directory in paths
-fn expand_tilde(path: &str) -> PathBuf {
-    if let Some(stripped) = path.strip_prefix("~/") {
-        if let Some(home) = std::env::var_os("HOME") {
-            let mut expanded = PathBuf::from(home);
-            expanded.push(stripped);
-            return expanded;
+/// Extract rubies_dir from command line words if -R or --rubies-dir flag is present
+fn extract_rubies_dir_from_line(words: &[&str]) -> Option<PathBuf> {
+    for i in 0..words.len() {
+        if (words[i] == "-R" || words[i] == "--rubies-dir") && i + 1 < words.len() {
+            return Some(expand_path(Path::new(words[i + 1])));
         }
-    } else if path == "~"
-        && let Some(home) = std::env::var_os("HOME")
-    {
-        return PathBuf::from(home);
     }
-    PathBuf::from(path)
+    None
 }
 
-/// Extract rubies_dir from command line words if -R or --rubies-dir flag is present
-fn extract_rubies_dir_from_line(words: &[&str]) -> Option<PathBuf> {
+/// Extract the project file from command line words if -P or --project flag is present
+fn extract_project_file_from_line(words: &[&str]) -> Option<PathBuf> {
     for i in 0..words.len() {
-        if (words[i] == "-R" || words[i] == "--rubies-dir") && i + 1 < words.len() {
-            return Some(expand_tilde(words[i + 1]));
+        if (words[i] == "-P" || words[i] == "--project") && i + 1 < words.len() {
+            return Some(expand_path(Path::new(words[i + 1])));
         }
     }
     None
 }
 
-fn suggest_directories(current: &str) {
+fn suggest_directories(current: &str) -> Vec<CompletionCandidate> {
     let current_path = std::path::Path::new(current);
 
     let (search_dir, prefix) = if current.is_empty() {
@@ -69,7 +97,7 @@ fn suggest_directories(current: &str) {
     };
 
     let Ok(entries) = std::fs::read_dir(&search_dir) else {
-        return;
+        return Vec::new();
     };
 
     let mut candidates = Vec::new();
@@ -113,12 +141,13 @@ fn suggest_directories(current: &str) {
     }
 
     candidates.sort();
-    for candidate in candidates {
-        println!("{}", candidate);
-    }
+    candidates
+        .into_iter()
+        .map(|c| CompletionCandidate::new(c, CompletionKind::Directory, None))
+        .collect()
 }
 
-fn suggest_files(current: &str) {
+fn suggest_files(current: &str) -> Vec<CompletionCandidate> {
     let current_path = std::path::Path::new(current);
 
     let (search_dir, prefix) = if current.is_empty() {
@@ -139,7 +168,7 @@ fn suggest_files(current: &str) {
     };
 
     let Ok(entries) = std::fs::read_dir(&search_dir) else {
-        return;
+        return Vec::new();
     };
 
     let mut candidates = Vec::new();
@@ -187,13 +216,21 @@ fn suggest_files(current: &str) {
                 name.to_string()
             };
 
-        candidates.push(candidate_path);
+        candidates.push((candidate_path, file_type.is_dir()));
     }
 
     candidates.sort();
-    for candidate in candidates {
-        println!("{}", candidate);
-    }
+    candidates
+        .into_iter()
+        .map(|(c, is_dir)| {
+            let kind = if is_dir {
+                CompletionKind::Directory
+            } else {
+                CompletionKind::File
+            };
+            CompletionCandidate::new(c, kind, None)
+        })
+        .collect()
 }
 
 /// Generate dynamic completions based on current line and cursor position
@@ -202,6 +239,30 @@ pub fn generate_completions(
     cursor_pos: &str,
     butler_runtime: Option<&rb_core::butler::ButlerRuntime>,
 ) {
+    for candidate in collect_completions(line, cursor_pos, butler_runtime) {
+        println!("{}", candidate.value);
+    }
+}
+
+/// Generate the same completions as a JSON array of `{value, kind, description}` objects
+pub fn generate_json_completions(
+    line: &str,
+    cursor_pos: &str,
+    butler_runtime: Option<&rb_core::butler::ButlerRuntime>,
+) {
+    let candidates = collect_completions(line, cursor_pos, butler_runtime);
+    match serde_json::to_string(&candidates) {
+        Ok(json) => println!("{}", json),
+        Err(e) => log::debug!("Unable to serialize completions: {}", e),
+    }
+}
+
+/// Completion engine shared by the bash and JSON formatters
+pub fn collect_completions(
+    line: &str,
+    cursor_pos: &str,
+    butler_runtime: Option<&rb_core::butler::ButlerRuntime>,
+) -> Vec<CompletionCandidate> {
     let cursor: usize = cursor_pos.parse().unwrap_or(line.len());
     let line = &line[..cursor.min(line.len())];
 
@@ -212,8 +273,7 @@ pub fn generate_completions(
     let rubies_dir = extract_rubies_dir_from_line(&words).or(rubies_dir);
 
     if words.is_empty() || words.len() == 1 {
-        print_commands("");
-        return;
+        return suggest_commands("");
     }
 
     let (current_word, prev_word) = if line.ends_with(' ') {
@@ -227,42 +287,32 @@ pub fn generate_completions(
 
     if let Some(prev) = prev_word {
         if prev == "-r" || prev == "--ruby" {
-            suggest_ruby_versions(rubies_dir, current_word);
-            return;
+            return suggest_ruby_versions(rubies_dir, butler_runtime, current_word);
         }
         if prev == "-R" || prev == "--rubies-dir" {
-            suggest_directories(current_word);
-            return;
+            return suggest_directories(current_word);
         }
         if prev == "-C" || prev == "--work-dir" {
-            suggest_directories(current_word);
-            return;
+            return suggest_directories(current_word);
         }
         if prev == "-G" || prev == "--gem-home" {
-            suggest_directories(current_word);
-            return;
+            return suggest_directories(current_word);
         }
         if prev == "-c" || prev == "--config" {
-            suggest_files(current_word);
-            return;
+            return suggest_files(current_word);
         }
         if prev == "-P" || prev == "--project" {
-            suggest_files(current_word);
-            return;
+            return suggest_files(current_word);
         }
         if prev == "shell-integration" {
-            if "bash".starts_with(current_word) {
-                println!("bash");
-            }
-            return;
+            return ["bash"]
+                .into_iter()
+                .filter(|shell| shell.starts_with(current_word))
+                .map(|shell| CompletionCandidate::new(shell, CompletionKind::Shell, None))
+                .collect();
         }
     }
 
-    if current_word.starts_with('-') {
-        print_flags();
-        return;
-    }
-
     let value_taking_flags = [
         "-r",
         "--ruby",
@@ -292,12 +342,15 @@ pub fn generate_completions(
         .and_then(|pos| words.get(pos + 1))
         .unwrap_or(&"");
 
+    if current_word.starts_with('-') {
+        return suggest_flags(command);
+    }
+
     let completing_command =
         command.is_empty() || (current_word == *command && !line.ends_with(' '));
 
     if completing_command {
-        print_commands(current_word);
-        return;
+        return suggest_commands(current_word);
     }
 
     let behavior = get_completion_behavior(command);
@@ -311,94 +364,154 @@ pub fn generate_completions(
     };
 
     match behavior {
-        CompletionBehavior::Scripts => {
-            if args_after_command == 0 {
-                suggest_script_names(current_word);
-            }
+        CompletionBehavior::Scripts if args_after_command == 0 => {
+            suggest_script_names(current_word, extract_project_file_from_line(&words))
         }
-        CompletionBehavior::Binstubs => {
-            if args_after_command == 0
-                && let Some(runtime) = butler_runtime
-            {
-                suggest_binstubs(current_word, runtime);
-            }
+        CompletionBehavior::Binstubs if args_after_command == 0 => {
             // If no runtime available, just don't suggest binstubs (no Ruby found)
+            butler_runtime
+                .map(|runtime| suggest_binstubs(current_word, runtime))
+                .unwrap_or_default()
         }
-        CompletionBehavior::DefaultOnly => {}
+        _ => Vec::new(),
     }
 }
 
-fn print_commands(prefix: &str) {
+fn suggest_commands(prefix: &str) -> Vec<CompletionCandidate> {
     let cmd = Cli::command();
+    let mut candidates = Vec::new();
 
     for subcommand in cmd.get_subcommands() {
         if subcommand.is_hide_set() {
             continue;
         }
 
+        let description = subcommand.get_about().map(|about| about.to_string());
+
         let name = subcommand.get_name();
         if name.starts_with(prefix) {
-            println!("{}", name);
+            candidates.push(CompletionCandidate::new(
+                name,
+                CompletionKind::Command,
+                description.clone(),
+            ));
         }
 
         for alias in subcommand.get_visible_aliases() {
             if alias.starts_with(prefix) {
-                println!("{}", alias);
+                candidates.push(CompletionCandidate::new(
+                    alias,
+                    CompletionKind::Command,
+                    description.clone(),
+                ));
             }
         }
     }
+
+    candidates
 }
 
-fn print_flags() {
+/// Global flags, followed by the flags of `command` when it names a subcommand
+fn suggest_flags(command: &str) -> Vec<CompletionCandidate> {
     let cmd = Cli::command();
+    let mut candidates = Vec::new();
 
-    for arg in cmd.get_arguments() {
+    let subcommand_args = cmd
+        .get_subcommands()
+        .find(|sub| sub.get_name() == command || sub.get_all_aliases().any(|a| a == command))
+        .into_iter()
+        .flat_map(|sub| sub.get_arguments())
+        .filter(|arg| !arg.is_global_set());
+
+    for arg in cmd.get_arguments().chain(subcommand_args) {
         if arg.is_positional() || arg.is_hide_set() {
             continue;
         }
 
+        let description = arg.get_help().map(|help| help.to_string());
+
         if let Some(short) = arg.get_short() {
-            println!("-{}", short);
+            candidates.push(CompletionCandidate::new(
+                format!("-{}", short),
+                CompletionKind::Flag,
+                description.clone(),
+            ));
         }
 
         if let Some(long) = arg.get_long() {
-            println!("--{}", long);
+            candidates.push(CompletionCandidate::new(
+                format!("--{}", long),
+                CompletionKind::Flag,
+                description.clone(),
+            ));
         }
     }
+
+    candidates
 }
 
-fn suggest_ruby_versions(rubies_dir: Option<PathBuf>, prefix: &str) {
-    let search_dir = resolve_search_dir(rubies_dir);
+/// Rubies from the rubies directory (`-R` on the line wins) and the additional ones, as selection sees them
+fn suggest_ruby_versions(
+    rubies_dir: Option<PathBuf>,
+    butler_runtime: Option<&rb_core::butler::ButlerRuntime>,
+    prefix: &str,
+) -> Vec<CompletionCandidate> {
+    let rubies_dir =
+        rubies_dir.or_else(|| butler_runtime.map(|runtime| runtime.rubies_dir().clone()));
+    let Ok(search_dir) = resolve_search_dir(rubies_dir) else {
+        return Vec::new();
+    };
+    let additional_dirs = butler_runtime
+        .map(|runtime| runtime.additional_rubies_dirs())
+        .unwrap_or_default();
 
-    if let Ok(rubies) = RubyRuntimeDetector::discover(&search_dir) {
-        for ruby in rubies {
-            let version = ruby.version.to_string();
-            if version.starts_with(prefix) {
-                println!("{}", version);
-            }
-        }
-    }
+    let Ok(rubies) = RubyRuntimeDetector::discover_in(&search_dir, additional_dirs) else {
+        return Vec::new();
+    };
+
+    rubies
+        .into_iter()
+        .filter(|ruby| ruby.version.to_string().starts_with(prefix))
+        .map(|ruby| {
+            CompletionCandidate::new(
+                ruby.version.to_string(),
+                CompletionKind::Version,
+                Some(format!("{} at {}", ruby.kind.as_str(), ruby.root.display())),
+            )
+        })
+        .collect()
 }
 
-fn suggest_script_names(prefix: &str) {
-    let current_dir = std::env::current_dir().ok();
-    if let Some(dir) = current_dir {
-        let project_file = dir.join("rbproject.toml");
-        if project_file.exists()
-            && let Ok(content) = std::fs::read_to_string(&project_file)
-            && let Ok(parsed) = toml::from_str::<toml::Value>(&content)
-            && let Some(scripts) = parsed.get("scripts").and_then(|s| s.as_table())
-        {
-            for script_name in scripts.keys() {
-                if script_name.starts_with(prefix) {
-                    println!("{}", script_name);
-                }
-            }
-        }
-    }
+fn suggest_script_names(prefix: &str, project_file: Option<PathBuf>) -> Vec<CompletionCandidate> {
+    let project = match project_file {
+        Some(path) => rb_core::project::ProjectRuntime::from_file(&path).ok(),
+        None => std::env::current_dir()
+            .ok()
+            .and_then(|dir| rb_core::project::RbprojectDetector::discover(&dir).ok())
+            .flatten(),
+    };
+    let Some(project) = project else {
+        return Vec::new();
+    };
+
+    project
+        .script_names()
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .map(|name| {
+            let description = project
+                .get_script_description(name)
+                .or_else(|| project.get_script_command(name))
+                .map(str::to_string);
+            CompletionCandidate::new(name, CompletionKind::Script, description)
+        })
+        .collect()
 }
 
-fn suggest_binstubs(prefix: &str, butler_runtime: &rb_core::butler::ButlerRuntime) {
+fn suggest_binstubs(
+    prefix: &str,
+    butler_runtime: &rb_core::butler::ButlerRuntime,
+) -> Vec<CompletionCandidate> {
     use std::collections::HashSet;
 
     let mut suggested = HashSet::new();
@@ -411,9 +524,10 @@ fn suggest_binstubs(prefix: &str, butler_runtime: &rb_core::butler::ButlerRuntim
 
     let mut items: Vec<_> = suggested.into_iter().collect();
     items.sort();
-    for item in items {
-        println!("{}", item);
-    }
+    items
+        .into_iter()
+        .map(|item| CompletionCandidate::new(item, CompletionKind::Binstub, None))
+        .collect()
 }
 
 fn collect_executables_from_dir(
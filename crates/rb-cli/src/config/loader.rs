@@ -1,6 +1,7 @@
 use super::locator::locate_config_file;
+use super::paths::expand_config_paths;
 use super::{ConfigError, RbConfig};
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::fs;
 use std::path::PathBuf;
 
@@ -9,30 +10,37 @@ use std::path::PathBuf;
 ///
 /// Supports both TOML and KDL formats (detected by file extension)
 ///
+/// Unknown keys are ignored unless `strict` is set, in which case the first one
+/// found is reported as `ConfigError::UnknownKey`.
+///
 /// # Arguments
 /// * `override_path` - Optional path to explicitly load config from (for testing)
-pub fn load_config(override_path: Option<PathBuf>) -> Result<RbConfig, ConfigError> {
+/// * `strict` - Reject keys the configuration does not recognise
+pub fn load_config(override_path: Option<PathBuf>, strict: bool) -> Result<RbConfig, ConfigError> {
     if let Some(config_path) = locate_config_file(override_path.clone()) {
         info!("Loading configuration from: {}", config_path.display());
 
         let contents = fs::read_to_string(&config_path)?;
 
         // Determine format based on file extension
-        let config: RbConfig = if config_path.extension().and_then(|s| s.to_str()) == Some("kdl") {
-            debug!("Parsing configuration as KDL format");
-            parse_kdl_config(&contents)?
-        } else {
-            debug!("Parsing configuration as TOML format");
-            toml::from_str(&contents)?
-        };
+        let mut config: RbConfig =
+            if config_path.extension().and_then(|s| s.to_str()) == Some("kdl") {
+                debug!("Parsing configuration as KDL format");
+                parse_kdl_config(&contents, strict)?
+            } else {
+                debug!("Parsing configuration as TOML format");
+                parse_toml_config(&contents, strict)?
+            };
+
+        expand_config_paths(&mut config);
 
         // Log what was loaded
         debug!("Configuration file contents parsed successfully");
         if let Some(ref dir) = config.rubies_dir {
             debug!("  rubies-dir: {}", dir.display());
         }
-        if let Some(ref version) = config.ruby_version {
-            debug!("  ruby-version: {}", version);
+        if !config.ruby_version.is_empty() {
+            debug!("  ruby-version: {}", config.ruby_version.join(", "));
         }
         if let Some(ref home) = config.gem_home {
             debug!("  gem-home: {}", home.display());
@@ -49,8 +57,49 @@ pub fn load_config(override_path: Option<PathBuf>) -> Result<RbConfig, ConfigErr
     }
 }
 
+/// Parse TOML configuration into RbConfig, collecting keys serde would skip
+fn parse_toml_config(content: &str, strict: bool) -> Result<RbConfig, ConfigError> {
+    let mut unknown = Vec::new();
+    let config: RbConfig = serde_ignored::deserialize(toml::Deserializer::new(content), |path| {
+        unknown.push(path.to_string())
+    })?;
+
+    if strict && let Some(key) = unknown.first() {
+        return Err(ConfigError::UnknownKey(key.clone()));
+    }
+    for key in &unknown {
+        warn!("Ignoring unknown configuration key '{}'", key);
+    }
+    Ok(config)
+}
+
+/// Node names understood by the KDL configuration format
+const KDL_KEYS: &[&str] = &[
+    "rubies-dir",
+    "additional-rubies-dirs",
+    "ruby-version",
+    "gem-home",
+    "gemset",
+    "default-ruby",
+    "include-prereleases",
+    "interactive-select",
+    "auto-install-missing",
+    "keep-binstubs",
+    "inherit-gem-path",
+    "include-site-ruby",
+    "no-bundle-exec",
+    "bundler-bin",
+    "exec-lock-retries",
+    "exec-from-bundler-root",
+    "shared-scripts",
+    "allow-system-ruby",
+    "create-gem-home",
+    "command-log",
+    "gems",
+];
+
 /// Parse KDL configuration into RbConfig
-fn parse_kdl_config(content: &str) -> Result<RbConfig, ConfigError> {
+fn parse_kdl_config(content: &str, strict: bool) -> Result<RbConfig, ConfigError> {
     let doc: kdl::KdlDocument = content.parse().map_err(|e: kdl::KdlError| {
         std::io::Error::new(
             std::io::ErrorKind::InvalidData,
@@ -58,6 +107,16 @@ fn parse_kdl_config(content: &str) -> Result<RbConfig, ConfigError> {
         )
     })?;
 
+    for node in doc.nodes() {
+        let name = node.name().value();
+        if !KDL_KEYS.contains(&name) {
+            if strict {
+                return Err(ConfigError::UnknownKey(name.to_string()));
+            }
+            warn!("Ignoring unknown configuration key '{}'", name);
+        }
+    }
+
     let mut config = RbConfig::default();
 
     // Parse rubies-dir
@@ -68,12 +127,24 @@ fn parse_kdl_config(content: &str) -> Result<RbConfig, ConfigError> {
         config.rubies_dir = Some(PathBuf::from(value));
     }
 
-    // Parse ruby-version
-    if let Some(node) = doc.get("ruby-version")
-        && let Some(entry) = node.entries().first()
-        && let Some(value) = entry.value().as_string()
-    {
-        config.ruby_version = Some(value.to_string());
+    // Parse additional-rubies-dirs, one or more directories searched after rubies-dir
+    if let Some(node) = doc.get("additional-rubies-dirs") {
+        config.additional_rubies_dirs = node
+            .entries()
+            .iter()
+            .filter_map(|entry| entry.value().as_string())
+            .map(PathBuf::from)
+            .collect();
+    }
+
+    // Parse ruby-version, one or more versions in order of preference
+    if let Some(node) = doc.get("ruby-version") {
+        config.ruby_version = node
+            .entries()
+            .iter()
+            .filter_map(|entry| entry.value().as_string())
+            .map(str::to_string)
+            .collect();
     }
 
     // Parse gem-home
@@ -84,6 +155,147 @@ fn parse_kdl_config(content: &str) -> Result<RbConfig, ConfigError> {
         config.gem_home = Some(PathBuf::from(value));
     }
 
+    // Parse include-prereleases
+    if let Some(node) = doc.get("include-prereleases")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.include_prereleases = Some(value);
+    }
+
+    // Parse interactive-select
+    if let Some(node) = doc.get("interactive-select")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.interactive_select = Some(value);
+    }
+
+    // Parse auto-install-missing
+    if let Some(node) = doc.get("auto-install-missing")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.auto_install_missing = Some(value);
+    }
+
+    // Parse keep-binstubs
+    if let Some(node) = doc.get("keep-binstubs")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.keep_binstubs = Some(value);
+    }
+
+    // Parse inherit-gem-path
+    if let Some(node) = doc.get("inherit-gem-path")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.inherit_gem_path = Some(value);
+    }
+
+    // Parse include-site-ruby
+    if let Some(node) = doc.get("include-site-ruby")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.include_site_ruby = Some(value);
+    }
+
+    // Parse no-bundle-exec
+    if let Some(node) = doc.get("no-bundle-exec")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.no_bundle_exec = Some(value);
+    }
+
+    // Parse gemset
+    if let Some(node) = doc.get("gemset")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_string()
+    {
+        config.gemset = Some(value.to_string());
+    }
+
+    // Parse default-ruby
+    if let Some(node) = doc.get("default-ruby")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_string()
+    {
+        config.default_ruby = Some(value.to_string());
+    }
+
+    // Parse bundler-bin
+    if let Some(node) = doc.get("bundler-bin")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_string()
+    {
+        config.bundler_bin = Some(value.to_string());
+    }
+
+    // Parse exec-lock-retries
+    if let Some(node) = doc.get("exec-lock-retries")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_integer()
+    {
+        config.exec_lock_retries = u32::try_from(value).ok();
+    }
+
+    // Parse exec-from-bundler-root
+    if let Some(node) = doc.get("exec-from-bundler-root")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.exec_from_bundler_root = Some(value);
+    }
+
+    // Parse shared-scripts
+    if let Some(node) = doc.get("shared-scripts")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.shared_scripts = Some(value);
+    }
+
+    // Parse allow-system-ruby
+    if let Some(node) = doc.get("allow-system-ruby")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.allow_system_ruby = Some(value);
+    }
+
+    // Parse create-gem-home
+    if let Some(node) = doc.get("create-gem-home")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_bool()
+    {
+        config.create_gem_home = Some(value);
+    }
+
+    // Parse command-log
+    if let Some(node) = doc.get("command-log")
+        && let Some(entry) = node.entries().first()
+        && let Some(value) = entry.value().as_string()
+    {
+        config.command_log = Some(PathBuf::from(value));
+    }
+
+    // Parse gems, one `"pattern" "directory"` child per Ruby version pattern
+    if let Some(children) = doc.get("gems").and_then(|node| node.children()) {
+        for child in children.nodes() {
+            if let Some(entry) = child.entries().first()
+                && let Some(value) = entry.value().as_string()
+            {
+                config
+                    .gem_bases
+                    .insert(child.name().value().to_string(), PathBuf::from(value));
+            }
+        }
+    }
+
     Ok(config)
 }
 
@@ -93,12 +305,12 @@ mod tests {
 
     #[test]
     fn test_load_config_returns_default_when_no_file() {
-        let result = load_config(None);
+        let result = load_config(None, false);
         assert!(result.is_ok());
 
         let config = result.unwrap();
         assert!(config.rubies_dir.is_none());
-        assert!(config.ruby_version.is_none());
+        assert!(config.ruby_version.is_empty());
         assert!(config.gem_home.is_none());
     }
 
@@ -114,15 +326,37 @@ mod tests {
         writeln!(file, r#"ruby-version = "3.2.0""#).expect("Failed to write config");
         drop(file);
 
-        let result = load_config(Some(config_path.clone()));
+        let result = load_config(Some(config_path.clone()), false);
         assert!(result.is_ok());
 
         let config = result.unwrap();
-        assert_eq!(config.ruby_version, Some("3.2.0".to_string()));
+        assert_eq!(config.ruby_version, vec!["3.2.0"]);
 
         let _ = fs::remove_file(&config_path);
     }
 
+    #[test]
+    fn test_load_config_expands_configured_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("rb.toml");
+        std::fs::write(
+            &config_path,
+            "rubies-dir = \"~/rubies\"\ngem-home = \"${CARGO_MANIFEST_DIR}/gems\"\nwork-dir = \"/srv/app\"\n",
+        )
+        .unwrap();
+
+        let config = load_config(Some(config_path), false).unwrap();
+
+        if let Some(home) = rb_core::home_dir() {
+            assert_eq!(config.rubies_dir, Some(home.join("rubies")));
+        }
+        assert_eq!(
+            config.gem_home,
+            Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("gems"))
+        );
+        assert_eq!(config.work_dir, Some(PathBuf::from("/srv/app")));
+    }
+
     #[test]
     fn test_load_kdl_config() {
         use std::fs;
@@ -137,14 +371,139 @@ gem-home "/opt/gems"
 "#;
         fs::write(&config_path, kdl_content).expect("Failed to write KDL config");
 
-        let result = load_config(Some(config_path.clone()));
+        let result = load_config(Some(config_path.clone()), false);
         assert!(result.is_ok());
 
         let config = result.unwrap();
         assert_eq!(config.rubies_dir, Some(PathBuf::from("/opt/rubies")));
-        assert_eq!(config.ruby_version, Some("3.3.0".to_string()));
+        assert_eq!(config.ruby_version, vec!["3.3.0"]);
         assert_eq!(config.gem_home, Some(PathBuf::from("/opt/gems")));
 
         let _ = fs::remove_file(&config_path);
     }
+
+    #[test]
+    fn test_load_kdl_config_with_include_prereleases() {
+        let config =
+            parse_kdl_config("include-prereleases #true\n", false).expect("Failed to parse KDL");
+        assert_eq!(config.include_prereleases, Some(true));
+    }
+
+    #[test]
+    fn test_unknown_toml_key_is_ignored_unless_strict() {
+        let content = "ruby_version = \"3.3.0\"\ngem-home = \"/opt/gems\"\n";
+
+        let config = parse_toml_config(content, false).expect("Lenient mode should ignore it");
+        assert!(config.ruby_version.is_empty());
+        assert_eq!(config.gem_home, Some(PathBuf::from("/opt/gems")));
+
+        match parse_toml_config(content, true) {
+            Err(ConfigError::UnknownKey(key)) => assert_eq!(key, "ruby_version"),
+            other => panic!("Expected UnknownKey error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_command_log() {
+        let config = parse_kdl_config("command-log \"/var/log/rb/commands.jsonl\"\n", true)
+            .expect("Failed to parse KDL");
+        assert_eq!(
+            config.command_log,
+            Some(PathBuf::from("/var/log/rb/commands.jsonl"))
+        );
+    }
+
+    #[test]
+    fn test_load_config_with_gem_bases() {
+        let toml = parse_toml_config(
+            "gem-home = \"/gems\"\n\n[gems]\n\"3.3.*\" = \"/fast-ssd/gems\"\n",
+            true,
+        )
+        .expect("Failed to parse TOML");
+        let kdl = parse_kdl_config(
+            "gem-home \"/gems\"\ngems {\n    \"3.3.*\" \"/fast-ssd/gems\"\n}\n",
+            true,
+        )
+        .expect("Failed to parse KDL");
+
+        for config in [toml, kdl] {
+            assert_eq!(
+                config.gem_bases.get("3.3.*"),
+                Some(&PathBuf::from("/fast-ssd/gems"))
+            );
+            assert_eq!(config.gem_home, Some(PathBuf::from("/gems")));
+        }
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_gemset() {
+        let config = parse_kdl_config("gemset \"testing\"\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.gemset.as_deref(), Some("testing"));
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_allow_system_ruby() {
+        let config =
+            parse_kdl_config("allow-system-ruby #true\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.allow_system_ruby, Some(true));
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_include_site_ruby() {
+        let config =
+            parse_kdl_config("include-site-ruby #true\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.include_site_ruby, Some(true));
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_no_bundle_exec() {
+        let config = parse_kdl_config("no-bundle-exec #true\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.no_bundle_exec, Some(true));
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_shared_scripts() {
+        let config = parse_kdl_config("shared-scripts #true\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.shared_scripts, Some(true));
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_additional_rubies_dirs() {
+        let config = parse_kdl_config(
+            "additional-rubies-dirs \"/opt/rubies\" \"/usr/local/rubies\"\n",
+            true,
+        )
+        .expect("Failed to parse KDL");
+        assert_eq!(
+            config.additional_rubies_dirs,
+            vec![
+                PathBuf::from("/opt/rubies"),
+                PathBuf::from("/usr/local/rubies")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_exec_lock_retries() {
+        let config = parse_kdl_config("exec-lock-retries 5\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.exec_lock_retries, Some(5));
+    }
+
+    #[test]
+    fn test_load_kdl_config_with_exec_from_bundler_root() {
+        let config =
+            parse_kdl_config("exec-from-bundler-root #true\n", true).expect("Failed to parse KDL");
+        assert_eq!(config.exec_from_bundler_root, Some(true));
+    }
+
+    #[test]
+    fn test_unknown_kdl_node_is_ignored_unless_strict() {
+        let content = "ruby_version \"3.3.0\"\n";
+
+        assert!(parse_kdl_config(content, false).is_ok());
+        match parse_kdl_config(content, true) {
+            Err(ConfigError::UnknownKey(key)) => assert_eq!(key, "ruby_version"),
+            other => panic!("Expected UnknownKey error, got {:?}", other),
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use log::debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Trait for reading environment variables - allows mocking in tests
 pub trait EnvReader {
@@ -15,16 +15,60 @@ impl EnvReader for StdEnvReader {
     }
 }
 
+/// File names looked for in a configuration directory, most preferred first
+///
+/// `config.*` is the XDG-style name; `rb.*` is the legacy name, still honored.
+const CONFIG_FILE_NAMES: [&str; 4] = ["config.kdl", "config.toml", "rb.kdl", "rb.toml"];
+
+/// File name for a configuration file Butler creates
+const NEW_CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The first configuration file present in `dir`, in `CONFIG_FILE_NAMES` order
+fn find_in_dir(dir: &Path, label: &str) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES.iter().find_map(|name| {
+        let config_path = dir.join(name);
+        debug!("  Checking {}: {}", label, config_path.display());
+        config_path.exists().then(|| {
+            debug!("  Found configuration file in {}", label);
+            config_path
+        })
+    })
+}
+
+/// $XDG_CONFIG_HOME/rb, when the variable holds an absolute path as the spec requires
+fn xdg_config_dir(env: &dyn EnvReader) -> Option<PathBuf> {
+    let xdg_config = PathBuf::from(env.var("XDG_CONFIG_HOME").ok()?);
+    if xdg_config.is_absolute() {
+        Some(xdg_config.join("rb"))
+    } else {
+        debug!(
+            "  Ignoring XDG_CONFIG_HOME '{}': not an absolute path",
+            xdg_config.display()
+        );
+        None
+    }
+}
+
+/// Home directory as seen through `env`, honoring `RB_HOME` like `rb_core::home_dir`
+fn home_dir(env: &dyn EnvReader) -> Option<PathBuf> {
+    rb_core::user_home::home_dir_with(
+        env.var(rb_core::user_home::RB_HOME_ENV)
+            .ok()
+            .map(Into::into),
+    )
+}
+
 /// Locate the configuration file following XDG Base Directory specification
 ///
-/// Supports both rb.kdl and rb.toml (preferring .kdl)
+/// In each directory config.kdl and config.toml are preferred, then the legacy
+/// rb.kdl and rb.toml (.kdl before .toml).
 ///
 /// Priority order:
 /// 1. Explicit override path (if provided)
 /// 2. $RB_CONFIG environment variable
-/// 3. $XDG_CONFIG_HOME/rb/rb.kdl or rb.toml (Unix/Linux)
-/// 4. ~/.config/rb/rb.kdl or rb.toml (Unix/Linux fallback)
-/// 5. %APPDATA%/rb/rb.kdl or rb.toml (Windows)
+/// 3. $XDG_CONFIG_HOME/rb/ (Unix/Linux)
+/// 4. ~/.config/rb/ (Unix/Linux fallback)
+/// 5. %APPDATA%/rb/ (Windows)
 /// 6. ~/.rb.kdl or ~/.rb.toml (cross-platform fallback)
 pub fn locate_config_file(override_path: Option<PathBuf>) -> Option<PathBuf> {
     locate_config_file_with_env(override_path, &StdEnvReader)
@@ -57,49 +101,24 @@ fn locate_config_file_with_env(
     }
 
     // 3. Try XDG_CONFIG_HOME (Unix/Linux)
-    if let Ok(xdg_config) = env.var("XDG_CONFIG_HOME") {
-        let base_path = PathBuf::from(xdg_config).join("rb");
-        // Try .kdl first, then .toml
-        for ext in &["rb.kdl", "rb.toml"] {
-            let config_path = base_path.join(ext);
-            debug!("  Checking XDG_CONFIG_HOME: {}", config_path.display());
-            if config_path.exists() {
-                debug!("  Found configuration file in XDG_CONFIG_HOME");
-                return Some(config_path);
-            }
-        }
+    if let Some(found) = xdg_config_dir(env).and_then(|dir| find_in_dir(&dir, "XDG_CONFIG_HOME")) {
+        return Some(found);
     }
 
     // Try home directory based paths
-    if let Some(home_dir) = home::home_dir() {
-        // Unix/Linux: ~/.config/rb/rb.kdl or rb.toml
+    if let Some(home_dir) = home_dir(env) {
+        // Unix/Linux: ~/.config/rb/
         #[cfg(not(target_os = "windows"))]
-        {
-            let base_path = home_dir.join(".config").join("rb");
-            for ext in &["rb.kdl", "rb.toml"] {
-                let config_path = base_path.join(ext);
-                debug!("  Checking ~/.config/rb/{}: {}", ext, config_path.display());
-                if config_path.exists() {
-                    debug!("  Found configuration file in ~/.config/rb/");
-                    return Some(config_path);
-                }
-            }
+        if let Some(found) = find_in_dir(&home_dir.join(".config").join("rb"), "~/.config/rb/") {
+            return Some(found);
         }
 
-        // Windows: %APPDATA%/rb/rb.kdl or rb.toml
+        // Windows: %APPDATA%/rb/
         #[cfg(target_os = "windows")]
+        if let Ok(appdata) = env.var("APPDATA")
+            && let Some(found) = find_in_dir(&PathBuf::from(appdata).join("rb"), "%APPDATA%/rb/")
         {
-            if let Ok(appdata) = std::env::var("APPDATA") {
-                let base_path = PathBuf::from(appdata).join("rb");
-                for ext in &["rb.kdl", "rb.toml"] {
-                    let config_path = base_path.join(ext);
-                    debug!("  Checking %APPDATA%/rb/{}: {}", ext, config_path.display());
-                    if config_path.exists() {
-                        debug!("  Found configuration file in %APPDATA%/rb/");
-                        return Some(config_path);
-                    }
-                }
-            }
+            return Some(found);
         }
 
         // Cross-platform fallback: ~/.rb.kdl or ~/.rb.toml
@@ -117,6 +136,53 @@ fn locate_config_file_with_env(
     None
 }
 
+/// Choose the configuration file that settings should be written to
+///
+/// An explicit override or $RB_CONFIG is used even when the file does not exist yet;
+/// otherwise the located file, or a new config.toml in the first preferred location.
+pub fn config_file_for_writing(override_path: Option<PathBuf>) -> Option<PathBuf> {
+    config_file_for_writing_with_env(override_path, &StdEnvReader)
+}
+
+fn config_file_for_writing_with_env(
+    override_path: Option<PathBuf>,
+    env: &dyn EnvReader,
+) -> Option<PathBuf> {
+    if let Some(path) = override_path {
+        return Some(path);
+    }
+    if let Ok(rb_config) = env.var("RB_CONFIG") {
+        return Some(PathBuf::from(rb_config));
+    }
+    if let Some(existing) = locate_config_file_with_env(None, env) {
+        return Some(existing);
+    }
+
+    if let Some(dir) = xdg_config_dir(env) {
+        return Some(dir.join(NEW_CONFIG_FILE_NAME));
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Ok(appdata) = env.var("APPDATA") {
+        return Some(PathBuf::from(appdata).join("rb").join(NEW_CONFIG_FILE_NAME));
+    }
+
+    let home_dir = home_dir(env)?;
+    #[cfg(not(target_os = "windows"))]
+    {
+        Some(
+            home_dir
+                .join(".config")
+                .join("rb")
+                .join(NEW_CONFIG_FILE_NAME),
+        )
+    }
+    #[cfg(target_os = "windows")]
+    {
+        Some(home_dir.join(".rb.toml"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,4 +272,63 @@ mod tests {
 
         let _ = fs::remove_dir_all(&xdg_base);
     }
+
+    #[test]
+    fn test_config_file_for_writing_accepts_missing_rb_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let wanted = temp_dir.path().join("missing").join("rb.toml");
+
+        let mock_env =
+            MockEnvReader::new().with_var("RB_CONFIG", wanted.to_string_lossy().to_string());
+        assert_eq!(
+            config_file_for_writing_with_env(None, &mock_env),
+            Some(wanted)
+        );
+    }
+
+    #[test]
+    fn test_xdg_config_home_prefers_config_toml_over_legacy_name() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let rb_dir = temp_dir.path().join("rb");
+        std::fs::create_dir_all(&rb_dir).unwrap();
+        let env =
+            MockEnvReader::new().with_var("XDG_CONFIG_HOME", temp_dir.path().to_string_lossy());
+
+        std::fs::write(rb_dir.join("rb.toml"), "# legacy").unwrap();
+        assert_eq!(
+            locate_config_file_with_env(None, &env),
+            Some(rb_dir.join("rb.toml"))
+        );
+
+        std::fs::write(rb_dir.join("config.toml"), "rubies-dir = \"/opt/rubies\"\n").unwrap();
+        let located = locate_config_file_with_env(None, &env).unwrap();
+        assert_eq!(located, rb_dir.join("config.toml"));
+
+        let config = crate::config::loader::load_config(Some(located), true).unwrap();
+        assert_eq!(
+            config.rubies_dir,
+            Some(std::path::PathBuf::from("/opt/rubies"))
+        );
+    }
+
+    #[test]
+    fn test_relative_xdg_config_home_is_ignored() {
+        let env = MockEnvReader::new().with_var("XDG_CONFIG_HOME", "relative/config");
+        assert_eq!(xdg_config_dir(&env), None);
+    }
+
+    #[test]
+    fn test_config_file_for_writing_creates_config_toml_under_xdg_config_home() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let empty_home = tempfile::TempDir::new().unwrap();
+        let env = MockEnvReader::new()
+            .with_var("XDG_CONFIG_HOME", temp_dir.path().to_string_lossy())
+            .with_var("RB_HOME", empty_home.path().to_string_lossy());
+
+        assert_eq!(locate_config_file_with_env(None, &env), None);
+        assert_eq!(
+            config_file_for_writing_with_env(None, &env),
+            Some(temp_dir.path().join("rb").join("config.toml"))
+        );
+    }
 }
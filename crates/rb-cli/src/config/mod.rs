@@ -1,12 +1,24 @@
 pub mod loader;
 pub mod locator;
+pub mod paths;
 pub mod value;
+pub mod writer;
 
 use clap::Args;
+use paths::{expand_config_paths, expand_path};
+use rb_core::bundler::DEFAULT_BUNDLER_BIN;
+use rb_core::butler::SelectionOptions;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 pub use value::{ConfigSource, ConfigValue};
 
+/// Retries `rb exec` grants a program that hit a held bundler lock, unless configured
+pub const DEFAULT_EXEC_LOCK_RETRIES: u32 = 2;
+
+/// Separator between directories in list-valued path settings, as in PATH
+const PATH_LIST_SEPARATOR: char = if cfg!(windows) { ';' } else { ':' };
+
 /// Shared configuration for both CLI and TOML
 /// This struct serves both purposes:
 /// - CLI argument parsing via clap::Args
@@ -25,16 +37,40 @@ pub struct RbConfig {
     #[serde(rename = "rubies-dir", skip_serializing_if = "Option::is_none")]
     pub rubies_dir: Option<PathBuf>,
 
-    /// Request a particular Ruby version for your environment
+    /// Further directories holding Rubies, searched after the rubies directory
+    #[arg(
+        long = "additional-rubies-dir",
+        global = true,
+        action = clap::ArgAction::Append,
+        value_delimiter = PATH_LIST_SEPARATOR,
+        help = "Also look for Rubies in this directory (repeatable, e.g. /opt/rubies)",
+        env = "RB_ADDITIONAL_RUBIES_DIRS",
+        value_hint = clap::ValueHint::DirPath
+    )]
+    #[serde(
+        rename = "additional-rubies-dirs",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub additional_rubies_dirs: Vec<PathBuf>,
+
+    /// Request Ruby versions for your environment, most preferred first
     #[arg(
         short = 'r',
         long = "ruby",
         global = true,
-        help = "Request a particular Ruby version for your environment",
+        action = clap::ArgAction::Append,
+        value_delimiter = ',',
+        help = "Request a particular Ruby version; repeat to list fallbacks in order of preference",
         env = "RB_RUBY_VERSION"
     )]
-    #[serde(rename = "ruby-version", skip_serializing_if = "Option::is_none")]
-    pub ruby_version: Option<String>,
+    #[serde(
+        rename = "ruby-version",
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub ruby_version: Vec<String>,
 
     /// Specify custom gem base directory
     #[arg(
@@ -48,6 +84,28 @@ pub struct RbConfig {
     #[serde(rename = "gem-home", skip_serializing_if = "Option::is_none")]
     pub gem_home: Option<PathBuf>,
 
+    /// Keep gems in a named set, isolated from the default gem home
+    #[arg(
+        short = 'g',
+        long = "gemset",
+        global = true,
+        help = "Use a named gem set, e.g. ~/.gem/ruby/X.Y.Z@name",
+        env = "RB_GEMSET"
+    )]
+    #[serde(rename = "gemset", skip_serializing_if = "Option::is_none")]
+    pub gemset: Option<String>,
+
+    /// Ruby used instead of the latest when no version is requested or required
+    #[arg(
+        long = "default-ruby",
+        global = true,
+        value_name = "VERSION",
+        help = "Use this Ruby instead of the latest when nothing selects one",
+        env = "RB_DEFAULT_RUBY"
+    )]
+    #[serde(rename = "default-ruby", skip_serializing_if = "Option::is_none")]
+    pub default_ruby: Option<String>,
+
     /// Politely decline Bundler's company and operate independently
     #[arg(
         short = 'B',
@@ -71,6 +129,175 @@ pub struct RbConfig {
     )]
     #[serde(rename = "work-dir", skip_serializing_if = "Option::is_none")]
     pub work_dir: Option<PathBuf>,
+
+    /// Consider prerelease Rubies (previews, release candidates) when picking the latest
+    #[arg(
+        long = "include-prereleases",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Consider preview and rc Rubies when selecting the latest",
+        env = "RB_INCLUDE_PRERELEASES"
+    )]
+    #[serde(
+        rename = "include-prereleases",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub include_prereleases: Option<bool>,
+
+    /// Ask which Ruby to use when nothing pins a version and a terminal is attached
+    #[arg(
+        long = "interactive-select",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Prompt for a Ruby when no version is pinned (terminal only)",
+        env = "RB_INTERACTIVE_SELECT"
+    )]
+    #[serde(rename = "interactive-select", skip_serializing_if = "Option::is_none")]
+    pub interactive_select: Option<bool>,
+
+    /// Offer to install a required Ruby with ruby-install when it is missing
+    #[arg(
+        long = "auto-install-missing",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Offer to install a missing required Ruby via ruby-install (terminal only)",
+        env = "RB_AUTO_INSTALL_MISSING"
+    )]
+    #[serde(
+        rename = "auto-install-missing",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub auto_install_missing: Option<bool>,
+
+    /// Keep pre-generated bundler binstubs on PATH in --no-bundler mode
+    #[arg(
+        long = "keep-binstubs",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Keep existing bundler binstubs on PATH when bundler is skipped",
+        env = "RB_KEEP_BINSTUBS"
+    )]
+    #[serde(rename = "keep-binstubs", skip_serializing_if = "Option::is_none")]
+    pub keep_binstubs: Option<bool>,
+
+    /// Keep directories from an existing GEM_PATH reachable (read-only) outside bundler
+    #[arg(
+        long = "inherit-gem-path",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Keep gem directories from the incoming GEM_PATH reachable outside bundler",
+        env = "RB_INHERIT_GEM_PATH"
+    )]
+    #[serde(rename = "inherit-gem-path", skip_serializing_if = "Option::is_none")]
+    pub inherit_gem_path: Option<bool>,
+
+    /// Add the Ruby's site_ruby directory to the composed gem directories
+    #[arg(
+        long = "include-site-ruby",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Add the Ruby's lib/ruby/site_ruby/X.Y.0 directory to the gem directories",
+        env = "RB_INCLUDE_SITE_RUBY"
+    )]
+    #[serde(rename = "include-site-ruby", skip_serializing_if = "Option::is_none")]
+    pub include_site_ruby: Option<bool>,
+
+    /// Run commands directly instead of prefixing them with `bundle exec`
+    #[arg(
+        long = "no-bundle-exec",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Run commands directly instead of through bundle exec, keeping the bundler environment",
+        env = "RB_NO_BUNDLE_EXEC"
+    )]
+    #[serde(rename = "no-bundle-exec", skip_serializing_if = "Option::is_none")]
+    pub no_bundle_exec: Option<bool>,
+
+    /// Program Butler invokes for bundler operations, for shimmed or relocated bundlers
+    #[arg(
+        long = "bundler-bin",
+        global = true,
+        help = "Invoke this bundler executable instead of 'bundle'",
+        env = "RB_BUNDLER_BIN",
+        value_hint = clap::ValueHint::CommandName
+    )]
+    #[serde(rename = "bundler-bin", skip_serializing_if = "Option::is_none")]
+    pub bundler_bin: Option<String>,
+
+    /// How often `rb exec` retries a program that failed on a held bundler lock
+    #[arg(
+        long = "exec-lock-retries",
+        global = true,
+        value_name = "N",
+        help = "Retry rb exec up to N times when bundler reports its lock is held",
+        env = "RB_EXEC_LOCK_RETRIES"
+    )]
+    #[serde(rename = "exec-lock-retries", skip_serializing_if = "Option::is_none")]
+    pub exec_lock_retries: Option<u32>,
+
+    /// Run `rb exec` programs from the bundler project root instead of the current directory
+    #[arg(
+        long = "exec-from-bundler-root",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Run rb exec from the bundler project root when invoked from a subdirectory",
+        env = "RB_EXEC_FROM_BUNDLER_ROOT"
+    )]
+    #[serde(
+        rename = "exec-from-bundler-root",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exec_from_bundler_root: Option<bool>,
+
+    /// Read `[tool.rb.scripts]` from rbscripts.toml or pyproject.toml when no project file exists
+    #[arg(
+        long = "shared-scripts",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Fall back to [tool.rb.scripts] in rbscripts.toml or pyproject.toml for rb run",
+        env = "RB_SHARED_SCRIPTS"
+    )]
+    #[serde(rename = "shared-scripts", skip_serializing_if = "Option::is_none")]
+    pub shared_scripts: Option<bool>,
+
+    /// Use the `ruby` on PATH when the rubies directories hold no Rubies
+    #[arg(
+        long = "allow-system-ruby",
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Fall back to the ruby on PATH when no Rubies are found in the rubies directory",
+        env = "RB_ALLOW_SYSTEM_RUBY"
+    )]
+    #[serde(rename = "allow-system-ruby", skip_serializing_if = "Option::is_none")]
+    pub allow_system_ruby: Option<bool>,
+
+    /// Create a missing gem home (and its bin directory) before exec and run
+    #[arg(
+        long = "create-gem-home",
+        global = true,
+        value_name = "BOOL",
+        help = "Create a missing gem home before rb exec and rb run (default: true)",
+        env = "RB_CREATE_GEM_HOME"
+    )]
+    #[serde(rename = "create-gem-home", skip_serializing_if = "Option::is_none")]
+    pub create_gem_home: Option<bool>,
+
+    /// Append a JSON line describing every `rb exec` and `rb run` to this file
+    #[arg(
+        long = "command-log",
+        global = true,
+        value_name = "PATH",
+        help = "Record each rb exec and rb run as a JSON line in PATH",
+        env = "RB_COMMAND_LOG",
+        value_hint = clap::ValueHint::FilePath
+    )]
+    #[serde(rename = "command-log", skip_serializing_if = "Option::is_none")]
+    pub command_log: Option<PathBuf>,
+
+    /// Gem base directories for Rubies matching a version pattern, e.g. `"3.3.*"`
+    #[arg(skip)]
+    #[serde(rename = "gems", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub gem_bases: BTreeMap<String, PathBuf>,
 }
 
 impl RbConfig {
@@ -81,93 +308,277 @@ impl RbConfig {
 
         debug!("Merging configuration (CLI arguments take precedence over config file)");
 
-        if self.rubies_dir.is_none() {
-            if let Some(ref dir) = other.rubies_dir {
-                debug!("  Using rubies-dir from config file: {}", dir.display());
-                self.rubies_dir = other.rubies_dir;
-            }
-        } else {
+        if let Some(ref dir) = self.rubies_dir {
+            debug!("  Using rubies-dir from CLI arguments: {}", dir.display());
+        } else if let Some(dir) = other.rubies_dir {
+            debug!("  Using rubies-dir from config file: {}", dir.display());
+            self.rubies_dir = Some(dir);
+        }
+
+        if !self.additional_rubies_dirs.is_empty() {
+            debug!(
+                "  Using additional-rubies-dirs from CLI arguments: {:?}",
+                self.additional_rubies_dirs
+            );
+        } else if !other.additional_rubies_dirs.is_empty() {
             debug!(
-                "  Using rubies-dir from CLI arguments: {}",
-                self.rubies_dir.as_ref().unwrap().display()
+                "  Using additional-rubies-dirs from config file: {:?}",
+                other.additional_rubies_dirs
             );
+            self.additional_rubies_dirs = other.additional_rubies_dirs;
         }
 
-        if self.ruby_version.is_none() {
-            if let Some(ref version) = other.ruby_version {
-                debug!("  Using ruby-version from config file: {}", version);
-                self.ruby_version = other.ruby_version;
-            }
-        } else {
+        if !self.ruby_version.is_empty() {
             debug!(
                 "  Using ruby-version from CLI arguments: {}",
-                self.ruby_version.as_ref().unwrap()
+                self.ruby_version.join(", ")
             );
+        } else if !other.ruby_version.is_empty() {
+            debug!(
+                "  Using ruby-version from config file: {}",
+                other.ruby_version.join(", ")
+            );
+            self.ruby_version = other.ruby_version;
         }
 
-        if self.gem_home.is_none() {
-            if let Some(ref home) = other.gem_home {
-                debug!("  Using gem-home from config file: {}", home.display());
-                self.gem_home = other.gem_home;
-            }
-        } else {
+        if let Some(ref home) = self.gem_home {
+            debug!("  Using gem-home from CLI arguments: {}", home.display());
+        } else if let Some(home) = other.gem_home {
+            debug!("  Using gem-home from config file: {}", home.display());
+            self.gem_home = Some(home);
+        }
+
+        if !self.gem_bases.is_empty() {
+            debug!("  Using gems from CLI arguments: {:?}", self.gem_bases);
+        } else if !other.gem_bases.is_empty() {
+            debug!("  Using gems from config file: {:?}", other.gem_bases);
+            self.gem_bases = other.gem_bases;
+        }
+
+        if let Some(ref gemset) = self.gemset {
+            debug!("  Using gemset from CLI arguments: {}", gemset);
+        } else if let Some(gemset) = other.gemset {
+            debug!("  Using gemset from config file: {}", gemset);
+            self.gemset = Some(gemset);
+        }
+
+        if let Some(ref version) = self.default_ruby {
+            debug!("  Using default-ruby from CLI arguments: {}", version);
+        } else if let Some(version) = other.default_ruby {
+            debug!("  Using default-ruby from config file: {}", version);
+            self.default_ruby = Some(version);
+        }
+
+        if let Some(no_bundler) = self.no_bundler {
+            debug!("  Using no-bundler from CLI arguments: {}", no_bundler);
+        } else if let Some(no_bundler) = other.no_bundler {
+            debug!("  Using no-bundler from config file: {}", no_bundler);
+            self.no_bundler = Some(no_bundler);
+        }
+
+        if let Some(ref dir) = self.work_dir {
+            debug!("  Using work-dir from CLI arguments: {}", dir.display());
+        } else if let Some(dir) = other.work_dir {
+            debug!("  Using work-dir from config file: {}", dir.display());
+            self.work_dir = Some(dir);
+        }
+
+        if let Some(include) = self.include_prereleases {
             debug!(
-                "  Using gem-home from CLI arguments: {}",
-                self.gem_home.as_ref().unwrap().display()
+                "  Using include-prereleases from CLI arguments: {}",
+                include
             );
+        } else if let Some(include) = other.include_prereleases {
+            debug!("  Using include-prereleases from config file: {}", include);
+            self.include_prereleases = Some(include);
         }
 
-        if self.no_bundler.is_none() {
-            if let Some(no_bundler) = other.no_bundler {
-                debug!("  Using no-bundler from config file: {}", no_bundler);
-                self.no_bundler = Some(no_bundler);
-            }
-        } else {
+        if let Some(interactive) = self.interactive_select {
             debug!(
-                "  Using no-bundler from CLI arguments: {}",
-                self.no_bundler.unwrap()
+                "  Using interactive-select from CLI arguments: {}",
+                interactive
             );
+        } else if let Some(interactive) = other.interactive_select {
+            debug!(
+                "  Using interactive-select from config file: {}",
+                interactive
+            );
+            self.interactive_select = Some(interactive);
         }
 
-        if self.work_dir.is_none() {
-            if let Some(ref dir) = other.work_dir {
-                debug!("  Using work-dir from config file: {}", dir.display());
-                self.work_dir = other.work_dir;
-            }
-        } else {
+        if let Some(auto_install) = self.auto_install_missing {
+            debug!(
+                "  Using auto-install-missing from CLI arguments: {}",
+                auto_install
+            );
+        } else if let Some(auto_install) = other.auto_install_missing {
             debug!(
-                "  Using work-dir from CLI arguments: {}",
-                self.work_dir.as_ref().unwrap().display()
+                "  Using auto-install-missing from config file: {}",
+                auto_install
             );
+            self.auto_install_missing = Some(auto_install);
+        }
+
+        if let Some(value) = self.keep_binstubs {
+            debug!("  Using keep-binstubs from CLI arguments: {}", value);
+        } else if let Some(value) = other.keep_binstubs {
+            debug!("  Using keep-binstubs from config file: {}", value);
+            self.keep_binstubs = Some(value);
+        }
+
+        if let Some(value) = self.inherit_gem_path {
+            debug!("  Using inherit-gem-path from CLI arguments: {}", value);
+        } else if let Some(value) = other.inherit_gem_path {
+            debug!("  Using inherit-gem-path from config file: {}", value);
+            self.inherit_gem_path = Some(value);
+        }
+
+        if let Some(value) = self.include_site_ruby {
+            debug!("  Using include-site-ruby from CLI arguments: {}", value);
+        } else if let Some(value) = other.include_site_ruby {
+            debug!("  Using include-site-ruby from config file: {}", value);
+            self.include_site_ruby = Some(value);
+        }
+
+        if let Some(value) = self.no_bundle_exec {
+            debug!("  Using no-bundle-exec from CLI arguments: {}", value);
+        } else if let Some(value) = other.no_bundle_exec {
+            debug!("  Using no-bundle-exec from config file: {}", value);
+            self.no_bundle_exec = Some(value);
+        }
+
+        if let Some(ref bin) = self.bundler_bin {
+            debug!("  Using bundler-bin from CLI arguments: {}", bin);
+        } else if let Some(bin) = other.bundler_bin {
+            debug!("  Using bundler-bin from config file: {}", bin);
+            self.bundler_bin = Some(bin);
+        }
+
+        if let Some(retries) = self.exec_lock_retries {
+            debug!("  Using exec-lock-retries from CLI arguments: {}", retries);
+        } else if let Some(retries) = other.exec_lock_retries {
+            debug!("  Using exec-lock-retries from config file: {}", retries);
+            self.exec_lock_retries = Some(retries);
+        }
+
+        if let Some(value) = self.exec_from_bundler_root {
+            debug!(
+                "  Using exec-from-bundler-root from CLI arguments: {}",
+                value
+            );
+        } else if let Some(value) = other.exec_from_bundler_root {
+            debug!("  Using exec-from-bundler-root from config file: {}", value);
+            self.exec_from_bundler_root = Some(value);
+        }
+
+        if let Some(value) = self.shared_scripts {
+            debug!("  Using shared-scripts from CLI arguments: {}", value);
+        } else if let Some(value) = other.shared_scripts {
+            debug!("  Using shared-scripts from config file: {}", value);
+            self.shared_scripts = Some(value);
+        }
+
+        if let Some(value) = self.allow_system_ruby {
+            debug!("  Using allow-system-ruby from CLI arguments: {}", value);
+        } else if let Some(value) = other.allow_system_ruby {
+            debug!("  Using allow-system-ruby from config file: {}", value);
+            self.allow_system_ruby = Some(value);
+        }
+
+        if let Some(value) = self.create_gem_home {
+            debug!("  Using create-gem-home from CLI arguments: {}", value);
+        } else if let Some(value) = other.create_gem_home {
+            debug!("  Using create-gem-home from config file: {}", value);
+            self.create_gem_home = Some(value);
+        }
+
+        if let Some(ref path) = self.command_log {
+            debug!("  Using command-log from CLI arguments: {}", path.display());
+        } else if let Some(path) = other.command_log {
+            debug!("  Using command-log from config file: {}", path.display());
+            self.command_log = Some(path);
         }
     }
 }
 
+/// Accept `ruby-version = "3.3.4"` as well as `ruby-version = ["3.3.4", "3.2.5"]`
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
 /// Configuration with tracked sources for each value
 /// This stores where each config value came from (CLI, env, file, or default)
 #[derive(Debug, Clone)]
 pub struct TrackedConfig {
     pub rubies_dir: ConfigValue<PathBuf>,
-    pub ruby_version: Option<ConfigValue<String>>,
+    pub additional_rubies_dirs: ConfigValue<Vec<PathBuf>>,
+    pub ruby_version: Option<ConfigValue<Vec<String>>>,
     pub gem_home: ConfigValue<PathBuf>,
+    pub gemset: Option<ConfigValue<String>>,
+    pub default_ruby: Option<ConfigValue<String>>,
     pub no_bundler: ConfigValue<bool>,
     pub work_dir: ConfigValue<PathBuf>,
+    pub include_prereleases: ConfigValue<bool>,
+    pub interactive_select: ConfigValue<bool>,
+    pub auto_install_missing: ConfigValue<bool>,
+    pub keep_binstubs: ConfigValue<bool>,
+    pub inherit_gem_path: ConfigValue<bool>,
+    pub include_site_ruby: ConfigValue<bool>,
+    pub no_bundle_exec: ConfigValue<bool>,
+    pub bundler_bin: ConfigValue<String>,
+    pub exec_lock_retries: ConfigValue<u32>,
+    pub exec_from_bundler_root: ConfigValue<bool>,
+    pub shared_scripts: ConfigValue<bool>,
+    pub allow_system_ruby: ConfigValue<bool>,
+    pub create_gem_home: ConfigValue<bool>,
+    pub command_log: Option<ConfigValue<PathBuf>>,
+    pub gem_bases: Option<ConfigValue<BTreeMap<String, PathBuf>>>,
 }
 
 impl TrackedConfig {
     /// Create a TrackedConfig from RbConfig, environment, and defaults
     /// Priority: CLI > Env > Config > Default
     pub fn from_merged(cli_config: &RbConfig, file_config: &RbConfig) -> Self {
+        Self::from_merged_with_env(cli_config, file_config, |name| std::env::var(name).ok())
+    }
+
+    /// Like `from_merged`, reading `RB_*` variables through `env` instead of the process environment
+    pub fn from_merged_with_env(
+        cli_config: &RbConfig,
+        file_config: &RbConfig,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
         use log::debug;
 
         debug!("Building tracked configuration with sources");
 
+        // File values are expanded as the file is loaded; CLI and env values are expanded here
+        let mut cli_config = cli_config.clone();
+        expand_config_paths(&mut cli_config);
+        let cli_config = &cli_config;
+        let env_path = |name: &str| env(name).map(|v| expand_path(&PathBuf::from(v)));
+
         let resolve_path_config = |cli: &Option<PathBuf>,
                                    file: &Option<PathBuf>,
                                    env_val: Option<PathBuf>,
                                    default: PathBuf|
          -> ConfigValue<PathBuf> {
-            if let Some(path) = cli {
+            let shown = |path: &PathBuf| path.display().to_string();
+            let (file_shown, env_shown) = (file.as_ref().map(shown), env_val.as_ref().map(shown));
+            let resolved = if let Some(path) = cli {
                 debug!("  Using value from CLI: {}", path.display());
                 ConfigValue::from_cli(path.clone())
             } else if let Some(path) = file {
@@ -179,14 +590,18 @@ impl TrackedConfig {
             } else {
                 debug!("  Using default value: {}", default.display());
                 ConfigValue::default_value(default)
-            }
+            };
+            resolved
+                .shadowing(ConfigSource::ConfigFile, file_shown)
+                .shadowing(ConfigSource::EnvVar, env_shown)
         };
 
         let resolve_string_config = |cli: &Option<String>,
                                      file: &Option<String>,
                                      env_val: Option<String>|
          -> Option<ConfigValue<String>> {
-            if let Some(val) = cli {
+            let (file_shown, env_shown) = (file.clone(), env_val.clone());
+            let resolved = if let Some(val) = cli {
                 debug!("  Using value from CLI: {}", val);
                 Some(ConfigValue::from_cli(val.clone()))
             } else if let Some(val) = file {
@@ -197,7 +612,38 @@ impl TrackedConfig {
                 Some(ConfigValue::from_env(val))
             } else {
                 None
-            }
+            };
+            resolved.map(|value| {
+                value
+                    .shadowing(ConfigSource::ConfigFile, file_shown)
+                    .shadowing(ConfigSource::EnvVar, env_shown)
+            })
+        };
+
+        let resolve_list_config = |cli: &Vec<String>,
+                                   file: &Vec<String>,
+                                   env_val: Option<Vec<String>>|
+         -> Option<ConfigValue<Vec<String>>> {
+            let env_val = env_val.filter(|v| !v.is_empty());
+            let file_shown = (!file.is_empty()).then(|| file.join(", "));
+            let env_shown = env_val.as_ref().map(|v| v.join(", "));
+            let resolved = if !cli.is_empty() {
+                debug!("  Using value from CLI: {}", cli.join(", "));
+                Some(ConfigValue::from_cli(cli.clone()))
+            } else if !file.is_empty() {
+                debug!("  Using value from config file: {}", file.join(", "));
+                Some(ConfigValue::from_file(file.clone()))
+            } else if let Some(val) = env_val {
+                debug!("  Using value from environment: {}", val.join(", "));
+                Some(ConfigValue::from_env(val))
+            } else {
+                None
+            };
+            resolved.map(|value| {
+                value
+                    .shadowing(ConfigSource::ConfigFile, file_shown)
+                    .shadowing(ConfigSource::EnvVar, env_shown)
+            })
         };
 
         let resolve_bool_config = |cli: &Option<bool>,
@@ -205,7 +651,8 @@ impl TrackedConfig {
                                    env_val: Option<bool>,
                                    default: bool|
          -> ConfigValue<bool> {
-            if let Some(val) = cli {
+            let shown = |val: bool| if val { "yes" } else { "no" }.to_string();
+            let resolved = if let Some(val) = cli {
                 debug!("  Using value from CLI: {}", val);
                 ConfigValue::from_cli(*val)
             } else if let Some(val) = file {
@@ -217,21 +664,55 @@ impl TrackedConfig {
             } else {
                 debug!("  Using default value: {}", default);
                 ConfigValue::default_value(default)
-            }
+            };
+            resolved
+                .shadowing(ConfigSource::ConfigFile, file.map(shown))
+                .shadowing(ConfigSource::EnvVar, env_val.map(shown))
         };
 
-        let env_rubies_dir = std::env::var("RB_RUBIES_DIR").ok().map(PathBuf::from);
-        let env_ruby_version = std::env::var("RB_RUBY_VERSION").ok();
-        let env_gem_home = std::env::var("RB_GEM_HOME").ok().map(PathBuf::from);
-        let env_no_bundler = std::env::var("RB_NO_BUNDLER")
-            .ok()
-            .and_then(|v| v.parse::<bool>().ok());
-        let env_work_dir = std::env::var("RB_WORK_DIR").ok().map(PathBuf::from);
+        let env_rubies_dir = env_path("RB_RUBIES_DIR");
+        let env_additional_rubies_dirs = env("RB_ADDITIONAL_RUBIES_DIRS").map(|v| {
+            std::env::split_paths(&v)
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .map(|dir| expand_path(&dir))
+                .collect::<Vec<_>>()
+        });
+        let env_ruby_version = env("RB_RUBY_VERSION").map(|v| {
+            v.split(',')
+                .map(|version| version.trim().to_string())
+                .filter(|version| !version.is_empty())
+                .collect::<Vec<_>>()
+        });
+        let env_gem_home = env_path("RB_GEM_HOME");
+        let env_gemset = env("RB_GEMSET");
+        let env_default_ruby = env("RB_DEFAULT_RUBY");
+        let env_no_bundler = env("RB_NO_BUNDLER").and_then(|v| v.parse::<bool>().ok());
+        let env_work_dir = env_path("RB_WORK_DIR");
+        let env_include_prereleases =
+            env("RB_INCLUDE_PRERELEASES").and_then(|v| v.parse::<bool>().ok());
+        let env_interactive_select =
+            env("RB_INTERACTIVE_SELECT").and_then(|v| v.parse::<bool>().ok());
+        let env_auto_install_missing =
+            env("RB_AUTO_INSTALL_MISSING").and_then(|v| v.parse::<bool>().ok());
+        let env_keep_binstubs = env("RB_KEEP_BINSTUBS").and_then(|v| v.parse::<bool>().ok());
+        let env_inherit_gem_path = env("RB_INHERIT_GEM_PATH").and_then(|v| v.parse::<bool>().ok());
+        let env_include_site_ruby =
+            env("RB_INCLUDE_SITE_RUBY").and_then(|v| v.parse::<bool>().ok());
+        let env_no_bundle_exec = env("RB_NO_BUNDLE_EXEC").and_then(|v| v.parse::<bool>().ok());
+        let env_bundler_bin = env("RB_BUNDLER_BIN");
+        let env_exec_lock_retries = env("RB_EXEC_LOCK_RETRIES").and_then(|v| v.parse::<u32>().ok());
+        let env_exec_from_bundler_root =
+            env("RB_EXEC_FROM_BUNDLER_ROOT").and_then(|v| v.parse::<bool>().ok());
+        let env_shared_scripts = env("RB_SHARED_SCRIPTS").and_then(|v| v.parse::<bool>().ok());
+        let env_allow_system_ruby =
+            env("RB_ALLOW_SYSTEM_RUBY").and_then(|v| v.parse::<bool>().ok());
+        let env_create_gem_home = env("RB_CREATE_GEM_HOME").and_then(|v| v.parse::<bool>().ok());
+        let env_command_log = env_path("RB_COMMAND_LOG");
 
-        let default_rubies_dir = home::home_dir()
+        let default_rubies_dir = rb_core::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join(".rubies");
-        let default_gem_home = home::home_dir()
+        let default_gem_home = rb_core::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join(".gem");
         let default_work_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
@@ -244,8 +725,41 @@ impl TrackedConfig {
             default_rubies_dir,
         );
 
+        debug!("Resolving additional_rubies_dirs:");
+        let shown_dirs = |dirs: &Vec<PathBuf>| {
+            (!dirs.is_empty()).then(|| {
+                dirs.iter()
+                    .map(|dir| dir.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        };
+        let additional_file_shown = shown_dirs(&file_config.additional_rubies_dirs);
+        let additional_env_shown = env_additional_rubies_dirs.as_ref().and_then(shown_dirs);
+        let additional_rubies_dirs = if !cli_config.additional_rubies_dirs.is_empty() {
+            debug!(
+                "  Using value from CLI: {:?}",
+                cli_config.additional_rubies_dirs
+            );
+            ConfigValue::from_cli(cli_config.additional_rubies_dirs.clone())
+        } else if !file_config.additional_rubies_dirs.is_empty() {
+            debug!(
+                "  Using value from config file: {:?}",
+                file_config.additional_rubies_dirs
+            );
+            ConfigValue::from_file(file_config.additional_rubies_dirs.clone())
+        } else if let Some(dirs) = env_additional_rubies_dirs.filter(|dirs| !dirs.is_empty()) {
+            debug!("  Using value from environment: {:?}", dirs);
+            ConfigValue::from_env(dirs)
+        } else {
+            debug!("  Using default value: none");
+            ConfigValue::default_value(Vec::new())
+        }
+        .shadowing(ConfigSource::ConfigFile, additional_file_shown)
+        .shadowing(ConfigSource::EnvVar, additional_env_shown);
+
         debug!("Resolving ruby_version:");
-        let ruby_version = resolve_string_config(
+        let ruby_version = resolve_list_config(
             &cli_config.ruby_version,
             &file_config.ruby_version,
             env_ruby_version,
@@ -259,6 +773,16 @@ impl TrackedConfig {
             default_gem_home,
         );
 
+        debug!("Resolving gemset:");
+        let gemset = resolve_string_config(&cli_config.gemset, &file_config.gemset, env_gemset);
+
+        debug!("Resolving default_ruby:");
+        let default_ruby = resolve_string_config(
+            &cli_config.default_ruby,
+            &file_config.default_ruby,
+            env_default_ruby,
+        );
+
         debug!("Resolving no_bundler:");
         let no_bundler = resolve_bool_config(
             &cli_config.no_bundler,
@@ -275,12 +799,189 @@ impl TrackedConfig {
             default_work_dir,
         );
 
+        debug!("Resolving include_prereleases:");
+        let include_prereleases = resolve_bool_config(
+            &cli_config.include_prereleases,
+            &file_config.include_prereleases,
+            env_include_prereleases,
+            false,
+        );
+
+        debug!("Resolving interactive_select:");
+        let interactive_select = resolve_bool_config(
+            &cli_config.interactive_select,
+            &file_config.interactive_select,
+            env_interactive_select,
+            false,
+        );
+
+        debug!("Resolving auto_install_missing:");
+        let auto_install_missing = resolve_bool_config(
+            &cli_config.auto_install_missing,
+            &file_config.auto_install_missing,
+            env_auto_install_missing,
+            false,
+        );
+
+        debug!("Resolving keep_binstubs:");
+        let keep_binstubs = resolve_bool_config(
+            &cli_config.keep_binstubs,
+            &file_config.keep_binstubs,
+            env_keep_binstubs,
+            false,
+        );
+
+        debug!("Resolving inherit_gem_path:");
+        let inherit_gem_path = resolve_bool_config(
+            &cli_config.inherit_gem_path,
+            &file_config.inherit_gem_path,
+            env_inherit_gem_path,
+            false,
+        );
+
+        debug!("Resolving include_site_ruby:");
+        let include_site_ruby = resolve_bool_config(
+            &cli_config.include_site_ruby,
+            &file_config.include_site_ruby,
+            env_include_site_ruby,
+            false,
+        );
+
+        debug!("Resolving no_bundle_exec:");
+        let no_bundle_exec = resolve_bool_config(
+            &cli_config.no_bundle_exec,
+            &file_config.no_bundle_exec,
+            env_no_bundle_exec,
+            false,
+        );
+
+        debug!("Resolving bundler_bin:");
+        let bundler_bin = resolve_string_config(
+            &cli_config.bundler_bin,
+            &file_config.bundler_bin,
+            env_bundler_bin,
+        )
+        .unwrap_or_else(|| {
+            debug!("  Using default value: {}", DEFAULT_BUNDLER_BIN);
+            ConfigValue::default_value(DEFAULT_BUNDLER_BIN.to_string())
+        });
+
+        debug!("Resolving exec_lock_retries:");
+        let exec_lock_retries = if let Some(val) = cli_config.exec_lock_retries {
+            debug!("  Using value from CLI: {}", val);
+            ConfigValue::from_cli(val)
+        } else if let Some(val) = file_config.exec_lock_retries {
+            debug!("  Using value from config file: {}", val);
+            ConfigValue::from_file(val)
+        } else if let Some(val) = env_exec_lock_retries {
+            debug!("  Using value from environment: {}", val);
+            ConfigValue::from_env(val)
+        } else {
+            debug!("  Using default value: {}", DEFAULT_EXEC_LOCK_RETRIES);
+            ConfigValue::default_value(DEFAULT_EXEC_LOCK_RETRIES)
+        }
+        .shadowing(
+            ConfigSource::ConfigFile,
+            file_config.exec_lock_retries.map(|val| val.to_string()),
+        )
+        .shadowing(
+            ConfigSource::EnvVar,
+            env_exec_lock_retries.map(|val| val.to_string()),
+        );
+
+        debug!("Resolving exec_from_bundler_root:");
+        let exec_from_bundler_root = resolve_bool_config(
+            &cli_config.exec_from_bundler_root,
+            &file_config.exec_from_bundler_root,
+            env_exec_from_bundler_root,
+            false,
+        );
+
+        debug!("Resolving shared_scripts:");
+        let shared_scripts = resolve_bool_config(
+            &cli_config.shared_scripts,
+            &file_config.shared_scripts,
+            env_shared_scripts,
+            false,
+        );
+
+        debug!("Resolving allow_system_ruby:");
+        let allow_system_ruby = resolve_bool_config(
+            &cli_config.allow_system_ruby,
+            &file_config.allow_system_ruby,
+            env_allow_system_ruby,
+            false,
+        );
+
+        debug!("Resolving create_gem_home:");
+        let create_gem_home = resolve_bool_config(
+            &cli_config.create_gem_home,
+            &file_config.create_gem_home,
+            env_create_gem_home,
+            true,
+        );
+
+        debug!("Resolving command_log:");
+        let shown = |path: &PathBuf| path.display().to_string();
+        let command_log = if let Some(path) = &cli_config.command_log {
+            debug!("  Using value from CLI: {}", path.display());
+            Some(ConfigValue::from_cli(path.clone()))
+        } else if let Some(path) = &file_config.command_log {
+            debug!("  Using value from config file: {}", path.display());
+            Some(ConfigValue::from_file(path.clone()))
+        } else if let Some(path) = &env_command_log {
+            debug!("  Using value from environment: {}", path.display());
+            Some(ConfigValue::from_env(path.clone()))
+        } else {
+            None
+        }
+        .map(|value| {
+            value
+                .shadowing(
+                    ConfigSource::ConfigFile,
+                    file_config.command_log.as_ref().map(shown),
+                )
+                .shadowing(ConfigSource::EnvVar, env_command_log.as_ref().map(shown))
+        });
+
+        debug!("Resolving gem_bases:");
+        let gem_bases = if !cli_config.gem_bases.is_empty() {
+            debug!("  Using value from CLI: {:?}", cli_config.gem_bases);
+            Some(ConfigValue::from_cli(cli_config.gem_bases.clone()))
+        } else if !file_config.gem_bases.is_empty() {
+            debug!(
+                "  Using value from config file: {:?}",
+                file_config.gem_bases
+            );
+            Some(ConfigValue::from_file(file_config.gem_bases.clone()))
+        } else {
+            None
+        };
+
         Self {
             rubies_dir,
+            additional_rubies_dirs,
             ruby_version,
             gem_home,
+            gemset,
+            default_ruby,
             no_bundler,
             work_dir,
+            include_prereleases,
+            interactive_select,
+            auto_install_missing,
+            keep_binstubs,
+            inherit_gem_path,
+            include_site_ruby,
+            no_bundle_exec,
+            bundler_bin,
+            exec_lock_retries,
+            exec_from_bundler_root,
+            shared_scripts,
+            allow_system_ruby,
+            create_gem_home,
+            command_log,
+            gem_bases,
         }
     }
 
@@ -288,29 +989,168 @@ impl TrackedConfig {
     pub fn to_rb_config(&self) -> RbConfig {
         RbConfig {
             rubies_dir: Some(self.rubies_dir.value.clone()),
-            ruby_version: self.ruby_version.as_ref().map(|v| v.value.clone()),
+            additional_rubies_dirs: self.additional_rubies_dirs.value.clone(),
+            ruby_version: self
+                .ruby_version
+                .as_ref()
+                .map(|v| v.value.clone())
+                .unwrap_or_default(),
             gem_home: Some(self.gem_home.value.clone()),
+            gemset: self.gemset.as_ref().map(|v| v.value.clone()),
+            default_ruby: self.default_ruby.as_ref().map(|v| v.value.clone()),
             no_bundler: Some(self.no_bundler.value),
             work_dir: Some(self.work_dir.value.clone()),
+            include_prereleases: Some(self.include_prereleases.value),
+            interactive_select: Some(self.interactive_select.value),
+            auto_install_missing: Some(self.auto_install_missing.value),
+            keep_binstubs: Some(self.keep_binstubs.value),
+            inherit_gem_path: Some(self.inherit_gem_path.value),
+            include_site_ruby: Some(self.include_site_ruby.value),
+            no_bundle_exec: Some(self.no_bundle_exec.value),
+            bundler_bin: Some(self.bundler_bin.value.clone()),
+            exec_lock_retries: Some(self.exec_lock_retries.value),
+            exec_from_bundler_root: Some(self.exec_from_bundler_root.value),
+            shared_scripts: Some(self.shared_scripts.value),
+            allow_system_ruby: Some(self.allow_system_ruby.value),
+            create_gem_home: Some(self.create_gem_home.value),
+            command_log: self.command_log.as_ref().map(|v| v.value.clone()),
+            gem_bases: self
+                .gem_bases
+                .as_ref()
+                .map(|v| v.value.clone())
+                .unwrap_or_default(),
         }
     }
 
-    /// Get ruby_version for ButlerRuntime (returns None if unresolved)
-    pub fn ruby_version_for_runtime(&self) -> Option<String> {
+    /// `RB_*` variables that would reproduce every explicitly set value, in flag order
+    ///
+    /// Defaults and values resolved during discovery are left out, so the result only
+    /// captures what was actually configured.
+    pub fn to_env_vars(&self) -> Vec<(&'static str, String)> {
+        fn path(value: &ConfigValue<PathBuf>) -> Option<String> {
+            value
+                .is_explicit()
+                .then(|| value.get().display().to_string())
+        }
+        fn flag(value: &ConfigValue<bool>) -> Option<String> {
+            value.is_explicit().then(|| value.get().to_string())
+        }
+        fn text(value: &ConfigValue<String>) -> Option<String> {
+            value.is_explicit().then(|| value.get().clone())
+        }
+
+        [
+            ("RB_RUBIES_DIR", path(&self.rubies_dir)),
+            (
+                "RB_ADDITIONAL_RUBIES_DIRS",
+                self.additional_rubies_dirs
+                    .is_explicit()
+                    .then(|| std::env::join_paths(self.additional_rubies_dirs.get()).ok())
+                    .flatten()
+                    .map(|dirs| dirs.to_string_lossy().into_owned()),
+            ),
+            (
+                "RB_RUBY_VERSION",
+                self.ruby_version
+                    .as_ref()
+                    .filter(|v| v.is_explicit())
+                    .map(|v| v.get().join(",")),
+            ),
+            ("RB_GEM_HOME", path(&self.gem_home)),
+            ("RB_GEMSET", self.gemset.as_ref().and_then(text)),
+            ("RB_DEFAULT_RUBY", self.default_ruby.as_ref().and_then(text)),
+            ("RB_NO_BUNDLER", flag(&self.no_bundler)),
+            ("RB_WORK_DIR", path(&self.work_dir)),
+            ("RB_INCLUDE_PRERELEASES", flag(&self.include_prereleases)),
+            ("RB_INTERACTIVE_SELECT", flag(&self.interactive_select)),
+            ("RB_AUTO_INSTALL_MISSING", flag(&self.auto_install_missing)),
+            ("RB_KEEP_BINSTUBS", flag(&self.keep_binstubs)),
+            ("RB_INHERIT_GEM_PATH", flag(&self.inherit_gem_path)),
+            ("RB_INCLUDE_SITE_RUBY", flag(&self.include_site_ruby)),
+            ("RB_NO_BUNDLE_EXEC", flag(&self.no_bundle_exec)),
+            ("RB_BUNDLER_BIN", text(&self.bundler_bin)),
+            (
+                "RB_EXEC_LOCK_RETRIES",
+                self.exec_lock_retries
+                    .is_explicit()
+                    .then(|| self.exec_lock_retries.get().to_string()),
+            ),
+            (
+                "RB_EXEC_FROM_BUNDLER_ROOT",
+                flag(&self.exec_from_bundler_root),
+            ),
+            ("RB_SHARED_SCRIPTS", flag(&self.shared_scripts)),
+            ("RB_ALLOW_SYSTEM_RUBY", flag(&self.allow_system_ruby)),
+            ("RB_CREATE_GEM_HOME", flag(&self.create_gem_home)),
+            ("RB_COMMAND_LOG", self.command_log.as_ref().and_then(path)),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect()
+    }
+
+    /// Ruby selection preferences for ButlerRuntime discovery
+    pub fn selection_options(&self) -> SelectionOptions {
+        SelectionOptions {
+            include_prereleases: *self.include_prereleases.get(),
+            keep_binstubs: *self.keep_binstubs.get(),
+            inherit_gem_path: *self.inherit_gem_path.get(),
+            include_site_ruby: *self.include_site_ruby.get(),
+            no_bundle_exec: *self.no_bundle_exec.get(),
+            fallback_rubies: self.requested_rubies().into_iter().skip(1).collect(),
+            gemset: self.gemset.as_ref().map(|v| v.value.clone()),
+            additional_rubies_dirs: self.additional_rubies_dirs.get().clone(),
+            default_ruby: self.default_ruby.as_ref().map(|v| v.value.clone()),
+            allow_system_ruby: *self.allow_system_ruby.get(),
+            gem_bases: self.version_gem_bases(),
+        }
+    }
+
+    /// Per-version gem bases, unless `--gem-home` or `RB_GEM_HOME` overrides them all
+    ///
+    /// A `gem-home` in the config file is only the fallback for Rubies no base matches.
+    fn version_gem_bases(&self) -> Vec<(String, PathBuf)> {
+        if matches!(
+            self.gem_home.source,
+            ConfigSource::Cli | ConfigSource::EnvVar
+        ) {
+            return Vec::new();
+        }
+        self.gem_bases
+            .as_ref()
+            .map(|bases| bases.get().clone().into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// File that `rb exec` and `rb run` append their JSON-lines entries to, when configured
+    pub fn command_log_path(&self) -> Option<PathBuf> {
+        self.command_log.as_ref().map(|v| v.get().clone())
+    }
+
+    /// Requested Ruby versions in order of preference (empty if unresolved)
+    fn requested_rubies(&self) -> Vec<String> {
         self.ruby_version
             .as_ref()
             .filter(|v| !v.is_unresolved())
             .map(|v| v.value.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the preferred ruby_version for ButlerRuntime (returns None if unresolved)
+    ///
+    /// Further requested versions reach the runtime through `selection_options`.
+    pub fn ruby_version_for_runtime(&self) -> Option<String> {
+        self.requested_rubies().into_iter().next()
     }
 
     /// Update ruby_version with resolved value from ButlerRuntime
     pub fn resolve_ruby_version(&mut self, resolved_version: String) {
         if let Some(ref mut version) = self.ruby_version {
             if version.is_unresolved() {
-                version.resolve(resolved_version);
+                version.resolve(vec![resolved_version]);
             }
         } else {
-            self.ruby_version = Some(ConfigValue::resolved(resolved_version));
+            self.ruby_version = Some(ConfigValue::resolved(vec![resolved_version]));
         }
     }
 
@@ -326,6 +1166,7 @@ impl TrackedConfig {
 pub enum ConfigError {
     IoError(std::io::Error),
     ParseError(toml::de::Error),
+    UnknownKey(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -333,6 +1174,11 @@ impl std::fmt::Display for ConfigError {
         match self {
             ConfigError::IoError(e) => write!(f, "Failed to read configuration file: {}", e),
             ConfigError::ParseError(e) => write!(f, "Failed to parse configuration file: {}", e),
+            ConfigError::UnknownKey(key) => write!(
+                f,
+                "Unknown configuration key '{}' (strict configuration is enabled)",
+                key
+            ),
         }
     }
 }
@@ -360,16 +1206,15 @@ mod tests {
         let mut cli_config = RbConfig::default();
         let file_config = RbConfig {
             rubies_dir: Some(PathBuf::from("/test/rubies")),
-            ruby_version: Some("3.3.0".to_string()),
+            ruby_version: vec!["3.3.0".to_string()],
             gem_home: Some(PathBuf::from("/test/gems")),
-            no_bundler: None,
-            work_dir: None,
+            ..Default::default()
         };
 
         cli_config.merge_with(file_config);
 
         assert_eq!(cli_config.rubies_dir, Some(PathBuf::from("/test/rubies")));
-        assert_eq!(cli_config.ruby_version, Some("3.3.0".to_string()));
+        assert_eq!(cli_config.ruby_version, vec!["3.3.0"]);
         assert_eq!(cli_config.gem_home, Some(PathBuf::from("/test/gems")));
         assert_eq!(cli_config.no_bundler, None);
     }
@@ -378,24 +1223,23 @@ mod tests {
     fn test_merge_with_cli_takes_precedence() {
         let mut cli_config = RbConfig {
             rubies_dir: Some(PathBuf::from("/cli/rubies")),
-            ruby_version: Some("3.2.0".to_string()),
+            ruby_version: vec!["3.2.0".to_string()],
             gem_home: None,
-            no_bundler: None,
-            work_dir: None,
+            ..Default::default()
         };
         let file_config = RbConfig {
             rubies_dir: Some(PathBuf::from("/file/rubies")),
-            ruby_version: Some("3.3.0".to_string()),
+            ruby_version: vec!["3.3.0".to_string()],
             gem_home: Some(PathBuf::from("/file/gems")),
             no_bundler: Some(true),
-            work_dir: None,
+            ..Default::default()
         };
 
         cli_config.merge_with(file_config);
 
         // CLI values should be preserved
         assert_eq!(cli_config.rubies_dir, Some(PathBuf::from("/cli/rubies")));
-        assert_eq!(cli_config.ruby_version, Some("3.2.0".to_string()));
+        assert_eq!(cli_config.ruby_version, vec!["3.2.0"]);
         // File value should fill in missing CLI value
         assert_eq!(cli_config.gem_home, Some(PathBuf::from("/file/gems")));
         assert_eq!(cli_config.no_bundler, Some(true));
@@ -405,23 +1249,21 @@ mod tests {
     fn test_merge_with_partial_file_config() {
         let mut cli_config = RbConfig {
             rubies_dir: None,
-            ruby_version: Some("3.2.0".to_string()),
+            ruby_version: vec!["3.2.0".to_string()],
             gem_home: None,
-            no_bundler: None,
-            work_dir: None,
+            ..Default::default()
         };
         let file_config = RbConfig {
             rubies_dir: Some(PathBuf::from("/file/rubies")),
-            ruby_version: None,
+            ruby_version: vec![],
             gem_home: Some(PathBuf::from("/file/gems")),
-            no_bundler: None,
-            work_dir: None,
+            ..Default::default()
         };
 
         cli_config.merge_with(file_config);
 
         assert_eq!(cli_config.rubies_dir, Some(PathBuf::from("/file/rubies")));
-        assert_eq!(cli_config.ruby_version, Some("3.2.0".to_string()));
+        assert_eq!(cli_config.ruby_version, vec!["3.2.0"]);
         assert_eq!(cli_config.gem_home, Some(PathBuf::from("/file/gems")));
         assert_eq!(cli_config.no_bundler, None);
     }
@@ -437,18 +1279,44 @@ mod tests {
         let config: RbConfig = toml::from_str(toml_str).expect("Failed to parse TOML");
 
         assert_eq!(config.rubies_dir, Some(PathBuf::from("/opt/rubies")));
-        assert_eq!(config.ruby_version, Some("3.3.0".to_string()));
+        assert_eq!(config.ruby_version, vec!["3.3.0"]);
         assert_eq!(config.gem_home, Some(PathBuf::from("/opt/gems")));
     }
 
+    #[test]
+    fn test_ruby_version_accepts_a_list_of_preferences() {
+        let config: RbConfig =
+            toml::from_str(r#"ruby-version = ["3.3.4", "3.2.5"]"#).expect("Failed to parse TOML");
+        assert_eq!(config.ruby_version, vec!["3.3.4", "3.2.5"]);
+
+        let tracked = TrackedConfig::from_merged(&RbConfig::default(), &config);
+        assert_eq!(tracked.ruby_version_for_runtime().as_deref(), Some("3.3.4"));
+        assert_eq!(tracked.selection_options().fallback_rubies, vec!["3.2.5"]);
+    }
+
+    #[test]
+    fn test_repeated_ruby_flags_keep_their_order() {
+        use clap::Parser;
+
+        let cli =
+            crate::Cli::try_parse_from(["rb", "-r", "3.3.4", "-r", "3.2.5", "info", "config"])
+                .expect("Failed to parse arguments");
+        assert_eq!(cli.config.ruby_version, vec!["3.3.4", "3.2.5"]);
+
+        let single = crate::Cli::try_parse_from(["rb", "--ruby", "3.3.4", "info", "config"])
+            .expect("Failed to parse arguments");
+        let tracked = TrackedConfig::from_merged(&single.config, &RbConfig::default());
+        assert_eq!(tracked.ruby_version_for_runtime().as_deref(), Some("3.3.4"));
+        assert!(tracked.selection_options().fallback_rubies.is_empty());
+    }
+
     #[test]
     fn test_toml_serialization() {
         let config = RbConfig {
             rubies_dir: Some(PathBuf::from("/opt/rubies")),
-            ruby_version: Some("3.3.0".to_string()),
+            ruby_version: vec!["3.3.0".to_string()],
             gem_home: Some(PathBuf::from("/opt/gems")),
-            no_bundler: None,
-            work_dir: None,
+            ..Default::default()
         };
 
         let toml_str = toml::to_string(&config).expect("Failed to serialize to TOML");
@@ -460,4 +1328,289 @@ mod tests {
         assert!(toml_str.contains("gem-home"));
         assert!(toml_str.contains("/opt/gems"));
     }
+
+    #[test]
+    fn test_include_prereleases_from_file_config() {
+        let file_config: RbConfig =
+            toml::from_str("include-prereleases = true").expect("Failed to parse TOML");
+
+        let tracked = TrackedConfig::from_merged(&RbConfig::default(), &file_config);
+
+        assert!(*tracked.include_prereleases.get());
+        assert!(tracked.selection_options().include_prereleases);
+    }
+
+    #[test]
+    fn test_interactive_select_from_file_config() {
+        let file_config: RbConfig =
+            toml::from_str("interactive-select = true").expect("Failed to parse TOML");
+        let tracked = TrackedConfig::from_merged(&RbConfig::default(), &file_config);
+
+        assert!(*tracked.interactive_select.get());
+        assert!(
+            !*TrackedConfig::from_merged(&RbConfig::default(), &RbConfig::default())
+                .interactive_select
+                .get()
+        );
+    }
+
+    #[test]
+    fn test_auto_install_missing_from_file_config() {
+        let file_config: RbConfig =
+            toml::from_str("auto-install-missing = true").expect("Failed to parse TOML");
+        let tracked = TrackedConfig::from_merged(&RbConfig::default(), &file_config);
+
+        assert!(*tracked.auto_install_missing.get());
+        assert_eq!(tracked.to_rb_config().auto_install_missing, Some(true));
+    }
+
+    #[test]
+    fn test_bundler_bin_from_file_config() {
+        let file_config: RbConfig =
+            toml::from_str("bundler-bin = \"bundler\"").expect("Failed to parse TOML");
+        let tracked = TrackedConfig::from_merged(&RbConfig::default(), &file_config);
+
+        assert_eq!(tracked.bundler_bin.get(), "bundler");
+        assert_eq!(tracked.bundler_bin.source, ConfigSource::ConfigFile);
+    }
+
+    #[test]
+    fn test_expands_paths_from_env_and_cli() {
+        let home = rb_core::home_dir().expect("home directory");
+        let tracked = TrackedConfig::from_merged_with_env(
+            &RbConfig::default(),
+            &RbConfig::default(),
+            |name| match name {
+                "RB_RUBIES_DIR" => Some("~/rubies-xyz".to_string()),
+                "RB_COMMAND_LOG" => Some("${RB_TEST_UNSET_VAR}/log".to_string()),
+                _ => None,
+            },
+        );
+        assert_eq!(*tracked.rubies_dir.get(), home.join("rubies-xyz"));
+        assert_eq!(tracked.rubies_dir.source, ConfigSource::EnvVar);
+        // Unset variables stay as written
+        assert_eq!(
+            tracked.command_log.unwrap().get(),
+            &PathBuf::from("${RB_TEST_UNSET_VAR}/log")
+        );
+
+        let cli_config = RbConfig {
+            gem_home: Some(PathBuf::from("~/gems")),
+            additional_rubies_dirs: vec![PathBuf::from("~/more-rubies")],
+            ..RbConfig::default()
+        };
+        let tracked =
+            TrackedConfig::from_merged_with_env(&cli_config, &RbConfig::default(), |_| None);
+        assert_eq!(*tracked.gem_home.get(), home.join("gems"));
+        assert_eq!(
+            *tracked.additional_rubies_dirs.get(),
+            vec![home.join("more-rubies")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expands_variables_in_env_paths() {
+        let home_var = std::env::var("HOME").expect("HOME is set");
+        let tracked = TrackedConfig::from_merged_with_env(
+            &RbConfig::default(),
+            &RbConfig::default(),
+            |name| (name == "RB_RUBIES_DIR").then(|| "${HOME}/rubies-xyz".to_string()),
+        );
+        assert_eq!(
+            *tracked.rubies_dir.get(),
+            PathBuf::from(home_var).join("rubies-xyz")
+        );
+    }
+
+    #[test]
+    fn test_exec_lock_retries_defaults_and_reads_env() {
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &RbConfig::default(), |_| {
+                None
+            });
+        assert_eq!(*tracked.exec_lock_retries.get(), DEFAULT_EXEC_LOCK_RETRIES);
+        assert_eq!(tracked.exec_lock_retries.source, ConfigSource::Default);
+
+        let tracked = TrackedConfig::from_merged_with_env(
+            &RbConfig::default(),
+            &RbConfig::default(),
+            |name| (name == "RB_EXEC_LOCK_RETRIES").then(|| "0".to_string()),
+        );
+        assert_eq!(*tracked.exec_lock_retries.get(), 0);
+        assert_eq!(tracked.exec_lock_retries.source, ConfigSource::EnvVar);
+    }
+
+    #[test]
+    fn test_create_gem_home_defaults_on_and_can_be_disabled() {
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &RbConfig::default(), |_| {
+                None
+            });
+        assert!(*tracked.create_gem_home.get());
+        assert!(tracked.to_env_vars().is_empty());
+
+        let tracked = TrackedConfig::from_merged_with_env(
+            &RbConfig::default(),
+            &RbConfig::default(),
+            |name| (name == "RB_CREATE_GEM_HOME").then(|| "false".to_string()),
+        );
+        assert!(!*tracked.create_gem_home.get());
+        assert_eq!(
+            tracked.to_env_vars(),
+            vec![("RB_CREATE_GEM_HOME", "false".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_additional_rubies_dirs_reach_selection_options() {
+        let file_config: RbConfig = toml::from_str("additional-rubies-dirs = [\"/opt/rubies\"]")
+            .expect("Failed to parse TOML");
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &file_config, |_| None);
+        assert_eq!(
+            tracked.additional_rubies_dirs.source,
+            ConfigSource::ConfigFile
+        );
+        assert_eq!(
+            tracked.selection_options().additional_rubies_dirs,
+            vec![PathBuf::from("/opt/rubies")]
+        );
+
+        let joined = std::env::join_paths(["/opt/rubies", "/srv/rubies"]).unwrap();
+        let tracked = TrackedConfig::from_merged_with_env(
+            &RbConfig::default(),
+            &RbConfig::default(),
+            |name| {
+                (name == "RB_ADDITIONAL_RUBIES_DIRS").then(|| joined.to_string_lossy().into_owned())
+            },
+        );
+        assert_eq!(
+            tracked.additional_rubies_dirs.get(),
+            &vec![PathBuf::from("/opt/rubies"), PathBuf::from("/srv/rubies")]
+        );
+        assert_eq!(
+            tracked.to_env_vars(),
+            vec![(
+                "RB_ADDITIONAL_RUBIES_DIRS",
+                joined.to_string_lossy().into_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_gem_bases_reach_selection_options_unless_gem_home_given_on_cli_or_env() {
+        let file_config: RbConfig =
+            toml::from_str("gem-home = \"/gems\"\n[gems]\n\"3.3.*\" = \"/fast-ssd/gems\"\n")
+                .expect("Failed to parse TOML");
+
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &file_config, |_| None);
+        assert_eq!(
+            tracked.selection_options().gem_bases,
+            vec![("3.3.*".to_string(), PathBuf::from("/fast-ssd/gems"))]
+        );
+        assert_eq!(tracked.to_rb_config().gem_bases, file_config.gem_bases);
+
+        let cli_config = RbConfig {
+            gem_home: Some(PathBuf::from("/tmp/gems")),
+            ..Default::default()
+        };
+        let tracked = TrackedConfig::from_merged_with_env(&cli_config, &file_config, |_| None);
+        assert!(tracked.selection_options().gem_bases.is_empty());
+
+        let file_config: RbConfig = toml::from_str("[gems]\n\"3.3.*\" = \"/fast-ssd/gems\"\n")
+            .expect("Failed to parse TOML");
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &file_config, |name| {
+                (name == "RB_GEM_HOME").then(|| "/env/gems".to_string())
+            });
+        assert_eq!(tracked.gem_home.source, ConfigSource::EnvVar);
+        assert!(tracked.selection_options().gem_bases.is_empty());
+    }
+
+    #[test]
+    fn test_gemset_reaches_selection_options() {
+        use clap::Parser;
+
+        let file_config: RbConfig =
+            toml::from_str("gemset = \"testing\"").expect("Failed to parse TOML");
+        let tracked = TrackedConfig::from_merged(&RbConfig::default(), &file_config);
+
+        let gemset = tracked.gemset.as_ref().expect("gemset should be resolved");
+        assert_eq!(gemset.get(), "testing");
+        assert_eq!(gemset.source, ConfigSource::ConfigFile);
+        assert_eq!(
+            tracked.selection_options().gemset.as_deref(),
+            Some("testing")
+        );
+
+        let cli = crate::Cli::try_parse_from(["rb", "-g", "ci", "info", "config"])
+            .expect("Failed to parse arguments");
+        assert_eq!(cli.config.gemset.as_deref(), Some("ci"));
+    }
+
+    #[test]
+    fn test_env_vars_reproduce_effective_config() {
+        let file_config: RbConfig = toml::from_str(
+            r#"
+rubies-dir = "/opt/rubies"
+ruby-version = ["3.3.4", "3.2.5"]
+gemset = "ci"
+keep-binstubs = true
+bundler-bin = "bundler"
+"#,
+        )
+        .expect("Failed to parse TOML");
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &file_config, |name| {
+                (name == "RB_NO_BUNDLER").then(|| "true".to_string())
+            });
+
+        let vars = tracked.to_env_vars();
+        let names: Vec<&str> = vars.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                "RB_RUBIES_DIR",
+                "RB_RUBY_VERSION",
+                "RB_GEMSET",
+                "RB_NO_BUNDLER",
+                "RB_KEEP_BINSTUBS",
+                "RB_BUNDLER_BIN"
+            ]
+        );
+
+        let lines: Vec<String> = vars
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        let reparsed = TrackedConfig::from_merged_with_env(
+            &RbConfig::default(),
+            &RbConfig::default(),
+            |name| {
+                lines.iter().find_map(|line| {
+                    line.strip_prefix(name)
+                        .and_then(|rest| rest.strip_prefix('='))
+                        .map(str::to_string)
+                })
+            },
+        );
+
+        assert_eq!(
+            toml::to_string(&reparsed.to_rb_config()).unwrap(),
+            toml::to_string(&tracked.to_rb_config()).unwrap()
+        );
+        assert_eq!(reparsed.to_env_vars(), vars);
+    }
+
+    #[test]
+    fn test_env_vars_skip_defaults() {
+        let tracked =
+            TrackedConfig::from_merged_with_env(&RbConfig::default(), &RbConfig::default(), |_| {
+                None
+            });
+
+        assert!(tracked.to_env_vars().is_empty());
+    }
 }
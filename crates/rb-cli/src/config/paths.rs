@@ -0,0 +1,129 @@
+use super::RbConfig;
+use super::locator::{EnvReader, StdEnvReader};
+use std::path::{Path, PathBuf};
+
+/// Expand a leading `~` and any `$VAR` or `${VAR}` in a configured path
+///
+/// `~` is the home directory Butler uses for its defaults (honouring `RB_HOME`).
+/// Unset variables, and paths that are not valid UTF-8, are left as written.
+pub fn expand_path(path: &Path) -> PathBuf {
+    expand_path_with(path, rb_core::home_dir(), &StdEnvReader)
+}
+
+/// Expand `~` and environment variables in every path setting of `config`
+pub fn expand_config_paths(config: &mut RbConfig) {
+    for path in [
+        &mut config.rubies_dir,
+        &mut config.gem_home,
+        &mut config.work_dir,
+        &mut config.command_log,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        *path = expand_path(path);
+    }
+    for dir in &mut config.additional_rubies_dirs {
+        *dir = expand_path(dir);
+    }
+    for base in config.gem_bases.values_mut() {
+        *base = expand_path(base);
+    }
+}
+
+fn expand_path_with(path: &Path, home: Option<PathBuf>, env: &dyn EnvReader) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path.to_path_buf();
+    };
+
+    let expanded = expand_variables(text, env);
+    match (expanded.strip_prefix('~'), home) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with(['/', '\\']) => home.join(&rest[1..]),
+        _ => PathBuf::from(expanded),
+    }
+}
+
+/// Replace `$VAR` and `${VAR}` with their values, keeping unset ones verbatim
+fn expand_variables(text: &str, env: &dyn EnvReader) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        let (name, consumed) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+
+        match env.var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[dollar..dollar + 1 + consumed]),
+        }
+        rest = &after[consumed..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MapEnv(HashMap<&'static str, &'static str>);
+
+    impl EnvReader for MapEnv {
+        fn var(&self, key: &str) -> Result<String, std::env::VarError> {
+            self.0
+                .get(key)
+                .map(|v| v.to_string())
+                .ok_or(std::env::VarError::NotPresent)
+        }
+    }
+
+    fn expand(path: &str) -> PathBuf {
+        let env = MapEnv(HashMap::from([
+            ("HOME", "/home/alex"),
+            ("RUBIES", "rubies"),
+        ]));
+        expand_path_with(Path::new(path), Some(PathBuf::from("/home/alex")), &env)
+    }
+
+    #[test]
+    fn expands_tilde() {
+        assert_eq!(expand("~/x"), PathBuf::from("/home/alex/x"));
+        assert_eq!(expand("~"), PathBuf::from("/home/alex"));
+        assert_eq!(expand("~alex/x"), PathBuf::from("~alex/x"));
+    }
+
+    #[test]
+    fn expands_variables_with_and_without_braces() {
+        assert_eq!(expand("$HOME/x"), PathBuf::from("/home/alex/x"));
+        assert_eq!(expand("${HOME}/x"), PathBuf::from("/home/alex/x"));
+        assert_eq!(
+            expand("/opt/${RUBIES}/3.3"),
+            PathBuf::from("/opt/rubies/3.3")
+        );
+    }
+
+    #[test]
+    fn leaves_plain_paths_and_unset_variables_alone() {
+        assert_eq!(expand("/opt/rubies"), PathBuf::from("/opt/rubies"));
+        assert_eq!(expand("$UNSET/x"), PathBuf::from("$UNSET/x"));
+        assert_eq!(expand("${UNSET}/x"), PathBuf::from("${UNSET}/x"));
+        assert_eq!(expand("/price$"), PathBuf::from("/price$"));
+        assert_eq!(expand("${HOME"), PathBuf::from("${HOME"));
+    }
+}
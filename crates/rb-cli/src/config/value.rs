@@ -35,55 +35,43 @@ impl fmt::Display for ConfigSource {
 pub struct ConfigValue<T> {
     pub value: T,
     pub source: ConfigSource,
+    /// Values lower-priority sources also set, which this one overrides
+    pub shadowed: Vec<(ConfigSource, String)>,
 }
 
 impl<T> ConfigValue<T> {
     pub fn new(value: T, source: ConfigSource) -> Self {
-        Self { value, source }
-    }
-
-    pub fn default_value(value: T) -> Self {
         Self {
             value,
-            source: ConfigSource::Default,
+            source,
+            shadowed: Vec::new(),
         }
     }
 
+    pub fn default_value(value: T) -> Self {
+        Self::new(value, ConfigSource::Default)
+    }
+
     pub fn from_env(value: T) -> Self {
-        Self {
-            value,
-            source: ConfigSource::EnvVar,
-        }
+        Self::new(value, ConfigSource::EnvVar)
     }
 
     pub fn from_file(value: T) -> Self {
-        Self {
-            value,
-            source: ConfigSource::ConfigFile,
-        }
+        Self::new(value, ConfigSource::ConfigFile)
     }
 
     pub fn from_cli(value: T) -> Self {
-        Self {
-            value,
-            source: ConfigSource::Cli,
-        }
+        Self::new(value, ConfigSource::Cli)
     }
 
     /// Create an unresolved value (placeholder for later resolution)
     pub fn unresolved(value: T) -> Self {
-        Self {
-            value,
-            source: ConfigSource::Unresolved,
-        }
+        Self::new(value, ConfigSource::Unresolved)
     }
 
     /// Mark value as resolved during environment discovery
     pub fn resolved(value: T) -> Self {
-        Self {
-            value,
-            source: ConfigSource::Resolved,
-        }
+        Self::new(value, ConfigSource::Resolved)
     }
 
     pub fn is_unresolved(&self) -> bool {
@@ -134,7 +122,18 @@ impl<T> ConfigValue<T> {
         ConfigValue {
             value: f(self.value),
             source: self.source,
+            shadowed: self.shadowed,
+        }
+    }
+
+    /// Note that `source` also set `value`, when that source ranks below this one
+    pub fn shadowing(mut self, source: ConfigSource, value: Option<String>) -> Self {
+        if let Some(value) = value
+            && source.priority() < self.source.priority()
+        {
+            self.shadowed.push((source, value));
         }
+        self
     }
 
     /// Update value only if new source has higher priority
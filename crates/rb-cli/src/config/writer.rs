@@ -0,0 +1,168 @@
+//! Changing single settings in the configuration file, leaving the rest as written
+
+use super::ConfigError;
+use log::debug;
+use std::fs;
+use std::path::Path;
+
+/// Set the string setting `key` to `value` in the TOML or KDL config at `path`
+///
+/// The file and its directory are created when missing. Other settings, comments and
+/// layout are kept; only the line (or node) for `key` is replaced or added.
+pub fn set_string(path: &Path, key: &str, value: &str) -> Result<(), ConfigError> {
+    let existing = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let is_kdl = path.extension().is_some_and(|ext| ext == "kdl");
+    let updated = if is_kdl {
+        set_kdl_string(&existing, key, value)?
+    } else {
+        set_toml_string(&existing, key, value)?
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    debug!("Writing {} to {}", key, path.display());
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Replace the top-level `key = ...` line, or add one ahead of the first table
+fn set_toml_string(content: &str, key: &str, value: &str) -> Result<String, ConfigError> {
+    let assignment = format!("{} = {}", key, toml::Value::String(value.to_string()));
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    let mut in_table = false;
+    let mut skipping_array = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if skipping_array {
+            skipping_array = !trimmed.trim_end().ends_with(']');
+            continue;
+        }
+        if trimmed.starts_with('[') {
+            if !replaced {
+                lines.push(assignment.clone());
+                replaced = true;
+            }
+            in_table = true;
+        }
+        let assigns_key = trimmed
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='));
+        if !in_table && assigns_key {
+            if !replaced {
+                lines.push(assignment.clone());
+                replaced = true;
+            }
+            // A multi-line array continues until its closing bracket
+            let value_part = trimmed.split_once('=').map_or("", |(_, v)| v.trim());
+            skipping_array = value_part.starts_with('[') && !value_part.ends_with(']');
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    if !replaced {
+        lines.push(assignment);
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    toml::from_str::<toml::Table>(&updated).map_err(ConfigError::ParseError)?;
+    Ok(updated)
+}
+
+/// Replace the arguments of the `key` node, or append the node
+fn set_kdl_string(content: &str, key: &str, value: &str) -> Result<String, ConfigError> {
+    let mut doc: kdl::KdlDocument = content.parse().map_err(|e: kdl::KdlError| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Failed to parse KDL: {}", e),
+        )
+    })?;
+
+    let mut node = kdl::KdlNode::new(key);
+    node.push(kdl::KdlEntry::new(value));
+    node.autoformat();
+
+    let nodes = doc.nodes_mut();
+    match nodes.iter().position(|n| n.name().value() == key) {
+        Some(index) => nodes[index] = node,
+        None => nodes.push(node),
+    }
+
+    let mut updated = doc.to_string();
+    if !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toml_value_is_added_before_tables_and_replaced_in_place() {
+        let original = "# my settings\nrubies-dir = \"/opt/rubies\"\n\n[extra]\nname = \"x\"\n";
+
+        let added = set_toml_string(original, "default-ruby", "3.2.5").unwrap();
+        assert_eq!(
+            added,
+            "# my settings\nrubies-dir = \"/opt/rubies\"\n\ndefault-ruby = \"3.2.5\"\n[extra]\nname = \"x\"\n"
+        );
+
+        let replaced = set_toml_string(&added, "default-ruby", "3.3.0").unwrap();
+        assert!(replaced.contains("default-ruby = \"3.3.0\""));
+        assert!(!replaced.contains("3.2.5"));
+        assert!(replaced.starts_with("# my settings\n"));
+    }
+
+    #[test]
+    fn test_toml_multiline_array_is_replaced_whole() {
+        let original = "ruby-version = [\n  \"3.3.0\",\n  \"3.2.5\",\n]\ngemset = \"ci\"\n";
+
+        let updated = set_toml_string(original, "ruby-version", "3.4.1").unwrap();
+
+        assert_eq!(updated, "ruby-version = \"3.4.1\"\ngemset = \"ci\"\n");
+    }
+
+    #[test]
+    fn test_kdl_node_is_replaced_or_appended() {
+        let original = "// my settings\nrubies-dir \"/opt/rubies\"\n";
+
+        let added = set_kdl_string(original, "default-ruby", "3.2.5").unwrap();
+        let doc: kdl::KdlDocument = added.parse().unwrap();
+        assert!(added.starts_with("// my settings\n"));
+        assert_eq!(
+            doc.get_arg("default-ruby").and_then(|v| v.as_string()),
+            Some("3.2.5")
+        );
+
+        let replaced = set_kdl_string(&added, "default-ruby", "3.3.0").unwrap();
+        let doc: kdl::KdlDocument = replaced.parse().unwrap();
+        assert_eq!(doc.nodes().len(), 2);
+        assert_eq!(
+            doc.get_arg("default-ruby").and_then(|v| v.as_string()),
+            Some("3.3.0")
+        );
+    }
+
+    #[test]
+    fn test_set_string_creates_missing_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("rb").join("rb.toml");
+
+        set_string(&path, "default-ruby", "3.2.5").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "default-ruby = \"3.2.5\"\n"
+        );
+    }
+}
@@ -1,15 +1,22 @@
 use crate::Commands;
 use crate::InfoCommands;
+use crate::commands::info::env::{environment_command, watch_environment};
 use crate::commands::info::info_config_command;
+use crate::commands::info::runtime::set_default_ruby_command;
 use crate::commands::{
-    exec_command, help_command, info_command, run_command, sync_command, version_command,
+    ExecOptions, RunOptions, SyncOptions, deps_command, doctor_command, exec_command,
+    gem_clean_command, help_command, info_command, platform_command, resolve_script_reference,
+    run_command, subshell_command, sync_command, version_command,
 };
+use crate::config::locator::config_file_for_writing;
+use crate::output::StdoutOutput;
 use crate::runtime_helpers::CommandContext;
 use rb_core::butler::ButlerError;
 
 use crate::runtime_helpers::{
-    bash_complete_command, new_command_wrapper, shell_integration_command_wrapper,
-    with_butler_runtime,
+    bash_complete_command, completions_refresh_command_wrapper, json_complete_command,
+    new_command_wrapper, pin_command_wrapper, shell_integration_command_wrapper,
+    unpin_command_wrapper, with_butler_runtime,
 };
 
 /// Dispatch command to appropriate handler
@@ -20,23 +27,142 @@ pub fn dispatch_command(
     match command {
         Commands::Version => version_command(),
         Commands::Help { command: help_cmd } => help_command(help_cmd),
-        Commands::New => new_command_wrapper(),
+        Commands::New {
+            filename,
+            dry_run,
+            force,
+            check,
+        } => new_command_wrapper(&filename, dry_run, force, check),
+        Commands::Pin { version } => pin_command_wrapper(&version),
+        Commands::Unpin => unpin_command_wrapper(),
         Commands::ShellIntegration { shell } => shell_integration_command_wrapper(shell),
         Commands::BashComplete { line, point } => bash_complete_command(context, &line, &point),
+        Commands::CompleteJson { line, point } => json_complete_command(context, &line, &point),
+        Commands::CompletionsRefresh => completions_refresh_command_wrapper(context),
 
-        Commands::Run { script, args } => {
+        Commands::Run {
+            script,
+            keep_going,
+            graph,
+            watch,
+            prefix,
+            measure,
+            env,
+            args,
+        } => {
             let project_file = context.project_file.clone();
+            let options = RunOptions {
+                env,
+                graph,
+                watch,
+                shared_scripts: *context.config.shared_scripts.get(),
+                create_gem_home: *context.config.create_gem_home.get(),
+                prefix,
+                command_log: context.config.command_log_path(),
+                measure,
+                keep_going,
+            };
             with_butler_runtime(context, |runtime| {
-                run_command(runtime.clone(), script, args, project_file)
+                run_command(runtime.clone(), script, args, project_file, &options)
             })
         }
-        Commands::Exec { args } => {
-            with_butler_runtime(context, |runtime| exec_command(runtime.clone(), args))
+        Commands::Exec {
+            clean_env,
+            print_path,
+            capture,
+            capture_stderr,
+            login,
+            allow_system,
+            measure,
+            env,
+            args,
+        } => {
+            let options = ExecOptions {
+                clean_env,
+                print_path,
+                env,
+                capture,
+                capture_stderr,
+                lock_retries: *context.config.exec_lock_retries.get(),
+                create_gem_home: *context.config.create_gem_home.get(),
+                login,
+                // Project scripts may call system tools, as they do under `rb run`
+                refuse_system: !args.first().is_some_and(|first| first.starts_with('@')),
+                allow_system,
+                from_bundler_root: *context.config.exec_from_bundler_root.get(),
+                command_log: context.config.command_log_path(),
+                measure,
+            };
+            let project_file = context.project_file.clone();
+            let shared_scripts = *context.config.shared_scripts.get();
+            with_butler_runtime(context, |runtime| {
+                let args = resolve_script_reference(runtime, args, project_file, shared_scripts)?;
+                exec_command(runtime.clone(), args, &options)
+            })
+        }
+        Commands::Sync {
+            retry,
+            jobs,
+            path,
+            deployment,
+            redownload,
+        } => {
+            let project_file = context.project_file.clone();
+            with_butler_runtime(context, |runtime| {
+                let options = SyncOptions {
+                    retries: retry,
+                    jobs,
+                    vendor_path: path,
+                    deployment,
+                    redownload,
+                };
+                sync_command(runtime.clone(), &options, project_file, &mut StdoutOutput)
+            })
+        }
+
+        Commands::GemClean { dry_run } => {
+            with_butler_runtime(context, |runtime| gem_clean_command(runtime, dry_run))
+        }
+
+        Commands::Platform { command } => with_butler_runtime(context, |runtime| {
+            platform_command(runtime, &command, &mut StdoutOutput)
+        }),
+
+        Commands::Subshell => with_butler_runtime(context, subshell_command),
+
+        Commands::Doctor { format } => {
+            with_butler_runtime(context, |runtime| doctor_command(runtime, format))
         }
-        Commands::Sync => with_butler_runtime(context, |runtime| sync_command(runtime.clone())),
+
+        Commands::Deps { format, group } => with_butler_runtime(context, |runtime| {
+            deps_command(runtime, format, group.as_deref(), &mut StdoutOutput)
+        }),
 
         Commands::Info { command } => match command {
-            InfoCommands::Config => info_config_command(&context.config),
+            InfoCommands::Config { format } => info_config_command(&context.config, format),
+            InfoCommands::Runtime {
+                default: Some(version),
+                ..
+            } => {
+                let config_path = config_file_for_writing(context.config_file.clone());
+                with_butler_runtime(context, |runtime| {
+                    set_default_ruby_command(runtime, &version, config_path, &mut StdoutOutput)
+                })
+            }
+            InfoCommands::Env {
+                explain,
+                watch: true,
+            } => {
+                let project_file = context.project_file.clone();
+                let current_dir = std::env::current_dir().map_err(|e| {
+                    ButlerError::General(format!("Unable to determine current directory: {}", e))
+                })?;
+                watch_environment(&current_dir, &mut StdoutOutput, |out| {
+                    with_butler_runtime(context, |runtime| {
+                        environment_command(runtime, project_file.clone(), explain, out)
+                    })
+                })
+            }
             _ => {
                 let project_file = context.project_file.clone();
                 with_butler_runtime(context, |runtime| {
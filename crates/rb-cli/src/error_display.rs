@@ -1,5 +1,6 @@
 use colored::Colorize;
 use rb_core::butler::ButlerError;
+use rb_core::ruby::RubyRuntimeDetector;
 use std::path::Path;
 
 pub fn format_no_suitable_ruby(
@@ -23,13 +24,25 @@ pub fn format_no_suitable_ruby(
     }
 
     msg.push('\n');
-    msg.push_str(
-        "May I suggest installing Ruby using ruby-install or a similar distinguished tool?",
-    );
+    if RubyRuntimeDetector::is_read_only(rubies_dir) {
+        msg.push_str(&read_only_rubies_dir_note(rubies_dir));
+    } else {
+        msg.push_str(
+            "May I suggest installing Ruby using ruby-install or a similar distinguished tool?",
+        );
+    }
 
     msg
 }
 
+/// Why installing into `rubies_dir` cannot work, and where to turn instead
+pub fn read_only_rubies_dir_note(rubies_dir: &Path) -> String {
+    format!(
+        "The rubies directory {} is read-only, so no Ruby can be installed there.\nMay I suggest installing into a writable directory and pointing RB_RUBIES_DIR (or -R) at it?",
+        rubies_dir.display()
+    )
+}
+
 pub fn format_command_not_found(command: &str) -> String {
     format!(
         "🎩 My sincerest apologies, but the command '{}' appears to be
@@ -42,10 +55,12 @@ command remains elusive.
 Might I suggest:
   • Verifying the command name is spelled correctly
   • Installing the appropriate gem: {}
-  • Checking if bundler management is required: {}",
+  • Checking if bundler management is required: {}
+  • Allowing system commands from your PATH: {}",
         command.bright_yellow(),
         format!("gem install {}", command).cyan(),
-        "bundle install".cyan()
+        "bundle install".cyan(),
+        "rb exec --allow-system".cyan()
     )
 }
 
@@ -63,9 +78,24 @@ pub fn format_general_error(msg: &str) -> String {
     format!("❌ {}", msg)
 }
 
+/// Exit code for failures without a more specific class
+pub const EXIT_GENERAL: i32 = 1;
+/// Exit code when no installed Ruby satisfies the request
+pub const EXIT_NO_RUBY: i32 = 2;
+/// Exit code when the rubies directory does not exist
+pub const EXIT_RUBIES_DIR_MISSING: i32 = 3;
+/// Exit code when the program to run is not in the environment
+pub const EXIT_COMMAND_NOT_FOUND: i32 = 4;
+/// Exit code when bundler fails to prepare the bundle
+pub const EXIT_BUNDLER_FAILED: i32 = 5;
+
+/// The exit code Butler leaves with for `error`, one per failure class
 pub fn error_exit_code(error: &ButlerError) -> i32 {
     match error {
-        ButlerError::CommandNotFound(_) => 127,
-        _ => 1,
+        ButlerError::General(_) => EXIT_GENERAL,
+        ButlerError::NoSuitableRuby(_) => EXIT_NO_RUBY,
+        ButlerError::RubiesDirectoryNotFound(_) => EXIT_RUBIES_DIR_MISSING,
+        ButlerError::CommandNotFound(_) => EXIT_COMMAND_NOT_FOUND,
+        ButlerError::BundlerFailed(_) => EXIT_BUNDLER_FAILED,
     }
 }
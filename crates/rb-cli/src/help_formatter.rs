@@ -17,9 +17,17 @@ pub fn print_custom_help(cmd: &clap::Command) {
     );
     println!();
 
-    let workflow_commands = ["run", "exec", "sync"];
-    let diagnostic_commands = ["info"];
-    let utility_commands = ["new", "version", "help", "shell-integration"];
+    let workflow_commands = ["run", "exec", "sync", "platform", "shell"];
+    let diagnostic_commands = ["info", "doctor", "deps"];
+    let utility_commands = [
+        "new",
+        "pin",
+        "unpin",
+        "gem-clean",
+        "version",
+        "help",
+        "shell-integration",
+    ];
 
     println!("{}", "Commands:".green().bold());
     for subcmd in cmd.get_subcommands() {
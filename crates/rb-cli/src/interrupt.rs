@@ -0,0 +1,34 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+/// Everyone waiting to hear about Ctrl-C
+static SUBSCRIBERS: Mutex<Vec<Sender<()>>> = Mutex::new(Vec::new());
+
+/// Whether the process-wide Ctrl-C handler could be installed
+static HANDLER: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// Receive a message each time Ctrl-C is pressed
+///
+/// Only one Ctrl-C handler may exist per process, so watch mode and timed scripts
+/// share this one. Installing it means Ctrl-C no longer ends Butler by itself;
+/// subscribers decide what to do instead.
+pub fn subscribe() -> Result<Receiver<()>, String> {
+    HANDLER
+        .get_or_init(|| {
+            ctrlc::set_handler(|| {
+                SUBSCRIBERS
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .retain(|subscriber| subscriber.send(()).is_ok());
+            })
+            .map_err(|e| e.to_string())
+        })
+        .clone()?;
+
+    let (sender, receiver) = mpsc::channel();
+    SUBSCRIBERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(sender);
+    Ok(receiver)
+}
@@ -1,10 +1,17 @@
+pub mod command_log;
 pub mod commands;
 pub mod completion;
 pub mod config;
 pub mod dispatch;
 pub mod error_display;
 pub mod help_formatter;
+pub mod interrupt;
+pub mod output;
+pub mod ruby_installer;
+pub mod ruby_picker;
+pub mod run_summary;
 pub mod runtime_helpers;
+pub mod watch;
 
 use clap::builder::styling::{AnsiColor, Effects, Styles};
 use clap::{Parser, Subcommand, ValueEnum};
@@ -37,6 +44,15 @@ impl From<LogLevel> for log::LevelFilter {
     }
 }
 
+/// How command output is presented
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Emoji, ornaments and colors (default)
+    Human,
+    /// Plain ASCII without emoji or colors, for logs and limited terminals
+    Plain,
+}
+
 #[derive(Parser)]
 #[command(name = "rb")]
 #[command(about = "🎩 Ruby Butler - Your distinguished Ruby environment manager")]
@@ -94,6 +110,16 @@ pub struct Cli {
     )]
     pub config_file: Option<std::path::PathBuf>,
 
+    /// Reject unknown keys in the configuration file
+    #[arg(
+        long = "strict-config",
+        global = true,
+        help = "Fail on unknown keys in the configuration file instead of ignoring them",
+        env = "RB_STRICT_CONFIG",
+        action = clap::ArgAction::SetTrue
+    )]
+    pub strict_config: bool,
+
     /// Specify custom project file location
     #[arg(
         short = 'P',
@@ -105,6 +131,26 @@ pub struct Cli {
     )]
     pub project_file: Option<std::path::PathBuf>,
 
+    /// Disable colored output
+    #[arg(
+        long = "no-color",
+        global = true,
+        help = "Present output without colors (also honours NO_COLOR)",
+        env = "RB_NO_COLOR",
+        action = clap::ArgAction::SetTrue
+    )]
+    pub no_color: bool,
+
+    /// Presentation style for command output
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value_t = OutputFormat::Human,
+        help = "Present output with emoji and ornaments (human) or as plain ASCII without colors (plain)",
+        env = "RB_FORMAT"
+    )]
+    pub format: OutputFormat,
+
     /// Flattened configuration options (works for both CLI and config file)
     #[command(flatten)]
     pub config: RbConfig,
@@ -125,6 +171,31 @@ impl Cli {
             self.log_level.clone().unwrap_or(LogLevel::None)
         }
     }
+
+    /// Whether colored output should be disabled, via --no-color or the NO_COLOR convention
+    pub fn color_disabled(&self) -> bool {
+        self.no_color
+            || self.plain_output()
+            || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+    }
+
+    /// Whether output should be plain ASCII, via `--format plain` before or after the command
+    pub fn plain_output(&self) -> bool {
+        self.format == OutputFormat::Plain
+            || matches!(
+                self.command,
+                Some(Commands::Doctor {
+                    format: DoctorFormat::Plain
+                }) | Some(Commands::Deps {
+                    format: DepsFormat::Plain,
+                    ..
+                }) | Some(Commands::Info {
+                    command: InfoCommands::Config {
+                        format: ConfigFormat::Plain
+                    },
+                })
+            )
+    }
 }
 
 #[derive(Subcommand)]
@@ -138,14 +209,70 @@ pub enum Commands {
     )]
     Run {
         /// Name of the script to execute (from rbproject.toml), or omit to list available scripts
-        #[arg(help = "Name of the script to execute (omit to list available scripts)")]
+        ///
+        /// Further arguments naming defined scripts are run after it, in order.
+        #[arg(
+            help = "Name of the script to execute (omit to list available scripts); name more to run them in turn"
+        )]
         script: Option<String>,
 
-        /// Additional arguments to pass to the script
+        /// Keep running the remaining scripts after one fails, like `make -k`
+        #[arg(
+            long = "keep-going",
+            short = 'k',
+            help = "Run every named script even after a failure, reporting all failures at the end"
+        )]
+        keep_going: bool,
+
+        /// Print which scripts invoke others as an indented tree
+        #[arg(
+            long = "graph",
+            help = "Show the script dependency tree instead of running"
+        )]
+        graph: bool,
+
+        /// Run once, then again whenever files in the project change
+        #[arg(
+            long = "watch",
+            help = "Rerun the script whenever project files change (Ctrl-C to stop)"
+        )]
+        watch: bool,
+
+        /// Label each line of the script's output, so interleaved scripts stay readable
+        ///
+        /// Scripts the run starts inherit the setting, so a nested `rb run` labels its
+        /// lines within the outer prefix.
+        #[arg(
+            long = "prefix",
+            env = "RB_RUN_PREFIX",
+            help = "Prefix each line of the script's output with [script]"
+        )]
+        prefix: bool,
+
+        /// Report how long the script took, on stderr once it finishes
+        #[arg(
+            long = "measure",
+            help = "Print the script's wall-clock duration to stderr"
+        )]
+        measure: bool,
+
+        /// Extra environment variables for this run only, applied over Butler's environment
+        #[arg(
+            long = "env",
+            value_name = "KEY=VALUE",
+            value_parser = parse_env_assignment,
+            help = "Set an environment variable for the script (repeatable)"
+        )]
+        env: Vec<(String, String)>,
+
+        /// Further scripts to run in turn, then arguments for the script
+        ///
+        /// Leading values naming defined scripts join the batch; the rest are passed to the
+        /// script as arguments, which only works when a single script runs.
         #[arg(
             trailing_var_arg = true,
             allow_hyphen_values = true,
-            help = "Additional arguments to pass to the script"
+            help = "More defined scripts to run in turn, otherwise arguments to pass to the script"
         )]
         args: Vec<String>,
     },
@@ -153,14 +280,138 @@ pub enum Commands {
     /// ⚡ Execute commands within your meticulously prepared Ruby environment
     #[command(visible_alias = "x")]
     Exec {
-        /// The program and its arguments to execute with proper environmental preparation
+        /// Start from a minimal environment instead of inheriting the current one
+        #[arg(
+            long = "clean-env",
+            help = "Start from a minimal environment plus Butler's composed variables"
+        )]
+        clean_env: bool,
+
+        /// Extra environment variables for this run only, applied over Butler's environment
+        #[arg(
+            long = "env",
+            value_name = "KEY=VALUE",
+            value_parser = parse_env_assignment,
+            help = "Set an environment variable for the program (repeatable)"
+        )]
+        env: Vec<(String, String)>,
+
+        /// Print the PATH the program would see, then exit without running it
+        #[arg(
+            long = "print-path",
+            help = "Print the composed PATH and exit without executing anything"
+        )]
+        print_path: bool,
+
+        /// Write the program's stdout and stderr to a file instead of the terminal
+        #[arg(
+            long = "capture",
+            value_name = "FILE",
+            help = "Write the program's combined output to FILE",
+            value_hint = clap::ValueHint::FilePath
+        )]
+        capture: Option<std::path::PathBuf>,
+
+        /// Keep stderr apart from the captured stdout
+        #[arg(
+            long = "capture-stderr-separate",
+            value_name = "FILE",
+            requires = "capture",
+            help = "With --capture, write stderr to FILE instead of alongside stdout",
+            value_hint = clap::ValueHint::FilePath
+        )]
+        capture_stderr: Option<std::path::PathBuf>,
+
+        /// Run the program through `$SHELL -lc` so the login shell's setup runs first
+        ///
+        /// Within a bundler project the shell itself is started via `bundle exec`, so
+        /// rc files that rebuild PATH can shadow the bundle's executables.
+        #[arg(
+            long = "login",
+            help = "Run the program through your login shell ($SHELL -lc) so its rc files load first"
+        )]
+        login: bool,
+
+        /// Fall back to the inherited PATH for programs no gem or binstub provides
+        ///
+        /// Without it such programs are refused as not found. In a bundler project they
+        /// then run directly instead of through `bundle exec`.
+        #[arg(
+            long = "allow-system",
+            help = "Allow system commands from the inherited PATH, run outside bundle exec"
+        )]
+        allow_system: bool,
+
+        /// Report how long the program took, on stderr once it finishes
+        #[arg(
+            long = "measure",
+            help = "Print the program's wall-clock duration to stderr"
+        )]
+        measure: bool,
+
+        /// The program and its arguments to execute with proper environmental preparation;
+        /// `@script` runs that project script's command instead
         #[arg(trailing_var_arg = true)]
         args: Vec<String>,
     },
 
     /// 🔄 Synchronize your bundler environment with distinguished precision
     #[command(visible_alias = "s")]
-    Sync,
+    Sync {
+        /// Retry a failed bundle install this many times before giving up
+        #[arg(
+            long = "retry",
+            value_name = "N",
+            default_value_t = 0,
+            help = "Retry a failed bundle install up to N times"
+        )]
+        retry: u32,
+
+        /// Parallel install jobs, defaulting to the available CPUs
+        #[arg(
+            long = "jobs",
+            short = 'j',
+            value_name = "N",
+            help = "Install with N parallel jobs (default: available CPUs)"
+        )]
+        jobs: Option<u32>,
+
+        /// Install gems into this directory for this run only
+        #[arg(
+            long = "path",
+            value_name = "DIR",
+            help = "Vendor gems into DIR for this run without changing configuration"
+        )]
+        path: Option<std::path::PathBuf>,
+
+        /// Configure bundler's deployment mode: frozen lockfile, vendored gems, no development groups
+        #[arg(
+            long = "deployment",
+            help = "Install for deployment: frozen lockfile, vendored gems, without development and test groups"
+        )]
+        deployment: bool,
+
+        /// Reinstall every gem even when the bundle is already satisfied
+        #[arg(
+            long = "redownload",
+            help = "Force a clean reinstall, fetching and rebuilding every gem"
+        )]
+        redownload: bool,
+    },
+
+    /// 🖥️  Manage the platforms locked in Gemfile.lock
+    #[command(about = "🖥️  Manage the platforms locked in Gemfile.lock")]
+    Platform {
+        #[command(subcommand)]
+        command: PlatformCommands,
+    },
+
+    /// 🐚 Enter a subshell with your Ruby environment already active
+    #[command(
+        name = "shell",
+        about = "🐚 Enter a subshell with your Ruby environment already active"
+    )]
+    Subshell,
 
     /// 🔍 Inspect Ruby Butler state and configuration
     #[command(
@@ -174,12 +425,97 @@ pub enum Commands {
         command: InfoCommands,
     },
 
+    /// 🩺 Examine your Ruby environment for common irregularities
+    #[command(about = "🩺 Examine your Ruby environment for common irregularities")]
+    Doctor {
+        /// How findings are reported
+        #[arg(
+            long = "format",
+            value_enum,
+            default_value_t = DoctorFormat::Human,
+            help = "Report findings for people (human), as plain ASCII (plain) or as GitHub Actions annotations (github)"
+        )]
+        format: DoctorFormat,
+    },
+
+    /// 🌳 Show the dependency tree locked in Gemfile.lock
+    #[command(about = "🌳 Show the dependency tree locked in Gemfile.lock")]
+    Deps {
+        /// How the tree is presented
+        #[arg(
+            long = "format",
+            value_enum,
+            default_value_t = DepsFormat::Tree,
+            help = "Show an indented tree (tree), the tree as plain ASCII (plain) or nested JSON (json)"
+        )]
+        format: DepsFormat,
+
+        /// Only the gems the Gemfile declares in this group
+        #[arg(
+            long = "group",
+            value_name = "GROUP",
+            help = "Show only gems declared in this Gemfile group, with their dependencies"
+        )]
+        group: Option<String>,
+    },
+
     /// 📝 Create a minimal rbproject.toml in the current directory
     #[command(
         about = "📝 Create a minimal rbproject.toml in the current directory",
         next_help_heading = "Utility Commands"
     )]
-    New,
+    New {
+        /// Project file name to create; gem.* names are aliases for rbproject.*
+        #[arg(
+            long = "filename",
+            default_value = "rbproject.toml",
+            value_parser = ["rbproject.toml", "rbproject.kdl", "gem.toml", "gem.kdl"],
+            help = "Project file to create"
+        )]
+        filename: String,
+
+        /// Print the template instead of writing it
+        #[arg(
+            long = "dry-run",
+            help = "Print the project file to stdout without writing it"
+        )]
+        dry_run: bool,
+
+        /// Replace an existing project file of the same name
+        #[arg(
+            long = "force",
+            help = "Overwrite an existing project file of the same name"
+        )]
+        force: bool,
+
+        /// Verify instead that a valid project file already exists, for CI
+        #[arg(
+            long = "check",
+            conflicts_with_all = ["dry_run", "force"],
+            help = "Check that this directory has a project file that parses; writes nothing"
+        )]
+        check: bool,
+    },
+
+    /// 📌 Pin this project to a Ruby version, ahead of .ruby-version and Gemfile
+    #[command(about = "📌 Pin this project to a Ruby version, ahead of .ruby-version and Gemfile")]
+    Pin {
+        /// Version to pin, optionally engine-qualified (e.g. 3.2.5 or jruby-9.4.5.0)
+        #[arg(value_name = "VERSION")]
+        version: String,
+    },
+
+    /// 📍 Remove this project's Ruby pin and return to detection
+    #[command(about = "📍 Remove this project's Ruby pin and return to detection")]
+    Unpin,
+
+    /// 🧹 Remove outdated gem versions from the user gem home
+    #[command(about = "🧹 Remove outdated gem versions from the user gem home")]
+    GemClean {
+        /// Report what `gem cleanup` would remove without removing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 
     /// 📋 Display Ruby Butler version information
     #[command(about = "📋 Display Ruby Butler version information")]
@@ -201,6 +537,10 @@ pub enum Commands {
         shell: Option<Shell>,
     },
 
+    /// 🔄 Rebuild the Ruby and script lists offered by shell completion
+    #[command(about = "🔄 Refresh the Rubies and scripts offered by shell completion")]
+    CompletionsRefresh,
+
     /// Internal: Bash completion generator (hidden from help, used by shell integration)
     #[command(name = "__bash_complete", hide = true)]
     BashComplete {
@@ -212,21 +552,108 @@ pub enum Commands {
         #[arg(help = "Cursor position (COMP_POINT)")]
         point: String,
     },
+
+    /// Internal: JSON completion generator for editor integrations (hidden from help)
+    #[command(name = "__complete_json", hide = true)]
+    CompleteJson {
+        /// The complete command line being completed
+        #[arg(help = "Complete command line")]
+        line: String,
+
+        /// The cursor position in the line
+        #[arg(help = "Cursor position")]
+        point: String,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum InfoCommands {
     /// 🔍 Detected Rubies and selected runtime
-    Runtime,
+    Runtime {
+        /// Only list Rubies matching a version prefix or requirement
+        #[arg(
+            long = "only",
+            value_name = "PATTERN",
+            help = "Only list Rubies matching a version prefix (3.3) or requirement ('>=3.2')"
+        )]
+        only: Option<String>,
+
+        /// Only list Rubies installed within a recent window
+        #[arg(
+            long = "since",
+            value_name = "DURATION",
+            help = "Only list Rubies whose installation changed within DURATION (e.g. 7d, 12h, 2w)"
+        )]
+        since: Option<String>,
+
+        /// Order in which Rubies are listed
+        #[arg(
+            long = "sort",
+            value_enum,
+            default_value_t = RuntimeSort::Version,
+            help = "List Rubies by version (newest first), directory name, or installation time (newest first)"
+        )]
+        sort: RuntimeSort,
+
+        /// Reverse the listing order
+        #[arg(long = "reverse", help = "Reverse the listing order")]
+        reverse: bool,
+
+        /// Make an installed Ruby the fallback when no project asks for one
+        #[arg(
+            long = "default",
+            value_name = "VERSION",
+            help = "Save VERSION as default-ruby in the config file, used when nothing else selects a Ruby"
+        )]
+        default: Option<String>,
+    },
 
     /// 🌍 Effective Ruby/Bundler environment
-    Env,
+    Env {
+        /// Report why the active Ruby was selected
+        #[arg(long = "explain", help = "Explain why the active Ruby was selected")]
+        explain: bool,
+
+        /// Redraw whenever a file shaping the environment changes
+        #[arg(
+            long = "watch",
+            help = "Redraw when .ruby-version, the Gemfile or the project file changes (Ctrl-C to stop)"
+        )]
+        watch: bool,
+    },
 
     /// 📁 Resolved rbproject.toml and settings
     Project,
 
     /// ⚙️  Merged configuration with sources
-    Config,
+    Config {
+        /// How to present the configuration
+        #[arg(
+            long = "format",
+            value_enum,
+            default_value_t = ConfigFormat::Human,
+            help = "Show values with their sources (human), the same as plain ASCII (plain) or as RB_* variables (env)"
+        )]
+        format: ConfigFormat,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PlatformCommands {
+    /// Add a platform (e.g. x86_64-linux) via bundle lock --add-platform
+    Add {
+        /// Platform to add
+        platform: String,
+    },
+
+    /// Remove a platform via bundle lock --remove-platform
+    Remove {
+        /// Platform to remove
+        platform: String,
+    },
+
+    /// List the platforms in Gemfile.lock
+    List,
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -234,13 +661,53 @@ pub enum Shell {
     Bash,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ConfigFormat {
+    /// Each setting with the source it came from
+    Human,
+    /// The human report as plain ASCII without emoji or colors
+    Plain,
+    /// `RB_*=value` lines reproducing the explicitly set values
+    Env,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RuntimeSort {
+    /// Newest version first
+    Version,
+    /// Installation directory name, alphabetically
+    Name,
+    /// Most recently installed first
+    Mtime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DepsFormat {
+    /// Gems indented under the gems that depend on them
+    Tree,
+    /// The tree as plain ASCII without emoji or colors
+    Plain,
+    /// Nested objects with name, version and dependencies
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum DoctorFormat {
+    /// Decorated report for reading in a terminal
+    Human,
+    /// The report as plain ASCII without emoji or colors
+    Plain,
+    /// `::error::` and `::warning::` workflow commands for GitHub Actions
+    Github,
+}
+
 pub use commands::{
     exec_command, info_command, new_command, run_command, shell_integration_command, sync_command,
 };
 
 use log::debug;
-use rb_core::butler::ButlerRuntime;
-use std::path::PathBuf;
+use rb_core::butler::{ButlerError, ButlerRuntime};
+use std::path::{Path, PathBuf};
 
 const DEFAULT_RUBIES_DIR: &str = ".rubies";
 
@@ -248,9 +715,9 @@ pub fn create_ruby_context(
     rubies_dir: Option<PathBuf>,
     ruby_version: Option<String>,
 ) -> ButlerRuntime {
-    let search_dir = resolve_search_dir(rubies_dir);
-
-    match ButlerRuntime::discover_and_create(&search_dir, ruby_version.as_deref()) {
+    match resolve_search_dir(rubies_dir).and_then(|search_dir| {
+        ButlerRuntime::discover_and_create(&search_dir, ruby_version.as_deref())
+    }) {
         Ok(butler) => butler,
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -259,33 +726,61 @@ pub fn create_ruby_context(
     }
 }
 
-pub fn resolve_search_dir(rubies_dir: Option<PathBuf>) -> PathBuf {
-    rubies_dir.unwrap_or_else(|| {
-        if let Ok(env_dir) = std::env::var("RB_RUBIES_DIR") {
-            let path = PathBuf::from(env_dir);
-            debug!(
-                "Using rubies directory from RB_RUBIES_DIR: {}",
-                path.display()
-            );
-            return path;
-        }
+/// Parse a `KEY=VALUE` environment assignment given on the command line
+fn parse_env_assignment(assignment: &str) -> Result<(String, String), String> {
+    match assignment.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!(
+            "'{}' is not a KEY=VALUE assignment (for example: --env RAILS_ENV=test)",
+            assignment
+        )),
+    }
+}
 
-        // Fall back to default ~/.rubies
-        let home_dir = home::home_dir().expect("Could not determine home directory");
-        debug!("Using home directory: {}", home_dir.display());
-        let rubies_dir = home_dir.join(DEFAULT_RUBIES_DIR);
+pub fn resolve_search_dir(rubies_dir: Option<PathBuf>) -> Result<PathBuf, ButlerError> {
+    resolve_search_dir_with(
+        rubies_dir,
+        std::env::var("RB_RUBIES_DIR").ok(),
+        rb_core::home_dir(),
+    )
+}
+
+fn resolve_search_dir_with(
+    rubies_dir: Option<PathBuf>,
+    env_dir: Option<String>,
+    home_dir: Option<PathBuf>,
+) -> Result<PathBuf, ButlerError> {
+    if let Some(rubies_dir) = rubies_dir {
+        return Ok(config::paths::expand_path(&rubies_dir));
+    }
+
+    if let Some(env_dir) = env_dir {
+        let path = config::paths::expand_path(Path::new(&env_dir));
         debug!(
-            "No rubies directory specified, using default: {}",
-            rubies_dir.display()
+            "Using rubies directory from RB_RUBIES_DIR: {}",
+            path.display()
         );
-        rubies_dir
-    })
+        return Ok(path);
+    }
+
+    // Fall back to default ~/.rubies
+    let home_dir = home_dir.ok_or_else(|| {
+        ButlerError::General(rb_core::user_home::HOME_DIR_UNAVAILABLE.to_string())
+    })?;
+    debug!("Using home directory: {}", home_dir.display());
+    let rubies_dir = home_dir.join(DEFAULT_RUBIES_DIR);
+    debug!(
+        "No rubies directory specified, using default: {}",
+        rubies_dir.display()
+    );
+    Ok(rubies_dir)
 }
 
 impl Cli {
     /// Merge CLI arguments with config file defaults (CLI takes precedence)
     pub fn with_config_defaults(mut self) -> Result<Self, ConfigError> {
-        let file_config = config::loader::load_config(self.config_file.clone())?;
+        let file_config =
+            config::loader::load_config(self.config_file.clone(), self.strict_config)?;
         self.config.merge_with(file_config);
         Ok(self)
     }
@@ -293,11 +788,17 @@ impl Cli {
     /// Merge CLI arguments with config file, returning both for tracked config
     /// Returns (cli_with_merged_config, file_config) for source tracking
     pub fn with_config_defaults_tracked(self) -> Result<(Self, config::RbConfig), ConfigError> {
-        let file_config = config::loader::load_config(self.config_file.clone())?;
+        let file_config =
+            config::loader::load_config(self.config_file.clone(), self.strict_config)?;
         Ok((self, file_config))
     }
 }
 
+/// Disable all colored output for the remainder of the process
+pub fn disable_colors() {
+    colored::control::set_override(false);
+}
+
 /// Initialize the logger with the specified log level
 pub fn init_logger(log_level: LogLevel) {
     env_logger::Builder::from_default_env()
@@ -313,10 +814,28 @@ mod tests {
     use super::*;
     use rb_tests::RubySandbox;
 
+    #[test]
+    fn test_parse_env_assignment() {
+        assert_eq!(
+            parse_env_assignment("RAILS_ENV=test"),
+            Ok(("RAILS_ENV".to_string(), "test".to_string()))
+        );
+        assert_eq!(
+            parse_env_assignment("OPTS=a=b"),
+            Ok(("OPTS".to_string(), "a=b".to_string()))
+        );
+        assert_eq!(
+            parse_env_assignment("EMPTY="),
+            Ok(("EMPTY".to_string(), String::new()))
+        );
+        assert!(parse_env_assignment("RAILS_ENV").is_err());
+        assert!(parse_env_assignment("=test").is_err());
+    }
+
     #[test]
     fn test_resolve_search_dir_with_provided_path() {
         let test_path = PathBuf::from("/test/rubies");
-        let result = resolve_search_dir(Some(test_path.clone()));
+        let result = resolve_search_dir(Some(test_path.clone())).unwrap();
         assert_eq!(result, test_path);
     }
 
@@ -328,7 +847,7 @@ mod tests {
             std::env::remove_var("RB_RUBIES_DIR");
         }
 
-        let result = resolve_search_dir(None);
+        let result = resolve_search_dir(None).unwrap();
 
         // Restore original environment
         if let Some(val) = original_env {
@@ -342,6 +861,19 @@ mod tests {
         assert!(result.is_absolute());
     }
 
+    #[test]
+    fn test_resolve_search_dir_without_home_is_an_error() {
+        let error = resolve_search_dir_with(None, None, None).unwrap_err();
+        assert!(error.to_string().contains("RB_RUBIES_DIR"));
+        assert!(error.to_string().contains("RB_HOME"));
+
+        // Explicit locations need no home directory
+        assert_eq!(
+            resolve_search_dir_with(None, Some("/opt/rubies".to_string()), None).unwrap(),
+            PathBuf::from("/opt/rubies")
+        );
+    }
+
     #[test]
     fn test_create_ruby_context_with_sandbox() {
         let sandbox = RubySandbox::new().expect("Failed to create sandbox");
@@ -392,10 +924,19 @@ mod tests {
             verbose: false,
             very_verbose: false,
             config_file: None,
+            strict_config: false,
             project_file: None,
+            no_color: false,
+            format: OutputFormat::Human,
             config: RbConfig::default(),
             command: Some(Commands::Info {
-                command: InfoCommands::Runtime,
+                command: InfoCommands::Runtime {
+                    only: None,
+                    since: None,
+                    sort: RuntimeSort::Version,
+                    reverse: false,
+                    default: None,
+                },
             }),
         };
         assert!(matches!(cli.effective_log_level(), LogLevel::Info));
@@ -406,10 +947,19 @@ mod tests {
             verbose: true,
             very_verbose: false,
             config_file: None,
+            strict_config: false,
             project_file: None,
+            no_color: false,
+            format: OutputFormat::Human,
             config: RbConfig::default(),
             command: Some(Commands::Info {
-                command: InfoCommands::Runtime,
+                command: InfoCommands::Runtime {
+                    only: None,
+                    since: None,
+                    sort: RuntimeSort::Version,
+                    reverse: false,
+                    default: None,
+                },
             }),
         };
         assert!(matches!(cli.effective_log_level(), LogLevel::Info));
@@ -420,10 +970,19 @@ mod tests {
             verbose: false,
             very_verbose: true,
             config_file: None,
+            strict_config: false,
             project_file: None,
+            no_color: false,
+            format: OutputFormat::Human,
             config: RbConfig::default(),
             command: Some(Commands::Info {
-                command: InfoCommands::Runtime,
+                command: InfoCommands::Runtime {
+                    only: None,
+                    since: None,
+                    sort: RuntimeSort::Version,
+                    reverse: false,
+                    default: None,
+                },
             }),
         };
         assert!(matches!(cli.effective_log_level(), LogLevel::Debug));
@@ -0,0 +1,246 @@
+use colored::*;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether stdout output is rewritten as plain ASCII, set once from `--format plain`
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Present all further stdout output as plain ASCII, without emoji or ornaments
+pub fn enable_plain_output() {
+    PLAIN_OUTPUT.store(true, Ordering::Relaxed);
+}
+
+fn plain_output_enabled() -> bool {
+    PLAIN_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Rewrite `text` as plain ASCII: emoji and ornaments dropped, typographic marks spelled out
+///
+/// Letters outside ASCII are kept, so names and paths are never mangled. Spacing that
+/// only separated a dropped symbol from the text goes with it.
+pub fn plain_text(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut after_dropped = false;
+
+    for ch in text.chars() {
+        if after_dropped && ch == ' ' && (plain.is_empty() || plain.ends_with(' ')) {
+            continue;
+        }
+        let replacement = match ch {
+            c if c.is_ascii() || c.is_alphanumeric() => {
+                plain.push(c);
+                after_dropped = false;
+                continue;
+            }
+            '•' | '·' | '─' | '━' | '═' | '–' | '—' => "-",
+            '│' | '┃' | '║' => "|",
+            '→' | '⇒' => "->",
+            '←' => "<-",
+            '…' => "...",
+            '‘' | '’' => "'",
+            '“' | '”' => "\"",
+            '✓' | '✔' => "+",
+            '✗' | '✘' => "x",
+            _ => {
+                after_dropped = true;
+                continue;
+            }
+        };
+        plain.push_str(replacement);
+        after_dropped = false;
+    }
+
+    plain
+}
+
+/// Where commands send what they have to say
+///
+/// Commands write through this rather than printing directly, so their output
+/// can be captured in tests or redirected elsewhere.
+pub trait Output {
+    /// A line of output, written as given
+    fn line(&mut self, text: &str);
+
+    /// A line reporting that something went well
+    fn success(&mut self, text: &str);
+
+    /// A line reporting something worth attention
+    fn warn(&mut self, text: &str);
+
+    /// A line reporting a failure
+    fn error(&mut self, text: &str);
+
+    /// An empty line for spacing
+    fn blank(&mut self) {
+        self.line("");
+    }
+
+    /// Start over on an empty screen, where the output supports it
+    fn clear(&mut self) {}
+}
+
+/// Writes success, warning and error lines with their usual markers
+fn marked(marker: &str, text: &str) -> String {
+    format!("{} {}", marker, text)
+}
+
+fn success_marker() -> String {
+    let marker = if plain_output_enabled() { "OK:" } else { "✅" };
+    marker.green().to_string()
+}
+
+fn warn_marker() -> String {
+    let marker = if plain_output_enabled() {
+        "WARNING:"
+    } else {
+        "⚠️ "
+    };
+    marker.yellow().to_string()
+}
+
+fn error_marker() -> String {
+    let marker = if plain_output_enabled() {
+        "ERROR:"
+    } else {
+        "❌"
+    };
+    marker.red().to_string()
+}
+
+/// Prints to stdout, as commands always have
+#[derive(Debug, Default)]
+pub struct StdoutOutput;
+
+impl Output for StdoutOutput {
+    fn line(&mut self, text: &str) {
+        if plain_output_enabled() {
+            println!("{}", plain_text(text));
+        } else {
+            println!("{}", text);
+        }
+    }
+
+    fn success(&mut self, text: &str) {
+        self.line(&marked(&success_marker(), text));
+    }
+
+    fn warn(&mut self, text: &str) {
+        self.line(&marked(&warn_marker(), text));
+    }
+
+    fn error(&mut self, text: &str) {
+        self.line(&marked(&error_marker(), text));
+    }
+
+    fn clear(&mut self) {
+        if std::io::stdout().is_terminal() {
+            print!("\x1B[2J\x1B[H");
+        }
+    }
+}
+
+/// Prints to stderr, for reports that must not mix into a program's piped stdout
+#[derive(Debug, Default)]
+pub struct StderrOutput;
+
+impl Output for StderrOutput {
+    fn line(&mut self, text: &str) {
+        if plain_output_enabled() {
+            eprintln!("{}", plain_text(text));
+        } else {
+            eprintln!("{}", text);
+        }
+    }
+
+    fn success(&mut self, text: &str) {
+        self.line(&marked(&success_marker(), text));
+    }
+
+    fn warn(&mut self, text: &str) {
+        self.line(&marked(&warn_marker(), text));
+    }
+
+    fn error(&mut self, text: &str) {
+        self.line(&marked(&error_marker(), text));
+    }
+}
+
+/// Keeps every line in memory so tests can assert on what a command said
+#[derive(Debug, Default)]
+pub struct CapturedOutput {
+    lines: Vec<String>,
+}
+
+impl CapturedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// All captured lines joined with newlines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn contains(&self, needle: &str) -> bool {
+        self.lines.iter().any(|line| line.contains(needle))
+    }
+}
+
+impl Output for CapturedOutput {
+    fn line(&mut self, text: &str) {
+        self.lines.push(text.to_string());
+    }
+
+    fn success(&mut self, text: &str) {
+        self.line(&marked("✅", text));
+    }
+
+    fn warn(&mut self, text: &str) {
+        self.line(&marked("⚠️ ", text));
+    }
+
+    fn error(&mut self, text: &str) {
+        self.line(&marked("❌", text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_output_keeps_lines_in_order_with_markers() {
+        let mut out = CapturedOutput::new();
+        out.line("first");
+        out.blank();
+        out.success("done");
+        out.warn("careful");
+        out.error("failed");
+
+        assert_eq!(
+            out.lines(),
+            ["first", "", "✅ done", "⚠️  careful", "❌ failed"]
+        );
+        assert!(out.contains("careful"));
+        assert_eq!(out.text(), "first\n\n✅ done\n⚠️  careful\n❌ failed");
+    }
+
+    #[test]
+    fn plain_text_drops_emoji_and_spells_out_marks() {
+        assert_eq!(
+            plain_text("🌍 Your Current Ruby Environment"),
+            "Your Current Ruby Environment"
+        );
+        assert_eq!(plain_text("  💎 CRuby (3.3.0)"), "  CRuby (3.3.0)");
+        assert_eq!(plain_text("⚠️  careful"), "careful");
+        assert_eq!(
+            plain_text("  • gems → /home/zoë/.gem"),
+            "  - gems -> /home/zoë/.gem"
+        );
+        assert_eq!(plain_text("═══"), "---");
+    }
+}
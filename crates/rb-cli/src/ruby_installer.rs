@@ -0,0 +1,204 @@
+use crate::error_display::read_only_rubies_dir_note;
+use colored::*;
+use log::{debug, info};
+use rb_core::butler::ButlerError;
+use rb_core::ruby::RubyRuntimeDetector;
+use semver::Version;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Something able to place a Ruby installation into the rubies directory
+pub trait RubyInstaller {
+    fn install(&self, version: &Version, rubies_dir: &Path) -> Result<(), ButlerError>;
+}
+
+/// Installs Rubies with `ruby-install` into `<rubies_dir>/ruby-<version>`
+pub struct RubyInstallTool;
+
+impl RubyInstaller for RubyInstallTool {
+    fn install(&self, version: &Version, rubies_dir: &Path) -> Result<(), ButlerError> {
+        let install_dir = rubies_dir.join(format!("ruby-{}", version));
+        info!(
+            "Installing Ruby {} into {} with ruby-install",
+            version,
+            install_dir.display()
+        );
+
+        let status = std::process::Command::new("ruby-install")
+            .arg("--install-dir")
+            .arg(&install_dir)
+            .arg("ruby")
+            .arg(version.to_string())
+            .status()
+            .map_err(|e| {
+                ButlerError::General(format!(
+                    "Unable to run ruby-install: {}\n\nPlease ensure ruby-install is available on your PATH.",
+                    e
+                ))
+            })?;
+
+        if !status.success() {
+            return Err(ButlerError::General(format!(
+                "ruby-install could not install Ruby {} (exit code {:?})",
+                version,
+                status.code()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Offer to install a required Ruby that is missing, returning whether it was installed
+///
+/// Nothing is asked unless the feature is `enabled` and the session is `interactive`,
+/// so CI keeps failing fast instead of waiting for an answer. Empty input accepts.
+pub fn offer_install<R: BufRead, W: Write>(
+    version: &Version,
+    rubies_dir: &Path,
+    enabled: bool,
+    interactive: bool,
+    installer: &dyn RubyInstaller,
+    mut input: R,
+    mut output: W,
+) -> Result<bool, ButlerError> {
+    if !enabled || !interactive {
+        debug!(
+            "Not offering to install Ruby {} (enabled: {}, interactive: {})",
+            version, enabled, interactive
+        );
+        return Ok(false);
+    }
+
+    if RubyRuntimeDetector::is_read_only(rubies_dir) {
+        writeln!(
+            output,
+            "{} Ruby {} is required but not installed. {}",
+            "⚠️ ".yellow(),
+            version.to_string().cyan(),
+            read_only_rubies_dir_note(rubies_dir)
+        )
+        .map_err(|e| ButlerError::General(format!("Unable to write installation note: {}", e)))?;
+        return Ok(false);
+    }
+
+    let io_error = |e: std::io::Error| {
+        ButlerError::General(format!("Unable to read installation answer: {}", e))
+    };
+
+    write!(
+        output,
+        "{} Ruby {} is required but not installed. Install it with ruby-install now? [Y/n]: ",
+        "🎩".bright_blue(),
+        version.to_string().cyan()
+    )
+    .map_err(io_error)?;
+    output.flush().map_err(io_error)?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer).map_err(io_error)?;
+
+    match answer.trim().to_lowercase().as_str() {
+        "" | "y" | "yes" => {
+            installer.install(version, rubies_dir)?;
+            Ok(true)
+        }
+        _ => {
+            debug!("Installation of Ruby {} declined", version);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct StubInstaller {
+        installed: RefCell<Vec<Version>>,
+    }
+
+    impl RubyInstaller for StubInstaller {
+        fn install(&self, version: &Version, _rubies_dir: &Path) -> Result<(), ButlerError> {
+            self.installed.borrow_mut().push(version.clone());
+            Ok(())
+        }
+    }
+
+    fn offer(enabled: bool, interactive: bool, answer: &str) -> (bool, Vec<Version>, String) {
+        let installer = StubInstaller::default();
+        let mut output = Vec::new();
+        let installed = offer_install(
+            &Version::new(3, 2, 5),
+            Path::new("/opt/rubies"),
+            enabled,
+            interactive,
+            &installer,
+            answer.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        (
+            installed,
+            installer.installed.into_inner(),
+            String::from_utf8(output).unwrap(),
+        )
+    }
+
+    #[test]
+    fn installs_when_enabled_interactive_and_accepted() {
+        let (installed, calls, prompt) = offer(true, true, "\n");
+
+        assert!(installed);
+        assert_eq!(calls, vec![Version::new(3, 2, 5)]);
+        assert!(prompt.contains("3.2.5"));
+    }
+
+    #[test]
+    fn declining_skips_installation() {
+        let (installed, calls, _) = offer(true, true, "n\n");
+
+        assert!(!installed);
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn never_prompts_unless_enabled_and_interactive() {
+        for (enabled, interactive) in [(false, true), (true, false), (false, false)] {
+            let (installed, calls, prompt) = offer(enabled, interactive, "y\n");
+
+            assert!(!installed);
+            assert!(calls.is_empty());
+            assert!(prompt.is_empty(), "No prompt expected, got: {}", prompt);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_rubies_dir_skips_prompt_with_note() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::set_permissions(temp_dir.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+        let installer = StubInstaller::default();
+        let mut output = Vec::new();
+        let installed = offer_install(
+            &Version::new(3, 2, 5),
+            temp_dir.path(),
+            true,
+            true,
+            &installer,
+            "y\n".as_bytes(),
+            &mut output,
+        );
+        std::fs::set_permissions(temp_dir.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(!installed.unwrap());
+        assert!(installer.installed.into_inner().is_empty());
+        let note = String::from_utf8(output).unwrap();
+        assert!(note.contains("read-only"), "note: {}", note);
+        assert!(note.contains("RB_RUBIES_DIR"), "note: {}", note);
+    }
+}
@@ -0,0 +1,168 @@
+use colored::*;
+use rb_core::ruby::RubyRuntime;
+use std::io::{self, BufRead, Write};
+
+/// Present a numbered list of Rubies and read the chosen number from `input`
+///
+/// Rubies are listed newest first, prereleases only with `include_prereleases`. Empty input
+/// or end of input selects the latest; anything unrecognised prompts again. Returns the
+/// chosen installation.
+pub fn prompt_ruby_selection<R: BufRead, W: Write>(
+    rubies: &[RubyRuntime],
+    include_prereleases: bool,
+    mut input: R,
+    mut output: W,
+) -> io::Result<Option<&RubyRuntime>> {
+    let mut choices: Vec<&RubyRuntime> = rubies
+        .iter()
+        .filter(|ruby| include_prereleases || ruby.version.pre.is_empty())
+        .collect();
+    choices.sort_by(|a, b| b.latest_order().cmp(&a.latest_order()));
+
+    let Some(latest) = choices.first().copied() else {
+        return Ok(None);
+    };
+
+    writeln!(
+        output,
+        "{}",
+        "🎩 No Ruby version is pinned here. Which shall it be?".bold()
+    )?;
+    for (index, ruby) in choices.iter().enumerate() {
+        writeln!(
+            output,
+            "  {} {}",
+            format!("{})", index + 1).cyan(),
+            ruby.version_name()
+        )?;
+    }
+
+    loop {
+        write!(output, "Selection [1]: ")?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(Some(latest));
+        }
+
+        let answer = line.trim();
+        if answer.is_empty() {
+            return Ok(Some(latest));
+        }
+
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=choices.len()).contains(&n) => return Ok(Some(choices[n - 1])),
+            _ => writeln!(
+                output,
+                "Please enter a number between 1 and {}.",
+                choices.len()
+            )?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rb_core::ruby::RubyType;
+    use semver::Version;
+
+    fn rubies() -> Vec<RubyRuntime> {
+        ["3.2.5", "3.4.1", "3.3.6"]
+            .iter()
+            .map(|v| {
+                RubyRuntime::new(
+                    RubyType::CRuby,
+                    Version::parse(v).unwrap(),
+                    format!("/opt/rubies/ruby-{}", v),
+                )
+            })
+            .collect()
+    }
+
+    fn pick(input: &str) -> Version {
+        let rubies = rubies();
+        let mut output = Vec::new();
+        prompt_ruby_selection(&rubies, false, input.as_bytes(), &mut output)
+            .unwrap()
+            .unwrap()
+            .version
+            .clone()
+    }
+
+    #[test]
+    fn picks_numbered_entry_newest_first() {
+        assert_eq!(pick("2\n"), Version::parse("3.3.6").unwrap());
+        assert_eq!(pick("3\n"), Version::parse("3.2.5").unwrap());
+    }
+
+    #[test]
+    fn empty_input_defaults_to_latest() {
+        assert_eq!(pick("\n"), Version::parse("3.4.1").unwrap());
+        assert_eq!(pick(""), Version::parse("3.4.1").unwrap());
+    }
+
+    #[test]
+    fn invalid_input_prompts_again() {
+        let rubies = rubies();
+        let mut output = Vec::new();
+        let chosen = prompt_ruby_selection(&rubies, false, "9\nabc\n3\n".as_bytes(), &mut output)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(chosen.version, Version::parse("3.2.5").unwrap());
+        let shown = String::from_utf8(output).unwrap();
+        assert_eq!(shown.matches("Please enter a number").count(), 2);
+    }
+
+    #[test]
+    fn prereleases_are_offered_only_when_included() {
+        let mut rubies = rubies();
+        rubies.push(RubyRuntime::new(
+            RubyType::CRuby,
+            Version::parse("3.5.0-preview1").unwrap(),
+            "/opt/rubies/ruby-3.5.0-preview1",
+        ));
+
+        let mut output = Vec::new();
+        let chosen = prompt_ruby_selection(&rubies, false, "\n".as_bytes(), &mut output)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chosen.version, Version::parse("3.4.1").unwrap());
+        assert!(!String::from_utf8(output).unwrap().contains("preview"));
+
+        let mut output = Vec::new();
+        let chosen = prompt_ruby_selection(&rubies, true, "\n".as_bytes(), &mut output)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chosen.version, Version::parse("3.5.0-preview1").unwrap());
+    }
+
+    #[test]
+    fn other_engines_are_not_offered_as_latest() {
+        let mut rubies = rubies();
+        rubies.push(RubyRuntime::new(
+            RubyType::TruffleRuby,
+            Version::parse("23.1.0").unwrap(),
+            "/opt/rubies/truffleruby-23.1.0",
+        ));
+
+        let mut output = Vec::new();
+        let chosen = prompt_ruby_selection(&rubies, false, "\n".as_bytes(), &mut output)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chosen.kind, RubyType::CRuby);
+        assert_eq!(chosen.version, Version::parse("3.4.1").unwrap());
+    }
+
+    #[test]
+    fn no_rubies_yields_none() {
+        let mut output = Vec::new();
+        assert!(
+            prompt_ruby_selection(&[], false, "1\n".as_bytes(), &mut output)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::output::Output;
+use colored::*;
+use log::{debug, warn};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use tempfile::NamedTempFile;
+
+/// Variable naming the file where nested `rb run` invocations record their steps
+pub const SUMMARY_ENV: &str = "RB_RUN_SUMMARY";
+
+/// The outcome of one script run within a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunStep {
+    pub script: String,
+    /// Exit code, or `None` when the script was terminated or could not be run
+    pub exit_code: Option<i32>,
+}
+
+impl RunStep {
+    pub fn new(script: impl Into<String>, exit_code: Option<i32>) -> Self {
+        Self {
+            script: script.into(),
+            exit_code,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// A fresh summary file for an outermost run, removed when dropped
+///
+/// The file is created exclusively and readable only by the current user, so no
+/// one else can plant it or a symlink in its place.
+pub fn create_summary() -> io::Result<NamedTempFile> {
+    tempfile::Builder::new()
+        .prefix("rb-run-summary-")
+        .tempfile()
+}
+
+/// Append `step` as a `script<TAB>code` line to the summary file the outermost run created
+pub fn record(path: &Path, step: &RunStep) -> io::Result<()> {
+    let code = step
+        .exit_code
+        .map_or_else(|| "-".to_string(), |code| code.to_string());
+    let mut file = OpenOptions::new().append(true).open(path)?;
+    writeln!(file, "{}\t{}", step.script, code)
+}
+
+/// Record `step` for the outermost run; a summary that cannot be written only warns
+pub fn record_or_warn(path: &Path, step: &RunStep) {
+    debug!("Recording step '{}' in {}", step.script, path.display());
+    if let Err(e) = record(path, step) {
+        warn!("Unable to record run summary in {}: {}", path.display(), e);
+    }
+}
+
+/// Read the steps recorded so far and empty the summary file
+pub fn take(path: &Path) -> Vec<RunStep> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let _ = OpenOptions::new().write(true).truncate(true).open(path);
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let (script, code) = line.rsplit_once('\t')?;
+            Some(RunStep::new(script, code.parse().ok()))
+        })
+        .collect()
+}
+
+/// List each step with whether it passed, in the order they finished
+pub fn present(steps: &[RunStep], out: &mut dyn Output) {
+    out.blank();
+    out.line(&"📋 Run Summary".bold().to_string());
+    for step in steps {
+        if step.passed() {
+            out.success(&step.script);
+        } else {
+            let detail = match step.exit_code {
+                Some(code) => format!("(exit code {})", code),
+                None => "(terminated)".to_string(),
+            };
+            out.error(&format!("{} {}", step.script, detail.bright_black()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::CapturedOutput;
+
+    #[test]
+    fn test_recorded_steps_are_summarised_in_order() {
+        let summary = create_summary().unwrap();
+        let path = summary.path().to_path_buf();
+
+        record(&path, &RunStep::new("lint", Some(0))).unwrap();
+        record(&path, &RunStep::new("test", Some(3))).unwrap();
+        record(&path, &RunStep::new("slow", None)).unwrap();
+
+        let steps = take(&path);
+        assert_eq!(
+            steps,
+            vec![
+                RunStep::new("lint", Some(0)),
+                RunStep::new("test", Some(3)),
+                RunStep::new("slow", None),
+            ]
+        );
+        assert!(take(&path).is_empty());
+
+        colored::control::set_override(false);
+        let mut out = CapturedOutput::new();
+        present(&steps, &mut out);
+        assert_eq!(
+            out.lines(),
+            [
+                "",
+                "📋 Run Summary",
+                "✅ lint",
+                "❌ test (exit code 3)",
+                "❌ slow (terminated)"
+            ]
+        );
+
+        drop(summary);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_record_does_not_create_a_missing_summary() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("summary");
+
+        assert!(record(&path, &RunStep::new("lint", Some(0))).is_err());
+        assert!(!path.exists());
+    }
+}
@@ -1,13 +1,20 @@
 use crate::Shell;
-use crate::commands::{new_command, shell_integration_command};
+use crate::commands::{
+    check_project_command, completions_refresh_command, new_command, pin_command,
+    shell_integration_command, unpin_command,
+};
 use crate::config::TrackedConfig;
-use rb_core::butler::{ButlerError, ButlerRuntime};
+use crate::output::StdoutOutput;
+use rb_core::butler::{ButlerError, ButlerRuntime, SelectionReason};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 /// Context information for command execution and error handling
 pub struct CommandContext {
     pub config: TrackedConfig,
     pub project_file: Option<PathBuf>,
+    /// The `--config` override, where settings changed by commands are written
+    pub config_file: Option<PathBuf>,
 }
 
 /// Create ButlerRuntime lazily and execute command with it
@@ -16,16 +23,62 @@ pub fn with_butler_runtime<F>(context: &mut CommandContext, f: F) -> Result<(),
 where
     F: FnOnce(&ButlerRuntime) -> Result<(), ButlerError>,
 {
+    // Built-in defaults live under the home directory; without one, ask for explicit locations
+    if context.config.rubies_dir.source.is_default() || context.config.gem_home.source.is_default()
+    {
+        rb_core::user_home::require_home_dir()?;
+    }
+
     let rubies_dir = context.config.rubies_dir.get().clone();
 
     let requested_version = context.config.ruby_version_for_runtime();
 
-    let butler_runtime = ButlerRuntime::discover_and_compose_with_gem_base(
-        rubies_dir,
-        requested_version,
-        Some(context.config.gem_home.get().clone()),
-        *context.config.no_bundler.get(),
-    )?;
+    let current_dir = std::env::current_dir().map_err(|e| {
+        ButlerError::General(format!("Unable to determine current directory: {}", e))
+    })?;
+
+    let compose = |requested_version: Option<String>| {
+        ButlerRuntime::discover_and_compose_with_options(
+            rubies_dir.clone(),
+            requested_version,
+            Some(context.config.gem_home.get().clone()),
+            *context.config.no_bundler.get(),
+            current_dir.clone(),
+            &context.config.selection_options(),
+        )
+        .map(|runtime| runtime.with_bundler_bin(context.config.bundler_bin.get().clone()))
+    };
+
+    let auto_install = *context.config.auto_install_missing.get();
+    let mut butler_runtime = match compose(requested_version.clone()) {
+        Err(ButlerError::NoSuitableRuby(message)) => {
+            let wanted = requested_version
+                .as_deref()
+                .and_then(|v| semver::Version::parse(v).ok());
+            match wanted {
+                Some(version) if install_missing_ruby(&version, &rubies_dir, auto_install)? => {
+                    compose(requested_version.clone())?
+                }
+                _ => return Err(ButlerError::NoSuitableRuby(message)),
+            }
+        }
+        composed => composed?,
+    };
+
+    if let Some(missing) = butler_runtime.missing_required_version().cloned()
+        && install_missing_ruby(&missing, &rubies_dir, auto_install)?
+    {
+        butler_runtime = compose(requested_version.clone())?;
+    }
+
+    if *context.config.interactive_select.get()
+        && let Some(chosen) = pick_ruby_interactively(
+            &butler_runtime,
+            context.config.selection_options().include_prereleases,
+        )?
+    {
+        butler_runtime = compose(Some(chosen))?.with_selection_reason(SelectionReason::Picked);
+    }
 
     if context.config.has_unresolved()
         && let Ok(ruby_runtime) = butler_runtime.selected_ruby()
@@ -37,10 +90,86 @@ where
     f(&butler_runtime)
 }
 
+/// Offer to install a missing Ruby when `auto-install-missing` is set and a terminal is attached
+fn install_missing_ruby(
+    version: &semver::Version,
+    rubies_dir: &std::path::Path,
+    enabled: bool,
+) -> Result<bool, ButlerError> {
+    let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+    crate::ruby_installer::offer_install(
+        version,
+        rubies_dir,
+        enabled,
+        interactive,
+        &crate::ruby_installer::RubyInstallTool,
+        std::io::stdin().lock(),
+        std::io::stdout(),
+    )
+}
+
+/// Ask which Ruby to use when selection fell back to the latest among several installations
+///
+/// Only prompts when both stdin and stdout are terminals, so scripts and CI are unaffected.
+/// Prereleases are offered only with `include_prereleases`, as for automatic selection.
+/// Returns the chosen version when it differs from the one already selected.
+fn pick_ruby_interactively(
+    butler_runtime: &ButlerRuntime,
+    include_prereleases: bool,
+) -> Result<Option<String>, ButlerError> {
+    let rubies = butler_runtime.ruby_installations();
+    let offered = rubies
+        .iter()
+        .filter(|ruby| include_prereleases || ruby.version.pre.is_empty())
+        .count();
+    if butler_runtime.selection_reason() != Some(SelectionReason::LatestFallback)
+        || offered < 2
+        || !std::io::stdin().is_terminal()
+        || !std::io::stdout().is_terminal()
+    {
+        return Ok(None);
+    }
+
+    let chosen = crate::ruby_picker::prompt_ruby_selection(
+        rubies,
+        include_prereleases,
+        std::io::stdin().lock(),
+        std::io::stdout(),
+    )
+    .map_err(|e| ButlerError::General(format!("Unable to read Ruby selection: {}", e)))?;
+
+    let selected = butler_runtime.selected_ruby().ok().map(|r| &r.version);
+    Ok(chosen
+        .filter(|ruby| Some(&ruby.version) != selected)
+        .map(|ruby| ruby.version.to_string()))
+}
+
 /// New command wrapper - no runtime needed
-pub fn new_command_wrapper() -> Result<(), ButlerError> {
+pub fn new_command_wrapper(
+    filename: &str,
+    dry_run: bool,
+    force: bool,
+    check: bool,
+) -> Result<(), ButlerError> {
     let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
-    new_command(&current_dir).map_err(ButlerError::General)
+    if check {
+        return check_project_command(&current_dir, &mut StdoutOutput)
+            .map_err(ButlerError::General);
+    }
+    new_command(&current_dir, filename, dry_run, force, &mut StdoutOutput)
+        .map_err(ButlerError::General)
+}
+
+/// Pin command wrapper - pins the current directory, no runtime needed
+pub fn pin_command_wrapper(version: &str) -> Result<(), ButlerError> {
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    pin_command(&current_dir, version, &mut StdoutOutput)
+}
+
+/// Unpin command wrapper - no runtime needed
+pub fn unpin_command_wrapper() -> Result<(), ButlerError> {
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    unpin_command(&current_dir, &mut StdoutOutput)
 }
 
 /// Shell integration command wrapper - no runtime needed
@@ -54,27 +183,51 @@ pub fn shell_integration_command_wrapper(shell: Option<Shell>) -> Result<(), But
     }
 }
 
+/// Completions refresh command wrapper - rediscovers without composing a runtime
+pub fn completions_refresh_command_wrapper(context: &CommandContext) -> Result<(), ButlerError> {
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    completions_refresh_command(
+        context.config.rubies_dir.get(),
+        context.config.additional_rubies_dirs.get(),
+        &current_dir,
+        &mut StdoutOutput,
+    )
+}
+
 /// Bash completion command - tries to create runtime but gracefully handles failure
 pub fn bash_complete_command(
     context: &CommandContext,
     line: &str,
     point: &str,
 ) -> Result<(), ButlerError> {
+    let butler_runtime = completion_runtime(context);
+    crate::completion::generate_completions(line, point, butler_runtime.as_ref());
+    Ok(())
+}
+
+/// Structured completions for editor integrations
+pub fn json_complete_command(
+    context: &CommandContext,
+    line: &str,
+    point: &str,
+) -> Result<(), ButlerError> {
+    let butler_runtime = completion_runtime(context);
+    crate::completion::generate_json_completions(line, point, butler_runtime.as_ref());
+    Ok(())
+}
+
+fn completion_runtime(context: &CommandContext) -> Option<ButlerRuntime> {
     let rubies_dir = context.config.rubies_dir.get().clone();
+    let current_dir = std::env::current_dir().ok()?;
 
     // Completion works for commands/flags even without Ruby
-    let butler_runtime = ButlerRuntime::discover_and_compose_with_gem_base(
+    ButlerRuntime::discover_and_compose_with_options(
         rubies_dir,
-        context
-            .config
-            .ruby_version
-            .as_ref()
-            .map(|v| v.get().clone()),
+        context.config.ruby_version_for_runtime(),
         Some(context.config.gem_home.get().clone()),
         *context.config.no_bundler.get(),
+        current_dir,
+        &context.config.selection_options(),
     )
-    .ok();
-
-    crate::completion::generate_completions(line, point, butler_runtime.as_ref());
-    Ok(())
+    .ok()
 }
@@ -0,0 +1,269 @@
+use crate::interrupt;
+use log::{debug, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use rb_core::butler::ButlerError;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::Duration;
+
+/// Quiet period after the last change before the script runs again
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Directories below the project root whose changes never warrant a rerun
+const EXCLUDED_DIRS: &[&str] = &[".rb", ".git", "vendor/bundle"];
+
+/// What the watch loop is told about
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// Files below the watched root were created, modified or removed
+    Changed(Vec<PathBuf>),
+    /// Ctrl-C was pressed; finish quietly
+    Stop,
+}
+
+/// Decides which changed paths are worth a rerun
+#[derive(Debug, Clone)]
+pub struct WatchFilter {
+    root: PathBuf,
+    excluded: Vec<PathBuf>,
+    /// When not empty, the only files whose changes count
+    files: Vec<PathBuf>,
+}
+
+impl WatchFilter {
+    /// Watch `root`, ignoring Butler's state, git internals and vendored gems
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        let root = canonical(root.as_ref());
+        let excluded = EXCLUDED_DIRS.iter().map(|dir| root.join(dir)).collect();
+        Self {
+            root,
+            excluded,
+            files: Vec::new(),
+        }
+    }
+
+    /// Only consider changes to the named files directly inside the root
+    pub fn only_files(mut self, names: &[&str]) -> Self {
+        self.files = names.iter().map(|name| self.root.join(name)).collect();
+        self
+    }
+
+    /// Additionally ignore `dirs`, such as gem directories living inside the project
+    pub fn excluding(mut self, dirs: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.excluded
+            .extend(dirs.into_iter().map(|dir| canonical(&dir)));
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_relevant(&self, path: &Path) -> bool {
+        path.starts_with(&self.root)
+            && !self.excluded.iter().any(|dir| path.starts_with(dir))
+            && (self.files.is_empty() || self.files.iter().any(|file| path == file))
+    }
+}
+
+/// Watchers report resolved paths, so compare against resolved ones where they exist
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Call `rerun` once changes settle for `debounce`, until told to stop
+///
+/// A burst of events results in a single rerun. Events arriving while `rerun` is
+/// busy are dropped, so files the script writes itself do not trigger it again.
+pub fn watch_loop(
+    events: &Receiver<WatchEvent>,
+    filter: &WatchFilter,
+    debounce: Duration,
+    mut rerun: impl FnMut(),
+) {
+    loop {
+        match events.recv() {
+            Ok(WatchEvent::Changed(paths)) if paths.iter().any(|p| filter.is_relevant(p)) => {
+                debug!("Change detected: {:?}", paths);
+            }
+            Ok(WatchEvent::Changed(paths)) => {
+                debug!("Ignoring change in excluded paths: {:?}", paths);
+                continue;
+            }
+            Ok(WatchEvent::Stop) | Err(_) => return,
+        }
+
+        loop {
+            match events.recv_timeout(debounce) {
+                Ok(WatchEvent::Changed(_)) => continue,
+                Ok(WatchEvent::Stop) | Err(RecvTimeoutError::Disconnected) => return,
+                Err(RecvTimeoutError::Timeout) => break,
+            }
+        }
+
+        rerun();
+
+        loop {
+            match events.try_recv() {
+                Ok(WatchEvent::Changed(_)) => continue,
+                Ok(WatchEvent::Stop) | Err(TryRecvError::Disconnected) => return,
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+    }
+}
+
+/// Watch the filter's root recursively and call `rerun` after each settled change
+///
+/// Returns once Ctrl-C is pressed.
+pub fn watch_and_rerun(filter: &WatchFilter, rerun: impl FnMut()) -> Result<(), ButlerError> {
+    let (tx, rx) = mpsc::channel();
+
+    let interrupts = interrupt::subscribe()
+        .map_err(|e| ButlerError::General(format!("Unable to listen for Ctrl-C: {}", e)))?;
+    let stop_tx = tx.clone();
+    std::thread::spawn(move || {
+        if interrupts.recv().is_ok() {
+            let _ = stop_tx.send(WatchEvent::Stop);
+        }
+    });
+
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<notify::Event>| match result {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                let _ = tx.send(WatchEvent::Changed(event.paths));
+            }
+            Ok(_) => {}
+            Err(e) => warn!("File watching error: {}", e),
+        })
+        .map_err(|e| ButlerError::General(format!("Unable to watch for file changes: {}", e)))?;
+
+    watcher
+        .watch(filter.root(), RecursiveMode::Recursive)
+        .map_err(|e| {
+            ButlerError::General(format!(
+                "Unable to watch {}: {}",
+                filter.root().display(),
+                e
+            ))
+        })?;
+
+    watch_loop(&rx, filter, DEBOUNCE, rerun);
+    debug!("File watching stopped");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Instant;
+
+    const TEST_DEBOUNCE: Duration = Duration::from_millis(100);
+
+    fn spawn_loop(
+        filter: WatchFilter,
+    ) -> (
+        mpsc::Sender<WatchEvent>,
+        Arc<AtomicUsize>,
+        thread::JoinHandle<()>,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&runs);
+        let handle = thread::spawn(move || {
+            watch_loop(&rx, &filter, TEST_DEBOUNCE, || {
+                counter.fetch_add(1, Ordering::SeqCst);
+            })
+        });
+        (tx, runs, handle)
+    }
+
+    fn wait_for_runs(runs: &AtomicUsize, expected: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while runs.load(Ordering::SeqCst) < expected && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn reruns_after_debounce_window() {
+        let (tx, runs, handle) = spawn_loop(WatchFilter::new("/project"));
+
+        let sent = Instant::now();
+        tx.send(WatchEvent::Changed(vec![PathBuf::from(
+            "/project/lib/app.rb",
+        )]))
+        .unwrap();
+        wait_for_runs(&runs, 1);
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(sent.elapsed() >= TEST_DEBOUNCE);
+
+        tx.send(WatchEvent::Stop).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn burst_of_changes_reruns_once() {
+        let (tx, runs, handle) = spawn_loop(WatchFilter::new("/project"));
+
+        for name in ["a.rb", "b.rb", "c.rb"] {
+            tx.send(WatchEvent::Changed(vec![Path::new("/project").join(name)]))
+                .unwrap();
+        }
+        wait_for_runs(&runs, 1);
+        thread::sleep(TEST_DEBOUNCE * 2);
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        tx.send(WatchEvent::Stop).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn excluded_changes_and_stop_never_rerun() {
+        let (tx, runs, handle) = spawn_loop(
+            WatchFilter::new("/project").excluding(vec![PathBuf::from("/project/gems")]),
+        );
+
+        for path in [
+            "/project/.rb/vendor/bundler/x.rb",
+            "/project/.git/index",
+            "/project/vendor/bundle/ruby/3.3.0/y.rb",
+            "/project/gems/z.rb",
+            "/elsewhere/file.rb",
+        ] {
+            tx.send(WatchEvent::Changed(vec![PathBuf::from(path)]))
+                .unwrap();
+        }
+        tx.send(WatchEvent::Stop).unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn only_files_limits_relevant_changes() {
+        let filter = WatchFilter::new("/project").only_files(&[".ruby-version", "Gemfile"]);
+
+        assert!(filter.is_relevant(Path::new("/project/.ruby-version")));
+        assert!(filter.is_relevant(Path::new("/project/Gemfile")));
+        assert!(!filter.is_relevant(Path::new("/project/lib/Gemfile")));
+        assert!(!filter.is_relevant(Path::new("/project/app.rb")));
+    }
+
+    #[test]
+    fn stop_during_debounce_skips_pending_rerun() {
+        let (tx, runs, handle) = spawn_loop(WatchFilter::new("/project"));
+
+        tx.send(WatchEvent::Changed(vec![PathBuf::from("/project/app.rb")]))
+            .unwrap();
+        tx.send(WatchEvent::Stop).unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+}
@@ -178,3 +178,1437 @@ fn test_all_major_features_are_commands() {
         "Options should list --very-verbose flag"
     );
 }
+
+#[test]
+fn test_no_color_env_disables_ansi_output() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("help")
+        // Force colors even though stdout is piped, so the opt-out is what removes them
+        .env("CLICOLOR_FORCE", "1")
+        .env("NO_COLOR", "1")
+        .env_remove("RB_NO_COLOR")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(output.status.success(), "help should succeed");
+    assert!(
+        !stdout.contains('\u{1b}'),
+        "Output should not contain ANSI escape sequences with NO_COLOR set"
+    );
+}
+
+#[test]
+fn test_no_color_flag_disables_ansi_output() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["--no-color", "help"])
+        .env("CLICOLOR_FORCE", "1")
+        .env_remove("NO_COLOR")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(output.status.success(), "help should succeed");
+    assert!(
+        !stdout.contains('\u{1b}'),
+        "Output should not contain ANSI escape sequences with --no-color"
+    );
+}
+
+#[test]
+fn test_forced_colors_emit_ansi_output() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("help")
+        .env("CLICOLOR_FORCE", "1")
+        .env_remove("NO_COLOR")
+        .env_remove("RB_NO_COLOR")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(
+        stdout.contains('\u{1b}'),
+        "Output should contain ANSI escape sequences when colors are forced"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_clean_env_drops_inherited_variables() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let parent_path = format!(
+        "/rb-test-parent-only:{}",
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let run_env = |extra: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rb"));
+        cmd.arg("-R")
+            .arg(sandbox.root())
+            .args(["exec", "--allow-system"])
+            .args(extra)
+            .arg("env")
+            .current_dir(&work_dir)
+            .env("RB_TEST_JUNK", "leaked")
+            .env("PATH", parent_path.clone());
+        output_to_string(&cmd.output().expect("Failed to execute rb").stdout)
+    };
+
+    let inherited = run_env(&[]);
+    assert!(
+        inherited.contains("RB_TEST_JUNK=leaked"),
+        "Without --clean-env the variable should be inherited"
+    );
+
+    let clean = run_env(&["--clean-env"]);
+    assert!(
+        !clean.contains("RB_TEST_JUNK"),
+        "With --clean-env the junk variable should be absent"
+    );
+    let path = clean
+        .lines()
+        .find_map(|line| line.strip_prefix("PATH="))
+        .expect("Butler's composed PATH should still be present");
+    assert!(
+        !path.contains("/rb-test-parent-only"),
+        "The parent's PATH should not leak through: {}",
+        path
+    );
+    assert!(path.ends_with("/usr/local/bin:/usr/bin:/bin"), "{}", path);
+    assert!(inherited.contains("/rb-test-parent-only"));
+}
+
+#[test]
+fn test_rb_home_moves_default_rubies_and_gem_dirs() {
+    let home = tempfile::TempDir::new().expect("Failed to create temp home");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["info", "config"])
+        .env("RB_HOME", home.path())
+        .env_remove("RB_RUBIES_DIR")
+        .env_remove("RB_GEM_HOME")
+        .env_remove("RB_CONFIG")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    let rubies_dir = home.path().join(".rubies");
+    let gem_home = home.path().join(".gem");
+    assert!(
+        stdout.contains(&format!("Rubies Directory: {}", rubies_dir.display())),
+        "Expected default rubies dir under RB_HOME, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains(&format!("Gem Home: {}", gem_home.display())),
+        "Expected default gem home under RB_HOME, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_info_config_env_format_lists_explicit_values() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["-R", "/opt/rubies", "--keep-binstubs"])
+        .args(["info", "config", "--format", "env"])
+        .env_remove("RB_CONFIG")
+        .env_remove("RB_GEM_HOME")
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(output.status.success());
+    let stdout = output_to_string(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(
+        lines.contains(&"RB_RUBIES_DIR=/opt/rubies"),
+        "got {}",
+        stdout
+    );
+    assert!(lines.contains(&"RB_KEEP_BINSTUBS=true"), "got {}", stdout);
+    assert!(
+        !stdout.contains("RB_GEM_HOME"),
+        "Defaults should be left out, got {}",
+        stdout
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_inherit_gem_path_keeps_incoming_gem_path_entries() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let gem_path = |extra: &[&str]| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_rb"));
+        cmd.arg("-R")
+            .arg(sandbox.root())
+            .args(extra)
+            .args(["exec", "--allow-system", "env"])
+            .current_dir(&work_dir)
+            .env("GEM_PATH", "/opt/shared-gems:/var/lib/gems/3.2.0");
+        output_to_string(&cmd.output().expect("Failed to execute rb").stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("GEM_PATH=").map(str::to_string))
+            .expect("GEM_PATH should be composed")
+    };
+
+    let replaced = gem_path(&[]);
+    assert!(!replaced.contains("/opt/shared-gems"));
+
+    let inherited = gem_path(&["--inherit-gem-path"]);
+    let entries: Vec<&str> = inherited.split(':').collect();
+    let shared = entries.iter().position(|e| *e == "/opt/shared-gems");
+    let system = entries.iter().position(|e| *e == "/var/lib/gems/3.2.0");
+    assert!(shared.is_some() && system.is_some(), "got {}", inherited);
+    assert!(
+        entries[0].contains(".gem"),
+        "User gems should stay first, got {}",
+        inherited
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_gemset_isolates_gem_home_and_path() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["-g", "testing", "exec", "--allow-system", "env"])
+        .current_dir(&work_dir)
+        .env_remove("RB_GEMSET")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+    let var = |name: &str| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix(&format!("{}=", name)).map(str::to_string))
+            .unwrap_or_default()
+    };
+
+    let gem_home = var("GEM_HOME");
+    assert!(gem_home.ends_with("3.2.5@testing"), "got {}", gem_home);
+    assert!(
+        var("PATH").starts_with(&format!("{}/bin:", gem_home)),
+        "PATH should lead with the gemset bin, got {}",
+        var("PATH")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_creates_missing_gem_home_unless_disabled() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    // Returns the GEM_HOME the program saw
+    let exec_with_gem_base = |gem_base: &std::path::Path, extra: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+            .arg("-R")
+            .arg(sandbox.root())
+            .arg("-G")
+            .arg(gem_base)
+            .args(extra)
+            .args(["exec", "--allow-system", "env"])
+            .current_dir(&work_dir)
+            .env_remove("RB_CREATE_GEM_HOME")
+            .output()
+            .expect("Failed to execute rb");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            output_to_string(&output.stderr)
+        );
+        output_to_string(&output.stdout)
+            .lines()
+            .find_map(|line| line.strip_prefix("GEM_HOME=").map(std::path::PathBuf::from))
+            .expect("GEM_HOME should be set")
+    };
+
+    let created = sandbox.root().join("gems-created");
+    let gem_home = exec_with_gem_base(&created, &[]);
+    assert!(gem_home.starts_with(&created));
+    assert!(gem_home.join("bin").is_dir());
+
+    let untouched = sandbox.root().join("gems-untouched");
+    exec_with_gem_base(&untouched, &["--create-gem-home", "false"]);
+    assert!(!untouched.exists());
+}
+
+#[test]
+fn test_runtime_lists_rubies_from_additional_rubies_dirs() {
+    let home_rubies = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    home_rubies.add_ruby_dir("3.2.5").unwrap();
+    let opt_rubies = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    opt_rubies.add_ruby_dir("3.3.4").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(home_rubies.root())
+        .arg("--additional-rubies-dir")
+        .arg(opt_rubies.root())
+        .args(["info", "runtime"])
+        .current_dir(home_rubies.root())
+        .env_remove("RB_ADDITIONAL_RUBIES_DIRS")
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    let stdout = output_to_string(&output.stdout);
+    assert!(stdout.contains("CRuby (3.3.4)"), "stdout: {}", stdout);
+    assert!(stdout.contains("CRuby (3.2.5)"), "stdout: {}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_login_runs_program_through_login_shell() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    // Stands in for a login shell whose rc files export something the program relies on
+    let shell = sandbox.root().join("login-shell");
+    std::fs::write(
+        &shell,
+        "#!/bin/sh\n[ \"$1\" = -lc ] || exit 97\nexport LOGIN_MARKER=from-login-rc\nexec /bin/sh -c \"$2\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&shell, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args([
+            "exec",
+            "--allow-system",
+            "--login",
+            "--env",
+            "BUTLER_MARKER=from-butler",
+            "sh",
+            "-c",
+            "echo \"$LOGIN_MARKER:$BUTLER_MARKER\" && printf '%s\\n' \"$0\"",
+            "it's quoted",
+        ])
+        .current_dir(&work_dir)
+        .env("SHELL", &shell)
+        .env_remove("LOGIN_MARKER")
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    let stdout = output_to_string(&output.stdout);
+    assert!(
+        stdout.contains("from-login-rc:from-butler"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("it's quoted"), "stdout: {}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_command_log_records_exec_without_env_values() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    let log = sandbox.root().join("logs").join("commands.jsonl");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .arg("--command-log")
+        .arg(&log)
+        .args([
+            "exec",
+            "--allow-system",
+            "--env",
+            "API_TOKEN=s3cret",
+            "sh",
+            "-c",
+            "exit 0",
+        ])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    let content = std::fs::read_to_string(&log).expect("command log should be written");
+    assert!(!content.contains("s3cret"));
+    let entry: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["command"], "exec");
+    assert_eq!(entry["program"], "sh");
+    assert_eq!(entry["args"], serde_json::json!(["-c", "exit 0"]));
+    assert_eq!(entry["ruby"], "3.2.5");
+    assert_eq!(entry["env"], serde_json::json!(["API_TOKEN"]));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_env_flags_reach_child_and_override_inherited() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args([
+            "exec",
+            "--allow-system",
+            "--env",
+            "RB_TEST_FIRST=one",
+            "--env",
+            "RB_TEST_JUNK=overridden",
+            "--env",
+            "RB_TEST_OPTS=a=b c",
+            "env",
+        ])
+        .current_dir(&work_dir)
+        .env("RB_TEST_JUNK", "leaked")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.lines().any(|l| l == "RB_TEST_FIRST=one"));
+    assert!(stdout.lines().any(|l| l == "RB_TEST_JUNK=overridden"));
+    assert!(stdout.lines().any(|l| l == "RB_TEST_OPTS=a=b c"));
+    assert!(!stdout.contains("RB_TEST_JUNK=leaked"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_capture_writes_output_to_file_and_keeps_exit_code() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    let script = "echo to-stdout; echo to-stderr >&2; exit 3";
+
+    let combined = work_dir.join("combined.log");
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["exec", "--allow-system", "--capture"])
+        .arg(&combined)
+        .args(["sh", "-c", script])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(!output_to_string(&output.stdout).contains("to-stdout"));
+    let captured = std::fs::read_to_string(&combined).unwrap();
+    assert!(captured.contains("to-stdout") && captured.contains("to-stderr"));
+
+    let stdout_file = work_dir.join("out.log");
+    let stderr_file = work_dir.join("err.log");
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["exec", "--allow-system", "--capture"])
+        .arg(&stdout_file)
+        .arg("--capture-stderr-separate")
+        .arg(&stderr_file)
+        .args(["sh", "-c", script])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(
+        std::fs::read_to_string(&stdout_file).unwrap(),
+        "to-stdout\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&stderr_file).unwrap(),
+        "to-stderr\n"
+    );
+}
+
+#[test]
+fn test_exec_env_rejects_assignment_without_equals() {
+    let output = run_rb_command(&["exec", "--env", "RAILS_ENV", "env"]);
+    let stderr = output_to_string(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("'RAILS_ENV' is not a KEY=VALUE assignment"),
+        "Unexpected error: {}",
+        stderr
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_print_path_shows_composed_path_without_running() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .arg("-G")
+        .arg(sandbox.root().join("gems"))
+        .args(["exec", "--print-path", "definitely-not-a-program"])
+        .current_dir(&work_dir)
+        .env("PATH", "/usr/bin:/bin")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "Printing the PATH should not attempt execution, stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    let entries: Vec<&str> = stdout.trim().split(':').collect();
+    assert!(
+        entries[0].starts_with(&sandbox.root().join("gems").display().to_string())
+            && entries[0].ends_with("bin"),
+        "PATH should begin with the gem bin directory, got: {}",
+        stdout
+    );
+    assert_eq!(
+        entries[1],
+        sandbox
+            .root()
+            .join("ruby-3.2.5")
+            .join("bin")
+            .display()
+            .to_string(),
+        "Ruby's bin directory should follow"
+    );
+    assert!(
+        stdout.trim().ends_with("/usr/bin:/bin"),
+        "Inherited PATH should come last, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_run_disabled_script_reports_error() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/rbproject.toml",
+            "[scripts]\ndeploy = { command = \"cap deploy\", enabled = false }\n",
+        )
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["run", "deploy"])
+        .current_dir(sandbox.root().join("work"))
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(
+        !output.status.success(),
+        "Running a disabled script should fail"
+    );
+    assert!(
+        output_to_string(&output.stderr).contains("script 'deploy' is disabled"),
+        "Error should explain the script is disabled"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_resolves_script_from_ancestor_project() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/rbproject.toml",
+            "[scripts]\ngreet = \"echo greeted-from-root\"\n",
+        )
+        .unwrap();
+    let nested_dir = sandbox.add_dir("work/lib/deeply/nested").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["run", "greet"])
+        .current_dir(&nested_dir)
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "Script from the ancestor project should run, stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert!(
+        stdout.contains("greeted-from-root"),
+        "Root-defined script should execute from a nested directory, got: {}",
+        stdout
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_uses_shared_scripts_only_when_enabled() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/pyproject.toml",
+            "[project]\nname = \"work\"\n\n[tool.rb.scripts]\ngreet = \"echo greeted-from-pyproject\"\n",
+        )
+        .unwrap();
+
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rb"))
+            .arg("-R")
+            .arg(sandbox.root())
+            .args(extra)
+            .args(["run", "greet"])
+            .current_dir(sandbox.root().join("work"))
+            .env_remove("RB_SHARED_SCRIPTS")
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    let output = run(&[]);
+    assert!(
+        !output.status.success(),
+        "pyproject.toml should be ignored without --shared-scripts"
+    );
+
+    let output = run(&["--shared-scripts"]);
+    let stdout = output_to_string(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Shared script should run, stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert!(
+        stdout.contains("greeted-from-pyproject"),
+        "Script from [tool.rb.scripts] should execute, got: {}",
+        stdout
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_shell_spawns_subshell_with_composed_environment() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    let fake_shell = sandbox
+        .add_file(
+            "fake-shell",
+            "#!/bin/sh\necho \"GEM_HOME=$GEM_HOME\"\necho \"RB_SUBSHELL=$RB_SUBSHELL\"\n",
+        )
+        .unwrap();
+    std::fs::set_permissions(&fake_shell, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .arg("-G")
+        .arg(sandbox.root().join("gems"))
+        .arg("shell")
+        .current_dir(&work_dir)
+        .env("SHELL", &fake_shell)
+        .env_remove("GEM_HOME")
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(
+        output.status.success(),
+        "Subshell exit should return control"
+    );
+    let gem_home_line = stdout
+        .lines()
+        .find(|line| line.starts_with("GEM_HOME="))
+        .expect("Fake shell should report GEM_HOME");
+    assert!(
+        gem_home_line.starts_with(&format!(
+            "GEM_HOME={}",
+            sandbox.root().join("gems").display()
+        )) && gem_home_line.ends_with("3.2.5"),
+        "Subshell should see Butler's GEM_HOME, got: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("RB_SUBSHELL=CRuby-3.2.5"),
+        "Subshell should carry the prompt indicator, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_info_env_explain_reports_selection_reason() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["info", "env", "--explain"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("latest available installation"),
+        "Explanation should name the latest fallback, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_run_lists_scripts_from_gem_toml() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file("work/gem.toml", "[scripts]\nspec = \"rspec\"\n")
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .arg("run")
+        .current_dir(sandbox.root().join("work"))
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("spec") && stdout.contains("gem.toml"),
+        "Listing should show scripts from gem.toml, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_new_with_filename_creates_gem_kdl() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["new", "--filename", "gem.kdl"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(output.status.success());
+    assert!(temp_dir.path().join("gem.kdl").exists());
+    assert!(!temp_dir.path().join("rbproject.toml").exists());
+}
+
+#[test]
+fn test_new_dry_run_prints_template_without_writing() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["new", "--dry-run"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(output.status.success());
+    assert_eq!(
+        output_to_string(&output.stdout),
+        rb_core::project::template::DEFAULT_RBPROJECT_TOML
+    );
+    assert!(!temp_dir.path().join("rbproject.toml").exists());
+}
+
+#[test]
+fn test_pin_selects_ruby_until_unpinned() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_ruby_dir("3.3.4").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    let rb = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rb"))
+            .arg("-R")
+            .arg(sandbox.root())
+            .args(args)
+            .current_dir(&work_dir)
+            .env_remove("RB_RUBY_VERSION")
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    assert!(rb(&["pin", "3.2.5"]).status.success());
+    let pinned = output_to_string(&rb(&["info", "env", "--explain"]).stdout);
+    assert!(pinned.contains("(3.2.5)"), "got {}", pinned);
+    assert!(pinned.contains("pinned with rb pin"), "got {}", pinned);
+
+    assert!(rb(&["unpin"]).status.success());
+    let unpinned = output_to_string(&rb(&["info", "env", "--explain"]).stdout);
+    assert!(unpinned.contains("(3.3.4)"), "got {}", unpinned);
+    assert!(!work_dir.join(".rb").join("ruby-version").exists());
+}
+
+#[test]
+fn test_platform_list_reads_lockfile_platforms() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_dir("app").unwrap();
+    sandbox
+        .add_file("app/Gemfile", "source 'https://rubygems.org'\n")
+        .unwrap();
+    sandbox
+        .add_file(
+            "app/Gemfile.lock",
+            "GEM\n  specs:\n\nPLATFORMS\n  ruby\n  x86_64-linux\n\nDEPENDENCIES\n",
+        )
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["platform", "list"])
+        .current_dir(sandbox.root().join("app"))
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(
+        stdout.contains("ruby") && stdout.contains("x86_64-linux"),
+        "Listing should show locked platforms, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_missing_rubies_dir_exits_with_code_3() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(temp_dir.path().join("no-rubies-here"))
+        .args(["exec", "ruby", "-v"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute rb");
+
+    assert_eq!(
+        output.status.code(),
+        Some(3),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+}
+
+#[test]
+fn test_unsatisfied_ruby_version_exits_with_code_2() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["-r", "9.9.9", "exec", "ruby", "-v"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+}
+
+#[test]
+fn test_info_project_shows_requested_ruby_as_given() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.3.4").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    std::fs::write(work_dir.join("rbproject.toml"), "[scripts]\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["--no-color", "-r", "3.3", "-r", "3.3.4", "info", "project"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    let stdout = output_to_string(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Requested Ruby: 3.3\n"),
+        "Requested Ruby should be shown as given: {}",
+        stdout
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_refuses_system_commands_without_allow_system() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let rb = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rb"))
+            .arg("-R")
+            .arg(sandbox.root())
+            .args(args)
+            .current_dir(&work_dir)
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    // `sh` comes only from the inherited PATH, never from the sandboxed Ruby
+    let refused = rb(&["exec", "sh", "-c", "exit 0"]);
+    assert_eq!(
+        refused.status.code(),
+        Some(4),
+        "stderr: {}",
+        output_to_string(&refused.stderr)
+    );
+    assert!(output_to_string(&refused.stderr).contains("--allow-system"));
+
+    let allowed = rb(&["exec", "--allow-system", "sh", "-c", "exit 0"]);
+    assert!(
+        allowed.status.success(),
+        "stderr: {}",
+        output_to_string(&allowed.stderr)
+    );
+}
+
+#[test]
+fn test_unknown_program_exits_with_code_4() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["exec", "butler-definitely-not-installed"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    assert_eq!(
+        output.status.code(),
+        Some(4),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_measure_reports_duration_and_keeps_exit_code() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/rbproject.toml",
+            "[scripts]\nfail = \"sh -c 'exit 6'\"\n",
+        )
+        .unwrap();
+
+    for args in [
+        &["exec", "--allow-system", "--measure", "sh", "-c", "exit 3"][..],
+        &["run", "--measure", "fail"][..],
+    ] {
+        let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+            .arg("-R")
+            .arg(sandbox.root())
+            .args(args)
+            .current_dir(&work_dir)
+            .output()
+            .expect("Failed to execute rb");
+
+        let stderr = output_to_string(&output.stderr);
+        let expected = if args[0] == "exec" { 3 } else { 6 };
+        assert_eq!(output.status.code(), Some(expected), "stderr: {}", stderr);
+        let line = stderr
+            .lines()
+            .find(|line| line.starts_with("⏱ "))
+            .unwrap_or_else(|| panic!("no duration line in stderr: {}", stderr));
+        assert!(line.ends_with('s'), "duration line: {}", line);
+        assert!(
+            line.trim_start_matches("⏱ ")
+                .trim_end_matches('s')
+                .parse::<f64>()
+                .is_ok(),
+            "duration line: {}",
+            line
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_runtime_default_saves_default_ruby_for_later_selection() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    sandbox.add_ruby_dir("3.3.0").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    let config = sandbox.root().join("config").join("rb.toml");
+
+    let rb = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rb"))
+            .env("RB_CONFIG", &config)
+            .arg("-R")
+            .arg(sandbox.root())
+            .args(args)
+            .current_dir(&work_dir)
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    let output = rb(&["info", "runtime", "--default", "3.2.5"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    let content = std::fs::read_to_string(&config).expect("config should be written");
+    assert!(content.contains("default-ruby = \"3.2.5\""), "{}", content);
+
+    let output = rb(&["exec", "--allow-system", "sh", "-c", "command -v ruby"]);
+    assert!(
+        output_to_string(&output.stdout).contains("ruby-3.2.5"),
+        "stdout: {}",
+        output_to_string(&output.stdout)
+    );
+
+    let output = rb(&["info", "runtime", "--default", "3.1.0"]);
+    assert!(!output.status.success());
+    let content = std::fs::read_to_string(&config).unwrap();
+    assert!(content.contains("default-ruby = \"3.2.5\""));
+}
+
+#[test]
+fn test_plain_output_covers_project_commands_and_errors() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let rb_plain = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rb"))
+            .args(["--format", "plain", "-R"])
+            .arg(sandbox.root())
+            .args(args)
+            .current_dir(&work_dir)
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    let missing = rb_plain(&["run", "test"]);
+    assert!(!missing.status.success());
+    let stderr = output_to_string(&missing.stderr);
+    assert!(
+        stderr.is_ascii(),
+        "Plain errors should be ASCII: {}",
+        stderr
+    );
+
+    let created = rb_plain(&["new"]);
+    assert!(created.status.success());
+    let stdout = output_to_string(&created.stdout);
+    assert!(stdout.contains("rbproject.toml"));
+    assert!(
+        stdout.is_ascii(),
+        "Plain output should be ASCII: {}",
+        stdout
+    );
+
+    for args in [&["info", "project"][..], &["run"], &["new", "--check"]] {
+        let output = rb_plain(args);
+        let stdout = output_to_string(&output.stdout);
+        assert!(output.status.success(), "{:?} failed", args);
+        assert!(
+            stdout.is_ascii(),
+            "Plain output should be ASCII: {}",
+            stdout
+        );
+        assert!(
+            !stdout.contains('\x1b'),
+            "Plain output should have no colors"
+        );
+    }
+
+    let config = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["info", "config", "--format", "plain"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+    let stdout = output_to_string(&config.stdout);
+    assert!(config.status.success());
+    assert!(
+        stdout.is_ascii(),
+        "Plain output should be ASCII: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_plain_format_keeps_environment_output_ascii() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["--format", "plain", "-R"])
+        .arg(sandbox.root())
+        .args(["info", "env", "--explain"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    let stdout = output_to_string(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert!(stdout.contains("Your Current Ruby Environment"));
+    assert!(
+        stdout.is_ascii(),
+        "Plain output should be ASCII: {}",
+        stdout
+    );
+    assert!(
+        !stdout.contains('\x1b'),
+        "Plain output should have no colors"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_forwards_piped_stdin_to_program() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args([
+            "exec",
+            "--allow-system",
+            "sh",
+            "-c",
+            "read line; echo \"received: $line\"",
+        ])
+        .current_dir(&work_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute rb");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"good evening\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert!(
+        output_to_string(&output.stdout).contains("received: good evening"),
+        "stdout: {}",
+        output_to_string(&output.stdout)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_allow_system_ruby_falls_back_to_ruby_on_path() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    let work_dir = sandbox.add_dir("work").unwrap();
+    let prefix = sandbox.add_dir("system").unwrap();
+    let bin = prefix.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let ruby = bin.join("ruby");
+    std::fs::write(
+        &ruby,
+        format!(
+            "#!/bin/sh\necho ruby\necho 3.1.4\necho '{}'\n",
+            prefix.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&ruby, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:/usr/bin:/bin", bin.display());
+    let missing = sandbox.root().join("no-rubies-here");
+
+    let rb = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_rb"))
+            .env("PATH", &path)
+            .env("RB_HOME", sandbox.root())
+            .arg("-R")
+            .arg(&missing)
+            .args(args)
+            .current_dir(&work_dir)
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    let output = rb(&["info", "env"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = rb(&["--allow-system-ruby", "info", "env"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert!(output_to_string(&output.stdout).contains("3.1.4"));
+
+    let output = rb(&[
+        "--allow-system-ruby",
+        "exec",
+        "--allow-system",
+        "sh",
+        "-c",
+        "command -v ruby",
+    ]);
+    assert_eq!(
+        output_to_string(&output.stdout).trim(),
+        ruby.display().to_string()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_summary_reports_each_step_of_a_chain() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/rbproject.toml",
+            "[scripts]\n\
+             first = \"sh -c 'exit 0'\"\n\
+             second = \"sh -c 'exit 3'\"\n\
+             ci = \"sh -c 'rb run first && rb run second'\"\n",
+        )
+        .unwrap();
+
+    // Nested `rb run` calls find this binary and the sandbox rubies
+    let rb_dir = std::path::Path::new(env!("CARGO_BIN_EXE_rb"))
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let path = std::env::join_paths(std::iter::once(rb_dir).chain(std::env::split_paths(
+        &std::env::var_os("PATH").unwrap_or_default(),
+    )))
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .args(["run", "ci"])
+        .env("RB_RUBIES_DIR", sandbox.root())
+        .env("PATH", path)
+        .env_remove("RB_RUN_SUMMARY")
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    let stdout = output_to_string(&output.stdout);
+    assert_eq!(
+        output.status.code(),
+        Some(3),
+        "stdout: {}\nstderr: {}",
+        stdout,
+        output_to_string(&output.stderr)
+    );
+    // The summary goes to stderr, leaving the scripts' stdout untouched
+    assert!(!stdout.contains("Run Summary"), "stdout: {}", stdout);
+    let stderr = output_to_string(&output.stderr);
+    let summary: Vec<&str> = stderr
+        .lines()
+        .skip_while(|line| !line.contains("Run Summary"))
+        .skip(1)
+        .collect();
+    assert_eq!(
+        summary,
+        ["✅ first", "❌ second (exit code 3)", "❌ ci (exit code 3)"],
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_keep_going_runs_every_script_after_a_failure() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/rbproject.toml",
+            "[scripts]\n\
+             lint = \"sh -c 'echo lint >> ran.log; exit 2'\"\n\
+             test = \"sh -c 'echo test >> ran.log'\"\n",
+        )
+        .unwrap();
+    let ran_log = work_dir.join("ran.log");
+
+    let rb = |args: &[&str]| {
+        let _ = std::fs::remove_file(&ran_log);
+        let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+            .args(args)
+            .env("RB_RUBIES_DIR", sandbox.root())
+            .env_remove("RB_RUN_SUMMARY")
+            .current_dir(&work_dir)
+            .output()
+            .expect("Failed to execute rb");
+        let ran = std::fs::read_to_string(&ran_log).unwrap_or_default();
+        (output, ran)
+    };
+
+    let (output, ran) = rb(&["run", "lint", "test"]);
+    assert_eq!(
+        output.status.code(),
+        Some(2),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    assert_eq!(ran, "lint\n", "The default stops at the first failure");
+
+    let (output, ran) = rb(&["run", "--keep-going", "lint", "test"]);
+    let stderr = output_to_string(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "stderr: {}", stderr);
+    assert_eq!(ran, "lint\ntest\n", "--keep-going runs both scripts");
+    assert!(
+        stderr.contains("1 of 2 scripts failed: lint"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_at_script_runs_project_script_through_exec() {
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = sandbox.add_dir("work").unwrap();
+    sandbox
+        .add_file(
+            "work/rbproject.toml",
+            "[scripts]\ntest = \"echo script-ran\"\n",
+        )
+        .unwrap();
+    let capture = work_dir.join("captured.log");
+
+    // --capture only exists on rb exec, so the output landing there proves the exec path
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .arg("exec")
+        .arg("--capture")
+        .arg(&capture)
+        .args(["@test", "with-args"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+    let captured = std::fs::read_to_string(&capture).unwrap();
+    assert_eq!(captured.trim(), "script-ran with-args");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["exec", "@missing"])
+        .current_dir(&work_dir)
+        .output()
+        .expect("Failed to execute rb");
+    assert!(!output.status.success());
+    assert!(
+        output_to_string(&output.stderr).contains("'missing' is not defined"),
+        "stderr: {}",
+        output_to_string(&output.stderr)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_missing_ruby_in_read_only_rubies_dir_notes_read_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = rb_tests::RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.2.5").unwrap();
+    let work_dir = tempfile::tempdir().unwrap();
+    let permissions = |mode| std::fs::Permissions::from_mode(mode);
+    std::fs::set_permissions(sandbox.root(), permissions(0o555)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rb"))
+        .arg("-R")
+        .arg(sandbox.root())
+        .args(["-r", "9.9.9", "info", "env"])
+        .current_dir(work_dir.path())
+        .output()
+        .expect("Failed to execute rb");
+    std::fs::set_permissions(sandbox.root(), permissions(0o755)).unwrap();
+
+    let stderr = output_to_string(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "stderr: {}", stderr);
+    assert!(stderr.contains("is read-only"), "stderr: {}", stderr);
+    assert!(stderr.contains("RB_RUBIES_DIR"), "stderr: {}", stderr);
+    assert!(!stderr.contains("ruby-install"), "stderr: {}", stderr);
+}
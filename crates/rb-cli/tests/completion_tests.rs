@@ -1,6 +1,16 @@
 use rb_tests::RubySandbox;
 use std::io::Write;
 
+/// Helper to place an executable stub where discovery expects `bin/ruby`
+#[cfg(unix)]
+fn create_ruby_executable(path: &std::path::Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::File::create(path).expect("Failed to create ruby executable");
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .expect("Failed to mark ruby executable");
+}
+
 /// Helper to capture stdout output from completion generation
 fn capture_completions(
     line: &str,
@@ -84,6 +94,25 @@ fn test_ruby_version_completion_with_prefix() {
     assert!(!completions.contains("3.2.1"));
 }
 
+#[test]
+fn test_ruby_version_completion_includes_additional_rubies_dirs() {
+    let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.4.5").unwrap();
+    let opt_rubies = RubySandbox::new().expect("Failed to create sandbox");
+    opt_rubies.add_ruby_dir("3.2.1").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_rb"))
+        .env("RB_RUBIES_DIR", sandbox.root())
+        .env("RB_ADDITIONAL_RUBIES_DIRS", opt_rubies.root())
+        .args(["__bash_complete", "rb -r ", "6"])
+        .output()
+        .expect("Failed to execute rb");
+    let completions = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+
+    assert!(completions.contains("3.4.5"));
+    assert!(completions.contains("3.2.1"));
+}
+
 #[test]
 #[cfg(unix)]
 fn test_tilde_expansion_in_rubies_dir_short_flag() {
@@ -96,11 +125,11 @@ fn test_tilde_expansion_in_rubies_dir_short_flag() {
 
     let ruby_345 = test_dir.join("ruby-3.4.5").join("bin");
     std::fs::create_dir_all(&ruby_345).expect("Failed to create ruby-3.4.5");
-    std::fs::File::create(ruby_345.join("ruby")).expect("Failed to create ruby executable");
+    create_ruby_executable(&ruby_345.join("ruby"));
 
     let ruby_344 = test_dir.join("ruby-3.4.4").join("bin");
     std::fs::create_dir_all(&ruby_344).expect("Failed to create ruby-3.4.4");
-    std::fs::File::create(ruby_344.join("ruby")).expect("Failed to create ruby executable");
+    create_ruby_executable(&ruby_344.join("ruby"));
 
     let cmd_line = "rb -R ~/.rb-test-rubies -r ";
     let cursor_pos = "28";
@@ -137,7 +166,7 @@ fn test_tilde_expansion_in_rubies_dir_long_flag() {
 
     let ruby_337 = test_dir.join("ruby-3.3.7").join("bin");
     std::fs::create_dir_all(&ruby_337).expect("Failed to create ruby-3.3.7");
-    std::fs::File::create(ruby_337.join("ruby")).expect("Failed to create ruby executable");
+    create_ruby_executable(&ruby_337.join("ruby"));
 
     let cmd_line = "rb --rubies-dir ~/.rb-test-rubies-long -r 3.3";
     let cursor_pos = "48";
@@ -169,7 +198,7 @@ fn test_tilde_only_expands_to_home() {
 
     let ruby_345 = test_dir.join("ruby-3.4.5").join("bin");
     std::fs::create_dir_all(&ruby_345).expect("Failed to create ruby-3.4.5");
-    std::fs::File::create(ruby_345.join("ruby")).expect("Failed to create ruby executable");
+    create_ruby_executable(&ruby_345.join("ruby"));
 
     // Test completion with just tilde (no trailing slash)
     let cmd_line = format!("rb -R {}/.rb-test-tilde-only -r ", home_dir);
@@ -276,6 +305,50 @@ fn test_script_completion_with_prefix() {
     );
 }
 
+#[test]
+fn test_script_completion_honors_project_flag() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let work_dir = temp_dir.path().join("work");
+    let elsewhere = temp_dir.path().join("elsewhere");
+    std::fs::create_dir_all(&work_dir).unwrap();
+    std::fs::create_dir_all(&elsewhere).unwrap();
+    std::fs::write(
+        work_dir.join("rbproject.toml"),
+        "[scripts]\nlocal-only = 'rake local'\n",
+    )
+    .unwrap();
+    let project_file = elsewhere.join("rbproject.toml");
+    std::fs::write(
+        &project_file,
+        "[scripts]\nrelease = 'rake release'\nlint = 'rubocop'\n",
+    )
+    .unwrap();
+
+    for flag in ["-P", "--project"] {
+        let line = format!("rb {} {} run ", flag, project_file.display());
+        let mut cmd = std::process::Command::new(env!("CARGO_BIN_EXE_rb"));
+        cmd.arg("__bash_complete")
+            .arg(&line)
+            .arg(line.len().to_string());
+        cmd.current_dir(&work_dir);
+
+        let output = cmd.output().expect("Failed to execute rb");
+        let completions = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+
+        assert!(
+            completions.contains("release") && completions.contains("lint"),
+            "Expected scripts from {} in completions, got: {}",
+            project_file.display(),
+            completions
+        );
+        assert!(
+            !completions.contains("local-only"),
+            "Should not complete scripts from the current directory, got: {}",
+            completions
+        );
+    }
+}
+
 #[test]
 fn test_binstubs_completion_from_bundler() {
     use std::fs;
@@ -705,6 +778,41 @@ fn test_flag_completion_shows_all_flags() {
     );
 }
 
+#[test]
+fn test_global_flags_after_runtime_command() {
+    let completions = capture_completions("rb runtime --", "13", None);
+
+    for flag in ["--verbose", "--ruby", "--rubies-dir", "--log-level"] {
+        assert!(
+            completions.lines().any(|line| line == flag),
+            "Expected {} after a subcommand, got: {}",
+            flag,
+            completions
+        );
+    }
+}
+
+#[test]
+fn test_sync_flags_include_global_and_own_flags() {
+    let completions = capture_completions("rb sync --", "10", None);
+
+    for flag in ["--verbose", "--ruby", "--retry", "--path"] {
+        assert!(
+            completions.lines().any(|line| line == flag),
+            "Expected {} for rb sync, got: {}",
+            flag,
+            completions
+        );
+    }
+
+    let alias_completions = capture_completions("rb s --", "7", None);
+    assert!(alias_completions.lines().any(|line| line == "--retry"));
+
+    // Flags belonging to sync are not offered at the top level
+    let top_level = capture_completions("rb --", "5", None);
+    assert!(!top_level.lines().any(|line| line == "--retry"));
+}
+
 #[test]
 fn test_command_alias_completion() {
     let completions = capture_completions("rb r", "4", None);
@@ -836,6 +944,59 @@ fn test_run_command_first_arg_completes_scripts() {
     );
 }
 
+#[test]
+fn test_run_command_completion_skips_disabled_scripts() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let rbproject_path = temp_dir.path().join("rbproject.toml");
+
+    let mut file = std::fs::File::create(&rbproject_path).expect("Failed to create rbproject.toml");
+    writeln!(file, "[scripts]").unwrap();
+    writeln!(file, "test = \"rspec\"").unwrap();
+    writeln!(
+        file,
+        "deploy = {{ command = \"cap deploy\", enabled = false }}"
+    )
+    .unwrap();
+
+    let mut cmd = std::process::Command::new(env!("CARGO_BIN_EXE_rb"));
+    cmd.arg("__bash_complete").arg("rb run ").arg("7");
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().expect("Failed to execute rb");
+    let completions = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+
+    assert!(
+        completions.contains("test"),
+        "Expected 'test' script in completions"
+    );
+    assert!(
+        !completions.contains("deploy"),
+        "Disabled 'deploy' script should not be completed"
+    );
+}
+
+#[test]
+fn test_run_command_completes_scripts_from_gem_toml() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::fs::write(
+        temp_dir.path().join("gem.toml"),
+        "[scripts]\nspec = \"rspec\"\n",
+    )
+    .expect("Failed to create gem.toml");
+
+    let mut cmd = std::process::Command::new(env!("CARGO_BIN_EXE_rb"));
+    cmd.arg("__bash_complete").arg("rb run ").arg("7");
+    cmd.current_dir(temp_dir.path());
+
+    let output = cmd.output().expect("Failed to execute rb");
+    let completions = String::from_utf8(output.stdout).expect("Invalid UTF-8 output");
+
+    assert!(
+        completions.contains("spec"),
+        "Expected 'spec' script from gem.toml in completions"
+    );
+}
+
 #[test]
 fn test_run_command_second_arg_returns_empty() {
     let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
@@ -1145,3 +1306,98 @@ fn test_version_command_completion_with_prefix() {
         completions
     );
 }
+
+/// Helper to capture the structured completions emitted for editors
+fn capture_json_completions(
+    line: &str,
+    cursor_pos: &str,
+    rubies_dir: Option<std::path::PathBuf>,
+) -> Vec<serde_json::Value> {
+    let mut cmd = std::process::Command::new(env!("CARGO_BIN_EXE_rb"));
+
+    if let Some(dir) = rubies_dir {
+        cmd.env("RB_RUBIES_DIR", &dir);
+    }
+
+    cmd.arg("__complete_json").arg(line).arg(cursor_pos);
+
+    let output = cmd.output().expect("Failed to execute rb");
+    serde_json::from_slice(&output.stdout).expect("Completion output should be a JSON array")
+}
+
+#[test]
+fn test_json_completion_describes_commands() {
+    let completions = capture_json_completions("rb ru", "5", None);
+
+    let run = completions
+        .iter()
+        .find(|c| c["value"] == "run")
+        .expect("run should be suggested");
+    assert_eq!(run["kind"], "command");
+    assert!(
+        run["description"].as_str().is_some_and(|d| !d.is_empty()),
+        "Commands should carry their description, got: {}",
+        run
+    );
+    assert!(completions.iter().all(|c| c["kind"] == "command"));
+}
+
+#[test]
+fn test_json_completion_marks_version_candidates() {
+    let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+    sandbox.add_ruby_dir("3.4.5").unwrap();
+    sandbox.add_ruby_dir("3.3.7").unwrap();
+
+    let completions = capture_json_completions("rb -r 3.", "8", Some(sandbox.root().to_path_buf()));
+
+    let values: Vec<&str> = completions
+        .iter()
+        .map(|c| c["value"].as_str().unwrap())
+        .collect();
+    assert_eq!(values, vec!["3.4.5", "3.3.7"]);
+    assert!(completions.iter().all(|c| c["kind"] == "version"));
+}
+
+#[test]
+fn test_json_completion_marks_flags() {
+    let completions = capture_json_completions("rb --", "5", None);
+
+    let verbose = completions
+        .iter()
+        .find(|c| c["value"] == "--verbose")
+        .expect("--verbose should be suggested");
+    assert_eq!(verbose["kind"], "flag");
+    assert!(verbose["description"].is_string());
+}
+
+#[test]
+fn test_completions_refresh_picks_up_newly_added_ruby() {
+    let sandbox = RubySandbox::new().expect("Failed to create sandbox");
+    sandbox
+        .add_ruby_dir("3.3.6")
+        .expect("Failed to create ruby-3.3.6");
+
+    let refresh = || {
+        std::process::Command::new(env!("CARGO_BIN_EXE_rb"))
+            .arg("-R")
+            .arg(sandbox.root())
+            .arg("completions-refresh")
+            .output()
+            .expect("Failed to execute rb")
+    };
+
+    let output = refresh();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 Ruby"));
+
+    sandbox
+        .add_ruby_dir("3.4.1")
+        .expect("Failed to create ruby-3.4.1");
+    let output = refresh();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 Rubies"));
+
+    let completions = capture_completions("rb -r ", "6", Some(sandbox.root().to_path_buf()));
+    assert!(completions.contains("3.4.1"));
+    assert!(completions.contains("3.3.6"));
+}
@@ -1,7 +1,7 @@
 use rb_cli::config::{RbConfig, TrackedConfig};
 use rb_cli::dispatch::dispatch_command;
 use rb_cli::runtime_helpers::CommandContext;
-use rb_cli::{Commands, InfoCommands};
+use rb_cli::{Commands, ConfigFormat, InfoCommands, RuntimeSort};
 use std::path::PathBuf;
 
 fn create_test_context() -> CommandContext {
@@ -9,6 +9,7 @@ fn create_test_context() -> CommandContext {
     CommandContext {
         config: TrackedConfig::from_merged(&config, &RbConfig::default()),
         project_file: None,
+        config_file: None,
     }
 }
 
@@ -45,7 +46,15 @@ fn test_dispatch_new_command() {
     let original_dir = std::env::current_dir().unwrap();
     std::env::set_current_dir(&temp_dir).unwrap();
 
-    let result = dispatch_command(Commands::New, &mut context);
+    let result = dispatch_command(
+        Commands::New {
+            filename: "rbproject.toml".to_string(),
+            dry_run: false,
+            force: false,
+            check: false,
+        },
+        &mut context,
+    );
     assert!(result.is_ok());
 
     std::env::set_current_dir(&original_dir).unwrap();
@@ -57,7 +66,9 @@ fn test_dispatch_info_config_command() {
     let mut context = create_test_context();
     let result = dispatch_command(
         Commands::Info {
-            command: InfoCommands::Config,
+            command: InfoCommands::Config {
+                format: ConfigFormat::Human,
+            },
         },
         &mut context,
     );
@@ -73,7 +84,13 @@ fn test_dispatch_creates_runtime_lazily() {
     // Note: This test may output to stdout - that's expected behavior for the command
     let _ = dispatch_command(
         Commands::Info {
-            command: InfoCommands::Runtime,
+            command: InfoCommands::Runtime {
+                only: None,
+                since: None,
+                sort: RuntimeSort::Version,
+                reverse: false,
+                default: None,
+            },
         },
         &mut context,
     );
@@ -92,6 +109,7 @@ fn test_context_preserves_config() {
     let mut context = CommandContext {
         config: TrackedConfig::from_merged(&config, &RbConfig::default()),
         project_file: None,
+        config_file: None,
     };
 
     // Config should persist across command dispatch
@@ -42,15 +42,15 @@ fn test_format_command_not_found_provides_guidance() {
 }
 
 #[test]
-fn test_error_exit_code_returns_1_for_no_suitable_ruby() {
+fn test_error_exit_code_returns_2_for_no_suitable_ruby() {
     let error = ButlerError::NoSuitableRuby("test".to_string());
-    assert_eq!(error_exit_code(&error), 1);
+    assert_eq!(error_exit_code(&error), 2);
 }
 
 #[test]
-fn test_error_exit_code_returns_127_for_command_not_found() {
+fn test_error_exit_code_returns_4_for_command_not_found() {
     let error = ButlerError::CommandNotFound("test".to_string());
-    assert_eq!(error_exit_code(&error), 127);
+    assert_eq!(error_exit_code(&error), 4);
 }
 
 #[test]
@@ -60,7 +60,13 @@ fn test_error_exit_code_returns_1_for_general_error() {
 }
 
 #[test]
-fn test_error_exit_code_returns_1_for_rubies_directory_not_found() {
+fn test_error_exit_code_returns_3_for_rubies_directory_not_found() {
     let error = ButlerError::RubiesDirectoryNotFound(PathBuf::from("/test"));
-    assert_eq!(error_exit_code(&error), 1);
+    assert_eq!(error_exit_code(&error), 3);
+}
+
+#[test]
+fn test_error_exit_code_returns_5_for_bundler_failure() {
+    let error = ButlerError::BundlerFailed("bundle install failed".to_string());
+    assert_eq!(error_exit_code(&error), 5);
 }
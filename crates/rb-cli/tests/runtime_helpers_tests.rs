@@ -7,6 +7,7 @@ fn create_test_context() -> CommandContext {
     CommandContext {
         config: TrackedConfig::from_merged(&config, &RbConfig::default()),
         project_file: None,
+        config_file: None,
     }
 }
 
@@ -18,7 +19,7 @@ fn test_new_command_wrapper_creates_file() {
     let original_dir = std::env::current_dir().unwrap();
     std::env::set_current_dir(&temp_dir).unwrap();
 
-    let result = new_command_wrapper();
+    let result = new_command_wrapper("rbproject.toml", false, false, false);
     assert!(result.is_ok());
 
     assert!(temp_dir.join("rbproject.toml").exists());
@@ -47,7 +48,7 @@ fn test_new_command_wrapper_fails_if_file_exists() {
     let original_dir = std::env::current_dir().unwrap();
     std::env::set_current_dir(&temp_dir).unwrap();
 
-    let result = new_command_wrapper();
+    let result = new_command_wrapper("rbproject.toml", false, false, false);
     assert!(
         result.is_err(),
         "Expected error when rbproject.toml already exists"
@@ -74,6 +75,7 @@ fn test_command_context_stores_config() {
     let context = CommandContext {
         config: TrackedConfig::from_merged(&config, &RbConfig::default()),
         project_file: None,
+        config_file: None,
     };
 
     assert!(context.project_file.is_none());
@@ -0,0 +1,210 @@
+//! Reading the dependency graph locked in Gemfile.lock
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A gem as locked in one of Gemfile.lock's `specs:` lists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedSpec {
+    /// Locked version, platform suffix included (e.g. `1.16.0-x86_64-linux`)
+    pub version: String,
+    /// Names of the gems it depends on, in lockfile order
+    pub dependencies: Vec<String>,
+}
+
+/// A gem in the rendered dependency tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencyNode {
+    pub name: String,
+    /// Locked version, when the lockfile has a spec for the gem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub dependencies: Vec<DependencyNode>,
+}
+
+/// Top-level dependencies and locked specs from Gemfile.lock
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockedDependencies {
+    /// Gems listed under DEPENDENCIES, i.e. declared in the Gemfile
+    pub top_level: Vec<String>,
+    /// Every locked spec by name; for platform variants the first listed is kept
+    pub specs: BTreeMap<String, LockedSpec>,
+}
+
+impl LockedDependencies {
+    /// Parse the DEPENDENCIES section and every `specs:` list (GEM, GIT, PATH) of `content`
+    pub fn parse(content: &str) -> Self {
+        let mut locked = Self::default();
+        let mut section = "";
+        let mut in_specs = false;
+        let mut current: Option<String> = None;
+
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !line.starts_with(' ') {
+                section = line.trim_end();
+                in_specs = false;
+                current = None;
+                continue;
+            }
+
+            let indent = line.len() - line.trim_start().len();
+            let (name, detail) = split_entry(line.trim());
+
+            if section == "DEPENDENCIES" && indent == 2 {
+                // Gems from git or path sources carry a trailing `!`
+                locked
+                    .top_level
+                    .push(name.trim_end_matches('!').to_string());
+                continue;
+            }
+
+            match indent {
+                2 => in_specs = line.trim() == "specs:",
+                4 if in_specs => {
+                    current = None;
+                    if !locked.specs.contains_key(name) {
+                        locked.specs.insert(
+                            name.to_string(),
+                            LockedSpec {
+                                version: detail.unwrap_or_default().to_string(),
+                                dependencies: Vec::new(),
+                            },
+                        );
+                        current = Some(name.to_string());
+                    }
+                }
+                6 if in_specs => {
+                    if let Some(spec) = current.as_ref().and_then(|c| locked.specs.get_mut(c)) {
+                        spec.dependencies.push(name.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        locked
+    }
+
+    /// The top-level dependencies with everything they pull in, nested
+    ///
+    /// A gem already on the path from the root is not expanded again, so
+    /// dependency cycles end instead of recursing forever.
+    pub fn tree(&self) -> Vec<DependencyNode> {
+        let mut path = Vec::new();
+        self.top_level
+            .iter()
+            .map(|name| self.node(name, &mut path))
+            .collect()
+    }
+
+    fn node<'a>(&'a self, name: &'a str, path: &mut Vec<&'a str>) -> DependencyNode {
+        let spec = self.specs.get(name);
+        let mut dependencies = Vec::new();
+        if !path.contains(&name) {
+            path.push(name);
+            for dependency in spec.map(|s| s.dependencies.as_slice()).unwrap_or_default() {
+                dependencies.push(self.node(dependency, path));
+            }
+            path.pop();
+        }
+
+        DependencyNode {
+            name: name.to_string(),
+            version: spec.map(|s| s.version.clone()),
+            dependencies,
+        }
+    }
+}
+
+/// Split `rack (>= 2.2.4)` into the name and the parenthesised detail
+fn split_entry(entry: &str) -> (&str, Option<&str>) {
+    match entry.split_once(" (") {
+        Some((name, rest)) => (name, Some(rest.trim_end_matches(')'))),
+        None => (entry, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCKFILE: &str = "\
+GIT
+  remote: https://github.com/example/house_style.git
+  revision: 0123456789abcdef
+  specs:
+    house_style (0.4.0)
+      rubocop (>= 1.50)
+
+GEM
+  remote: https://rubygems.org/
+  specs:
+    nokogiri (1.16.0-x86_64-linux)
+      racc (~> 1.4)
+    nokogiri (1.16.0-arm64-darwin)
+      racc (~> 1.4)
+    racc (1.7.3)
+    rack (3.0.8)
+    rubocop (1.60.0)
+      parallel (~> 1.10)
+    parallel (1.24.0)
+
+PLATFORMS
+  arm64-darwin
+  x86_64-linux
+
+DEPENDENCIES
+  house_style!
+  nokogiri (~> 1.16)
+  rack
+
+BUNDLED WITH
+   2.5.16
+";
+
+    #[test]
+    fn test_parses_top_level_dependencies_and_specs() {
+        let locked = LockedDependencies::parse(LOCKFILE);
+
+        assert_eq!(locked.top_level, ["house_style", "nokogiri", "rack"]);
+        assert_eq!(
+            locked.specs["nokogiri"],
+            LockedSpec {
+                version: "1.16.0-x86_64-linux".to_string(),
+                dependencies: vec!["racc".to_string()],
+            }
+        );
+        assert_eq!(locked.specs["house_style"].dependencies, ["rubocop"]);
+        assert!(locked.specs["rack"].dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_tree_nests_transitive_dependencies() {
+        let tree = LockedDependencies::parse(LOCKFILE).tree();
+
+        let names: Vec<&str> = tree.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, ["house_style", "nokogiri", "rack"]);
+
+        let rubocop = &tree[0].dependencies[0];
+        assert_eq!(rubocop.name, "rubocop");
+        assert_eq!(rubocop.version.as_deref(), Some("1.60.0"));
+        assert_eq!(rubocop.dependencies[0].name, "parallel");
+        assert_eq!(rubocop.dependencies[0].version.as_deref(), Some("1.24.0"));
+    }
+
+    #[test]
+    fn test_tree_stops_at_cycles() {
+        let locked = LockedDependencies::parse(
+            "GEM\n  specs:\n    a (1.0)\n      b\n    b (1.0)\n      a\n\nDEPENDENCIES\n  a\n",
+        );
+
+        let tree = locked.tree();
+        let b = &tree[0].dependencies[0];
+        assert_eq!(b.name, "b");
+        assert_eq!(b.dependencies[0].name, "a");
+        assert!(b.dependencies[0].dependencies.is_empty());
+    }
+}
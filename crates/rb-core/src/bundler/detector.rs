@@ -1,6 +1,9 @@
-use log::{debug, info};
+use log::{debug, info, warn};
 use std::path::{Path, PathBuf};
 
+/// Gemfile names bundler recognises, in the order it prefers them
+pub const GEMFILE_NAMES: [&str; 2] = ["Gemfile", "gems.rb"];
+
 pub struct BundlerRuntimeDetector;
 
 impl BundlerRuntimeDetector {
@@ -8,21 +11,54 @@ impl BundlerRuntimeDetector {
     /// and walking up the directory tree until one is found or we reach the root.
     /// Returns the root directory containing the Gemfile.
     pub fn discover(start_dir: &Path) -> std::io::Result<Option<PathBuf>> {
+        Ok(Self::discover_gemfile(start_dir)?
+            .and_then(|gemfile| gemfile.parent().map(Path::to_path_buf)))
+    }
+
+    /// Discover the Gemfile bundler would use from `start_dir`
+    ///
+    /// Honors `BUNDLE_GEMFILE` when it names an existing file, otherwise searches
+    /// upwards for `Gemfile` or bundler's alternate `gems.rb`.
+    pub fn discover_gemfile(start_dir: &Path) -> std::io::Result<Option<PathBuf>> {
+        let bundle_gemfile = std::env::var_os("BUNDLE_GEMFILE")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+        Self::discover_gemfile_with(start_dir, bundle_gemfile.as_deref())
+    }
+
+    /// Discover the Gemfile from `start_dir`, preferring an explicit `bundle_gemfile`
+    ///
+    /// A relative `bundle_gemfile` is resolved against `start_dir`, as bundler does
+    /// against the working directory.
+    pub fn discover_gemfile_with(
+        start_dir: &Path,
+        bundle_gemfile: Option<&Path>,
+    ) -> std::io::Result<Option<PathBuf>> {
         debug!(
             "Starting Bundler discovery from directory: {}",
             start_dir.display()
         );
 
+        if let Some(bundle_gemfile) = bundle_gemfile {
+            let gemfile_path = start_dir.join(bundle_gemfile);
+            if gemfile_path.is_file() {
+                info!("Using BUNDLE_GEMFILE: {}", gemfile_path.display());
+                return Ok(Some(gemfile_path));
+            }
+            warn!(
+                "BUNDLE_GEMFILE points to {}, which does not exist; searching for a Gemfile instead",
+                gemfile_path.display()
+            );
+        }
+
         let mut current_dir = start_dir.to_path_buf();
 
         loop {
             debug!("Checking directory for Gemfile: {}", current_dir.display());
-            let gemfile_path = current_dir.join("Gemfile");
-
-            if gemfile_path.exists() && gemfile_path.is_file() {
+            if let Some(gemfile_path) = Self::gemfile_in(&current_dir) {
                 info!("Found Gemfile at: {}", gemfile_path.display());
                 debug!("Returning bundler root: {}", current_dir.display());
-                return Ok(Some(current_dir));
+                return Ok(Some(gemfile_path));
             } else {
                 debug!("No Gemfile found in: {}", current_dir.display());
             }
@@ -46,6 +82,14 @@ impl BundlerRuntimeDetector {
         Ok(None)
     }
 
+    /// The Gemfile present directly in `dir`, if any
+    pub fn gemfile_in(dir: &Path) -> Option<PathBuf> {
+        GEMFILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+
     pub fn discover_from_cwd() -> std::io::Result<Option<PathBuf>> {
         let cwd = std::env::current_dir()?;
         debug!(
@@ -148,4 +192,53 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn discover_recognizes_gems_rb() -> io::Result<()> {
+        let sandbox = BundlerSandbox::new()?;
+        let project_dir = sandbox.add_dir("gems-rb-app")?;
+        sandbox.add_file("gems-rb-app/gems.rb", "source 'https://rubygems.org'\n")?;
+        let lib_dir = sandbox.add_dir("gems-rb-app/lib")?;
+
+        assert_eq!(
+            BundlerRuntimeDetector::discover_gemfile_with(&lib_dir, None)?,
+            Some(project_dir.join("gems.rb"))
+        );
+        assert_eq!(
+            BundlerRuntimeDetector::discover(&lib_dir)?,
+            Some(project_dir)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn discover_prefers_bundle_gemfile_override() -> io::Result<()> {
+        let sandbox = BundlerSandbox::new()?;
+        let project_dir = sandbox.add_bundler_project("override-app", false)?;
+        sandbox.add_dir("override-app/gemfiles")?;
+        let alternate = sandbox.add_file(
+            "override-app/gemfiles/rails7.gemfile",
+            "source 'https://rubygems.org'\n",
+        )?;
+
+        assert_eq!(
+            BundlerRuntimeDetector::discover_gemfile_with(
+                &project_dir,
+                Some(Path::new("gemfiles/rails7.gemfile"))
+            )?,
+            Some(alternate)
+        );
+
+        // A dangling override falls back to the regular search
+        assert_eq!(
+            BundlerRuntimeDetector::discover_gemfile_with(
+                &project_dir,
+                Some(Path::new("missing.gemfile"))
+            )?,
+            Some(project_dir.join("Gemfile"))
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,194 @@
+//! Working out which Gemfile groups each gem belongs to
+
+use log::debug;
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Group bundler assigns to gems declared outside any group
+pub const DEFAULT_GROUP: &str = "default";
+
+/// Gem-to-groups associations read from a Gemfile
+///
+/// This is a lightweight reading of the Gemfile, not an evaluation: it follows
+/// `group ... do ... end` blocks (nested ones included) and inline `group:` /
+/// `groups:` options on `gem` lines, which covers how groups are declared in practice.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GemGroups {
+    gems: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl GemGroups {
+    /// Parse the group declarations in Gemfile `content`
+    pub fn parse(content: &str) -> Self {
+        let gem_line = Regex::new(r#"^gem\s*\(?\s*["']([^"']+)["']"#).expect("static regex");
+        let group_line =
+            Regex::new(r"^group\s*\(?\s*(.+?)\)?\s+do(?:\s*\|.*\|)?$").expect("static regex");
+        let inline_groups =
+            Regex::new(r"(?:\bgroups?:|:groups?\s*=>)\s*(\[[^\]]*\]|:\w+|[\x22']\w+[\x22'])")
+                .expect("static regex");
+
+        // One entry per open block; blocks other than `group` contribute no groups
+        let mut open_blocks: Vec<Vec<String>> = Vec::new();
+        let mut gems: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(c) = group_line.captures(line) {
+                open_blocks.push(group_names(&c[1]));
+            } else if line == "end" {
+                open_blocks.pop();
+            } else if let Some(c) = gem_line.captures(line) {
+                let mut groups: BTreeSet<String> = open_blocks.iter().flatten().cloned().collect();
+                if let Some(inline) = inline_groups.captures(line) {
+                    groups.extend(group_names(&inline[1]));
+                }
+                if groups.is_empty() {
+                    groups.insert(DEFAULT_GROUP.to_string());
+                }
+                gems.entry(c[1].to_string()).or_default().extend(groups);
+            } else if opens_block(line) {
+                open_blocks.push(Vec::new());
+            }
+        }
+
+        Self { gems }
+    }
+
+    /// Read the groups declared in `gemfile`, treating a missing or unreadable file as empty
+    pub fn read(gemfile: &Path) -> Self {
+        match std::fs::read_to_string(gemfile) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => {
+                debug!("No gem groups read from {}: {}", gemfile.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Groups `gem` belongs to, empty when the Gemfile does not declare it
+    pub fn groups_of(&self, gem: &str) -> Vec<&str> {
+        self.gems
+            .get(gem)
+            .map(|groups| groups.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Gems belonging to `group`, in name order
+    pub fn gems_in(&self, group: &str) -> Vec<&str> {
+        self.gems
+            .iter()
+            .filter(|(_, groups)| groups.contains(group))
+            .map(|(gem, _)| gem.as_str())
+            .collect()
+    }
+
+    /// Whether `gem` belongs to `group`
+    pub fn is_in_group(&self, gem: &str, group: &str) -> bool {
+        self.gems
+            .get(gem)
+            .is_some_and(|groups| groups.contains(group))
+    }
+}
+
+/// Names in a group list like `:development, :test` or `[:development, "test"]`
+fn group_names(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|name| name.trim_matches(|c: char| c.is_whitespace() || "[]:\"'()".contains(c)))
+        .take_while(|name| !name.contains([':', '=', '>']))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a line opens a block closed by a later `end` (`platforms ... do`, `if`, ...)
+fn opens_block(line: &str) -> bool {
+    let keyword = line.split_whitespace().next().unwrap_or("");
+    line.ends_with(" do")
+        || line.contains(" do |")
+        || matches!(
+            keyword,
+            "if" | "unless" | "case" | "begin" | "while" | "until" | "def"
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_GEMFILE: &str = r#"
+source "https://rubygems.org"
+
+gem "rails", "~> 7.1"
+gem "puma"
+
+group :development, :test do
+  gem "rspec-rails"
+
+  group :development do
+    gem "web-console" # nested blocks add to the enclosing groups
+  end
+
+  platforms :mri do
+    gem "debug"
+  end
+end
+
+gem "rubocop", require: false, group: :development
+gem "capybara", groups: [:test, :system]
+gem "factory_bot", :group => "test"
+
+if ENV["WITH_PG"]
+  gem "pg"
+end
+
+group(:production) do
+  gem "lograge"
+end
+"#;
+
+    #[test]
+    fn test_gems_outside_groups_are_default() {
+        let groups = GemGroups::parse(SAMPLE_GEMFILE);
+
+        assert_eq!(groups.groups_of("rails"), vec!["default"]);
+        assert_eq!(groups.groups_of("puma"), vec!["default"]);
+        assert_eq!(groups.groups_of("pg"), vec!["default"]);
+    }
+
+    #[test]
+    fn test_block_groups_apply_to_nested_gems() {
+        let groups = GemGroups::parse(SAMPLE_GEMFILE);
+
+        assert_eq!(groups.groups_of("rspec-rails"), vec!["development", "test"]);
+        assert_eq!(groups.groups_of("web-console"), vec!["development", "test"]);
+        assert_eq!(groups.groups_of("debug"), vec!["development", "test"]);
+        assert_eq!(groups.groups_of("lograge"), vec!["production"]);
+    }
+
+    #[test]
+    fn test_inline_group_options() {
+        let groups = GemGroups::parse(SAMPLE_GEMFILE);
+
+        assert_eq!(groups.groups_of("rubocop"), vec!["development"]);
+        assert_eq!(groups.groups_of("capybara"), vec!["system", "test"]);
+        assert_eq!(groups.groups_of("factory_bot"), vec!["test"]);
+    }
+
+    #[test]
+    fn test_gems_in_group() {
+        let groups = GemGroups::parse(SAMPLE_GEMFILE);
+
+        assert_eq!(
+            groups.gems_in("development"),
+            vec!["debug", "rspec-rails", "rubocop", "web-console"]
+        );
+        assert!(groups.is_in_group("capybara", "system"));
+        assert!(!groups.is_in_group("rails", "test"));
+        assert!(groups.groups_of("nokogiri").is_empty());
+    }
+}
@@ -3,14 +3,103 @@ use crate::butler::runtime_provider::RuntimeProvider;
 use crate::ruby::RubyVersionExt;
 use log::debug;
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+pub mod dependencies;
+pub mod detector;
+pub mod groups;
+pub mod settings;
+pub use dependencies::{DependencyNode, LockedDependencies};
+pub use detector::BundlerRuntimeDetector;
+pub use groups::GemGroups;
+pub use settings::BundlerSettings;
+
+/// Base delay between `bundle install` retries, multiplied by the attempt number
+const SYNC_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Program invoked for bundler operations unless configured otherwise
+pub const DEFAULT_BUNDLER_BIN: &str = "bundle";
+
+/// Groups left out of installs in deployment mode
+pub const DEPLOYMENT_WITHOUT_GROUPS: &str = "development:test";
+
+/// Errors that can occur while Butler drives bundler
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundlerError {
+    /// The bundler executable could not be found
+    NotInstalled,
+    /// `bundle check`, or the bundle configuration it relies on, could not be run
+    CheckFailed(String),
+    /// `bundle install` did not complete; `details` carries what bundler reported
+    InstallFailed { exit_code: i32, details: String },
+    /// `bundle lock` could not update Gemfile.lock
+    LockFailed(String),
+}
+
+impl BundlerError {
+    /// Classify a failure to start bundler, reporting anything but a missing executable via `otherwise`
+    fn from_launch(error: std::io::Error, otherwise: fn(String) -> BundlerError) -> Self {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            BundlerError::NotInstalled
+        } else {
+            otherwise(error.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for BundlerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BundlerError::NotInstalled => write!(
+                f,
+                "Bundler executable not found. Please install bundler with: gem install bundler"
+            ),
+            BundlerError::CheckFailed(msg) => write!(f, "Unable to check the bundle: {}", msg),
+            BundlerError::InstallFailed { exit_code, details } => {
+                write!(f, "Bundle install failed (exit code: {})", exit_code)?;
+                if !details.is_empty() {
+                    write!(f, ". Error details: {}", details)?;
+                }
+                Ok(())
+            }
+            BundlerError::LockFailed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BundlerError {}
+
+impl From<BundlerError> for std::io::Error {
+    fn from(error: BundlerError) -> Self {
+        match error {
+            BundlerError::NotInstalled => {
+                std::io::Error::new(std::io::ErrorKind::NotFound, error.to_string())
+            }
+            other => std::io::Error::other(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BundlerRuntime {
     /// Root directory containing the Gemfile
     pub root: PathBuf,
     /// Ruby version for this bundler context
     pub ruby_version: Version,
+    /// Gemfile in use (Gemfile, gems.rb, or wherever BUNDLE_GEMFILE points)
+    gemfile: PathBuf,
+    /// Vendor directory used instead of .rb/vendor/bundler, for this process only
+    vendor_dir_override: Option<PathBuf>,
+    /// Program invoked for bundler operations (`bundle` unless configured otherwise)
+    bundler_bin: String,
+    /// Parallel install jobs written to the local bundle config, if any
+    install_jobs: Option<u32>,
+    /// Whether installs are configured for deployment (frozen, without development groups)
+    deployment: bool,
+    /// Whether installs fetch and rebuild every gem, even those already installed
+    redownload: bool,
 }
 
 impl BundlerRuntime {
@@ -23,12 +112,104 @@ impl BundlerRuntime {
             ruby_version
         );
 
-        Self { root, ruby_version }
+        let gemfile =
+            BundlerRuntimeDetector::gemfile_in(&root).unwrap_or_else(|| root.join("Gemfile"));
+
+        Self {
+            root,
+            ruby_version,
+            gemfile,
+            vendor_dir_override: None,
+            bundler_bin: DEFAULT_BUNDLER_BIN.to_string(),
+            install_jobs: None,
+            deployment: false,
+            redownload: false,
+        }
+    }
+
+    /// Install into `vendor_dir` instead of .rb/vendor/bundler without touching Butler's config
+    pub fn with_vendor_dir(mut self, vendor_dir: impl AsRef<Path>) -> Self {
+        let vendor_dir = vendor_dir.as_ref().to_path_buf();
+        debug!(
+            "Overriding bundler vendor directory: {}",
+            vendor_dir.display()
+        );
+        self.vendor_dir_override = Some(vendor_dir);
+        self
+    }
+
+    /// Invoke `bundler_bin` (a name on PATH or a full path) instead of `bundle`
+    pub fn with_bundler_bin(mut self, bundler_bin: impl Into<String>) -> Self {
+        self.bundler_bin = bundler_bin.into();
+        debug!("Using bundler executable: {}", self.bundler_bin);
+        self
+    }
+
+    /// Configure bundler to install with `jobs` parallel jobs alongside the vendor path
+    pub fn with_install_jobs(mut self, jobs: u32) -> Self {
+        debug!("Bundle installs will use {} jobs", jobs);
+        self.install_jobs = Some(jobs);
+        self
+    }
+
+    /// Parallel install jobs this runtime configures, if any
+    pub fn install_jobs(&self) -> Option<u32> {
+        self.install_jobs
+    }
+
+    /// Configure bundler for deployment: a frozen lockfile, the vendor path, no development groups
+    pub fn with_deployment(mut self) -> Self {
+        debug!("Bundle installs will use deployment mode");
+        self.deployment = true;
+        self
+    }
+
+    /// Whether this runtime configures deployment mode
+    pub fn is_deployment(&self) -> bool {
+        self.deployment
+    }
+
+    /// Reinstall every gem with `bundle install --redownload`, even when the bundle is satisfied
+    pub fn with_redownload(mut self) -> Self {
+        debug!("Bundle installs will redownload all gems");
+        self.redownload = true;
+        self
+    }
+
+    /// Whether installs redownload gems that are already present
+    pub fn is_redownload(&self) -> bool {
+        self.redownload
+    }
+
+    /// Returns the program invoked for bundler operations
+    pub fn bundler_bin(&self) -> &str {
+        &self.bundler_bin
+    }
+
+    /// Use `gemfile` (e.g. from BUNDLE_GEMFILE) instead of the one found in the root
+    pub fn with_gemfile(mut self, gemfile: impl AsRef<Path>) -> Self {
+        self.gemfile = gemfile.as_ref().to_path_buf();
+        debug!("Using Gemfile: {}", self.gemfile.display());
+        self
     }
 
     /// Returns the full path to the Gemfile
     pub fn gemfile_path(&self) -> PathBuf {
-        self.root.join("Gemfile")
+        self.gemfile.clone()
+    }
+
+    /// Returns the full path to the lockfile matching the Gemfile
+    ///
+    /// Follows bundler's naming: `gems.rb` locks to `gems.locked`, anything else to `<gemfile>.lock`.
+    pub fn lockfile_path(&self) -> PathBuf {
+        match self.gemfile.file_name().and_then(|name| name.to_str()) {
+            Some("gems.rb") => self.gemfile.with_file_name("gems.locked"),
+            _ => {
+                let mut lockfile = self.gemfile.clone().into_os_string();
+                lockfile.push(".lock");
+                PathBuf::from(lockfile)
+            }
+        }
     }
 
     /// Returns the application config directory (.rb)
@@ -36,9 +217,71 @@ impl BundlerRuntime {
         self.root.join(".rb")
     }
 
-    /// Returns the vendor bundler directory (.rb/vendor/bundler)
+    /// Returns the config file bundler uses here, since BUNDLE_APP_CONFIG points at .rb
+    pub fn config_file(&self) -> PathBuf {
+        self.app_config_dir().join("config")
+    }
+
+    /// Locally stored bundler settings (path, without, jobs), empty when none are set
+    pub fn settings(&self) -> BundlerSettings {
+        BundlerSettings::read(&self.config_file())
+    }
+
+    /// Bundler's global config file: `BUNDLE_USER_CONFIG`, `$BUNDLE_USER_HOME/config`
+    /// or `~/.bundle/config`, as bundler itself looks for it
+    fn global_config_file() -> Option<PathBuf> {
+        if let Some(file) = std::env::var_os("BUNDLE_USER_CONFIG").filter(|v| !v.is_empty()) {
+            return Some(PathBuf::from(file));
+        }
+        if let Some(dir) = std::env::var_os("BUNDLE_USER_HOME").filter(|v| !v.is_empty()) {
+            return Some(PathBuf::from(dir).join("config"));
+        }
+        crate::user_home::home_dir().map(|home| home.join(".bundle").join("config"))
+    }
+
+    /// Checkouts of gems overridden with `bundle config set local.<gem> <path>`
+    ///
+    /// Reads Butler's config, the project's `.bundle/config` and bundler's global config,
+    /// earlier ones winning for a gem set in several. Relative paths are taken from the
+    /// project root and directories that do not exist are skipped.
+    pub fn local_override_dirs(&self) -> Vec<PathBuf> {
+        let mut config_files = vec![self.config_file(), self.root.join(".bundle").join("config")];
+        config_files.extend(Self::global_config_file());
+        self.local_override_dirs_from(&config_files)
+    }
+
+    fn local_override_dirs_from(&self, config_files: &[PathBuf]) -> Vec<PathBuf> {
+        let mut seen = Vec::new();
+        let mut dirs = Vec::new();
+        for config_file in config_files {
+            for (gem, path) in BundlerSettings::read(config_file).local_overrides {
+                if seen.contains(&gem) {
+                    continue;
+                }
+                let dir = self.root.join(&path);
+                if dir.is_dir() {
+                    debug!("Local override for {}: {}", gem, dir.display());
+                    dirs.push(dir);
+                } else {
+                    debug!("Skipping missing local override for {}: {}", gem, path);
+                }
+                seen.push(gem);
+            }
+        }
+        dirs
+    }
+
+    /// Groups each gem in the Gemfile is declared in
+    pub fn gem_groups(&self) -> GemGroups {
+        GemGroups::read(&self.gemfile_path())
+    }
+
+    /// Returns the vendor bundler directory (.rb/vendor/bundler unless overridden)
     pub fn vendor_dir(&self) -> PathBuf {
-        self.app_config_dir().join("vendor").join("bundler")
+        match &self.vendor_dir_override {
+            Some(dir) => dir.clone(),
+            None => self.app_config_dir().join("vendor").join("bundler"),
+        }
     }
 
     /// Returns the ruby-specific vendor directory (.rb/vendor/bundler/ruby/X.Y.0)
@@ -49,6 +292,29 @@ impl BundlerRuntime {
             .join(ruby_version.ruby_abi_version())
     }
 
+    /// Directories where `bundle cache` keeps packaged gems, in the order they are consulted
+    pub fn gem_cache_dirs(&self) -> Vec<PathBuf> {
+        vec![
+            self.root.join("vendor").join("cache"),
+            self.vendor_dir().join("cache"),
+        ]
+    }
+
+    /// The first existing gem cache and how many `.gem` files it holds
+    pub fn gem_cache(&self) -> Option<GemCache> {
+        let dir = self.gem_cache_dirs().into_iter().find(|dir| dir.is_dir())?;
+        let gem_count = std::fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "gem"))
+                    .count()
+            })
+            .unwrap_or(0);
+        debug!("Gem cache {} holds {} gems", dir.display(), gem_count);
+        Some(GemCache { dir, gem_count })
+    }
+
     /// Detect Ruby version from .ruby-version file or Gemfile ruby declaration
     pub fn ruby_version(&self) -> Option<Version> {
         let detector = self.compose_version_detector();
@@ -81,83 +347,108 @@ impl BundlerRuntime {
     pub fn check_sync(
         &self,
         butler_runtime: &crate::butler::ButlerRuntime,
-    ) -> std::io::Result<bool> {
+    ) -> Result<bool, BundlerError> {
         debug!("Checking bundle synchronization status");
 
         self.configure_local_path(butler_runtime)?;
 
-        let output = Command::new("bundle")
+        let output = Command::new(&self.bundler_bin)
             .arg("check")
             .current_dir(&self.root)
-            .output_with_context(butler_runtime);
-
-        match output {
-            Ok(output) => {
-                let is_synced = output.status.success();
-                debug!(
-                    "Bundle check result: {} (exit code: {})",
-                    is_synced,
-                    output.status.code().unwrap_or(-1)
-                );
-
-                if is_synced {
-                    debug!("Bundle check passed, updating lockfile to match Gemfile");
-                    self.update_lockfile_quietly(butler_runtime)?;
-                }
+            .output_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::CheckFailed))?;
 
-                Ok(is_synced)
-            }
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    Err(std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "Bundler executable not found. Please install bundler with: gem install bundler",
-                    ))
-                } else {
-                    Err(e)
-                }
-            }
+        let is_synced = output.status.success();
+        debug!(
+            "Bundle check result: {} (exit code: {})",
+            is_synced,
+            output.status.code().unwrap_or(-1)
+        );
+
+        if is_synced {
+            debug!("Bundle check passed, updating lockfile to match Gemfile");
+            self.update_lockfile_quietly(butler_runtime)?;
         }
+
+        Ok(is_synced)
     }
 
-    /// Configure bundler to use local vendor directory
+    /// Check whether the Gemfile's dependencies are satisfied without changing anything
+    ///
+    /// Unlike `check_sync`, this neither writes bundler config nor updates the lockfile:
+    /// the vendor path is passed through the environment and `bundle check` runs with
+    /// `--dry-run`, so diagnostics can call it freely.
+    pub fn is_synced(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+    ) -> Result<bool, BundlerError> {
+        debug!("Checking bundle satisfaction read-only");
+
+        let output = Command::new(&self.bundler_bin)
+            .args(["check", "--dry-run"])
+            .env("BUNDLE_PATH", self.vendor_dir().to_string_lossy().as_ref())
+            .current_dir(&self.root)
+            .output_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::CheckFailed))?;
+
+        debug!(
+            "Read-only bundle check exit code: {}",
+            output.status.code().unwrap_or(-1)
+        );
+        Ok(output.status.success())
+    }
+
+    /// Configure bundler to use local vendor directory, plus install jobs and deployment when set
+    ///
+    /// Deployment settings persist in the local bundle config for later installs.
     pub fn configure_local_path(
         &self,
         butler_runtime: &crate::butler::ButlerRuntime,
-    ) -> std::io::Result<()> {
+    ) -> Result<(), BundlerError> {
         debug!(
             "Configuring bundle path to vendor directory: {}",
             self.vendor_dir().display()
         );
+        self.set_local_config(
+            butler_runtime,
+            "path",
+            self.vendor_dir().to_string_lossy().as_ref(),
+        )?;
+
+        if let Some(jobs) = self.install_jobs {
+            self.set_local_config(butler_runtime, "jobs", &jobs.to_string())?;
+        }
+
+        if self.deployment {
+            self.set_local_config(butler_runtime, "deployment", "true")?;
+            self.set_local_config(butler_runtime, "without", DEPLOYMENT_WITHOUT_GROUPS)?;
+        }
+
+        Ok(())
+    }
 
-        let status = Command::new("bundle")
-            .args(["config", "set", "path", "--local"])
-            .arg(self.vendor_dir().to_string_lossy().as_ref())
+    /// Store `key` = `value` in the project's local bundle config
+    fn set_local_config(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+        key: &str,
+        value: &str,
+    ) -> Result<(), BundlerError> {
+        let status = Command::new(&self.bundler_bin)
+            .args(["config", "set", key, "--local", value])
             .current_dir(&self.root)
-            .status_with_context(butler_runtime);
+            .status_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::CheckFailed))?;
 
-        match status {
-            Ok(status) => {
-                if status.success() {
-                    debug!("Successfully configured bundle path");
-                    Ok(())
-                } else {
-                    Err(std::io::Error::other(format!(
-                        "Failed to configure bundle path (exit code: {})",
-                        status.code().unwrap_or(-1)
-                    )))
-                }
-            }
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    Err(std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "Bundler executable not found. Please install bundler with: gem install bundler",
-                    ))
-                } else {
-                    Err(e)
-                }
-            }
+        if status.success() {
+            debug!("Successfully configured bundle {}: {}", key, value);
+            Ok(())
+        } else {
+            Err(BundlerError::CheckFailed(format!(
+                "Failed to configure bundle {} (exit code: {})",
+                key,
+                status.code().unwrap_or(-1)
+            )))
         }
     }
 
@@ -165,7 +456,7 @@ impl BundlerRuntime {
         &self,
         butler_runtime: &crate::butler::ButlerRuntime,
         mut output_handler: F,
-    ) -> std::io::Result<()>
+    ) -> Result<(), BundlerError>
     where
         F: FnMut(&str),
     {
@@ -174,31 +465,33 @@ impl BundlerRuntime {
 
         debug!("Installing bundle dependencies");
 
-        let child_result = Command::new("bundle")
-            .arg("install")
+        let interrupted = |e: std::io::Error| BundlerError::InstallFailed {
+            exit_code: -1,
+            details: e.to_string(),
+        };
+
+        let mut command = Command::new(&self.bundler_bin);
+        command.arg("install");
+        if self.redownload {
+            command.arg("--redownload");
+        }
+
+        let mut child = command
             .current_dir(&self.root)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped()) // Capture stderr to analyze errors
-            .execute_with_context(butler_runtime);
-
-        let mut child = match child_result {
-            Ok(child) => child,
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    return Err(std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "Bundler executable not found. Please install bundler with: gem install bundler",
-                    ));
-                } else {
-                    return Err(e);
-                }
-            }
-        };
+            .execute_with_context(butler_runtime)
+            .map_err(|e| {
+                BundlerError::from_launch(e, |details| BundlerError::InstallFailed {
+                    exit_code: -1,
+                    details,
+                })
+            })?;
 
         if let Some(stdout) = child.stdout.take() {
             let reader = BufReader::new(stdout);
             for line in reader.lines() {
-                let line = line?;
+                let line = line.map_err(interrupted)?;
                 output_handler(&line);
             }
         }
@@ -207,31 +500,130 @@ impl BundlerRuntime {
         if let Some(stderr) = child.stderr.take() {
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
-                let line = line?;
+                let line = line.map_err(interrupted)?;
                 eprintln!("{}", line); // Still show stderr to user
                 stderr_content.push_str(&line);
                 stderr_content.push('\n');
             }
         }
 
-        let status = child.wait()?;
+        let status = child.wait().map_err(interrupted)?;
 
         if status.success() {
             debug!("Bundle install completed successfully");
             Ok(())
         } else {
+            Err(BundlerError::InstallFailed {
+                exit_code: status.code().unwrap_or(-1),
+                details: stderr_content.trim().to_string(),
+            })
+        }
+    }
+
+    /// Platforms recorded in the PLATFORMS section of Gemfile.lock
+    pub fn locked_platforms(&self) -> std::io::Result<Vec<String>> {
+        let content = std::fs::read_to_string(self.lockfile_path())?;
+        Ok(parse_lockfile_platforms(&content))
+    }
+
+    /// Top-level dependencies and locked specs recorded in Gemfile.lock
+    pub fn locked_dependencies(&self) -> std::io::Result<LockedDependencies> {
+        let content = std::fs::read_to_string(self.lockfile_path())?;
+        Ok(LockedDependencies::parse(&content))
+    }
+
+    /// Bundler version recorded in the BUNDLED WITH section of Gemfile.lock, if any
+    pub fn locked_bundler_version(&self) -> std::io::Result<Option<String>> {
+        let content = std::fs::read_to_string(self.lockfile_path())?;
+        Ok(parse_lockfile_bundled_with(&content))
+    }
+
+    /// Version reported by `bundle --version` in the composed environment
+    pub fn installed_bundler_version(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+    ) -> Result<String, BundlerError> {
+        let output = Command::new(&self.bundler_bin)
+            .arg("--version")
+            .current_dir(&self.root)
+            .output_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::CheckFailed))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_bundler_version_output(&stdout).ok_or_else(|| {
+            BundlerError::CheckFailed(format!(
+                "unexpected bundle --version output: {}",
+                stdout.trim()
+            ))
+        })
+    }
+
+    /// The locked and installed bundler versions, when Gemfile.lock pins a different one
+    ///
+    /// A project without a lockfile or without a BUNDLED WITH section has nothing to compare.
+    pub fn bundler_version_mismatch(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+    ) -> Result<Option<(String, String)>, BundlerError> {
+        let Ok(Some(locked)) = self.locked_bundler_version() else {
+            debug!("No BUNDLED WITH version to compare against");
+            return Ok(None);
+        };
+        let installed = self.installed_bundler_version(butler_runtime)?;
+        debug!("Bundler locked at {}, installed {}", locked, installed);
+        Ok((locked != installed).then_some((locked, installed)))
+    }
+
+    /// Add a platform to Gemfile.lock via `bundle lock --add-platform`
+    pub fn add_platform(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+        platform: &str,
+    ) -> Result<(), BundlerError> {
+        self.lock_platform(butler_runtime, "--add-platform", platform)
+    }
+
+    /// Remove a platform from Gemfile.lock via `bundle lock --remove-platform`
+    pub fn remove_platform(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+        platform: &str,
+    ) -> Result<(), BundlerError> {
+        self.lock_platform(butler_runtime, "--remove-platform", platform)
+    }
+
+    fn lock_platform(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+        flag: &str,
+        platform: &str,
+    ) -> Result<(), BundlerError> {
+        debug!("Running bundle lock {} {}", flag, platform);
+
+        let output = Command::new(&self.bundler_bin)
+            .arg("lock")
+            .arg(flag)
+            .arg(platform)
+            .current_dir(&self.root)
+            .output_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::LockFailed))?;
+
+        if output.status.success() {
+            debug!("Bundle lock {} {} succeeded", flag, platform);
+            Ok(())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
             let base_error = format!(
-                "Bundle install failed (exit code: {})",
-                status.code().unwrap_or(-1)
+                "Bundle lock {} {} failed (exit code: {})",
+                flag,
+                platform,
+                output.status.code().unwrap_or(-1)
             );
-
-            let enhanced_error = if !stderr_content.trim().is_empty() {
-                format!("{}. Error details: {}", base_error, stderr_content.trim())
-            } else {
+            Err(BundlerError::LockFailed(if stderr.trim().is_empty() {
                 base_error
-            };
-
-            Err(std::io::Error::other(enhanced_error))
+            } else {
+                format!("{}. Error details: {}", base_error, stderr.trim())
+            }))
         }
     }
 
@@ -240,16 +632,17 @@ impl BundlerRuntime {
     fn update_lockfile_quietly(
         &self,
         butler_runtime: &crate::butler::ButlerRuntime,
-    ) -> std::io::Result<()> {
+    ) -> Result<(), BundlerError> {
         debug!("Quietly updating Gemfile.lock to match Gemfile");
 
         // Run bundle lock --local to regenerate lockfile based on Gemfile
         // Uses --local to avoid network access since bundle check already passed
-        let output = Command::new("bundle")
+        let output = Command::new(&self.bundler_bin)
             .arg("lock")
             .arg("--local")
             .current_dir(&self.root)
-            .output_with_context(butler_runtime)?;
+            .output_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::LockFailed))?;
 
         if output.status.success() {
             debug!("Gemfile.lock updated successfully");
@@ -271,7 +664,7 @@ impl BundlerRuntime {
         &self,
         butler_runtime: &crate::butler::ButlerRuntime,
         output_handler: &mut F,
-    ) -> std::io::Result<()>
+    ) -> Result<(), BundlerError>
     where
         F: FnMut(&str),
     {
@@ -279,11 +672,12 @@ impl BundlerRuntime {
 
         // Run bundle lock --local to regenerate lockfile based on Gemfile
         // Uses --local to avoid network access since bundle check already passed
-        let output = Command::new("bundle")
+        let output = Command::new(&self.bundler_bin)
             .arg("lock")
             .arg("--local")
             .current_dir(&self.root)
-            .output_with_context(butler_runtime)?;
+            .output_with_context(butler_runtime)
+            .map_err(|e| BundlerError::from_launch(e, BundlerError::LockFailed))?;
 
         if !output.stdout.is_empty() {
             let stdout_str = String::from_utf8_lossy(&output.stdout);
@@ -303,7 +697,7 @@ impl BundlerRuntime {
             debug!("Gemfile.lock updated successfully");
             Ok(())
         } else {
-            Err(std::io::Error::other(format!(
+            Err(BundlerError::LockFailed(format!(
                 "Bundle lock failed (exit code: {})",
                 output.status.code().unwrap_or(-1)
             )))
@@ -313,16 +707,43 @@ impl BundlerRuntime {
     pub fn synchronize<F>(
         &self,
         butler_runtime: &crate::butler::ButlerRuntime,
+        output_handler: F,
+    ) -> Result<SyncResult, BundlerError>
+    where
+        F: FnMut(&str),
+    {
+        self.synchronize_with_retry(butler_runtime, 0, output_handler)
+    }
+
+    /// Synchronize like `synchronize`, re-running `bundle install` up to `retries`
+    /// more times when it fails. Each retry is announced through the output handler
+    /// and preceded by a backoff that grows with the attempt number.
+    pub fn synchronize_with_retry<F>(
+        &self,
+        butler_runtime: &crate::butler::ButlerRuntime,
+        retries: u32,
         mut output_handler: F,
-    ) -> std::io::Result<SyncResult>
+    ) -> Result<SyncResult, BundlerError>
     where
         F: FnMut(&str),
     {
-        debug!("Starting bundler synchronization");
+        debug!(
+            "Starting bundler synchronization (up to {} retries)",
+            retries
+        );
+
+        // A redownload reinstalls regardless of what bundle check reports
+        let synced = if self.redownload {
+            debug!("Redownload requested, skipping bundle check");
+            self.configure_local_path(butler_runtime)?;
+            false
+        } else {
+            self.check_sync(butler_runtime)?
+        };
 
         // check_sync already updates lockfile quietly, but for sync command
         // we want to show output, so we call update_lockfile explicitly
-        match self.check_sync(butler_runtime)? {
+        match synced {
             true => {
                 debug!("Bundler environment already synchronized");
 
@@ -333,7 +754,25 @@ impl BundlerRuntime {
             false => {
                 debug!("Bundler environment requires synchronization");
 
-                self.install_dependencies(butler_runtime, output_handler)?;
+                let mut attempt = 0;
+                loop {
+                    match self.install_dependencies(butler_runtime, &mut output_handler) {
+                        Ok(()) => break,
+                        Err(e) if attempt < retries => {
+                            attempt += 1;
+                            let backoff = SYNC_RETRY_BACKOFF * attempt;
+                            debug!("Bundle install attempt failed: {}", e);
+                            output_handler(&format!(
+                                "🔁 Retry {}/{}: bundle install failed, trying again in {}ms",
+                                attempt,
+                                retries,
+                                backoff.as_millis()
+                            ));
+                            std::thread::sleep(backoff);
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
 
                 Ok(SyncResult::Synchronized)
             }
@@ -341,6 +780,36 @@ impl BundlerRuntime {
     }
 }
 
+/// Extract the platform names listed under the PLATFORMS section of a Gemfile.lock
+pub fn parse_lockfile_platforms(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .skip_while(|line| line.trim_end() != "PLATFORMS")
+        .skip(1)
+        .take_while(|line| line.starts_with(' ') && !line.trim().is_empty())
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+/// Extract the version under the BUNDLED WITH section of a Gemfile.lock
+pub fn parse_lockfile_bundled_with(content: &str) -> Option<String> {
+    content
+        .lines()
+        .skip_while(|line| line.trim_end() != "BUNDLED WITH")
+        .nth(1)
+        .map(str::trim)
+        .filter(|version| !version.is_empty())
+        .map(str::to_string)
+}
+
+/// Extract the version from `bundle --version`, e.g. `Bundler version 2.5.16` or `2.6.2`
+pub fn parse_bundler_version_output(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
 /// Result of a bundler synchronization operation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SyncResult {
@@ -350,6 +819,13 @@ pub enum SyncResult {
     Synchronized,
 }
 
+/// Gems packaged by `bundle cache`, available for offline installs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GemCache {
+    pub dir: PathBuf,
+    pub gem_count: usize,
+}
+
 impl RuntimeProvider for BundlerRuntime {
     fn bin_dir(&self) -> Option<PathBuf> {
         if self.is_configured() {
@@ -373,14 +849,24 @@ impl RuntimeProvider for BundlerRuntime {
         }
     }
 
+    fn version(&self) -> Option<Version> {
+        Some(self.ruby_version.clone())
+    }
+
     fn compose_version_detector(&self) -> crate::ruby::CompositeDetector {
-        use crate::ruby::version_detector::{GemfileDetector, RubyVersionFileDetector};
+        use crate::ruby::version_detector::{
+            GemfileDetector, PinFileDetector, RtxTomlDetector, RubyVersionFileDetector,
+            ToolVersionsDetector,
+        };
 
-        // Bundler environment: check .ruby-version first, then Gemfile
-        // Future: could add vendor/.ruby-version for bundler-specific version pinning
+        // Bundler environment: an `rb pin` wins, then .ruby-version, Gemfile,
+        // .tool-versions and .rtx.toml
         crate::ruby::CompositeDetector::new(vec![
+            Box::new(PinFileDetector),
             Box::new(RubyVersionFileDetector),
             Box::new(GemfileDetector),
+            Box::new(ToolVersionsDetector),
+            Box::new(RtxTomlDetector),
         ])
     }
 
@@ -415,6 +901,129 @@ mod tests {
         BundlerRuntime::new(root, Version::new(3, 3, 7))
     }
 
+    const SAMPLE_LOCKFILE: &str = "GEM
+  remote: https://rubygems.org/
+  specs:
+    rake (13.2.1)
+
+PLATFORMS
+  arm64-darwin-23
+  ruby
+  x86_64-linux
+
+DEPENDENCIES
+  rake
+
+BUNDLED WITH
+   2.5.16
+";
+
+    #[test]
+    fn gem_cache_counts_packaged_gems() -> io::Result<()> {
+        let sandbox = BundlerSandbox::new()?;
+        let project_dir = sandbox.add_bundler_project("cached-app", false)?;
+        let br = bundler_rt(&project_dir);
+        assert_eq!(br.gem_cache(), None);
+
+        let bundler_cache = br.vendor_dir().join("cache");
+        fs::create_dir_all(&bundler_cache)?;
+        fs::write(bundler_cache.join("rake-13.2.1.gem"), "")?;
+        assert_eq!(br.gem_cache().map(|cache| cache.gem_count), Some(1));
+
+        let cache = project_dir.join("vendor").join("cache");
+        fs::create_dir_all(&cache)?;
+        for name in ["rake-13.2.1.gem", "rack-3.1.8.gem", "notes.txt"] {
+            fs::write(cache.join(name), "")?;
+        }
+        assert_eq!(
+            br.gem_cache(),
+            Some(GemCache {
+                dir: cache,
+                gem_count: 2
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_lockfile_platforms_reads_platforms_section() {
+        assert_eq!(
+            parse_lockfile_platforms(SAMPLE_LOCKFILE),
+            vec!["arm64-darwin-23", "ruby", "x86_64-linux"]
+        );
+    }
+
+    #[test]
+    fn parse_lockfile_platforms_without_section_is_empty() {
+        assert!(parse_lockfile_platforms("GEM\n  specs:\n").is_empty());
+    }
+
+    #[test]
+    fn parse_lockfile_bundled_with_reads_version() {
+        assert_eq!(
+            parse_lockfile_bundled_with(SAMPLE_LOCKFILE),
+            Some("2.5.16".to_string())
+        );
+        assert_eq!(parse_lockfile_bundled_with("GEM\n  specs:\n"), None);
+    }
+
+    #[test]
+    fn parse_bundler_version_output_handles_both_styles() {
+        assert_eq!(
+            parse_bundler_version_output("Bundler version 2.5.16\n"),
+            Some("2.5.16".to_string())
+        );
+        assert_eq!(
+            parse_bundler_version_output("2.6.2\n"),
+            Some("2.6.2".to_string())
+        );
+        assert_eq!(parse_bundler_version_output(""), None);
+    }
+
+    #[test]
+    fn locked_platforms_reads_gemfile_lock() -> io::Result<()> {
+        let sandbox = BundlerSandbox::new()?;
+        let project_dir = sandbox.add_bundler_project("platforms-app", false)?;
+        fs::write(project_dir.join("Gemfile.lock"), SAMPLE_LOCKFILE)?;
+
+        let br = bundler_rt(&project_dir);
+
+        assert_eq!(br.lockfile_path(), project_dir.join("Gemfile.lock"));
+        assert_eq!(
+            br.locked_platforms()?,
+            vec!["arm64-darwin-23", "ruby", "x86_64-linux"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn gems_rb_project_uses_gems_locked() -> io::Result<()> {
+        let sandbox = BundlerSandbox::new()?;
+        let project_dir = sandbox.add_dir("gems-rb-app")?;
+        fs::write(
+            project_dir.join("gems.rb"),
+            "source 'https://rubygems.org'\n",
+        )?;
+
+        let br = bundler_rt(&project_dir);
+
+        assert_eq!(br.gemfile_path(), project_dir.join("gems.rb"));
+        assert_eq!(br.lockfile_path(), project_dir.join("gems.locked"));
+        Ok(())
+    }
+
+    #[test]
+    fn with_gemfile_reflects_bundle_gemfile_override() {
+        let root = Path::new("/home/user/my-app");
+        let br = bundler_rt(root).with_gemfile(root.join("gemfiles/rails7.gemfile"));
+
+        assert_eq!(br.gemfile_path(), root.join("gemfiles/rails7.gemfile"));
+        assert_eq!(
+            br.lockfile_path(),
+            root.join("gemfiles/rails7.gemfile.lock")
+        );
+    }
+
     #[test]
     fn new_creates_proper_paths() {
         let root = Path::new("/home/user/my-app");
@@ -430,6 +1039,12 @@ mod tests {
         assert_eq!(br.ruby_version(), None); // No filesystem access in this test
     }
 
+    #[test]
+    fn runtime_provider_version_reports_bound_ruby() {
+        let br = bundler_rt("/home/user/my-app");
+        assert_eq!(RuntimeProvider::version(&br), Some(Version::new(3, 3, 7)));
+    }
+
     #[test]
     fn bin_dir_is_vendor_bin() {
         let br = bundler_rt("/home/user/project");
@@ -665,7 +1280,35 @@ gem 'pg'
 
         Ok(())
     }
-}
 
-pub mod detector;
-pub use detector::BundlerRuntimeDetector;
+    #[test]
+    fn local_overrides_include_global_config_with_project_winning() -> io::Result<()> {
+        let sandbox = BundlerSandbox::new()?;
+        let project_dir = sandbox.add_bundler_project("local-app", false)?;
+        let project_rack = sandbox.add_dir("src/rack")?;
+        let global_rack = sandbox.add_dir("global/rack")?;
+        let global_rails = sandbox.add_dir("global/rails")?;
+        sandbox.add_file(
+            "local-app/.bundle/config",
+            format!("---\nBUNDLE_LOCAL__RACK: \"{}\"\n", project_rack.display()),
+        )?;
+        let global_config = sandbox.add_file(
+            "global/config",
+            format!(
+                "---\nBUNDLE_LOCAL__RACK: \"{}\"\nBUNDLE_LOCAL__RAILS: \"{}\"\n",
+                global_rack.display(),
+                global_rails.display()
+            ),
+        )?;
+
+        let br = bundler_rt(&project_dir);
+        let dirs = br.local_override_dirs_from(&[
+            br.config_file(),
+            project_dir.join(".bundle").join("config"),
+            global_config,
+        ]);
+
+        assert_eq!(dirs, vec![project_rack, global_rails]);
+        Ok(())
+    }
+}
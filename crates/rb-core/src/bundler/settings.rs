@@ -0,0 +1,116 @@
+//! Reading the bundler settings a project has stored locally
+
+use log::debug;
+use std::path::Path;
+
+/// The bundler settings Butler surfaces: where gems go, which groups are skipped, how many jobs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundlerSettings {
+    /// `BUNDLE_PATH`, the install location
+    pub path: Option<String>,
+    /// `BUNDLE_WITHOUT`, groups left out of installs
+    pub without: Vec<String>,
+    /// `BUNDLE_JOBS`, parallel install jobs
+    pub jobs: Option<u32>,
+    /// `BUNDLE_LOCAL__<GEM>` entries: gems checked out locally, as (gem name, path)
+    pub local_overrides: Vec<(String, String)>,
+}
+
+impl BundlerSettings {
+    /// Parse the YAML-style `KEY: "value"` lines bundler writes to its config file
+    pub fn parse(content: &str) -> Self {
+        let mut settings = Self::default();
+
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+
+            match key.trim() {
+                "BUNDLE_PATH" => settings.path = Some(value.to_string()),
+                "BUNDLE_WITHOUT" => {
+                    settings.without = value
+                        .split([':', ' '])
+                        .filter(|group| !group.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                "BUNDLE_JOBS" => settings.jobs = value.parse().ok(),
+                key => {
+                    if let Some(gem) = key.strip_prefix("BUNDLE_LOCAL__") {
+                        settings
+                            .local_overrides
+                            .push((Self::gem_name_from_key(gem), value.to_string()));
+                    }
+                }
+            }
+        }
+
+        settings
+    }
+
+    /// Undo bundler's key encoding, where `-` becomes `___` and names are upcased
+    fn gem_name_from_key(key: &str) -> String {
+        key.replace("___", "-").to_lowercase()
+    }
+
+    /// Read settings from `config_file`, treating a missing or unreadable file as empty
+    pub fn read(config_file: &Path) -> Self {
+        match std::fs::read_to_string(config_file) {
+            Ok(content) => Self::parse(&content),
+            Err(e) => {
+                debug!(
+                    "No bundler settings read from {}: {}",
+                    config_file.display(),
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether none of the surfaced settings is stored
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_path_without_and_jobs() {
+        let settings = BundlerSettings::parse(
+            "---\nBUNDLE_PATH: \"/app/.rb/vendor/bundler\"\nBUNDLE_WITHOUT: \"development:test\"\nBUNDLE_JOBS: \"4\"\nBUNDLE_FROZEN: \"true\"\n",
+        );
+
+        assert_eq!(settings.path.as_deref(), Some("/app/.rb/vendor/bundler"));
+        assert_eq!(settings.without, vec!["development", "test"]);
+        assert_eq!(settings.jobs, Some(4));
+    }
+
+    #[test]
+    fn test_parses_local_overrides() {
+        let settings = BundlerSettings::parse(
+            "---\nBUNDLE_LOCAL__RACK: \"/src/rack\"\nBUNDLE_LOCAL__ACTIVE___SUPPORT: \"../as\"\n",
+        );
+
+        assert_eq!(
+            settings.local_overrides,
+            vec![
+                ("rack".to_string(), "/src/rack".to_string()),
+                ("active-support".to_string(), "../as".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_file_yields_empty_settings() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let settings = BundlerSettings::read(&temp_dir.path().join("config"));
+
+        assert!(settings.is_empty());
+    }
+}
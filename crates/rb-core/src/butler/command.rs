@@ -1,9 +1,42 @@
 use super::{ButlerError, ButlerRuntime};
 use log::debug;
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Output, Stdio};
 
+/// Variables carried over from the parent process when the environment is cleared.
+/// These are the essentials most programs need to function, not configuration.
+const ESSENTIAL_ENV_VARS: &[&str] = &[
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "SHELL",
+    "TERM",
+    "LANG",
+    "LC_ALL",
+    "TMPDIR",
+    "SYSTEMROOT",
+    "COMSPEC",
+    "PATHEXT",
+    "TEMP",
+    "TMP",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+];
+
+/// The system directories a cleared environment's PATH keeps after Butler's own,
+/// in place of the parent's PATH
+fn minimal_system_path() -> String {
+    if cfg!(windows) {
+        let root = std::env::var("SYSTEMROOT").unwrap_or_else(|_| r"C:\Windows".to_string());
+        format!(r"{root}\System32;{root};{root}\System32\Wbem")
+    } else {
+        "/usr/local/bin:/usr/bin:/bin".to_string()
+    }
+}
+
 /// A sophisticated command execution abstraction that understands Ruby environments
 /// and executes commands with appropriate context and preparation.
 pub struct Command {
@@ -11,6 +44,10 @@ pub struct Command {
     args: Vec<String>,
     current_dir: Option<PathBuf>,
     env_vars: HashMap<String, String>,
+    clear_env: bool,
+    own_process_group: bool,
+    /// `None` until `allow_system` decides; the whole composed PATH is searched meanwhile
+    allow_system: Option<bool>,
     stdout: Option<Stdio>,
     stderr: Option<Stdio>,
     stdin: Option<Stdio>,
@@ -24,6 +61,9 @@ impl Command {
             args: Vec::new(),
             current_dir: None,
             env_vars: HashMap::new(),
+            clear_env: false,
+            own_process_group: false,
+            allow_system: None,
             stdout: None,
             stderr: None,
             stdin: None,
@@ -65,6 +105,35 @@ impl Command {
         self
     }
 
+    /// Start from a minimal environment instead of inheriting the parent's.
+    ///
+    /// The child receives only essential variables (HOME, USER, TERM, ...) plus the
+    /// butler runtime's composed variables and any explicitly set via `env`. PATH holds
+    /// Butler's directories followed by the standard system ones, not the parent's PATH.
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.clear_env = true;
+        self
+    }
+
+    /// Start the child as the leader of its own process group (Unix only).
+    ///
+    /// Everything the child spawns joins that group, so the whole tree can be signalled
+    /// at once through the child's pid.
+    pub fn own_process_group(&mut self) -> &mut Self {
+        self.own_process_group = true;
+        self
+    }
+
+    /// Decide whether programs that only the inherited PATH provides may run.
+    ///
+    /// Once decided, the program is looked up in Butler's bin directories first. When
+    /// `allow` is set the search extends to the inherited PATH and such system commands run
+    /// directly, outside `bundle exec`; otherwise they are refused as not found.
+    pub fn allow_system(&mut self, allow: bool) -> &mut Self {
+        self.allow_system = Some(allow);
+        self
+    }
+
     /// Configure stdout
     pub fn stdout<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Self {
         self.stdout = Some(cfg.into());
@@ -122,9 +191,17 @@ impl Command {
     /// if a command is available. It considers both direct command execution and
     /// bundle exec scenarios.
     pub fn command_exists(&self, butler_runtime: &ButlerRuntime) -> bool {
+        if self.allow_system == Some(false) && self.is_system_command(butler_runtime) {
+            debug!(
+                "'{}' is only on the inherited PATH and system commands are not allowed",
+                self.program
+            );
+            return false;
+        }
+
         if self.should_use_bundle_exec(butler_runtime) {
             // For bundle exec commands, check if both bundle and the target command exist
-            let bundle_cmd = Command::new("bundle");
+            let bundle_cmd = Command::new(butler_runtime.bundler_bin());
             if !bundle_cmd.command_exists_direct(butler_runtime) {
                 debug!("Bundle command not found, cannot use bundle exec");
                 return false;
@@ -142,10 +219,7 @@ impl Command {
 
     /// Check if a command exists directly (without bundle exec)
     fn command_exists_direct(&self, butler_runtime: &ButlerRuntime) -> bool {
-        let existing_path = std::env::var("PATH").ok();
-        let env_vars = butler_runtime.env_vars(existing_path);
-
-        if let Some(butler_path) = env_vars.get("PATH") {
+        if let Some(butler_path) = self.search_path(butler_runtime) {
             debug!(
                 "Checking command existence for '{}' with butler PATH",
                 self.program
@@ -234,13 +308,48 @@ impl Command {
         // Only use bundle exec if:
         // 1. Bundler runtime is configured
         // 2. The command is not a bundle command itself (bundle install, bundle check, etc.)
-        if let Some(_bundler_runtime) = butler_runtime.bundler_runtime() {
+        // 3. The command is not a system command allowed to run on its own
+        // 4. Bundle exec wrapping has not been disabled
+        if !butler_runtime.uses_bundle_exec() {
+            return false;
+        }
+        if let Some(bundler_runtime) = butler_runtime.bundler_runtime() {
             !self.is_bundle_command()
+                && self.program != bundler_runtime.bundler_bin()
+                && !(self.allow_system == Some(true) && self.is_system_command(butler_runtime))
         } else {
             false
         }
     }
 
+    /// Check if only the inherited PATH provides this command, not Butler's bin directories
+    fn is_system_command(&self, butler_runtime: &ButlerRuntime) -> bool {
+        let current_dir = std::env::current_dir().unwrap_or_default();
+        let butler_bins = std::env::join_paths(butler_runtime.bin_dirs()).ok();
+        if which::which_in(&self.program, butler_bins, &current_dir).is_ok() {
+            return false;
+        }
+
+        let found = which::which_in(&self.program, std::env::var_os("PATH"), &current_dir).is_ok();
+        if found {
+            debug!("'{}' is a system command", self.program);
+        }
+        found
+    }
+
+    /// PATH the program is looked up in: Butler's bin directories alone while system
+    /// commands are refused, otherwise the composed PATH ending with the inherited one
+    fn search_path(&self, butler_runtime: &ButlerRuntime) -> Option<OsString> {
+        if self.allow_system == Some(false) {
+            return std::env::join_paths(butler_runtime.bin_dirs()).ok();
+        }
+        let existing_path = std::env::var("PATH").ok();
+        butler_runtime
+            .env_vars(existing_path)
+            .remove("PATH")
+            .map(OsString::from)
+    }
+
     /// Check if this is a bundle command (bundle install, bundle check, etc.)
     fn is_bundle_command(&self) -> bool {
         self.program == "bundle" || self.program == "bundler"
@@ -252,11 +361,7 @@ impl Command {
     /// On Unix systems, this preserves the original behavior.
     fn resolve_executable_path(&self, butler_runtime: &ButlerRuntime) -> String {
         // Try to resolve the executable using the which crate with the composed environment
-        let existing_path = std::env::var("PATH").ok();
-        let env_vars = butler_runtime.env_vars(existing_path);
-
-        // Create a temporary environment with the butler runtime PATH
-        if let Some(butler_path) = env_vars.get("PATH") {
+        if let Some(butler_path) = self.search_path(butler_runtime) {
             debug!("Resolving executable '{}' with butler PATH", self.program);
 
             // Use which to find the executable in the butler environment
@@ -308,8 +413,22 @@ impl Command {
             direct_cmd
         };
 
+        if self.clear_env {
+            debug!("Clearing inherited environment, keeping essentials only");
+            cmd.env_clear();
+            for key in ESSENTIAL_ENV_VARS {
+                if let Some(value) = std::env::var_os(key) {
+                    cmd.env(key, value);
+                }
+            }
+        }
+
         // Apply butler runtime environment variables, preserving existing PATH
-        let existing_path = std::env::var("PATH").ok();
+        let existing_path = if self.clear_env {
+            Some(minimal_system_path())
+        } else {
+            std::env::var("PATH").ok()
+        };
         for (key, value) in butler_runtime.env_vars(existing_path) {
             cmd.env(key, value);
         }
@@ -335,23 +454,19 @@ impl Command {
             cmd.stdin(stdin);
         }
 
+        #[cfg(unix)]
+        if self.own_process_group {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
         cmd
     }
 
-    /// Resolve the bundle executable path for cross-platform execution
+    /// Resolve the configured bundle executable path for cross-platform execution
     fn resolve_bundle_executable(&self, butler_runtime: &ButlerRuntime) -> String {
         // Create a temporary command to resolve bundle executable
-        let bundle_program = "bundle".to_string();
-        let temp_cmd = Command {
-            program: bundle_program,
-            args: Vec::new(),
-            current_dir: None,
-            env_vars: HashMap::new(),
-            stdout: None,
-            stderr: None,
-            stdin: None,
-        };
-        temp_cmd.resolve_executable_path(butler_runtime)
+        Command::new(butler_runtime.bundler_bin()).resolve_executable_path(butler_runtime)
     }
 }
 
@@ -378,6 +493,15 @@ mod tests {
         assert_eq!(cmd.args, vec!["-v", "--version", "test"]);
     }
 
+    #[test]
+    fn test_env_clear_marks_command() {
+        let mut cmd = Command::new("env");
+        assert!(!cmd.clear_env);
+
+        cmd.env_clear();
+        assert!(cmd.clear_env);
+    }
+
     #[test]
     fn test_bundle_command_detection() {
         let bundle_cmd = Command::new("bundle");
@@ -397,6 +521,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/test"),
+            variant: None,
         };
 
         // Test without bundler runtime
@@ -431,6 +556,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/nonexistent"),
+            variant: None,
         };
 
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
@@ -447,6 +573,50 @@ mod tests {
         // but we can verify the method runs without panicking
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_system_command_resolves_through_inherited_path() {
+        let ruby_runtime = RubyRuntime {
+            kind: RubyType::CRuby,
+            version: Version::new(3, 0, 0),
+            root: PathBuf::from("/nonexistent"),
+            variant: None,
+        };
+        let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
+
+        // No gem or binstub provides `sh`; the composed PATH ends with the inherited one
+        let sh = Command::new("sh");
+        assert!(sh.command_exists(&butler_runtime));
+        assert_eq!(
+            PathBuf::from(sh.resolve_executable_path(&butler_runtime)),
+            which::which("sh").unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_system_command_is_refused_unless_allowed() {
+        let ruby_runtime = RubyRuntime {
+            kind: RubyType::CRuby,
+            version: Version::new(3, 0, 0),
+            root: PathBuf::from("/nonexistent"),
+            variant: None,
+        };
+        let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
+
+        let mut sh = Command::new("sh");
+        sh.allow_system(false);
+        assert!(!sh.command_exists(&butler_runtime));
+        assert_eq!(sh.resolve_executable_path(&butler_runtime), "sh");
+
+        sh.allow_system(true);
+        assert!(sh.command_exists(&butler_runtime));
+        assert_eq!(
+            PathBuf::from(sh.resolve_executable_path(&butler_runtime)),
+            which::which("sh").unwrap()
+        );
+    }
+
     #[test]
     fn test_command_exists_for_nonexistent_command() {
         use crate::ruby::{RubyRuntime, RubyType};
@@ -457,6 +627,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/nonexistent"),
+            variant: None,
         };
 
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
@@ -479,6 +650,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/test"),
+            variant: None,
         };
 
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
@@ -503,6 +675,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/nonexistent"),
+            variant: None,
         };
 
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
@@ -530,6 +703,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/nonexistent"),
+            variant: None,
         };
 
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
@@ -557,6 +731,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/nonexistent"),
+            variant: None,
         };
 
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
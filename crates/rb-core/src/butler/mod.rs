@@ -1,7 +1,6 @@
 use crate::bundler::{BundlerRuntime, BundlerRuntimeDetector};
 use crate::gems::GemRuntime;
-use crate::ruby::{RubyDiscoveryError, RubyRuntime, RubyRuntimeDetector};
-use home;
+use crate::ruby::{RubyDiscoveryError, RubyRuntime, RubyRuntimeDetector, RubyType};
 use log::{debug, info};
 use semver::Version;
 use std::collections::HashMap;
@@ -10,9 +9,11 @@ use std::path::{Path, PathBuf};
 
 pub mod command;
 pub mod runtime_provider;
+pub mod snapshot;
 
 pub use command::Command;
 pub use runtime_provider::RuntimeProvider;
+pub use snapshot::{BundlerSnapshot, EnvironmentSnapshot};
 
 /// Helper to compose detectors based on environment context during early discovery phase.
 ///
@@ -33,6 +34,11 @@ impl DetectorComposer {
         temp_runtime.compose_version_detector()
     }
 
+    /// Compose version detector outside bundler, where only an `rb pin` applies
+    pub fn version_detector_standard() -> crate::ruby::CompositeDetector {
+        crate::ruby::CompositeDetector::new(vec![Box::new(crate::ruby::PinFileDetector)])
+    }
+
     /// Compose gem path detector for bundler environment by delegating to BundlerRuntime
     ///
     /// Use this when bundler is detected - excludes user gems to maintain bundle isolation
@@ -60,6 +66,120 @@ impl DetectorComposer {
         let temp_runtime = GemRuntime::for_base_dir(&PathBuf::new(), &Version::new(0, 0, 0));
         temp_runtime.compose_gem_path_detector()
     }
+
+    /// Standard (non-bundler) composition that also keeps the incoming GEM_PATH reachable
+    pub fn gem_path_detector_inheriting() -> crate::gems::gem_path_detector::CompositeGemPathDetector
+    {
+        use crate::gems::gem_path_detector::{CompositeGemPathDetector, InheritedGemPathDetector};
+
+        // The standard chain decides alone when there is no incoming GEM_PATH
+        CompositeGemPathDetector::new(vec![Box::new(InheritedGemPathDetector::from_env(
+            Self::gem_path_detector_standard(),
+        ))])
+    }
+}
+
+/// Preferences steering how a Ruby is selected and the environment composed around it
+#[derive(Debug, Clone, Default)]
+pub struct SelectionOptions {
+    /// Allow prerelease Rubies (e.g. 3.4.0-preview1) to be chosen as the latest
+    pub include_prereleases: bool,
+    /// Keep existing bundler binstubs on PATH even when bundler is skipped
+    pub keep_binstubs: bool,
+    /// Keep directories from the incoming GEM_PATH reachable outside bundler
+    pub inherit_gem_path: bool,
+    /// Add the Ruby's site_ruby directory after its own gem directory
+    pub include_site_ruby: bool,
+    /// Run commands directly instead of through `bundle exec`, keeping the bundler environment
+    pub no_bundle_exec: bool,
+    /// Versions tried in order when the requested Ruby is not installed
+    pub fallback_rubies: Vec<String>,
+    /// Named gem set isolating the gem home, e.g. `~/.gem/ruby/3.3.6@testing`
+    pub gemset: Option<String>,
+    /// Further directories searched for Rubies after the rubies directory, e.g. `/opt/rubies`
+    pub additional_rubies_dirs: Vec<PathBuf>,
+    /// Version chosen instead of the latest when nothing requests or requires one
+    pub default_ruby: Option<String>,
+    /// Fall back to the `ruby` on PATH when no Rubies are found in the rubies directories
+    pub allow_system_ruby: bool,
+    /// Gem base directories for Rubies matching a version pattern, e.g. `3.3.*`
+    pub gem_bases: Vec<(String, PathBuf)>,
+}
+
+impl SelectionOptions {
+    /// Gem base directory configured for `version`, the most specific matching pattern winning
+    pub fn gem_base_for(&self, version: &Version) -> Option<&Path> {
+        self.gem_bases
+            .iter()
+            .filter(|(pattern, _)| version_matches_pattern(version, pattern))
+            .max_by_key(|(pattern, _)| pattern.split('.').filter(|part| *part != "*").count())
+            .map(|(_, base)| base.as_path())
+    }
+}
+
+/// Whether `version` matches a pattern like `3.3.*` or `3.3`, component by component
+///
+/// A `*` matches any remaining components; a shorter pattern matches as a prefix.
+fn version_matches_pattern(version: &Version, pattern: &str) -> bool {
+    let version = version.to_string();
+    let mut components = version.split(['.', '-']);
+    for part in pattern.trim().split('.') {
+        if part == "*" {
+            return true;
+        }
+        if components.next() != Some(part) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Why a particular Ruby was chosen during environment composition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionReason {
+    /// Requested explicitly with --ruby
+    RequestedFlag,
+    /// Pinned for the project with `rb pin`
+    Pinned,
+    /// Required by the project's .ruby-version file
+    VersionFile,
+    /// Required by the project's Gemfile `ruby` declaration
+    Gemfile,
+    /// Required by another version manager's config file, such as `.rtx.toml`
+    ToolConfig(&'static str),
+    /// The configured `default-ruby`, nothing more specific applied
+    ConfiguredDefault,
+    /// Latest available installation, nothing more specific applied
+    LatestFallback,
+    /// Chosen from the interactive picker offered instead of the latest installation
+    Picked,
+}
+
+impl SelectionReason {
+    /// Map the name of the version detector that found a requirement to a reason
+    fn from_detector(name: &'static str) -> Self {
+        match name {
+            ".rb/ruby-version" => SelectionReason::Pinned,
+            ".ruby-version" => SelectionReason::VersionFile,
+            "Gemfile" => SelectionReason::Gemfile,
+            other => SelectionReason::ToolConfig(other),
+        }
+    }
+}
+
+impl std::fmt::Display for SelectionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SelectionReason::RequestedFlag => write!(f, "requested with --ruby"),
+            SelectionReason::Pinned => write!(f, "pinned with rb pin"),
+            SelectionReason::VersionFile => write!(f, "required by .ruby-version"),
+            SelectionReason::Gemfile => write!(f, "required by Gemfile"),
+            SelectionReason::ToolConfig(file) => write!(f, "required by {}", file),
+            SelectionReason::ConfiguredDefault => write!(f, "configured default-ruby"),
+            SelectionReason::LatestFallback => write!(f, "latest available installation"),
+            SelectionReason::Picked => write!(f, "chosen from the interactive picker"),
+        }
+    }
 }
 
 /// Errors that can occur during ButlerRuntime operations
@@ -71,6 +191,8 @@ pub enum ButlerError {
     NoSuitableRuby(String),
     /// Specified command was not found in the environment
     CommandNotFound(String),
+    /// Bundler failed to install, check or update the bundle
+    BundlerFailed(String),
     /// General error with message
     General(String),
 }
@@ -95,7 +217,7 @@ impl std::fmt::Display for ButlerError {
                     command
                 )
             }
-            ButlerError::General(msg) => {
+            ButlerError::BundlerFailed(msg) | ButlerError::General(msg) => {
                 write!(f, "{}", msg)
             }
         }
@@ -116,10 +238,18 @@ pub struct ButlerRuntime {
 
     // Discovery context
     rubies_dir: PathBuf,
+    additional_rubies_dirs: Vec<PathBuf>,
     current_dir: PathBuf,
     ruby_installations: Vec<RubyRuntime>,
     requested_ruby_version: Option<String>,
+    resolved_ruby_version: Option<String>,
     gem_base_dir: Option<PathBuf>,
+    selection_reason: Option<SelectionReason>,
+    missing_required_version: Option<Version>,
+    binstub_dir: Option<PathBuf>,
+    inherited_gem_dirs: Vec<PathBuf>,
+    include_site_ruby: bool,
+    no_bundle_exec: bool,
 }
 
 impl ButlerRuntime {
@@ -148,10 +278,18 @@ impl ButlerRuntime {
             gem_runtime,
             bundler_runtime: None,
             rubies_dir,
+            additional_rubies_dirs: Vec::new(),
             current_dir,
             ruby_installations: vec![],
             requested_ruby_version: None,
+            resolved_ruby_version: None,
             gem_base_dir: None,
+            selection_reason: None,
+            missing_required_version: None,
+            binstub_dir: None,
+            inherited_gem_dirs: Vec::new(),
+            include_site_ruby: false,
+            no_bundle_exec: false,
         }
     }
 
@@ -165,10 +303,18 @@ impl ButlerRuntime {
             gem_runtime: None,
             bundler_runtime: None,
             rubies_dir,
+            additional_rubies_dirs: Vec::new(),
             current_dir,
             ruby_installations: vec![],
             requested_ruby_version: None,
+            resolved_ruby_version: None,
             gem_base_dir: None,
+            selection_reason: None,
+            missing_required_version: None,
+            binstub_dir: None,
+            inherited_gem_dirs: Vec::new(),
+            include_site_ruby: false,
+            no_bundle_exec: false,
         }
     }
 
@@ -213,44 +359,82 @@ impl ButlerRuntime {
         gem_base_dir: Option<PathBuf>,
         skip_bundler: bool,
         current_dir: PathBuf,
+    ) -> Result<Self, ButlerError> {
+        Self::discover_and_compose_with_options(
+            rubies_dir,
+            requested_ruby_version,
+            gem_base_dir,
+            skip_bundler,
+            current_dir,
+            &SelectionOptions::default(),
+        )
+    }
+
+    /// Perform comprehensive environment discovery with explicit selection preferences
+    pub fn discover_and_compose_with_options(
+        rubies_dir: PathBuf,
+        requested_ruby_version: Option<String>,
+        gem_base_dir: Option<PathBuf>,
+        skip_bundler: bool,
+        current_dir: PathBuf,
+        options: &SelectionOptions,
     ) -> Result<Self, ButlerError> {
         debug!("Starting comprehensive environment discovery");
         debug!("Rubies directory: {}", rubies_dir.display());
         debug!("Current directory: {}", current_dir.display());
         debug!("Requested Ruby version: {:?}", requested_ruby_version);
 
+        if let Some(name) = &options.gemset
+            && !GemRuntime::is_valid_gemset_name(name)
+        {
+            return Err(ButlerError::General(format!(
+                "Invalid gemset name '{}'\n\nUse letters, digits, '-', '_' or '.'.",
+                name
+            )));
+        }
+
         debug!("Discovering Ruby installations");
-        let ruby_installations = match RubyRuntimeDetector::discover(&rubies_dir) {
-            Ok(installations) => installations,
-            Err(RubyDiscoveryError::DirectoryNotFound(path)) => {
-                return Err(ButlerError::RubiesDirectoryNotFound(path));
-            }
-            Err(e) => {
-                debug!("Ruby discovery failed: {:?}", e);
-                vec![]
-            }
+        let ruby_installations =
+            match RubyRuntimeDetector::discover_in(&rubies_dir, &options.additional_rubies_dirs) {
+                Ok(installations) => installations,
+                Err(RubyDiscoveryError::DirectoryNotFound(path)) if !options.allow_system_ruby => {
+                    return Err(ButlerError::RubiesDirectoryNotFound(path));
+                }
+                Err(e) => {
+                    debug!("Ruby discovery failed: {:?}", e);
+                    vec![]
+                }
+            };
+
+        let ruby_installations = if ruby_installations.is_empty() && options.allow_system_ruby {
+            debug!("No managed Rubies found, looking for a system ruby on PATH");
+            RubyRuntimeDetector::system_ruby(std::env::var_os("PATH"))
+                .into_iter()
+                .collect()
+        } else {
+            ruby_installations
         };
 
         info!("Found {} Ruby installations", ruby_installations.len());
 
         if ruby_installations.is_empty() {
             debug!("No Ruby installations found, returning empty runtime");
-            return Ok(Self::empty(rubies_dir, current_dir));
+            return Ok(Self {
+                additional_rubies_dirs: options.additional_rubies_dirs.clone(),
+                ..Self::empty(rubies_dir, current_dir)
+            });
         }
 
         // Step 2: Detect bundler environment (skip if requested)
-        let bundler_root = if skip_bundler {
+        let bundler_gemfile = if skip_bundler {
             debug!("Bundler detection skipped (--no-bundler flag set)");
             None
         } else {
             debug!("Detecting bundler environment");
-            match BundlerRuntimeDetector::discover(&current_dir) {
-                Ok(Some(bundler_root)) => {
-                    debug!(
-                        "Bundler environment detected at: {}",
-                        bundler_root.display()
-                    );
-                    Some(bundler_root)
+            match BundlerRuntimeDetector::discover_gemfile(&current_dir) {
+                Ok(Some(gemfile)) => {
+                    debug!("Bundler environment detected for: {}", gemfile.display());
+                    Some(gemfile)
                 }
                 Ok(None) => {
                     debug!("No bundler environment detected");
@@ -264,26 +448,36 @@ impl ButlerRuntime {
         };
 
         // Extract version requirements from project directory
-        let required_ruby_version = if bundler_root.is_some() {
-            let detector = DetectorComposer::version_detector_for_bundler();
-            detector.detect(&current_dir)
+        let detector = if bundler_gemfile.is_some() {
+            DetectorComposer::version_detector_for_bundler()
         } else {
-            None
+            DetectorComposer::version_detector_standard()
         };
+        let required_ruby_version =
+            detector
+                .detect_candidates(&current_dir)
+                .map(|(candidates, source)| {
+                    let (engine, version) =
+                        Self::first_installed_candidate(&ruby_installations, candidates);
+                    (version, engine, SelectionReason::from_detector(source))
+                });
 
         // Select the most appropriate Ruby installation
         let selected_ruby = Self::select_ruby_runtime(
             &ruby_installations,
             &requested_ruby_version,
             &required_ruby_version,
+            options,
         );
 
         // If no Ruby selected, handle appropriately
-        let Some(selected_ruby) = selected_ruby else {
+        let Some((selected_ruby, selection_reason)) = selected_ruby else {
             if let Some(requested) = &requested_ruby_version {
+                let mut wanted = vec![requested.as_str()];
+                wanted.extend(options.fallback_rubies.iter().map(String::as_str));
                 return Err(ButlerError::NoSuitableRuby(format!(
                     "Requested Ruby version {} not found",
-                    requested
+                    wanted.join(" or ")
                 )));
             }
             // Otherwise return empty runtime
@@ -291,18 +485,52 @@ impl ButlerRuntime {
             return Ok(Self::empty(rubies_dir, current_dir));
         };
 
-        let bundler_runtime =
-            bundler_root.map(|root| BundlerRuntime::new(root, selected_ruby.version.clone()));
+        let missing_required_version = required_ruby_version
+            .filter(|(version, engine, _)| {
+                requested_ruby_version.is_none()
+                    && !Self::meets_requirement(&selected_ruby, version, *engine)
+            })
+            .map(|(version, _, _)| version);
+        if let Some(missing) = &missing_required_version {
+            info!(
+                "Required Ruby {} is not installed, using {} instead",
+                missing, selected_ruby.version
+            );
+        }
+
+        let bundler_runtime = bundler_gemfile.and_then(|gemfile| {
+            let root = gemfile.parent()?.to_path_buf();
+            Some(BundlerRuntime::new(root, selected_ruby.version.clone()).with_gemfile(gemfile))
+        });
+
+        let binstub_dir = if skip_bundler && options.keep_binstubs {
+            Self::existing_binstub_dir(&current_dir, &selected_ruby.version)
+        } else {
+            None
+        };
 
         // Detect and compose gem path configuration
         use crate::gems::gem_path_detector::GemPathContext;
 
         let gem_detector = if bundler_runtime.is_some() {
             DetectorComposer::gem_path_detector_for_bundler()
+        } else if options.inherit_gem_path {
+            DetectorComposer::gem_path_detector_inheriting()
         } else {
             DetectorComposer::gem_path_detector_standard()
         };
 
+        let gem_base_dir = match options.gem_base_for(&selected_ruby.version) {
+            Some(base) => {
+                debug!(
+                    "Using gem base {} configured for Ruby {}",
+                    base.display(),
+                    selected_ruby.version
+                );
+                Some(base.to_path_buf())
+            }
+            None => gem_base_dir,
+        };
         let gem_context =
             GemPathContext::new(&current_dir, &selected_ruby, gem_base_dir.as_deref());
 
@@ -313,12 +541,22 @@ impl ButlerRuntime {
         );
 
         let gem_runtime = gem_path_config.gem_home().map(|gem_home| {
-            GemRuntime::for_base_dir(
+            let gem_runtime = GemRuntime::for_engine(
                 gem_home.parent().unwrap_or(gem_home),
+                selected_ruby.kind,
                 &selected_ruby.version,
-            )
+            );
+            match &options.gemset {
+                Some(name) => gem_runtime.with_gemset(name),
+                None => gem_runtime,
+            }
         });
 
+        info!(
+            "Selected Ruby {} ({})",
+            selected_ruby.version, selection_reason
+        );
+
         info!(
             "Environment composition complete: Ruby {}, Gem directories: {}, Bundler: {}",
             selected_ruby.version,
@@ -330,49 +568,82 @@ impl ButlerRuntime {
             }
         );
 
+        // A fallback may have been chosen over the first requested version
+        let resolved_ruby_version = requested_ruby_version
+            .as_ref()
+            .map(|_| selected_ruby.version.to_string());
+
         Ok(Self {
             ruby_runtime: Some(selected_ruby),
             gem_runtime,
             bundler_runtime,
             rubies_dir,
+            additional_rubies_dirs: options.additional_rubies_dirs.clone(),
             current_dir,
             ruby_installations,
             requested_ruby_version,
+            resolved_ruby_version,
             gem_base_dir,
+            selection_reason: Some(selection_reason),
+            missing_required_version,
+            binstub_dir,
+            inherited_gem_dirs: gem_path_config.inherited_dirs,
+            include_site_ruby: options.include_site_ruby,
+            no_bundle_exec: options.no_bundle_exec,
         })
     }
 
+    /// Bundler's vendor bin directory for the enclosing project, if binstubs were generated there
+    fn existing_binstub_dir(current_dir: &Path, ruby_version: &Version) -> Option<PathBuf> {
+        let root = BundlerRuntimeDetector::discover(current_dir)
+            .ok()
+            .flatten()?;
+        let bin_dir = BundlerRuntime::new(root, ruby_version.clone()).bin_dir();
+        if bin_dir.is_dir() {
+            debug!("Keeping bundler binstubs on PATH: {}", bin_dir.display());
+            Some(bin_dir)
+        } else {
+            debug!("No bundler binstubs found at {}", bin_dir.display());
+            None
+        }
+    }
+
     /// Select the most appropriate Ruby runtime based on requirements
     fn select_ruby_runtime(
         rubies: &[RubyRuntime],
         requested_version: &Option<String>,
-        required_version: &Option<Version>,
-    ) -> Option<RubyRuntime> {
+        required_version: &Option<(Version, Option<RubyType>, SelectionReason)>,
+        options: &SelectionOptions,
+    ) -> Option<(RubyRuntime, SelectionReason)> {
         if rubies.is_empty() {
             return None;
         }
 
         if let Some(requested) = requested_version {
-            // Use explicitly requested version
-            match Version::parse(requested) {
-                Ok(req_version) => {
-                    let found = rubies.iter().find(|r| r.version == req_version).cloned();
-                    return found;
-                }
-                Err(_e) => {
-                    debug!("Invalid Ruby version format: {}", requested);
-                    return None;
+            // Use the first installed version in order of preference
+            for candidate in std::iter::once(requested).chain(&options.fallback_rubies) {
+                match Version::parse(candidate) {
+                    Ok(req_version) => {
+                        if let Some(ruby) = rubies.iter().find(|r| r.version == req_version) {
+                            return Some((ruby.clone(), SelectionReason::RequestedFlag));
+                        }
+                        debug!("Requested Ruby {} not installed", candidate);
+                    }
+                    Err(_e) => {
+                        debug!("Invalid Ruby version format: {}", candidate);
+                    }
                 }
             }
-        } else if let Some(required_version) = required_version {
-            // Use version from bundler environment
+            return None;
+        } else if let Some((required_version, engine, reason)) = required_version {
+            // Use version from bundler environment, on the engine it names if any
             let found = rubies
                 .iter()
-                .find(|r| r.version == *required_version)
+                .find(|r| Self::meets_requirement(r, required_version, *engine))
                 .cloned();
 
             if let Some(ruby) = found {
-                return Some(ruby);
+                return Some((ruby, *reason));
             } else {
                 debug!(
                     "Required Ruby version {} not found, falling back to latest",
@@ -382,8 +653,62 @@ impl ButlerRuntime {
             }
         }
 
-        // Use latest available Ruby
-        rubies.iter().max_by_key(|r| &r.version).cloned()
+        if let Some(default) = &options.default_ruby {
+            match Version::parse(default) {
+                Ok(default_version) => {
+                    if let Some(ruby) = rubies.iter().find(|r| r.version == default_version) {
+                        return Some((ruby.clone(), SelectionReason::ConfiguredDefault));
+                    }
+                    debug!("Default Ruby {} not installed, using latest", default);
+                }
+                Err(_e) => {
+                    debug!("Invalid default Ruby version format: {}", default);
+                }
+            }
+        }
+
+        // Use latest available Ruby, preferring stable releases over previews
+        let latest_stable = rubies
+            .iter()
+            .filter(|r| options.include_prereleases || r.version.pre.is_empty())
+            .max_by_key(|r| r.latest_order());
+
+        let latest = match latest_stable {
+            Some(ruby) => Some(ruby.clone()),
+            None => {
+                debug!("Only prerelease Rubies available, selecting latest prerelease");
+                rubies.iter().max_by_key(|r| r.latest_order()).cloned()
+            }
+        };
+        latest.map(|ruby| (ruby, SelectionReason::LatestFallback))
+    }
+
+    /// The first candidate that is installed, or the preferred one when none is
+    ///
+    /// Sources such as `.tool-versions` list fallbacks, each on its own engine; the
+    /// requirement becomes the first of them that can actually be met.
+    fn first_installed_candidate(
+        rubies: &[RubyRuntime],
+        candidates: Vec<crate::ruby::version_detector::RubyCandidate>,
+    ) -> (Option<RubyType>, Version) {
+        let preferred = candidates[0].clone();
+        candidates
+            .into_iter()
+            .find(|(engine, candidate)| {
+                let installed = rubies
+                    .iter()
+                    .any(|ruby| Self::meets_requirement(ruby, candidate, *engine));
+                if !installed {
+                    debug!("Candidate Ruby {} not installed", candidate);
+                }
+                installed
+            })
+            .unwrap_or(preferred)
+    }
+
+    /// Whether `ruby` is the required version, on the required engine when one is named
+    fn meets_requirement(ruby: &RubyRuntime, version: &Version, engine: Option<RubyType>) -> bool {
+        ruby.version == *version && engine.is_none_or(|engine| ruby.kind == engine)
     }
 
     /// Accessor methods for the discovery context
@@ -391,6 +716,11 @@ impl ButlerRuntime {
         &self.rubies_dir
     }
 
+    /// Further directories searched for Rubies after `rubies_dir`
+    pub fn additional_rubies_dirs(&self) -> &[PathBuf] {
+        &self.additional_rubies_dirs
+    }
+
     pub fn current_dir(&self) -> &PathBuf {
         &self.current_dir
     }
@@ -399,10 +729,26 @@ impl ButlerRuntime {
         &self.ruby_installations
     }
 
+    /// The Ruby version as it was requested, before any fallback was tried
     pub fn requested_ruby_version(&self) -> Option<&str> {
         self.requested_ruby_version.as_deref()
     }
 
+    /// The version of the Ruby selected for a request, which may be one of the fallbacks
+    pub fn resolved_ruby_version(&self) -> Option<&str> {
+        self.resolved_ruby_version.as_deref()
+    }
+
+    /// Why the selected Ruby was chosen, if it was chosen by discovery
+    pub fn selection_reason(&self) -> Option<SelectionReason> {
+        self.selection_reason
+    }
+
+    /// Version required by the project that is not installed, when selection fell back to another
+    pub fn missing_required_version(&self) -> Option<&Version> {
+        self.missing_required_version.as_ref()
+    }
+
     pub fn selected_ruby(&self) -> Result<&RubyRuntime, ButlerError> {
         self.ruby_runtime.as_ref().ok_or_else(|| {
             ButlerError::NoSuitableRuby(
@@ -411,6 +757,67 @@ impl ButlerRuntime {
         })
     }
 
+    /// Point the bundler runtime at a different vendor directory for the rest of this process
+    pub fn with_bundler_vendor_dir(mut self, vendor_dir: impl AsRef<Path>) -> Self {
+        self.bundler_runtime = self
+            .bundler_runtime
+            .map(|bundler| bundler.with_vendor_dir(vendor_dir));
+        self
+    }
+
+    /// Have bundler install with `jobs` parallel jobs
+    pub fn with_bundler_jobs(mut self, jobs: u32) -> Self {
+        self.bundler_runtime = self
+            .bundler_runtime
+            .map(|bundler| bundler.with_install_jobs(jobs));
+        self
+    }
+
+    /// Have bundler install in deployment mode
+    pub fn with_bundler_deployment(mut self) -> Self {
+        self.bundler_runtime = self
+            .bundler_runtime
+            .map(|bundler| bundler.with_deployment());
+        self
+    }
+
+    /// Have bundler reinstall every gem, fetching them afresh
+    pub fn with_bundler_redownload(mut self) -> Self {
+        self.bundler_runtime = self
+            .bundler_runtime
+            .map(|bundler| bundler.with_redownload());
+        self
+    }
+
+    /// Record why the selected Ruby was chosen, when the caller chose it on the user's behalf
+    pub fn with_selection_reason(mut self, reason: SelectionReason) -> Self {
+        self.selection_reason = Some(reason);
+        self
+    }
+
+    /// Run bundler operations through `bundler_bin` instead of `bundle`
+    pub fn with_bundler_bin(mut self, bundler_bin: impl Into<String>) -> Self {
+        let bundler_bin = bundler_bin.into();
+        self.bundler_runtime = self
+            .bundler_runtime
+            .map(|bundler| bundler.with_bundler_bin(bundler_bin));
+        self
+    }
+
+    /// Program used for bundler operations, `bundle` when no bundler runtime is active
+    pub fn bundler_bin(&self) -> &str {
+        self.bundler_runtime
+            .as_ref()
+            .map_or(crate::bundler::DEFAULT_BUNDLER_BIN, |bundler| {
+                bundler.bundler_bin()
+            })
+    }
+
+    /// Whether commands in a bundler project are prefixed with `bundle exec`
+    pub fn uses_bundle_exec(&self) -> bool {
+        self.bundler_runtime.is_some() && !self.no_bundle_exec
+    }
+
     pub fn bundler_runtime(&self) -> Option<&BundlerRuntime> {
         self.bundler_runtime.as_ref()
     }
@@ -458,6 +865,15 @@ impl ButlerRuntime {
             dirs.push(bundler_bin);
         }
 
+        // Pre-generated binstubs kept on request while bundler is skipped
+        if let Some(ref binstub_dir) = self.binstub_dir {
+            debug!(
+                "Adding bundler binstub directory to PATH: {}",
+                binstub_dir.display()
+            );
+            dirs.push(binstub_dir.clone());
+        }
+
         // Gem runtime bin dir (only if NOT in bundler context for isolation)
         if self.bundler_runtime.is_none() {
             if let Some(ref gem_runtime) = self.gem_runtime {
@@ -488,11 +904,16 @@ impl ButlerRuntime {
     ///
     /// When in bundler context (bundler_runtime present):
     /// 1. Bundler vendor directory (.rb/vendor/bundler/ruby/X.Y.Z) - bundled gems only
-    /// 2. Ruby lib directory (~/.rubies/ruby-X.Y.Z/lib/ruby/gems/X.Y.0) - system gems
+    /// 2. Local overrides (`bundle config set local.<gem> <path>`) - gem checkouts
+    /// 3. Ruby lib directory (~/.rubies/ruby-X.Y.Z/lib/ruby/gems/X.Y.0) - system gems
     ///
     /// When NOT in bundler context:
     /// 1. User gem home (~/.gem/ruby/X.Y.Z) - user-installed gems
-    /// 2. Ruby lib directory (~/.rubies/ruby-X.Y.Z/lib/ruby/gems/X.Y.0) - system gems
+    /// 2. Inherited GEM_PATH entries (only with `inherit_gem_path`) - read-only
+    /// 3. Ruby lib directory (~/.rubies/ruby-X.Y.Z/lib/ruby/gems/X.Y.0) - system gems
+    ///
+    /// In either context, `include_site_ruby` adds the Ruby's site_ruby directory
+    /// (lib/ruby/site_ruby/X.Y.0) last.
     ///
     /// NOTE: User gems are NOT available in bundler context for proper isolation.
     /// Use --no-bundler to opt out of bundler context and access user gems.
@@ -507,6 +928,14 @@ impl ButlerRuntime {
             dirs.push(bundler_gem);
         }
 
+        // Gems bundler takes from local checkouts live outside the vendor directory
+        if let Some(ref bundler_runtime) = self.bundler_runtime {
+            for local in bundler_runtime.local_override_dirs() {
+                debug!("Adding bundler local override: {}", local.display());
+                dirs.push(local);
+            }
+        }
+
         // User gem home (only if NOT in bundler context for isolation)
         if self.bundler_runtime.is_none() {
             if let Some(ref gem_runtime) = self.gem_runtime {
@@ -520,11 +949,26 @@ impl ButlerRuntime {
             debug!("Skipping user gem home (bundler isolation)");
         }
 
+        // Inherited GEM_PATH entries come after user gems, read-only
+        for inherited in &self.inherited_gem_dirs {
+            if !dirs.contains(inherited) {
+                debug!("Adding inherited gem directory: {}", inherited.display());
+                dirs.push(inherited.clone());
+            }
+        }
+
         // Ruby runtime lib dir always included (if Ruby available)
         if let Some(ref ruby_runtime) = self.ruby_runtime {
             let ruby_lib = ruby_runtime.lib_dir();
             debug!("Adding ruby lib directory for gems: {}", ruby_lib.display());
             dirs.push(ruby_lib);
+
+            if self.include_site_ruby
+                && let Some(site_ruby) = ruby_runtime.site_ruby_dir()
+            {
+                debug!("Adding site_ruby directory: {}", site_ruby.display());
+                dirs.push(site_ruby);
+            }
         } else {
             debug!("No Ruby runtime available, skipping ruby lib directory");
         }
@@ -549,6 +993,61 @@ impl ButlerRuntime {
         result
     }
 
+    /// Make sure the gem home exists and accepts new files
+    ///
+    /// Creates the directory when missing, then writes and removes a probe file to confirm
+    /// write permission. Succeeds trivially when no gem runtime is composed.
+    pub fn ensure_gem_home_writable(&self) -> Result<(), ButlerError> {
+        let Some(gem_home) = self.gem_home() else {
+            debug!("No gem home to validate");
+            return Ok(());
+        };
+
+        std::fs::create_dir_all(&gem_home).map_err(|e| {
+            ButlerError::General(format!(
+                "The gem home {} could not be created: {}",
+                gem_home.display(),
+                e
+            ))
+        })?;
+
+        let probe = gem_home.join(format!(".rb-write-check-{}", std::process::id()));
+        std::fs::write(&probe, b"").map_err(|e| {
+            ButlerError::General(format!(
+                "The gem home {} is not writable: {}\nAdjust its permissions or choose another location with --gem-home.",
+                gem_home.display(),
+                e
+            ))
+        })?;
+        let _ = std::fs::remove_file(&probe);
+
+        debug!("Gem home {} is writable", gem_home.display());
+        Ok(())
+    }
+
+    /// Create the gem home and its bin directory when missing, so a fresh Ruby can install gems
+    ///
+    /// The gem home goes through `ensure_gem_home_writable`. Does nothing under bundler
+    /// isolation, where bundler decides where gems go.
+    pub fn create_gem_home(&self) -> Result<(), ButlerError> {
+        if self.bundler_runtime.is_some() {
+            debug!("Bundler isolation active, not creating a gem home");
+            return Ok(());
+        }
+        self.ensure_gem_home_writable()?;
+
+        if let Some(gem_runtime) = &self.gem_runtime {
+            std::fs::create_dir_all(&gem_runtime.gem_bin).map_err(|e| {
+                ButlerError::General(format!(
+                    "The gem bin directory {} could not be created: {}",
+                    gem_runtime.gem_bin.display(),
+                    e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
     /// Build PATH string with bin directories prepended to the existing PATH
     pub fn build_path(&self, existing_path: Option<String>) -> String {
         debug!("Building PATH environment variable");
@@ -578,6 +1077,71 @@ impl ButlerRuntime {
         result
     }
 
+    /// Capture the composed environment as owned, serializable data
+    ///
+    /// Fails when no Ruby is selected, mirroring `selected_ruby()`.
+    pub fn snapshot(&self) -> Result<EnvironmentSnapshot, ButlerError> {
+        let ruby = self.selected_ruby()?;
+
+        let bundler = self
+            .bundler_runtime
+            .as_ref()
+            .map(|bundler| BundlerSnapshot {
+                root: bundler.root.clone(),
+                gemfile: bundler.gemfile_path(),
+                app_config_dir: bundler.app_config_dir(),
+                vendor_dir: bundler.vendor_dir(),
+                required_ruby_version: bundler.ruby_version(),
+            });
+
+        Ok(EnvironmentSnapshot {
+            ruby_version: ruby.version.clone(),
+            ruby_root: ruby.root.clone(),
+            gem_home: self.gem_home(),
+            bin_dirs: self.bin_dirs(),
+            gem_dirs: self.gem_dirs(),
+            bundler,
+        })
+    }
+
+    /// Detect whether another `ruby` shadows the selected Ruby in the composed PATH
+    ///
+    /// Resolves `ruby` against the PATH built from `existing_path` and returns the
+    /// executable that would actually run when it differs from the selected Ruby's
+    /// executable. Returns None when the selected Ruby wins or no Ruby is selected.
+    pub fn shadowing_ruby(&self, existing_path: Option<String>) -> Option<PathBuf> {
+        let selected = self.ruby_runtime.as_ref()?.ruby_executable_path();
+        let path = self.build_path(existing_path);
+
+        let resolved = match which::which_in("ruby", Some(path), &self.current_dir) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                debug!("Unable to resolve ruby in composed PATH: {}", e);
+                return None;
+            }
+        };
+
+        let same_executable = match (resolved.canonicalize(), selected.canonicalize()) {
+            (Ok(resolved), Ok(selected)) => resolved == selected,
+            _ => resolved == selected,
+        };
+
+        if same_executable {
+            debug!(
+                "Selected Ruby resolves first in PATH: {}",
+                selected.display()
+            );
+            None
+        } else {
+            debug!(
+                "Ruby in PATH ({}) shadows selected Ruby ({})",
+                resolved.display(),
+                selected.display()
+            );
+            Some(resolved)
+        }
+    }
+
     /// Compose environment variables like chruby does
     /// Returns a HashMap with PATH, GEM_HOME, GEM_PATH, and bundler variables set appropriately
     pub fn env_vars(&self, existing_path: Option<String>) -> HashMap<String, String> {
@@ -657,10 +1221,7 @@ impl ButlerRuntime {
 
     /// Get the default rubies directory (~/.rubies)
     pub fn default_rubies_dir() -> Result<PathBuf, ButlerError> {
-        let home_dir = home::home_dir().ok_or_else(|| {
-            ButlerError::General("Could not determine home directory".to_string())
-        })?;
-        Ok(home_dir.join(".rubies"))
+        Ok(crate::user_home::require_home_dir()?.join(".rubies"))
     }
 }
 
@@ -676,6 +1237,28 @@ mod tests {
         RubyRuntime::new(RubyType::CRuby, Version::parse(version).unwrap(), root)
     }
 
+    #[test]
+    fn test_gem_base_for_prefers_most_specific_pattern() {
+        let options = SelectionOptions {
+            gem_bases: vec![
+                ("3.*".to_string(), PathBuf::from("/gems/three")),
+                ("3.3.*".to_string(), PathBuf::from("/fast-ssd/gems")),
+                ("3.4".to_string(), PathBuf::from("/gems/next")),
+            ],
+            ..Default::default()
+        };
+        let base = |version: &str| options.gem_base_for(&Version::parse(version).unwrap());
+
+        assert_eq!(base("3.3.6"), Some(Path::new("/fast-ssd/gems")));
+        assert_eq!(base("3.2.5"), Some(Path::new("/gems/three")));
+        assert_eq!(base("3.4.0-preview1"), Some(Path::new("/gems/next")));
+        assert_eq!(base("2.7.8"), None);
+        assert_eq!(
+            SelectionOptions::default().gem_base_for(&Version::new(3, 3, 6)),
+            None
+        );
+    }
+
     #[test]
     fn test_butler_runtime_with_only_ruby() {
         let ruby = create_ruby_runtime("3.2.1", "/opt/ruby-3.2.1");
@@ -759,4 +1342,334 @@ mod tests {
         );
         assert_eq!(path, expected);
     }
+
+    #[cfg(unix)]
+    fn write_executable(path: &Path) {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "#!/bin/sh\necho ruby\n").unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shadowing_ruby_none_when_selected_ruby_resolves_first() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ruby_root = temp_dir.path().join("ruby-3.2.1");
+        let ruby = create_ruby_runtime("3.2.1", ruby_root.to_str().unwrap());
+        write_executable(&ruby.ruby_executable_path());
+
+        let system_bin = temp_dir.path().join("usr").join("bin");
+        write_executable(&system_bin.join("ruby"));
+
+        let butler = ButlerRuntime::new(ruby, None);
+        let shadow = butler.shadowing_ruby(Some(system_bin.display().to_string()));
+
+        assert_eq!(shadow, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shadowing_ruby_detects_conflicting_ruby_earlier_in_path() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ruby_root = temp_dir.path().join("ruby-3.2.1");
+        let ruby = create_ruby_runtime("3.2.1", ruby_root.to_str().unwrap());
+        write_executable(&ruby.ruby_executable_path());
+
+        // A stray ruby inside the gem bin directory precedes the Ruby bin directory
+        let gem_runtime = GemRuntime::for_base_dir(&temp_dir.path().join(".gem"), &ruby.version);
+        let conflicting = gem_runtime.gem_bin.join("ruby");
+        write_executable(&conflicting);
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+        let shadow = butler.shadowing_ruby(None);
+
+        assert_eq!(shadow, Some(conflicting));
+    }
+
+    #[test]
+    fn test_select_latest_skips_prereleases_by_default() {
+        let rubies = vec![
+            create_ruby_runtime("3.4.0-preview1", "/opt/ruby-3.4.0-preview1"),
+            create_ruby_runtime("3.3.4", "/opt/ruby-3.3.4"),
+            create_ruby_runtime("3.2.5", "/opt/ruby-3.2.5"),
+        ];
+
+        let selected =
+            ButlerRuntime::select_ruby_runtime(&rubies, &None, &None, &SelectionOptions::default())
+                .unwrap()
+                .0;
+
+        assert_eq!(selected.version, Version::parse("3.3.4").unwrap());
+    }
+
+    #[test]
+    fn test_select_latest_includes_prereleases_when_opted_in() {
+        let rubies = vec![
+            create_ruby_runtime("3.3.4", "/opt/ruby-3.3.4"),
+            create_ruby_runtime("3.4.0-preview1", "/opt/ruby-3.4.0-preview1"),
+        ];
+        let options = SelectionOptions {
+            include_prereleases: true,
+            ..Default::default()
+        };
+
+        let (selected, _) =
+            ButlerRuntime::select_ruby_runtime(&rubies, &None, &None, &options).unwrap();
+
+        assert_eq!(selected.version, Version::parse("3.4.0-preview1").unwrap());
+    }
+
+    #[test]
+    fn test_select_explicitly_requested_prerelease() {
+        let rubies = vec![
+            create_ruby_runtime("3.3.4", "/opt/ruby-3.3.4"),
+            create_ruby_runtime("3.4.0-preview1", "/opt/ruby-3.4.0-preview1"),
+        ];
+
+        let selected = ButlerRuntime::select_ruby_runtime(
+            &rubies,
+            &Some("3.4.0-preview1".to_string()),
+            &None,
+            &SelectionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            selected.0.version,
+            Version::parse("3.4.0-preview1").unwrap()
+        );
+        assert_eq!(selected.1, SelectionReason::RequestedFlag);
+    }
+
+    #[test]
+    fn test_select_falls_back_to_prerelease_when_nothing_stable() {
+        let rubies = vec![create_ruby_runtime(
+            "3.4.0-preview1",
+            "/opt/ruby-3.4.0-preview1",
+        )];
+
+        let selected =
+            ButlerRuntime::select_ruby_runtime(&rubies, &None, &None, &SelectionOptions::default())
+                .unwrap()
+                .0;
+
+        assert_eq!(selected.version, Version::parse("3.4.0-preview1").unwrap());
+    }
+
+    #[test]
+    fn test_first_installed_requested_version_wins() {
+        let rubies = vec![
+            create_ruby_runtime("3.3.4", "/opt/ruby-3.3.4"),
+            create_ruby_runtime("3.2.5", "/opt/ruby-3.2.5"),
+        ];
+        let options = SelectionOptions {
+            fallback_rubies: vec!["3.2.5".to_string()],
+            ..Default::default()
+        };
+
+        let (selected, reason) = ButlerRuntime::select_ruby_runtime(
+            &rubies,
+            &Some("3.3.4".to_string()),
+            &None,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(selected.version, Version::parse("3.3.4").unwrap());
+        assert_eq!(reason, SelectionReason::RequestedFlag);
+    }
+
+    #[test]
+    fn test_requested_versions_fall_back_in_order() {
+        let rubies = vec![
+            create_ruby_runtime("3.4.1", "/opt/ruby-3.4.1"),
+            create_ruby_runtime("3.2.5", "/opt/ruby-3.2.5"),
+            create_ruby_runtime("3.1.6", "/opt/ruby-3.1.6"),
+        ];
+        let options = SelectionOptions {
+            fallback_rubies: vec!["3.2.5".to_string(), "3.1.6".to_string()],
+            ..Default::default()
+        };
+
+        let (selected, _) = ButlerRuntime::select_ruby_runtime(
+            &rubies,
+            &Some("3.3.4".to_string()),
+            &None,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(selected.version, Version::parse("3.2.5").unwrap());
+
+        let nothing_installed = SelectionOptions {
+            fallback_rubies: vec!["3.0.7".to_string()],
+            ..Default::default()
+        };
+        assert!(
+            ButlerRuntime::select_ruby_runtime(
+                &rubies,
+                &Some("3.3.4".to_string()),
+                &None,
+                &nothing_installed,
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_select_falls_back_to_latest_when_required_version_missing() {
+        let rubies = vec![
+            create_ruby_runtime("3.3.4", "/opt/ruby-3.3.4"),
+            create_ruby_runtime("3.2.5", "/opt/ruby-3.2.5"),
+        ];
+        let required = Some((
+            Version::parse("3.1.0").unwrap(),
+            None,
+            SelectionReason::Gemfile,
+        ));
+
+        let (selected, reason) = ButlerRuntime::select_ruby_runtime(
+            &rubies,
+            &None,
+            &required,
+            &SelectionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(selected.version, Version::parse("3.3.4").unwrap());
+        assert_eq!(reason, SelectionReason::LatestFallback);
+    }
+
+    #[test]
+    fn test_select_configured_default_only_when_nothing_applies() {
+        let rubies = vec![
+            create_ruby_runtime("3.3.4", "/opt/ruby-3.3.4"),
+            create_ruby_runtime("3.2.5", "/opt/ruby-3.2.5"),
+        ];
+        let options = SelectionOptions {
+            default_ruby: Some("3.2.5".to_string()),
+            ..Default::default()
+        };
+
+        let (selected, reason) =
+            ButlerRuntime::select_ruby_runtime(&rubies, &None, &None, &options).unwrap();
+        assert_eq!(selected.version, Version::parse("3.2.5").unwrap());
+        assert_eq!(reason, SelectionReason::ConfiguredDefault);
+
+        // A project's requirement still wins over the default
+        let required = Some((
+            Version::parse("3.3.4").unwrap(),
+            None,
+            SelectionReason::VersionFile,
+        ));
+        let (selected, reason) =
+            ButlerRuntime::select_ruby_runtime(&rubies, &None, &required, &options).unwrap();
+        assert_eq!(selected.version, Version::parse("3.3.4").unwrap());
+        assert_eq!(reason, SelectionReason::VersionFile);
+
+        // A default that is not installed falls back to the latest
+        let missing = SelectionOptions {
+            default_ruby: Some("3.0.7".to_string()),
+            ..Default::default()
+        };
+        let (selected, reason) =
+            ButlerRuntime::select_ruby_runtime(&rubies, &None, &None, &missing).unwrap();
+        assert_eq!(selected.version, Version::parse("3.3.4").unwrap());
+        assert_eq!(reason, SelectionReason::LatestFallback);
+    }
+
+    #[test]
+    fn test_select_required_version_only_on_named_engine() {
+        let rubies = vec![
+            create_ruby_runtime("9.4.5+0", "/opt/ruby-9.4.5.0"),
+            RubyRuntime::new(
+                RubyType::JRuby,
+                Version::parse("9.4.5+0").unwrap(),
+                "/opt/jruby-9.4.5.0",
+            ),
+        ];
+        let required = Some((
+            Version::parse("9.4.5+0").unwrap(),
+            Some(RubyType::JRuby),
+            SelectionReason::VersionFile,
+        ));
+
+        let (selected, reason) = ButlerRuntime::select_ruby_runtime(
+            &rubies,
+            &None,
+            &required,
+            &SelectionOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(selected.kind, RubyType::JRuby);
+        assert_eq!(reason, SelectionReason::VersionFile);
+    }
+
+    #[test]
+    fn test_create_gem_home_creates_home_and_bin() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ruby = create_ruby_runtime("3.2.1", "/opt/ruby-3.2.1");
+        let gem_runtime = GemRuntime::for_base_dir(&temp_dir.path().join(".gem"), &ruby.version);
+        let gem_bin = gem_runtime.gem_bin.clone();
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+
+        assert!(butler.create_gem_home().is_ok());
+        assert!(gem_bin.is_dir());
+        assert!(
+            butler.create_gem_home().is_ok(),
+            "Existing directories are fine"
+        );
+    }
+
+    #[test]
+    fn test_ensure_gem_home_writable_creates_missing_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ruby = create_ruby_runtime("3.2.1", "/opt/ruby-3.2.1");
+        let gem_runtime = GemRuntime::for_base_dir(&temp_dir.path().join(".gem"), &ruby.version);
+        let gem_home = gem_runtime.gem_home.clone();
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+
+        assert!(butler.ensure_gem_home_writable().is_ok());
+        assert!(gem_home.is_dir());
+        assert_eq!(std::fs::read_dir(&gem_home).unwrap().count(), 0);
+    }
+
+    // procfs refuses new files even to root, which permission bits would not
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_ensure_gem_home_writable_reports_read_only_directory() {
+        let ruby = create_ruby_runtime("3.2.1", "/opt/ruby-3.2.1");
+        let gem_runtime = GemRuntime {
+            gem_home: PathBuf::from("/proc"),
+            gem_bin: PathBuf::from("/proc/bin"),
+            ruby_version: ruby.version.clone(),
+        };
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+        match butler.ensure_gem_home_writable() {
+            Err(ButlerError::General(message)) => assert!(message.contains("is not writable")),
+            other => panic!("Expected a non-writable error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ensure_gem_home_writable_reports_uncreatable_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let ruby = create_ruby_runtime("3.2.1", "/opt/ruby-3.2.1");
+        // A plain file where the gem base directory should be
+        let blocker = temp_dir.path().join(".gem");
+        std::fs::write(&blocker, b"").unwrap();
+        let gem_runtime = GemRuntime::for_base_dir(&blocker, &ruby.version);
+
+        let butler = ButlerRuntime::new(ruby, Some(gem_runtime));
+
+        match butler.ensure_gem_home_writable() {
+            Err(ButlerError::General(message)) => assert!(message.contains("could not be created")),
+            other => panic!("Expected a creation error, got {:?}", other),
+        }
+    }
 }
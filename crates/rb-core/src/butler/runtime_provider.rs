@@ -1,3 +1,4 @@
+use semver::Version;
 use std::path::PathBuf;
 
 use crate::gems::gem_path_detector::CompositeGemPathDetector;
@@ -8,6 +9,10 @@ pub trait RuntimeProvider {
     fn bin_dir(&self) -> Option<PathBuf>;
     /// Returns the gem directory, if available.
     fn gem_dir(&self) -> Option<PathBuf>;
+    /// Returns the Ruby version this runtime serves, if it is tied to one.
+    fn version(&self) -> Option<Version> {
+        None
+    }
 
     /// Compose a version detector appropriate for this runtime environment
     ///
@@ -64,5 +69,6 @@ mod tests {
         let p = DummyProvider;
         assert_eq!(p.bin_dir(), Some(PathBuf::from("/dummy/bin")));
         assert_eq!(p.gem_dir(), None);
+        assert_eq!(p.version(), None);
     }
 }
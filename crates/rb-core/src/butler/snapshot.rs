@@ -0,0 +1,38 @@
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Owned, serializable view of a composed Ruby environment.
+///
+/// Produced by `ButlerRuntime::snapshot()` for tools embedding rb-core that need
+/// the environment as data rather than by spawning a process with it applied.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentSnapshot {
+    /// Version of the selected Ruby
+    pub ruby_version: Version,
+    /// Installation root of the selected Ruby
+    pub ruby_root: PathBuf,
+    /// Gem home, if a gem runtime is composed
+    pub gem_home: Option<PathBuf>,
+    /// Bin directories in PATH priority order
+    pub bin_dirs: Vec<PathBuf>,
+    /// Gem directories in GEM_PATH priority order
+    pub gem_dirs: Vec<PathBuf>,
+    /// Bundler project details, if a bundler environment is active
+    pub bundler: Option<BundlerSnapshot>,
+}
+
+/// Bundler portion of an `EnvironmentSnapshot`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundlerSnapshot {
+    /// Directory containing the Gemfile
+    pub root: PathBuf,
+    /// Full path to the Gemfile
+    pub gemfile: PathBuf,
+    /// Bundler application config directory (BUNDLE_APP_CONFIG)
+    pub app_config_dir: PathBuf,
+    /// Directory where bundled gems are vendored
+    pub vendor_dir: PathBuf,
+    /// Ruby version required by the project, if declared
+    pub required_ruby_version: Option<Version>,
+}
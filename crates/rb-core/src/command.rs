@@ -236,6 +236,7 @@ mod tests {
             kind: RubyType::CRuby,
             version: Version::new(3, 0, 0),
             root: PathBuf::from("/test"),
+            variant: None,
         };
         let butler_runtime = ButlerRuntime::new(ruby_runtime, None);
         
@@ -19,7 +19,11 @@ impl GemPathDetector for CustomGemBaseDetector {
             custom_base.display()
         );
 
-        let gem_runtime = GemRuntime::for_base_dir(custom_base, &context.ruby_runtime.version);
+        let gem_runtime = GemRuntime::for_engine(
+            custom_base,
+            context.ruby_runtime.kind,
+            &context.ruby_runtime.version,
+        );
 
         let gem_dirs = vec![gem_runtime.gem_home.clone()];
         let gem_bin_dirs = vec![gem_runtime.gem_bin.clone()];
@@ -71,6 +75,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_keeps_other_engines_in_their_own_directory() {
+        let ruby = RubyRuntime::new(
+            RubyType::TruffleRuby,
+            Version::parse("23.1.0").unwrap(),
+            PathBuf::from("/rubies/truffleruby-23.1.0"),
+        );
+        let context = GemPathContext::new(
+            Path::new("/project"),
+            &ruby,
+            Some(Path::new("/custom/gems")),
+        );
+
+        let config = CustomGemBaseDetector.detect(&context).unwrap();
+        assert_eq!(
+            config.gem_home().unwrap(),
+            Path::new("/custom/gems").join("truffleruby").join("23.1.0")
+        );
+    }
+
     #[test]
     fn test_returns_none_when_no_custom_base() {
         let ruby = create_test_ruby();
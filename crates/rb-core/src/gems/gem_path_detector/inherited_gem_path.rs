@@ -0,0 +1,150 @@
+//! Inherited GEM_PATH detector - keeps pre-existing gem directories reachable
+
+use super::{CompositeGemPathDetector, GemPathConfig, GemPathContext, GemPathDetector};
+use log::debug;
+use std::path::PathBuf;
+
+/// Detector layering an incoming GEM_PATH over the standard gem composition
+///
+/// Whatever the wrapped composition detects (custom gem base or user gems) is kept,
+/// and directories the user already had on GEM_PATH (system-wide gem installs, for
+/// example) are appended after it as read-only entries; GEM_HOME is left untouched.
+/// When GEM_PATH is unset or empty the wrapped composition's result stands as it is.
+pub struct InheritedGemPathDetector {
+    inherited: Vec<PathBuf>,
+    base: CompositeGemPathDetector,
+}
+
+impl InheritedGemPathDetector {
+    /// Create a detector carrying over the given directories on top of `base`
+    pub fn new(inherited: Vec<PathBuf>, base: CompositeGemPathDetector) -> Self {
+        Self { inherited, base }
+    }
+
+    /// Create a detector from the GEM_PATH of the current process
+    pub fn from_env(base: CompositeGemPathDetector) -> Self {
+        let inherited = std::env::var_os("GEM_PATH")
+            .map(|gem_path| {
+                std::env::split_paths(&gem_path)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::new(inherited, base)
+    }
+}
+
+impl GemPathDetector for InheritedGemPathDetector {
+    fn detect(&self, context: &GemPathContext) -> Option<GemPathConfig> {
+        let config = self.base.detect(context);
+        if self.inherited.is_empty() {
+            debug!("No inherited GEM_PATH entries");
+            return Some(config);
+        }
+
+        let mut inherited: Vec<PathBuf> = Vec::new();
+        for dir in &self.inherited {
+            if !config.gem_dirs.contains(dir) && !inherited.contains(dir) {
+                inherited.push(dir.clone());
+            }
+        }
+        debug!(
+            "Inheriting {} GEM_PATH entries after user gems",
+            inherited.len()
+        );
+
+        Some(config.with_inherited_dirs(inherited))
+    }
+
+    fn name(&self) -> &'static str {
+        "inherited-gem-path"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gems::gem_path_detector::{CustomGemBaseDetector, UserGemsDetector};
+    use crate::ruby::{RubyRuntime, RubyType};
+    use semver::Version;
+    use std::path::Path;
+
+    fn create_test_ruby() -> RubyRuntime {
+        RubyRuntime::new(
+            RubyType::CRuby,
+            Version::parse("3.2.0").unwrap(),
+            PathBuf::from("/rubies/ruby-3.2.0"),
+        )
+    }
+
+    fn standard() -> CompositeGemPathDetector {
+        CompositeGemPathDetector::new(vec![
+            Box::new(CustomGemBaseDetector),
+            Box::new(UserGemsDetector),
+        ])
+    }
+
+    #[test]
+    fn test_defers_to_base_without_gem_path() {
+        let ruby = create_test_ruby();
+        let context = GemPathContext::new(Path::new("/project"), &ruby, Some(Path::new("/custom")));
+
+        let config = InheritedGemPathDetector::new(vec![], standard())
+            .detect(&context)
+            .unwrap();
+
+        assert_eq!(config, standard().detect(&context));
+        assert!(config.inherited_dirs().is_empty());
+    }
+
+    #[test]
+    fn test_appends_inherited_dirs_after_user_gems() {
+        let ruby = create_test_ruby();
+        let context = GemPathContext::new(Path::new("/project"), &ruby, None);
+        let detector = InheritedGemPathDetector::new(
+            vec![
+                PathBuf::from("/var/lib/gems/3.2.0"),
+                PathBuf::from("/opt/shared-gems"),
+                PathBuf::from("/var/lib/gems/3.2.0"),
+            ],
+            standard(),
+        );
+
+        let config = detector.detect(&context).unwrap();
+
+        assert!(
+            config
+                .gem_home()
+                .unwrap()
+                .to_string_lossy()
+                .contains(".gem")
+        );
+        assert_eq!(
+            config.inherited_dirs(),
+            &[
+                PathBuf::from("/var/lib/gems/3.2.0"),
+                PathBuf::from("/opt/shared-gems")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keeps_custom_gem_base_as_gem_home() {
+        let ruby = create_test_ruby();
+        let context = GemPathContext::new(
+            Path::new("/project"),
+            &ruby,
+            Some(Path::new("/custom/gems")),
+        );
+        let detector =
+            InheritedGemPathDetector::new(vec![PathBuf::from("/opt/shared-gems")], standard());
+
+        let config = detector.detect(&context).unwrap();
+
+        assert!(config.gem_home().unwrap().starts_with("/custom/gems"));
+        assert_eq!(
+            config.inherited_dirs(),
+            &[PathBuf::from("/opt/shared-gems")]
+        );
+    }
+}
@@ -56,10 +56,12 @@ use crate::ruby::RubyRuntime;
 
 pub mod bundler_isolation;
 pub mod custom_gem_base;
+pub mod inherited_gem_path;
 pub mod user_gems;
 
 pub use bundler_isolation::BundlerIsolationDetector;
 pub use custom_gem_base::CustomGemBaseDetector;
+pub use inherited_gem_path::InheritedGemPathDetector;
 pub use user_gems::UserGemsDetector;
 
 /// Represents a detected gem path configuration
@@ -69,6 +71,8 @@ pub struct GemPathConfig {
     pub gem_dirs: Vec<PathBuf>,
     /// Binary directories for executables
     pub gem_bin_dirs: Vec<PathBuf>,
+    /// Read-only directories carried over from an existing GEM_PATH, searched after user gems
+    pub inherited_dirs: Vec<PathBuf>,
 }
 
 impl GemPathConfig {
@@ -76,9 +80,16 @@ impl GemPathConfig {
         Self {
             gem_dirs,
             gem_bin_dirs,
+            inherited_dirs: Vec::new(),
         }
     }
 
+    /// Keep `inherited_dirs` reachable without ever installing into them
+    pub fn with_inherited_dirs(mut self, inherited_dirs: Vec<PathBuf>) -> Self {
+        self.inherited_dirs = inherited_dirs;
+        self
+    }
+
     pub fn gem_dirs(&self) -> &[PathBuf] {
         &self.gem_dirs
     }
@@ -87,6 +98,10 @@ impl GemPathConfig {
         &self.gem_bin_dirs
     }
 
+    pub fn inherited_dirs(&self) -> &[PathBuf] {
+        &self.inherited_dirs
+    }
+
     /// Primary gem home (first gem dir, if any)
     pub fn gem_home(&self) -> Option<&Path> {
         self.gem_dirs.first().map(|p| p.as_path())
@@ -9,7 +9,7 @@ use log::debug;
 /// This is the default fallback detector that always succeeds.
 /// It provides the standard Ruby gem path configuration:
 /// - Ruby's lib gems directory
-/// - User's home gem directory (~/.gem/ruby/X.Y.Z)
+/// - User's home gem directory (~/.gem/<engine>/X.Y.Z, e.g. ~/.gem/ruby/3.3.6)
 pub struct UserGemsDetector;
 
 impl GemPathDetector for UserGemsDetector {
@@ -18,9 +18,12 @@ impl GemPathDetector for UserGemsDetector {
 
         let ruby_gem_runtime = context.ruby_runtime.infer_gem_runtime().ok()?;
 
-        let user_gem_base = home::home_dir()?.join(".gem");
-        let user_gem_runtime =
-            GemRuntime::for_base_dir(&user_gem_base, &context.ruby_runtime.version);
+        let user_gem_base = crate::user_home::home_dir()?.join(".gem");
+        let user_gem_runtime = GemRuntime::for_engine(
+            &user_gem_base,
+            context.ruby_runtime.kind,
+            &context.ruby_runtime.version,
+        );
 
         let gem_dirs = vec![
             user_gem_runtime.gem_home.clone(),
@@ -1,14 +1,18 @@
 use crate::butler::runtime_provider::RuntimeProvider;
+use crate::ruby::RubyType;
 use log::debug;
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 pub mod gem_path_detector;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GemRuntime {
     pub gem_home: PathBuf,
     pub gem_bin: PathBuf,
+    /// Ruby version the gem home belongs to
+    pub ruby_version: Version,
 }
 
 impl GemRuntime {
@@ -17,19 +21,32 @@ impl GemRuntime {
     /// The gem_home will be base/ruby/version where version is the full version (x.y.z)
     /// base: e.g. ~/.gem, /usr/lib/ruby/gems
     pub fn for_base_dir(base: &Path, ruby_version: &Version) -> Self {
+        Self::for_engine(base, RubyType::CRuby, ruby_version)
+    }
+
+    /// Create a GemRuntime for a Ruby engine from a base directory
+    ///
+    /// The gem_home will be base/engine/version, so each engine keeps its gems apart:
+    /// `~/.gem/jruby/9.4.5.0` next to `~/.gem/ruby/3.3.6`. A fourth version part,
+    /// as in JRuby releases, is kept.
+    pub fn for_engine(base: &Path, kind: RubyType, ruby_version: &Version) -> Self {
         debug!(
-            "Creating GemRuntime for base: {}, Ruby version: {}",
+            "Creating GemRuntime for base: {}, {} version: {}",
             base.display(),
+            kind.as_str(),
             ruby_version
         );
 
-        let ver = format!(
+        let mut ver = format!(
             "{}.{}.{}",
             ruby_version.major, ruby_version.minor, ruby_version.patch
         );
+        if !ruby_version.build.is_empty() {
+            ver = format!("{}.{}", ver, ruby_version.build);
+        }
         debug!("Using full version string: {}", ver);
 
-        let gem_home = base.join("ruby").join(ver);
+        let gem_home = base.join(kind.engine()).join(ver);
         let gem_bin = gem_home.join("bin");
 
         debug!(
@@ -38,7 +55,35 @@ impl GemRuntime {
             gem_bin.display()
         );
 
-        Self { gem_home, gem_bin }
+        Self {
+            gem_home,
+            gem_bin,
+            ruby_version: ruby_version.clone(),
+        }
+    }
+
+    /// Isolate gems in the named set, e.g. `~/.gem/ruby/3.3.6@testing`
+    pub fn with_gemset(mut self, name: &str) -> Self {
+        let mut dir_name = self.gem_home.file_name().unwrap_or_default().to_os_string();
+        dir_name.push(format!("@{}", name));
+        self.gem_home.set_file_name(dir_name);
+        self.gem_bin = self.gem_home.join("bin");
+        debug!(
+            "Using gemset '{}' - gem_home: {}",
+            name,
+            self.gem_home.display()
+        );
+        self
+    }
+
+    /// Whether `name` can safely become part of a gem home directory name
+    pub fn is_valid_gemset_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && name != "."
+            && name != ".."
     }
 
     pub fn gem_dirs(&self) -> Vec<PathBuf> {
@@ -59,13 +104,21 @@ impl RuntimeProvider for GemRuntime {
         Some(self.gem_home.clone())
     }
 
+    fn version(&self) -> Option<Version> {
+        Some(self.ruby_version.clone())
+    }
+
     fn compose_version_detector(&self) -> crate::ruby::CompositeDetector {
-        use crate::ruby::version_detector::{GemfileDetector, RubyVersionFileDetector};
+        use crate::ruby::version_detector::{
+            GemfileDetector, RtxTomlDetector, RubyVersionFileDetector, ToolVersionsDetector,
+        };
 
-        // Gem environment: same as Ruby (check .ruby-version first, then Gemfile)
+        // Gem environment: same as Ruby (.ruby-version first, then Gemfile, .tool-versions, .rtx.toml)
         crate::ruby::CompositeDetector::new(vec![
             Box::new(RubyVersionFileDetector),
             Box::new(GemfileDetector),
+            Box::new(ToolVersionsDetector),
+            Box::new(RtxTomlDetector),
         ])
     }
 
@@ -99,6 +152,7 @@ mod tests {
         let gem = GemRuntime::for_base_dir(base, &ver);
         assert_eq!(gem.bin_dir(), Some(gem.gem_bin.clone()));
         assert_eq!(gem.gem_dir(), Some(gem.gem_home.clone()));
+        assert_eq!(gem.version(), Some(ver));
     }
 
     #[test]
@@ -113,4 +167,49 @@ mod tests {
         assert_eq!(gem.gem_home, expected_gem_home);
         assert_eq!(gem.gem_bin, expected_gem_bin);
     }
+
+    #[test]
+    fn test_for_engine_keeps_engines_apart() {
+        let base = Path::new("/home/user/.gem");
+
+        let jruby = GemRuntime::for_engine(
+            base,
+            RubyType::JRuby,
+            &crate::ruby::parse_engine_version("9.4.5.0").unwrap(),
+        );
+        assert_eq!(jruby.gem_home, base.join("jruby").join("9.4.5.0"));
+
+        let truffleruby = GemRuntime::for_engine(
+            base,
+            RubyType::TruffleRuby,
+            &Version::parse("23.1.0").unwrap(),
+        );
+        assert_eq!(
+            truffleruby.gem_home,
+            base.join("truffleruby").join("23.1.0")
+        );
+        assert_eq!(truffleruby.gem_bin, truffleruby.gem_home.join("bin"));
+    }
+
+    #[test]
+    fn test_with_gemset_suffixes_gem_home() {
+        let base = Path::new("/home/user/.gem");
+        let ver = Version::parse("3.3.6").unwrap();
+        let gem = GemRuntime::for_base_dir(base, &ver).with_gemset("testing");
+
+        let expected_gem_home = base.join("ruby").join("3.3.6@testing");
+        assert_eq!(gem.gem_home, expected_gem_home);
+        assert_eq!(gem.gem_bin, expected_gem_home.join("bin"));
+        assert_eq!(gem.ruby_version, ver);
+    }
+
+    #[test]
+    fn test_gemset_name_validation() {
+        for valid in ["testing", "rails-7", "ci_1", "v1.2"] {
+            assert!(GemRuntime::is_valid_gemset_name(valid), "{}", valid);
+        }
+        for invalid in ["", ".", "..", "a/b", "a\\b", "with space", "x@y"] {
+            assert!(!GemRuntime::is_valid_gemset_name(invalid), "{}", invalid);
+        }
+    }
 }
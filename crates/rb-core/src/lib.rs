@@ -3,9 +3,11 @@ pub mod butler;
 pub mod gems;
 pub mod project;
 pub mod ruby;
+pub mod user_home;
 
 pub use bundler::{BundlerRuntime, BundlerRuntimeDetector};
 pub use butler::{ButlerRuntime, Command as ButlerCommand};
 pub use gems::GemRuntime;
 pub use project::{ProjectRuntime, RbprojectDetector};
 pub use ruby::{RubyRuntime, RubyRuntimeDetector};
+pub use user_home::home_dir;
@@ -1,5 +1,5 @@
 use log::{debug, info};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::ProjectRuntime;
 
@@ -7,9 +7,16 @@ pub struct RbprojectDetector;
 
 impl RbprojectDetector {
     /// Supported project file names in order of preference
-    /// Priority: gem.kdl > gem.toml > rbproject.kdl > rbproject.toml
-    const PROJECT_FILENAMES: &'static [&'static str] =
-        &["gem.kdl", "gem.toml", "rbproject.kdl", "rbproject.toml"];
+    ///
+    /// The `gem.*` names are interchangeable aliases for `rbproject.*`; when both exist in
+    /// the same directory the rbproject file wins, and KDL wins over TOML for the same name.
+    /// Priority: rbproject.kdl > rbproject.toml > gem.kdl > gem.toml
+    pub const PROJECT_FILENAMES: &'static [&'static str] =
+        &["rbproject.kdl", "rbproject.toml", "gem.kdl", "gem.toml"];
+
+    /// Shared files whose `[tool.rb.scripts]` table stands in for a project file, in order of preference
+    pub const SHARED_SCRIPT_FILENAMES: &'static [&'static str] =
+        &["rbscripts.toml", "pyproject.toml"];
 
     /// Discover a ProjectRuntime by searching for project config files
     /// in the current directory and walking up the directory tree until one is found or we reach the root.
@@ -75,6 +82,61 @@ impl RbprojectDetector {
         Ok(None)
     }
 
+    /// Like `discover`, but when no project file exists anywhere up the tree, fall back
+    /// to the nearest shared file that carries a `[tool.rb.scripts]` table
+    pub fn discover_with_shared_scripts(
+        start_dir: &Path,
+    ) -> std::io::Result<Option<ProjectRuntime>> {
+        if let Some(project) = Self::discover(start_dir)? {
+            return Ok(Some(project));
+        }
+
+        for dir in start_dir.ancestors() {
+            for filename in Self::SHARED_SCRIPT_FILENAMES {
+                let path = dir.join(filename);
+                if !path.is_file() {
+                    continue;
+                }
+
+                if let Some(project) = ProjectRuntime::from_shared_scripts(&path)? {
+                    info!("Using shared scripts from: {}", path.display());
+                    return Ok(Some(project));
+                }
+            }
+        }
+
+        debug!(
+            "No shared scripts found starting from: {}",
+            start_dir.display()
+        );
+        Ok(None)
+    }
+
+    /// Every project file from `start_dir` up to the filesystem root, nearest first
+    ///
+    /// Each directory contributes the file `discover` would pick there, so aliases
+    /// shadowed within the same directory are left out. Files are not parsed.
+    pub fn discover_all(start_dir: &Path) -> Vec<PathBuf> {
+        let chain: Vec<PathBuf> = start_dir
+            .ancestors()
+            .filter_map(Self::project_file_in)
+            .collect();
+        debug!(
+            "Found {} project files above {}",
+            chain.len(),
+            start_dir.display()
+        );
+        chain
+    }
+
+    /// The preferred project file in `dir`, if any
+    fn project_file_in(dir: &Path) -> Option<PathBuf> {
+        Self::PROJECT_FILENAMES
+            .iter()
+            .map(|filename| dir.join(filename))
+            .find(|path| path.is_file())
+    }
+
     /// Convenience method to discover from current working directory
     pub fn discover_from_cwd() -> std::io::Result<Option<ProjectRuntime>> {
         let cwd = std::env::current_dir()?;
@@ -152,6 +214,33 @@ server = "rails server"
         Ok(())
     }
 
+    #[test]
+    fn discover_all_returns_nearest_project_file_first() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        let service = root.join("services").join("api");
+        let nested = service.join("lib").join("tasks");
+        fs::create_dir_all(&nested)?;
+
+        create_rbproject_toml(root, "[scripts]\n")?;
+        fs::write(service.join("gem.kdl"), "")?;
+        // Shadowed by gem.kdl in the same directory
+        fs::write(service.join("gem.toml"), "")?;
+        fs::write(service.join("lib").join("rbproject.kdl"), "")?;
+
+        let chain = RbprojectDetector::discover_all(&nested);
+
+        assert_eq!(
+            chain,
+            vec![
+                service.join("lib").join("rbproject.kdl"),
+                service.join("gem.kdl"),
+                root.join("rbproject.toml"),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn discover_returns_none_when_no_rbproject_found() -> io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -288,7 +377,7 @@ test = "rspec"
     }
 
     #[test]
-    fn discover_prefers_gem_toml_over_rbproject_toml() -> io::Result<()> {
+    fn discover_prefers_rbproject_toml_over_gem_toml() -> io::Result<()> {
         let temp_dir = TempDir::new()?;
         let project_dir = temp_dir.path();
 
@@ -311,10 +400,10 @@ test = "rspec from gem.toml"
         assert!(result.is_some());
         let project_runtime = result.unwrap();
         assert_eq!(project_runtime.root, project_dir);
-        assert_eq!(project_runtime.config_filename, "gem.toml");
+        assert_eq!(project_runtime.config_filename, "rbproject.toml");
         assert_eq!(
             project_runtime.get_script_command("test"),
-            Some("rspec from gem.toml")
+            Some("rspec from rbproject.toml")
         );
 
         Ok(())
@@ -358,13 +447,93 @@ test = "from gem.toml"
 
         assert!(result.is_some());
         let project_runtime = result.unwrap();
-        // Should pick gem.kdl (highest priority)
-        assert_eq!(project_runtime.config_filename, "gem.kdl");
+        // Should pick rbproject.kdl (highest priority)
+        assert_eq!(project_runtime.config_filename, "rbproject.kdl");
         assert_eq!(
             project_runtime.get_script_command("test"),
-            Some("from gem.kdl")
+            Some("from rbproject.kdl")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn discover_with_shared_scripts_reads_pyproject_tool_table() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_dir = temp_dir.path().join("polyglot");
+        let nested_dir = project_dir.join("app");
+        fs::create_dir_all(&nested_dir)?;
+        fs::write(
+            project_dir.join("pyproject.toml"),
+            r#"
+[project]
+name = "polyglot"
+
+[tool.black]
+line-length = 100
+
+[tool.rb.scripts]
+runner = "bundle exec"
+test = "rspec"
+lint = { command = "rubocop", description = "Check code quality" }
+"#,
+        )?;
+
+        assert!(RbprojectDetector::discover(&nested_dir)?.is_none());
+
+        let project = RbprojectDetector::discover_with_shared_scripts(&nested_dir)?
+            .expect("Expected shared scripts to be found");
+        assert_eq!(project.root, project_dir);
+        assert_eq!(project.config_filename, "pyproject.toml");
+        assert_eq!(project.script_names(), vec!["lint", "test"]);
+        assert_eq!(
+            project.get_script_invocation("test").as_deref(),
+            Some("bundle exec rspec")
         );
 
         Ok(())
     }
+
+    #[test]
+    fn discover_with_shared_scripts_skips_files_without_table() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_dir = temp_dir.path().join("python-only");
+        fs::create_dir_all(&project_dir)?;
+        fs::write(
+            project_dir.join("pyproject.toml"),
+            "[project]\nname = \"python-only\"\n",
+        )?;
+        fs::write(
+            temp_dir.path().join("rbscripts.toml"),
+            "[tool.rb.scripts]\ntest = \"rake test\"\n",
+        )?;
+
+        let project = RbprojectDetector::discover_with_shared_scripts(&project_dir)?
+            .expect("Expected the parent rbscripts.toml to be used");
+        assert_eq!(project.config_filename, "rbscripts.toml");
+        assert_eq!(project.get_script_command("test"), Some("rake test"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn discover_with_shared_scripts_prefers_project_files() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_dir = temp_dir.path().join("project");
+        fs::create_dir_all(&project_dir)?;
+        fs::write(
+            project_dir.join("rbscripts.toml"),
+            "[tool.rb.scripts]\ntest = \"from rbscripts.toml\"\n",
+        )?;
+        create_rbproject_toml(
+            temp_dir.path(),
+            "[scripts]\ntest = \"from rbproject.toml\"\n",
+        )?;
+
+        let project = RbprojectDetector::discover_with_shared_scripts(&project_dir)?
+            .expect("Expected a project");
+        assert_eq!(project.config_filename, "rbproject.toml");
+
+        Ok(())
+    }
 }
@@ -1,6 +1,6 @@
 use crate::butler::runtime_provider::RuntimeProvider;
 use log::{debug, info};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io;
@@ -10,7 +10,49 @@ pub mod detector;
 pub mod template;
 
 pub use detector::RbprojectDetector;
-pub use template::create_default_project;
+pub use template::{
+    create_default_project, create_project_file, project_template, write_project_file,
+};
+
+/// Options of `rb` and `rb run` that take the following word as their value
+const VALUE_OPTIONS: &[&str] = &[
+    "-L",
+    "--log-level",
+    "-c",
+    "--config",
+    "-P",
+    "--project",
+    "-R",
+    "--rubies-dir",
+    "--additional-rubies-dir",
+    "-r",
+    "--ruby",
+    "-G",
+    "--gem-home",
+    "-g",
+    "--gemset",
+    "--default-ruby",
+    "-C",
+    "--work-dir",
+    "--bundler-bin",
+    "--exec-lock-retries",
+    "--command-log",
+    "--then",
+    "--env",
+];
+
+/// The next word that is neither an option nor the value of one
+fn next_positional<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    while let Some(token) = tokens.next() {
+        if !token.starts_with('-') {
+            return Some(token);
+        }
+        if VALUE_OPTIONS.contains(&token) {
+            tokens.next();
+        }
+    }
+    None
+}
 
 /// Represents a script definition in rbproject.toml
 /// Supports both simple string format and detailed object format
@@ -19,14 +61,26 @@ pub use template::create_default_project;
 pub enum ScriptDefinition {
     /// Simple format: script = "command"
     Simple(String),
-    /// Detailed format: script = { command = "...", description = "..." }
+    /// Detailed format: script = { command = "...", description = "...", enabled = false, runner = false }
     Detailed {
         command: String,
         #[serde(default)]
         description: Option<String>,
+        #[serde(default = "default_true")]
+        enabled: bool,
+        /// Whether the project-wide `runner` prefix applies to this script
+        #[serde(default = "default_true")]
+        runner: bool,
+        /// Seconds the script may run before it is terminated
+        #[serde(default)]
+        timeout: Option<u64>,
     },
 }
 
+fn default_true() -> bool {
+    true
+}
+
 impl ScriptDefinition {
     pub fn command(&self) -> &str {
         match self {
@@ -41,6 +95,54 @@ impl ScriptDefinition {
             ScriptDefinition::Detailed { description, .. } => description.as_deref(),
         }
     }
+
+    /// Whether the script may be listed and run; only detailed scripts can be disabled
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ScriptDefinition::Simple(_) => true,
+            ScriptDefinition::Detailed { enabled, .. } => *enabled,
+        }
+    }
+
+    /// How long the script may run before it is terminated; only detailed scripts set one
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            ScriptDefinition::Simple(_) => None,
+            ScriptDefinition::Detailed { timeout, .. } => {
+                timeout.map(std::time::Duration::from_secs)
+            }
+        }
+    }
+
+    /// Whether the project-wide runner should prefix this script; opt out with `runner = false`
+    pub fn uses_runner(&self) -> bool {
+        match self {
+            ScriptDefinition::Simple(_) => true,
+            ScriptDefinition::Detailed { runner, .. } => *runner,
+        }
+    }
+}
+
+/// Owned, serializable description of one project script
+///
+/// Produced by `ProjectRuntime::scripts_sorted()` for JSON output and editors that
+/// need the scripts as data rather than through the CLI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptInfo {
+    /// Name the script is run by
+    pub name: String,
+    /// Command as written in the project file
+    pub command: String,
+    /// Command actually executed, with the project runner prepended when it applies
+    pub invocation: String,
+    /// Human-readable description, if one is given
+    pub description: Option<String>,
+    /// Whether the script may be listed and run
+    pub enabled: bool,
+    /// Whether the project-wide runner prefixes this script
+    pub uses_runner: bool,
+    /// Seconds the script may run before it is terminated
+    pub timeout_secs: Option<u64>,
 }
 
 /// Project metadata from [project] section
@@ -50,6 +152,68 @@ pub struct ProjectMetadata {
     pub name: Option<String>,
     #[serde(default)]
     pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+}
+
+impl ProjectMetadata {
+    /// Project name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Short description of the project
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Project version, as written
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Project homepage URL
+    pub fn homepage(&self) -> Option<&str> {
+        self.homepage.as_deref()
+    }
+
+    /// License identifier, such as `MIT`
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
+    /// Authors in the order listed, empty when none are declared
+    pub fn authors(&self) -> &[String] {
+        &self.authors
+    }
+}
+
+/// The [scripts] section: a shared `runner` prefix alongside the script definitions
+///
+/// `runner` is reserved here, so no script can be named `runner`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+struct ScriptsSection {
+    #[serde(default)]
+    runner: Option<String>,
+    #[serde(flatten)]
+    definitions: HashMap<String, ScriptDefinition>,
+}
+
+/// Commands from the [hooks] section, run around `rb sync`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+pub struct ProjectHooks {
+    /// Runs before bundler synchronizes; a failure stops the sync
+    #[serde(default)]
+    pub pre_sync: Option<String>,
+    /// Runs only after a successful synchronization
+    #[serde(default)]
+    pub post_sync: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -57,7 +221,28 @@ struct RbprojectConfig {
     #[serde(default)]
     project: ProjectMetadata,
     #[serde(default)]
-    scripts: HashMap<String, ScriptDefinition>,
+    scripts: ScriptsSection,
+    #[serde(default)]
+    hooks: ProjectHooks,
+}
+
+/// A shared file such as pyproject.toml, of which only `[tool.rb.scripts]` is read
+#[derive(Debug, Deserialize)]
+struct SharedScriptsFile {
+    #[serde(default)]
+    tool: Option<SharedToolTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SharedToolTable {
+    #[serde(default)]
+    rb: Option<SharedRbTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SharedRbTable {
+    #[serde(default)]
+    scripts: Option<ScriptsSection>,
 }
 
 /// Parse KDL format project configuration
@@ -72,20 +257,32 @@ fn parse_kdl(content: &str, filename: &str) -> io::Result<RbprojectConfig> {
 
     let mut metadata = ProjectMetadata::default();
     let mut scripts = HashMap::new();
-
-    if let Some(project_node) = document.get("project") {
-        if let Some(name_node) = project_node.children().and_then(|c| c.get("name"))
-            && let Some(name_val) = name_node.entries().first()
-            && let Some(name_str) = name_val.value().as_string()
-        {
-            metadata.name = Some(name_str.to_string());
-        }
-        if let Some(desc_node) = project_node.children().and_then(|c| c.get("description"))
-            && let Some(desc_val) = desc_node.entries().first()
-            && let Some(desc_str) = desc_val.value().as_string()
-        {
-            metadata.description = Some(desc_str.to_string());
-        }
+    let mut runner = None;
+    let mut hooks = ProjectHooks::default();
+
+    if let Some(children) = document.get("project").and_then(|node| node.children()) {
+        let field = |name: &str| {
+            children
+                .get(name)
+                .and_then(|node| node.entries().first())
+                .and_then(|e| e.value().as_string())
+                .map(str::to_string)
+        };
+        metadata.name = field("name");
+        metadata.description = field("description");
+        metadata.version = field("version");
+        metadata.homepage = field("homepage");
+        metadata.license = field("license");
+        metadata.authors = children
+            .get("authors")
+            .map(|node| {
+                node.entries()
+                    .iter()
+                    .filter_map(|e| e.value().as_string())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
     }
 
     if let Some(scripts_node) = document.get("scripts")
@@ -94,6 +291,16 @@ fn parse_kdl(content: &str, filename: &str) -> io::Result<RbprojectConfig> {
         for child in children.nodes() {
             let script_name = child.name().value().to_string();
 
+            // Reserved for the shared prefix, as in the TOML [scripts] section
+            if script_name == "runner" {
+                runner = child
+                    .entries()
+                    .first()
+                    .and_then(|e| e.value().as_string())
+                    .map(str::to_string);
+                continue;
+            }
+
             if let Some(command_entry) = child.entries().first() {
                 if let Some(command_str) = command_entry.value().as_string() {
                     scripts.insert(
@@ -104,6 +311,9 @@ fn parse_kdl(content: &str, filename: &str) -> io::Result<RbprojectConfig> {
             } else if let Some(script_children) = child.children() {
                 let mut command = None;
                 let mut description = None;
+                let mut enabled = true;
+                let mut uses_runner = true;
+                let mut timeout = None;
 
                 for prop in script_children.nodes() {
                     match prop.name().value() {
@@ -121,6 +331,27 @@ fn parse_kdl(content: &str, filename: &str) -> io::Result<RbprojectConfig> {
                                 description = Some(desc.to_string());
                             }
                         }
+                        "enabled" => {
+                            if let Some(flag) =
+                                prop.entries().first().and_then(|e| e.value().as_bool())
+                            {
+                                enabled = flag;
+                            }
+                        }
+                        "runner" => {
+                            if let Some(flag) =
+                                prop.entries().first().and_then(|e| e.value().as_bool())
+                            {
+                                uses_runner = flag;
+                            }
+                        }
+                        "timeout" => {
+                            if let Some(seconds) =
+                                prop.entries().first().and_then(|e| e.value().as_integer())
+                            {
+                                timeout = u64::try_from(seconds).ok();
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -131,6 +362,9 @@ fn parse_kdl(content: &str, filename: &str) -> io::Result<RbprojectConfig> {
                         ScriptDefinition::Detailed {
                             command: cmd,
                             description,
+                            enabled,
+                            runner: uses_runner,
+                            timeout,
                         },
                     );
                 }
@@ -138,9 +372,27 @@ fn parse_kdl(content: &str, filename: &str) -> io::Result<RbprojectConfig> {
         }
     }
 
+    if let Some(hooks_node) = document.get("hooks")
+        && let Some(children) = hooks_node.children()
+    {
+        let hook = |name: &str| {
+            children
+                .get(name)
+                .and_then(|node| node.entries().first())
+                .and_then(|e| e.value().as_string())
+                .map(str::to_string)
+        };
+        hooks.pre_sync = hook("pre_sync");
+        hooks.post_sync = hook("post_sync");
+    }
+
     Ok(RbprojectConfig {
         project: metadata,
-        scripts,
+        scripts: ScriptsSection {
+            runner,
+            definitions: scripts,
+        },
+        hooks,
     })
 }
 
@@ -154,6 +406,10 @@ pub struct ProjectRuntime {
     pub metadata: ProjectMetadata,
     /// Scripts defined in the [scripts] section
     pub scripts: HashMap<String, ScriptDefinition>,
+    /// Command prefix applied to every script, e.g. `bundle exec`
+    pub runner: Option<String>,
+    /// Commands run before and after `rb sync`
+    pub hooks: ProjectHooks,
 }
 
 impl ProjectRuntime {
@@ -178,9 +434,30 @@ impl ProjectRuntime {
             config_filename,
             metadata,
             scripts,
+            runner: None,
+            hooks: ProjectHooks::default(),
         }
     }
 
+    /// Prefix every script command with `runner` unless the script opts out
+    pub fn with_runner(mut self, runner: Option<String>) -> Self {
+        self.runner = runner.filter(|r| !r.trim().is_empty());
+        if let Some(ref runner) = self.runner {
+            debug!("Scripts run through: {}", runner);
+        }
+        self
+    }
+
+    /// Attach the [hooks] section, ignoring blank commands
+    pub fn with_hooks(mut self, hooks: ProjectHooks) -> Self {
+        let present = |command: Option<String>| command.filter(|c| !c.trim().is_empty());
+        self.hooks = ProjectHooks {
+            pre_sync: present(hooks.pre_sync),
+            post_sync: present(hooks.post_sync),
+        };
+        self
+    }
+
     pub fn from_file(config_path: impl AsRef<Path>) -> io::Result<Self> {
         let config_path = config_path.as_ref();
 
@@ -223,10 +500,15 @@ impl ProjectRuntime {
             info!("Loaded {}", config_filename);
         }
 
-        let script_names: Vec<&str> = config.scripts.keys().map(|s| s.as_str()).collect();
+        let ScriptsSection {
+            runner,
+            definitions: scripts,
+        } = config.scripts;
+
+        let script_names: Vec<&str> = scripts.keys().map(|s| s.as_str()).collect();
         info!(
             "Found {} script(s): {}",
-            config.scripts.len(),
+            scripts.len(),
             if script_names.is_empty() {
                 "none".to_string()
             } else {
@@ -234,7 +516,7 @@ impl ProjectRuntime {
             }
         );
 
-        for (name, script_def) in &config.scripts {
+        for (name, script_def) in &scripts {
             if let Some(desc) = script_def.description() {
                 debug!("Script '{}': {} ({})", name, script_def.command(), desc);
             } else {
@@ -242,11 +524,67 @@ impl ProjectRuntime {
             }
         }
 
-        Ok(Self::new(
-            root,
-            config_filename,
-            config.project,
-            config.scripts,
+        Ok(Self::new(root, config_filename, config.project, scripts)
+            .with_runner(runner)
+            .with_hooks(config.hooks))
+    }
+
+    /// Load the `[tool.rb.scripts]` table of a shared file like pyproject.toml
+    ///
+    /// Returns `None` when the file has no such table, since shared files routinely
+    /// exist for other tools alone.
+    pub fn from_shared_scripts(config_path: impl AsRef<Path>) -> io::Result<Option<Self>> {
+        let config_path = config_path.as_ref();
+
+        let config_filename = config_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid config file path"))?
+            .to_string();
+
+        debug!("Loading shared scripts from: {}", config_path.display());
+
+        let content = fs::read_to_string(config_path)?;
+        let shared: SharedScriptsFile = toml::from_str(&content).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Failed to parse {}: {}", config_filename, e),
+            )
+        })?;
+
+        let Some(section) = shared
+            .tool
+            .and_then(|tool| tool.rb)
+            .and_then(|rb| rb.scripts)
+        else {
+            debug!("No [tool.rb.scripts] table in {}", config_filename);
+            return Ok(None);
+        };
+
+        let root = config_path
+            .parent()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "Could not determine parent directory",
+                )
+            })?
+            .to_path_buf();
+
+        info!(
+            "Loaded {} shared script(s) from {}",
+            section.definitions.len(),
+            config_filename
+        );
+
+        Ok(Some(
+            Self::new(
+                root,
+                config_filename,
+                ProjectMetadata::default(),
+                section.definitions,
+            )
+            .with_runner(section.runner),
         ))
     }
 
@@ -270,12 +608,85 @@ impl ProjectRuntime {
         self.scripts.get(name).and_then(|s| s.description())
     }
 
-    /// Get all script names
+    /// The command to execute for a script, with the project runner prepended when it applies
+    pub fn get_script_invocation(&self, name: &str) -> Option<String> {
+        let script = self.scripts.get(name)?;
+        match &self.runner {
+            Some(runner) if script.uses_runner() => {
+                Some(format!("{} {}", runner.trim(), script.command()))
+            }
+            _ => Some(script.command().to_string()),
+        }
+    }
+
+    /// Whether the script exists but has been disabled with `enabled = false`
+    pub fn is_script_disabled(&self, name: &str) -> bool {
+        self.scripts.get(name).is_some_and(|s| !s.is_enabled())
+    }
+
+    /// Get the names of all enabled scripts
     pub fn script_names(&self) -> Vec<&str> {
-        let mut names: Vec<&str> = self.scripts.keys().map(|s| s.as_str()).collect();
+        let mut names: Vec<&str> = self
+            .scripts
+            .iter()
+            .filter(|(_, script)| script.is_enabled())
+            .map(|(name, _)| name.as_str())
+            .collect();
         names.sort();
         names
     }
+
+    /// Every script, disabled ones included, with its full definition in name order
+    pub fn scripts_sorted(&self) -> Vec<ScriptInfo> {
+        let mut scripts: Vec<ScriptInfo> = self
+            .scripts
+            .iter()
+            .map(|(name, script)| ScriptInfo {
+                name: name.clone(),
+                command: script.command().to_string(),
+                invocation: self
+                    .get_script_invocation(name)
+                    .unwrap_or_else(|| script.command().to_string()),
+                description: script.description().map(str::to_string),
+                enabled: script.is_enabled(),
+                uses_runner: script.uses_runner(),
+                timeout_secs: script.timeout().map(|timeout| timeout.as_secs()),
+            })
+            .collect();
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+        scripts
+    }
+
+    /// Names of other scripts invoked by a script's command via `rb run <name>`
+    ///
+    /// Returned in the order they appear in the command. Only names defined in this
+    /// project are reported; unknown names and self-references are ignored.
+    pub fn script_references(&self, name: &str) -> Vec<&str> {
+        let Some(command) = self.get_script_command(name) else {
+            return Vec::new();
+        };
+
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+        let mut references = Vec::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            if *token != "rb" {
+                continue;
+            }
+
+            let mut rest = tokens[i + 1..].iter().copied();
+            if matches!(next_positional(&mut rest), Some("run") | Some("r"))
+                && let Some(target) = next_positional(&mut rest)
+                && let Some((key, _)) = self.scripts.get_key_value(target)
+                && key != name
+                && !references.contains(&key.as_str())
+            {
+                references.push(key.as_str());
+            }
+        }
+
+        references
+    }
 }
 
 impl RuntimeProvider for ProjectRuntime {
@@ -290,12 +701,16 @@ impl RuntimeProvider for ProjectRuntime {
     }
 
     fn compose_version_detector(&self) -> crate::ruby::CompositeDetector {
-        use crate::ruby::version_detector::{GemfileDetector, RubyVersionFileDetector};
+        use crate::ruby::version_detector::{
+            GemfileDetector, RtxTomlDetector, RubyVersionFileDetector, ToolVersionsDetector,
+        };
 
-        // Project environment: check .ruby-version first, then Gemfile
+        // Project environment: check .ruby-version first, then Gemfile, .tool-versions, .rtx.toml
         crate::ruby::CompositeDetector::new(vec![
             Box::new(RubyVersionFileDetector),
             Box::new(GemfileDetector),
+            Box::new(ToolVersionsDetector),
+            Box::new(RtxTomlDetector),
         ])
     }
 
@@ -348,6 +763,49 @@ mod tests {
         assert_eq!(project.scripts, scripts);
     }
 
+    #[test]
+    fn scripts_sorted_lists_full_definitions_by_name() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let toml_content = r#"
+[scripts]
+runner = "bundle exec"
+test = "rspec"
+deploy = { command = "cap deploy", description = "Ship it", enabled = false, timeout = 600 }
+lint = { command = "rubocop", runner = false }
+"#;
+        let rbproject_path = create_rbproject_file(temp_dir.path(), toml_content)?;
+        let project = ProjectRuntime::from_file(&rbproject_path)?;
+
+        let scripts = project.scripts_sorted();
+
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["deploy", "lint", "test"]);
+        assert_eq!(
+            scripts[0],
+            ScriptInfo {
+                name: "deploy".to_string(),
+                command: "cap deploy".to_string(),
+                invocation: "bundle exec cap deploy".to_string(),
+                description: Some("Ship it".to_string()),
+                enabled: false,
+                uses_runner: true,
+                timeout_secs: Some(600),
+            }
+        );
+        assert_eq!(scripts[1].invocation, "rubocop");
+        assert!(!scripts[1].uses_runner);
+        assert_eq!(scripts[2].command, "rspec");
+        assert_eq!(scripts[2].invocation, "bundle exec rspec");
+        assert_eq!(scripts[2].description, None);
+        assert!(scripts[2].enabled);
+
+        let json = serde_json::to_value(&scripts[2]).unwrap();
+        assert_eq!(json["name"], "test");
+        assert_eq!(json["timeout_secs"], serde_json::Value::Null);
+
+        Ok(())
+    }
+
     #[test]
     fn from_file_parses_simple_scripts() -> io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -508,6 +966,67 @@ this is not valid toml
         assert_eq!(names, vec!["lint", "server", "test"]);
     }
 
+    #[test]
+    fn script_references_finds_rb_run_invocations() {
+        let mut scripts = HashMap::new();
+        scripts.insert(
+            "ci".to_string(),
+            ScriptDefinition::Simple("rb run lint && rb -B r test && rb run missing".to_string()),
+        );
+        scripts.insert(
+            "lint".to_string(),
+            ScriptDefinition::Simple("rubocop".to_string()),
+        );
+        scripts.insert(
+            "test".to_string(),
+            ScriptDefinition::Detailed {
+                command: "rb run test && rspec".to_string(),
+                description: None,
+                enabled: true,
+                runner: true,
+                timeout: None,
+            },
+        );
+
+        let project = ProjectRuntime::new(
+            "/tmp",
+            "rbproject.toml",
+            ProjectMetadata::default(),
+            scripts,
+        );
+
+        assert_eq!(project.script_references("ci"), vec!["lint", "test"]);
+        assert!(project.script_references("lint").is_empty());
+        assert!(project.script_references("test").is_empty());
+        assert!(project.script_references("unknown").is_empty());
+    }
+
+    #[test]
+    fn script_references_skip_option_values() {
+        let mut scripts = HashMap::new();
+        scripts.insert(
+            "ci".to_string(),
+            ScriptDefinition::Simple(
+                "rb -R lint run test && rb run --env MODE=fast lint".to_string(),
+            ),
+        );
+        for name in ["lint", "test", "MODE=fast"] {
+            scripts.insert(
+                name.to_string(),
+                ScriptDefinition::Simple("true".to_string()),
+            );
+        }
+
+        let project = ProjectRuntime::new(
+            "/tmp",
+            "rbproject.toml",
+            ProjectMetadata::default(),
+            scripts,
+        );
+
+        assert_eq!(project.script_references("ci"), vec!["test", "lint"]);
+    }
+
     #[test]
     fn runtime_provider_returns_none() {
         let temp_dir = TempDir::new().unwrap();
@@ -520,6 +1039,7 @@ this is not valid toml
 
         assert_eq!(project.bin_dir(), None);
         assert_eq!(project.gem_dir(), None);
+        assert_eq!(project.version(), None);
     }
 
     #[test]
@@ -631,6 +1151,9 @@ deploy = { command = "cap production deploy", description = "Deploy to productio
         let def = ScriptDefinition::Detailed {
             command: "test command".to_string(),
             description: Some("Test description".to_string()),
+            enabled: true,
+            runner: true,
+            timeout: None,
         };
         assert_eq!(def.command(), "test command");
         assert_eq!(def.description(), Some("Test description"));
@@ -641,6 +1164,9 @@ deploy = { command = "cap production deploy", description = "Deploy to productio
         let def = ScriptDefinition::Detailed {
             command: "test command".to_string(),
             description: None,
+            enabled: true,
+            runner: true,
+            timeout: None,
         };
         assert_eq!(def.command(), "test command");
         assert_eq!(def.description(), None);
@@ -709,6 +1235,33 @@ test = "rspec"
         Ok(())
     }
 
+    #[test]
+    fn from_file_parses_extended_project_metadata() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let toml_content = r#"
+[project]
+name = "butler-app"
+version = "1.4.0"
+homepage = "https://example.com/butler-app"
+license = "MIT"
+authors = ["Ada", "Grace"]
+repository = "declared but unknown"
+"#;
+        let rbproject_path = create_rbproject_file(temp_dir.path(), toml_content)?;
+
+        let project = ProjectRuntime::from_file(&rbproject_path)?;
+
+        assert_eq!(project.metadata.version(), Some("1.4.0"));
+        assert_eq!(
+            project.metadata.homepage(),
+            Some("https://example.com/butler-app")
+        );
+        assert_eq!(project.metadata.license(), Some("MIT"));
+        assert_eq!(project.metadata.authors(), ["Ada", "Grace"]);
+
+        Ok(())
+    }
+
     #[test]
     fn from_file_handles_missing_project_metadata() -> io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -778,6 +1331,197 @@ scripts {
         Ok(())
     }
 
+    #[test]
+    fn disabled_toml_scripts_are_excluded_from_listing() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let toml_content = r#"
+[scripts]
+test = "rspec"
+deploy = { command = "cap deploy", enabled = false }
+lint = { command = "rubocop", enabled = true }
+"#;
+        let project_path = temp_dir.path().join("rbproject.toml");
+        fs::write(&project_path, toml_content)?;
+
+        let project = ProjectRuntime::from_file(&project_path)?;
+
+        assert_eq!(project.script_names(), vec!["lint", "test"]);
+        assert!(project.has_script("deploy"));
+        assert!(project.is_script_disabled("deploy"));
+        assert!(!project.is_script_disabled("lint"));
+        assert!(!project.is_script_disabled("missing"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_kdl_scripts_are_excluded_from_listing() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let kdl_content = r#"
+scripts {
+    test "rspec"
+    deploy {
+        command "cap deploy"
+        enabled #false
+    }
+}
+"#;
+        let kdl_path = temp_dir.path().join("rb.kdl");
+        fs::write(&kdl_path, kdl_content)?;
+
+        let project = ProjectRuntime::from_file(&kdl_path)?;
+
+        assert_eq!(project.script_names(), vec!["test"]);
+        assert!(project.is_script_disabled("deploy"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn toml_runner_prefixes_scripts_unless_opted_out() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let toml_content = r#"
+[scripts]
+runner = "bundle exec"
+test = "rspec"
+lint = { command = "rubocop", description = "Run linter" }
+setup = { command = "bin/setup", runner = false }
+"#;
+        let project_path = temp_dir.path().join("rbproject.toml");
+        fs::write(&project_path, toml_content)?;
+
+        let project = ProjectRuntime::from_file(&project_path)?;
+
+        assert_eq!(project.runner.as_deref(), Some("bundle exec"));
+        assert_eq!(project.script_names(), vec!["lint", "setup", "test"]);
+        assert_eq!(
+            project.get_script_invocation("test").as_deref(),
+            Some("bundle exec rspec")
+        );
+        assert_eq!(
+            project.get_script_invocation("lint").as_deref(),
+            Some("bundle exec rubocop")
+        );
+        assert_eq!(
+            project.get_script_invocation("setup").as_deref(),
+            Some("bin/setup")
+        );
+        assert_eq!(project.get_script_command("test"), Some("rspec"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn kdl_runner_prefixes_scripts_unless_opted_out() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let kdl_content = r#"
+scripts {
+    runner "bundle exec"
+    test "rspec"
+    setup {
+        command "bin/setup"
+        runner #false
+    }
+}
+"#;
+        let kdl_path = temp_dir.path().join("rbproject.kdl");
+        fs::write(&kdl_path, kdl_content)?;
+
+        let project = ProjectRuntime::from_file(&kdl_path)?;
+
+        assert_eq!(project.script_names(), vec!["setup", "test"]);
+        assert_eq!(
+            project.get_script_invocation("test").as_deref(),
+            Some("bundle exec rspec")
+        );
+        assert_eq!(
+            project.get_script_invocation("setup").as_deref(),
+            Some("bin/setup")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn script_timeouts_parse_from_toml_and_kdl() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let toml_path = temp_dir.path().join("rbproject.toml");
+        fs::write(
+            &toml_path,
+            "[scripts]\nlint = \"rubocop\"\nserver = { command = \"rails s\", timeout = 60 }\n",
+        )?;
+        let kdl_dir = TempDir::new()?;
+        let kdl_path = kdl_dir.path().join("rbproject.kdl");
+        fs::write(
+            &kdl_path,
+            "scripts {\n    server {\n        command \"rails s\"\n        timeout 60\n    }\n}\n",
+        )?;
+
+        for path in [&toml_path, &kdl_path] {
+            let project = ProjectRuntime::from_file(path)?;
+            assert_eq!(
+                project.get_script("server").and_then(|s| s.timeout()),
+                Some(std::time::Duration::from_secs(60))
+            );
+        }
+        let project = ProjectRuntime::from_file(&toml_path)?;
+        assert_eq!(project.get_script("lint").and_then(|s| s.timeout()), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn scripts_run_unprefixed_without_runner() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_path = temp_dir.path().join("rbproject.toml");
+        fs::write(&project_path, "[scripts]\ntest = \"rspec\"\n")?;
+
+        let project = ProjectRuntime::from_file(&project_path)?;
+
+        assert_eq!(project.runner, None);
+        assert_eq!(
+            project.get_script_invocation("test").as_deref(),
+            Some("rspec")
+        );
+        assert_eq!(project.get_script_invocation("missing"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn toml_hooks_are_parsed() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let project_path = temp_dir.path().join("rbproject.toml");
+        fs::write(
+            &project_path,
+            "[hooks]\npre_sync = \"bin/check-env\"\npost_sync = \"rails db:prepare\"\n",
+        )?;
+
+        let project = ProjectRuntime::from_file(&project_path)?;
+
+        assert_eq!(project.hooks.pre_sync.as_deref(), Some("bin/check-env"));
+        assert_eq!(project.hooks.post_sync.as_deref(), Some("rails db:prepare"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn kdl_hooks_are_parsed() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let kdl_path = temp_dir.path().join("rbproject.kdl");
+        fs::write(
+            &kdl_path,
+            "hooks {\n    post_sync \"rails db:prepare\"\n}\n",
+        )?;
+
+        let project = ProjectRuntime::from_file(&kdl_path)?;
+
+        assert_eq!(project.hooks.pre_sync, None);
+        assert_eq!(project.hooks.post_sync.as_deref(), Some("rails db:prepare"));
+
+        Ok(())
+    }
+
     #[test]
     fn from_file_parses_detailed_kdl_scripts() -> io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -848,6 +1592,36 @@ scripts {
         Ok(())
     }
 
+    #[test]
+    fn from_file_parses_extended_kdl_project_metadata() -> io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let kdl_content = r#"
+project {
+    name "butler-app"
+    version "1.4.0"
+    homepage "https://example.com/butler-app"
+    license "MIT"
+    authors "Ada" "Grace"
+    repository "declared but unknown"
+}
+"#;
+        let kdl_path = temp_dir.path().join("rbproject.kdl");
+        fs::write(&kdl_path, kdl_content)?;
+
+        let project = ProjectRuntime::from_file(&kdl_path)?;
+
+        assert_eq!(project.metadata.name(), Some("butler-app"));
+        assert_eq!(project.metadata.version(), Some("1.4.0"));
+        assert_eq!(
+            project.metadata.homepage(),
+            Some("https://example.com/butler-app")
+        );
+        assert_eq!(project.metadata.license(), Some("MIT"));
+        assert_eq!(project.metadata.authors(), ["Ada", "Grace"]);
+
+        Ok(())
+    }
+
     #[test]
     fn from_file_handles_empty_kdl_scripts() -> io::Result<()> {
         let temp_dir = TempDir::new()?;
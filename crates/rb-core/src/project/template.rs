@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::Path;
 
+use super::RbprojectDetector;
+
 /// Default template content for rbproject.toml
 pub const DEFAULT_RBPROJECT_TOML: &str = r#"[project]
 name = "Butler project template"
@@ -10,6 +12,17 @@ description = "Please fill in"
 ruby-version = "ruby -v"
 "#;
 
+/// Default template content for rbproject.kdl
+pub const DEFAULT_RBPROJECT_KDL: &str = r#"project {
+    name "Butler project template"
+    description "Please fill in"
+}
+
+scripts {
+    ruby-version "ruby -v"
+}
+"#;
+
 /// Create a new rbproject.toml file in the specified directory
 ///
 /// This function creates a default rbproject.toml template. It will fail if the file
@@ -34,23 +47,76 @@ ruby-version = "ruby -v"
 /// assert!(result.is_ok());
 /// ```
 pub fn create_default_project(current_dir: &Path) -> Result<(), String> {
-    let project_file = current_dir.join("rbproject.toml");
+    create_project_file(current_dir, "rbproject.toml")
+}
+
+/// Create a default project file with the given name in the specified directory
+///
+/// `filename` must be one of the supported project file names (`rbproject.toml`,
+/// `rbproject.kdl`, `gem.toml` or `gem.kdl`); the template format follows its extension.
+/// Fails if any project file already exists in the directory, since the names are
+/// interchangeable and a second file would be silently shadowed.
+pub fn create_project_file(current_dir: &Path, filename: &str) -> Result<(), String> {
+    write_project_file(current_dir, filename, false)
+}
 
-    // Check if file already exists
-    if project_file.exists() {
+/// Create a default project file, replacing an existing file of the same name when `overwrite` is set
+///
+/// Other project files are never replaced: with `overwrite` the call still fails if a
+/// differently named project file exists, since it would shadow or be shadowed by the new one.
+pub fn write_project_file(
+    current_dir: &Path,
+    filename: &str,
+    overwrite: bool,
+) -> Result<(), String> {
+    let template = project_template(filename)?;
+
+    // Check if any project file already exists
+    let existing: Vec<&str> = RbprojectDetector::PROJECT_FILENAMES
+        .iter()
+        .copied()
+        .filter(|existing| current_dir.join(existing).exists())
+        .filter(|existing| !(overwrite && *existing == filename))
+        .collect();
+
+    if let Some(existing) = existing.first() {
+        if overwrite {
+            return Err(format!(
+                "A different project configuration ({}) already graces this directory.\n\
+                 Butler will only overwrite {} itself; kindly remove {} first.",
+                existing, filename, existing
+            ));
+        }
         return Err("A project configuration already graces this directory.\n\
              Butler respectfully declines to overwrite existing arrangements.\n\
-             Should you wish to begin anew, kindly remove the existing file first."
+             Should you wish to begin anew, kindly remove the existing file first or use --force."
             .to_string());
     }
 
     // Write the default template
-    fs::write(&project_file, DEFAULT_RBPROJECT_TOML)
-        .map_err(|e| format!("Failed to create rbproject.toml: {}", e))?;
+    fs::write(current_dir.join(filename), template)
+        .map_err(|e| format!("Failed to create {}: {}", filename, e))?;
 
     Ok(())
 }
 
+/// Template content for a supported project file name, in the format its extension implies
+pub fn project_template(filename: &str) -> Result<&'static str, String> {
+    if !RbprojectDetector::PROJECT_FILENAMES.contains(&filename) {
+        return Err(format!(
+            "Unsupported project file name '{}'. Choose one of: {}",
+            filename,
+            RbprojectDetector::PROJECT_FILENAMES.join(", ")
+        ));
+    }
+
+    Ok(if filename.ends_with(".kdl") {
+        DEFAULT_RBPROJECT_KDL
+    } else {
+        DEFAULT_RBPROJECT_TOML
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +181,60 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[test]
+    fn test_create_project_file_supports_gem_kdl() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        create_project_file(temp_dir.path(), "gem.kdl").unwrap();
+
+        let project = crate::project::ProjectRuntime::from_file(temp_dir.path().join("gem.kdl"))
+            .expect("Generated KDL should parse");
+        assert_eq!(project.get_script_command("ruby-version"), Some("ruby -v"));
+    }
+
+    #[test]
+    fn test_create_project_file_refuses_when_alias_exists() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("gem.toml"), "[scripts]\n").unwrap();
+
+        let result = create_project_file(temp_dir.path(), "rbproject.toml");
+
+        assert!(
+            result
+                .unwrap_err()
+                .contains("already graces this directory")
+        );
+        assert!(!temp_dir.path().join("rbproject.toml").exists());
+    }
+
+    #[test]
+    fn test_create_project_file_rejects_unknown_name() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let result = create_project_file(temp_dir.path(), "project.yml");
+
+        assert!(
+            result
+                .unwrap_err()
+                .contains("Unsupported project file name")
+        );
+    }
+
+    #[test]
+    fn test_write_project_file_overwrites_only_same_name() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project_file = temp_dir.path().join("rbproject.toml");
+        fs::write(&project_file, "[scripts]\nold = \"true\"\n").unwrap();
+
+        write_project_file(temp_dir.path(), "rbproject.toml", true).unwrap();
+        assert_eq!(
+            fs::read_to_string(&project_file).unwrap(),
+            DEFAULT_RBPROJECT_TOML
+        );
+
+        let result = write_project_file(temp_dir.path(), "gem.kdl", true);
+        assert!(result.unwrap_err().contains("rbproject.toml"));
+        assert!(!temp_dir.path().join("gem.kdl").exists());
+    }
 }
@@ -1,17 +1,96 @@
-use log::{debug, info};
+use log::{debug, info, warn};
 use regex::Regex;
+#[cfg(windows)]
 use semver::Version;
 use std::{
+    ffi::OsString,
     fs,
     path::{Path, PathBuf},
 };
 
-use super::{RubyDiscoveryError, RubyRuntime, RubyType};
+use super::{RubyDiscoveryError, RubyRuntime, RubyType, parse_engine_version};
 
 pub struct RubyRuntimeDetector;
 
+/// Ruby snippet printing the engine, version and installation prefix on separate lines
+const SYSTEM_RUBY_QUERY: &str =
+    r#"require "rbconfig"; puts RUBY_ENGINE, RUBY_ENGINE_VERSION, RbConfig::CONFIG["prefix"]"#;
+
+/// Read the engine, version and prefix lines printed by `SYSTEM_RUBY_QUERY`
+fn parse_system_ruby_query(output: &str) -> Option<RubyRuntime> {
+    let mut lines = output.lines().map(str::trim);
+    let kind = RubyType::from_engine(lines.next()?)?;
+    let version = parse_engine_version(lines.next()?)?;
+    let prefix = lines.next().filter(|prefix| !prefix.is_empty())?;
+    Some(RubyRuntime::new(kind, version, prefix))
+}
+
+/// Result of scanning a rubies directory, separating usable runtimes from leftovers
+#[derive(Debug, Clone, Default)]
+pub struct RubyDiscovery {
+    /// Installations with an executable `bin/ruby`, latest first
+    pub installations: Vec<RubyRuntime>,
+    /// Ruby-named directories lacking an executable `bin/ruby` (e.g. interrupted installs)
+    pub broken_installations: Vec<PathBuf>,
+}
+
 impl RubyRuntimeDetector {
+    /// Discover usable Ruby installations, skipping directories without an executable `bin/ruby`
     pub fn discover(root_dir: &Path) -> Result<Vec<RubyRuntime>, RubyDiscoveryError> {
+        Self::discover_all(root_dir).map(|discovery| discovery.installations)
+    }
+
+    /// Discover usable Rubies in `root_dir` and the `additional_dirs` next to it, latest first
+    ///
+    /// Mirrors chruby reading both `~/.rubies` and `/opt/rubies`: missing directories are
+    /// skipped, and `DirectoryNotFound` (naming `root_dir`) is reported only when none exist.
+    /// A Ruby reached through more than one listed directory appears once.
+    pub fn discover_in(
+        root_dir: &Path,
+        additional_dirs: &[PathBuf],
+    ) -> Result<Vec<RubyRuntime>, RubyDiscoveryError> {
+        let mut found_dir = false;
+        let mut seen = Vec::new();
+        let mut rubies: Vec<RubyRuntime> = Vec::new();
+
+        for dir in std::iter::once(root_dir).chain(additional_dirs.iter().map(PathBuf::as_path)) {
+            let installations = match Self::discover(dir) {
+                Ok(installations) => installations,
+                Err(RubyDiscoveryError::DirectoryNotFound(path)) => {
+                    debug!("Skipping missing rubies directory: {}", path.display());
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            found_dir = true;
+
+            for ruby in installations {
+                let key = (
+                    ruby.version.clone(),
+                    fs::canonicalize(&ruby.root).unwrap_or_else(|_| ruby.root.clone()),
+                );
+                if seen.contains(&key) {
+                    debug!("Skipping Ruby already discovered: {}", ruby.root.display());
+                    continue;
+                }
+                seen.push(key);
+                rubies.push(ruby);
+            }
+        }
+
+        if !found_dir {
+            return Err(RubyDiscoveryError::DirectoryNotFound(
+                root_dir.to_path_buf(),
+            ));
+        }
+
+        // Stable, so the earlier directory wins between equal versions
+        rubies.sort_by(|a, b| b.latest_order().cmp(&a.latest_order()));
+        Ok(rubies)
+    }
+
+    /// Discover Ruby installations, keeping track of broken ones alongside the usable runtimes
+    pub fn discover_all(root_dir: &Path) -> Result<RubyDiscovery, RubyDiscoveryError> {
         debug!(
             "Starting Ruby discovery in directory: {}",
             root_dir.display()
@@ -29,7 +108,11 @@ impl RubyRuntimeDetector {
         }
 
         let mut out = Vec::new();
-        let re = Regex::new(r"^ruby-(\d+)\.(\d+)\.(\d+)$").expect("static regex");
+        let mut broken = Vec::new();
+        let re = Regex::new(
+            r"^(ruby|jruby|truffleruby)-(\d+\.\d+\.\d+(?:\.\d+)?(?:-(?:preview|rc)\d+)?)(?:-([A-Za-z][A-Za-z0-9_.-]*))?$",
+        )
+        .expect("static regex");
 
         let entries = fs::read_dir(root_dir).map_err(|e| {
             RubyDiscoveryError::IoError(format!(
@@ -53,27 +136,36 @@ impl RubyRuntimeDetector {
             debug!("Examining directory: {}", name);
 
             if let Some(c) = re.captures(&name) {
-                let v = format!("{}.{}.{}", &c[1], &c[2], &c[3]);
+                let v = &c[2];
                 debug!(
                     "Found potential Ruby directory matching pattern: {} -> version {}",
                     name, v
                 );
 
-                if let Ok(version) = Version::parse(&v) {
+                if let (Some(kind), Some(version)) =
+                    (RubyType::from_engine(&c[1]), parse_engine_version(v))
+                {
                     let root: PathBuf = entry.path();
                     debug!(
                         "Successfully parsed version {} for Ruby at: {}",
                         version,
                         root.display()
                     );
-                    out.push(RubyRuntime {
-                        kind: RubyType::CRuby,
-                        version,
-                        root,
-                    });
+                    Self::admit(
+                        RubyRuntime {
+                            kind,
+                            version,
+                            root,
+                            variant: c.get(3).map(|variant| variant.as_str().to_string()),
+                        },
+                        &mut out,
+                        &mut broken,
+                    );
                 } else {
                     debug!("Failed to parse version from directory name: {}", name);
                 }
+            } else if let Some(ruby) = Self::detect_platform_layout(&entry.path(), &name) {
+                Self::admit(ruby, &mut out, &mut broken);
             } else {
                 debug!(
                     "Directory name {} does not match Ruby directory pattern",
@@ -82,12 +174,20 @@ impl RubyRuntimeDetector {
             }
         }
 
-        out.sort_by(|a, b| b.version.cmp(&a.version)); // latest first
+        out.sort_by(|a, b| b.latest_order().cmp(&a.latest_order())); // latest first, as selection ranks them
+        broken.sort();
         info!(
             "Discovered {} Ruby installations in {}",
             out.len(),
             root_dir.display()
         );
+        if !broken.is_empty() {
+            warn!(
+                "Ignoring {} broken Ruby installations in {}",
+                broken.len(),
+                root_dir.display()
+            );
+        }
 
         for ruby in &out {
             debug!(
@@ -98,13 +198,164 @@ impl RubyRuntimeDetector {
             );
         }
 
-        Ok(out)
+        Ok(RubyDiscovery {
+            installations: out,
+            broken_installations: broken,
+        })
+    }
+
+    /// Keep the runtime if its `bin/ruby` is usable, otherwise record its directory as broken
+    fn admit(ruby: RubyRuntime, out: &mut Vec<RubyRuntime>, broken: &mut Vec<PathBuf>) {
+        let executable = ruby.ruby_executable_path();
+        if is_executable(&executable) {
+            out.push(ruby);
+        } else {
+            debug!(
+                "Skipping {} at {}: missing or non-executable {}",
+                ruby.version_name(),
+                ruby.root.display(),
+                executable.display()
+            );
+            broken.push(ruby.root);
+        }
+    }
+
+    /// Recognize platform-specific installation layouts (RubyInstaller on Windows)
+    #[cfg(windows)]
+    fn detect_platform_layout(root: &Path, name: &str) -> Option<RubyRuntime> {
+        let (major, minor) = parse_rubyinstaller_dir_name(name)?;
+        debug!(
+            "Found RubyInstaller directory {} for Ruby {}.{}",
+            name, major, minor
+        );
+
+        let ruby_exe = root
+            .join("bin")
+            .join(format!("ruby{}", std::env::consts::EXE_SUFFIX));
+        let version = std::process::Command::new(&ruby_exe)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| parse_ruby_version_output(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_else(|| {
+                debug!(
+                    "Unable to query {}, assuming Ruby {}.{}.0",
+                    ruby_exe.display(),
+                    major,
+                    minor
+                );
+                Version::new(major, minor, 0)
+            });
+
+        Some(RubyRuntime {
+            kind: RubyType::CRuby,
+            version,
+            root: root.to_path_buf(),
+            variant: None,
+        })
+    }
+
+    #[cfg(not(windows))]
+    fn detect_platform_layout(_root: &Path, _name: &str) -> Option<RubyRuntime> {
+        None
+    }
+
+    /// Build a runtime for the `ruby` first found on `path`, for machines without managed Rubies
+    ///
+    /// The executable is asked for its engine, version and installation prefix, so the
+    /// runtime's bin and gem directories follow the system layout.
+    pub fn system_ruby(path: Option<OsString>) -> Option<RubyRuntime> {
+        let cwd = std::env::current_dir().ok()?;
+        let executable = match which::which_in("ruby", path, cwd) {
+            Ok(executable) => executable,
+            Err(e) => {
+                debug!("No system ruby found on PATH: {}", e);
+                return None;
+            }
+        };
+
+        debug!("Querying system ruby at {}", executable.display());
+        let output = match std::process::Command::new(&executable)
+            .args(["-e", SYSTEM_RUBY_QUERY])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                warn!(
+                    "System ruby {} failed to describe itself (exit code: {})",
+                    executable.display(),
+                    output.status.code().unwrap_or(-1)
+                );
+                return None;
+            }
+            Err(e) => {
+                warn!("Unable to run system ruby {}: {}", executable.display(), e);
+                return None;
+            }
+        };
+
+        let runtime = parse_system_ruby_query(&String::from_utf8_lossy(&output.stdout));
+        match &runtime {
+            Some(ruby) => info!(
+                "Using system {} {} at {}",
+                ruby.kind.as_str(),
+                ruby.version,
+                ruby.root.display()
+            ),
+            None => warn!(
+                "System ruby {} gave an unrecognised description",
+                executable.display()
+            ),
+        }
+        runtime
+    }
+
+    /// Whether new Rubies cannot be installed into the existing directory `dir`
+    ///
+    /// True when its permissions allow nobody to write, or when access(2) denies us
+    /// writing, as on a read-only mount. A missing directory is not read-only.
+    pub fn is_read_only(dir: &Path) -> bool {
+        let Ok(metadata) = fs::metadata(dir) else {
+            return false;
+        };
+        if metadata.permissions().readonly() {
+            debug!("Rubies directory {} is read-only", dir.display());
+            return true;
+        }
+        Self::denies_writes(dir)
+    }
+
+    /// Ask access(2) whether we may write into `dir`, without touching it
+    #[cfg(unix)]
+    fn denies_writes(dir: &Path) -> bool {
+        use std::os::unix::ffi::OsStrExt;
+
+        let Ok(path) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+            return false;
+        };
+        // SAFETY: `path` is a NUL-terminated string that outlives the call
+        if unsafe { libc::access(path.as_ptr(), libc::W_OK) } == 0 {
+            return false;
+        }
+        debug!(
+            "Rubies directory {} is not writable: {}",
+            dir.display(),
+            std::io::Error::last_os_error()
+        );
+        true
+    }
+
+    /// Only the read-only attribute tells writability apart here
+    #[cfg(not(unix))]
+    fn denies_writes(_dir: &Path) -> bool {
+        false
     }
 
     pub fn latest(list: &[RubyRuntime]) -> Option<RubyRuntime> {
         let result = list
             .iter()
-            .max_by(|a, b| a.version.cmp(&b.version))
+            .max_by(|a, b| a.latest_order().cmp(&b.latest_order()))
             .cloned();
 
         if let Some(ref latest) = result {
@@ -121,3 +372,157 @@ impl RubyRuntimeDetector {
         result
     }
 }
+
+/// Whether `path` is a regular file the current user could execute
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+/// Parse RubyInstaller directory names like `Ruby33-x64` or `Ruby27` into (major, minor)
+#[cfg(windows)]
+fn parse_rubyinstaller_dir_name(name: &str) -> Option<(u64, u64)> {
+    let re = Regex::new(r"(?i)^ruby(\d)(\d+)(?:-(?:x64|x86|arm64))?$").expect("static regex");
+    let c = re.captures(name)?;
+    Some((c[1].parse().ok()?, c[2].parse().ok()?))
+}
+
+/// Parse `ruby --version` output like `ruby 3.3.4 (2024-07-09 revision be1089c8ec) [x64-mingw-ucrt]`
+#[cfg(windows)]
+fn parse_ruby_version_output(output: &str) -> Option<Version> {
+    let re = Regex::new(r"^ruby (\d+)\.(\d+)\.(\d+)").expect("static regex");
+    let c = re.captures(output.trim())?;
+    Version::parse(&format!("{}.{}.{}", &c[1], &c[2], &c[3])).ok()
+}
+
+#[cfg(all(test, windows))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_system_ruby_query_output() {
+        let ruby = parse_system_ruby_query("ruby\n3.1.4\n/usr\n").unwrap();
+        assert_eq!(ruby.kind, RubyType::CRuby);
+        assert_eq!(ruby.version.to_string(), "3.1.4");
+        assert_eq!(ruby.root, PathBuf::from("/usr"));
+
+        let jruby = parse_system_ruby_query("jruby\n9.4.5.0\n/opt/jruby\n").unwrap();
+        assert_eq!(jruby.kind, RubyType::JRuby);
+
+        assert!(parse_system_ruby_query("ruby\n3.1.4\n").is_none());
+        assert!(parse_system_ruby_query("mruby\n3.2.0\n/usr\n").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_directory_is_detected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let rubies = temp_dir.path().join("rubies");
+        fs::create_dir(&rubies).unwrap();
+        assert!(!RubyRuntimeDetector::is_read_only(&rubies));
+        assert!(!RubyRuntimeDetector::is_read_only(
+            &temp_dir.path().join("missing")
+        ));
+
+        fs::set_permissions(&rubies, fs::Permissions::from_mode(0o555)).unwrap();
+        let read_only = RubyRuntimeDetector::is_read_only(&rubies);
+        fs::set_permissions(&rubies, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(read_only);
+        assert_eq!(fs::read_dir(&rubies).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn system_ruby_describes_the_ruby_on_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let prefix = temp_dir.path().join("system");
+        let bin = prefix.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let ruby = bin.join("ruby");
+        fs::write(
+            &ruby,
+            format!(
+                "#!/bin/sh\necho ruby\necho 3.1.4\necho '{}'\n",
+                prefix.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&ruby, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let system = RubyRuntimeDetector::system_ruby(Some(bin.into_os_string())).unwrap();
+
+        assert_eq!(system.kind, RubyType::CRuby);
+        assert_eq!(system.version.to_string(), "3.1.4");
+        assert_eq!(system.root, prefix);
+        assert_eq!(system.ruby_executable_path(), ruby);
+        assert!(
+            RubyRuntimeDetector::system_ruby(Some(temp_dir.path().as_os_str().to_owned()))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn parses_rubyinstaller_dir_names() {
+        assert_eq!(parse_rubyinstaller_dir_name("Ruby33-x64"), Some((3, 3)));
+        assert_eq!(parse_rubyinstaller_dir_name("Ruby27-x86"), Some((2, 7)));
+        assert_eq!(parse_rubyinstaller_dir_name("Ruby34-arm64"), Some((3, 4)));
+        assert_eq!(parse_rubyinstaller_dir_name("Ruby32"), Some((3, 2)));
+        assert_eq!(parse_rubyinstaller_dir_name("ruby33-x64"), Some((3, 3)));
+    }
+
+    #[test]
+    fn rejects_unrelated_dir_names() {
+        assert_eq!(parse_rubyinstaller_dir_name("Ruby-x64"), None);
+        assert_eq!(parse_rubyinstaller_dir_name("Ruby33-sparc"), None);
+        assert_eq!(parse_rubyinstaller_dir_name("Python312"), None);
+    }
+
+    #[test]
+    fn parses_ruby_version_output() {
+        assert_eq!(
+            parse_ruby_version_output(
+                "ruby 3.3.4 (2024-07-09 revision be1089c8ec) +YJIT [x64-mingw-ucrt]\r\n"
+            ),
+            Some(Version::new(3, 3, 4))
+        );
+        assert_eq!(
+            parse_ruby_version_output(
+                "ruby 2.7.8p225 (2023-03-30 revision 1f4d455848) [x64-mingw32]"
+            ),
+            Some(Version::new(2, 7, 8))
+        );
+        assert_eq!(parse_ruby_version_output("not ruby"), None);
+    }
+
+    #[test]
+    fn discovers_rubyinstaller_layout_with_unrunnable_executable() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let bin = temp_dir.path().join("Ruby33-x64").join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(bin.join("ruby.exe"), "").unwrap();
+
+        let rubies = RubyRuntimeDetector::discover(temp_dir.path()).unwrap();
+
+        assert_eq!(rubies.len(), 1);
+        assert_eq!(rubies[0].version, Version::new(3, 3, 0));
+        assert!(rubies[0].root.ends_with("Ruby33-x64"));
+    }
+}
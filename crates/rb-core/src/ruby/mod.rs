@@ -2,14 +2,17 @@ use crate::butler::runtime_provider::RuntimeProvider;
 use crate::gems::GemRuntime;
 use log::debug;
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use std::env::consts::EXE_SUFFIX;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub mod version_detector;
 pub mod version_ext;
 
 pub use version_detector::{
-    CompositeDetector, GemfileDetector, RubyVersionDetector, RubyVersionFileDetector,
+    CompositeDetector, GemfileDetector, PinFileDetector, RtxTomlDetector, RubyVersionDetector,
+    RubyVersionFileDetector, ToolVersionsDetector,
 };
 pub use version_ext::RubyVersionExt;
 
@@ -49,25 +52,72 @@ impl From<RubyDiscoveryError> for std::io::Error {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RubyType {
     /// MRI / CRuby
     CRuby,
+    /// JRuby, running on the JVM
+    JRuby,
+    /// TruffleRuby, running on GraalVM
+    TruffleRuby,
 }
 
 impl RubyType {
     pub fn as_str(&self) -> &'static str {
         match self {
             RubyType::CRuby => "CRuby",
+            RubyType::JRuby => "JRuby",
+            RubyType::TruffleRuby => "TruffleRuby",
         }
     }
+
+    /// Engine token used in version strings and directory names, like `jruby` in `jruby-9.4.5.0`
+    pub fn engine(&self) -> &'static str {
+        match self {
+            RubyType::CRuby => "ruby",
+            RubyType::JRuby => "jruby",
+            RubyType::TruffleRuby => "truffleruby",
+        }
+    }
+
+    /// The Ruby type an engine token names, if it is one Butler knows
+    pub fn from_engine(engine: &str) -> Option<Self> {
+        [RubyType::CRuby, RubyType::JRuby, RubyType::TruffleRuby]
+            .into_iter()
+            .find(|kind| kind.engine() == engine)
+    }
+}
+
+/// Parse a version the way Ruby engines number releases
+///
+/// Besides plain semver, this accepts JRuby's four-part numbers such as `9.4.5.0`,
+/// keeping the fourth part as build metadata (`9.4.5+0`).
+pub fn parse_engine_version(version: &str) -> Option<Version> {
+    let (numbers, pre) = match version.split_once('-') {
+        Some((numbers, pre)) => (numbers, Some(pre)),
+        None => (version, None),
+    };
+
+    let parts: Vec<&str> = numbers.split('.').collect();
+    let normalized = match (parts.as_slice(), pre) {
+        ([major, minor, patch, build], Some(pre)) => {
+            format!("{}.{}.{}-{}+{}", major, minor, patch, pre, build)
+        }
+        ([major, minor, patch, build], None) => format!("{}.{}.{}+{}", major, minor, patch, build),
+        _ => version.to_string(),
+    };
+
+    Version::parse(&normalized).ok()
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RubyRuntime {
     pub kind: RubyType,
     pub version: Version,
     pub root: PathBuf,
+    /// Build label trailing the version in the directory name, like `railsexpress`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
 }
 
 impl RubyRuntime {
@@ -76,12 +126,35 @@ impl RubyRuntime {
             kind,
             version,
             root: root.as_ref().to_path_buf(),
+            variant: None,
         }
     }
 
-    /// Identifier like "CRuby-3.2.1"
+    /// Label this runtime as a variant build of its version
+    pub fn with_variant(mut self, variant: impl Into<String>) -> Self {
+        self.variant = Some(variant.into());
+        self
+    }
+
+    /// Identifier like "CRuby-3.2.1", or "CRuby-3.2.1-railsexpress" for variant builds
     pub fn version_name(&self) -> String {
-        format!("{}-{}", self.kind.as_str(), self.version)
+        match &self.variant {
+            Some(variant) => format!("{}-{}-{}", self.kind.as_str(), self.version, variant),
+            None => format!("{}-{}", self.kind.as_str(), self.version),
+        }
+    }
+
+    /// Key for picking the latest Ruby: CRuby ranks above other engines, which number
+    /// their releases on their own scale (TruffleRuby 23.x is not newer than CRuby 3.x)
+    pub fn latest_order(&self) -> (bool, &Version) {
+        (self.kind == RubyType::CRuby, &self.version)
+    }
+
+    /// Last modification of the installation directory, a stand-in for when it was installed
+    pub fn installed_at(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.root)
+            .and_then(|metadata| metadata.modified())
+            .ok()
     }
 
     /// `<root>/bin`
@@ -110,15 +183,24 @@ impl RubyRuntime {
 
     /// `<root>/lib/ruby/gems/<major>.<minor>.0`
     ///
-    /// Note: RubyGems uses the ruby ABI dir (major.minor.0).
-    /// If you later discover a platform that differs, branch on `self.kind`.
+    /// Note: RubyGems uses the ruby ABI dir (major.minor.0) on CRuby. JRuby keeps
+    /// its gems in `lib/ruby/gems/shared` and TruffleRuby in `lib/gems`.
     pub fn lib_dir(&self) -> PathBuf {
-        let lib_dir = self
-            .root
-            .join("lib")
-            .join("ruby")
-            .join("gems")
-            .join(format!("{}.{}.0", self.version.major, self.version.minor));
+        let lib_dir = match self.kind {
+            RubyType::CRuby => self
+                .root
+                .join("lib")
+                .join("ruby")
+                .join("gems")
+                .join(format!("{}.{}.0", self.version.major, self.version.minor)),
+            RubyType::JRuby => self
+                .root
+                .join("lib")
+                .join("ruby")
+                .join("gems")
+                .join("shared"),
+            RubyType::TruffleRuby => self.root.join("lib").join("gems"),
+        };
         debug!(
             "Inferred lib directory for {} {}: {}",
             self.kind.as_str(),
@@ -138,7 +220,7 @@ impl RubyRuntime {
             gem_base.display()
         );
 
-        let gem_runtime = GemRuntime::for_base_dir(gem_base, &self.version);
+        let gem_runtime = GemRuntime::for_engine(gem_base, self.kind, &self.version);
         debug!(
             "Created gem runtime - home: {}, bin: {}",
             gem_runtime.gem_home.display(),
@@ -148,6 +230,22 @@ impl RubyRuntime {
         gem_runtime
     }
 
+    /// `<root>/lib/ruby/site_ruby/<major>.<minor>.0`, where locally installed libraries live
+    ///
+    /// Only CRuby keeps a versioned site_ruby directory; other engines return `None`.
+    pub fn site_ruby_dir(&self) -> Option<PathBuf> {
+        match self.kind {
+            RubyType::CRuby => Some(
+                self.root
+                    .join("lib")
+                    .join("ruby")
+                    .join("site_ruby")
+                    .join(format!("{}.{}.0", self.version.major, self.version.minor)),
+            ),
+            RubyType::JRuby | RubyType::TruffleRuby => None,
+        }
+    }
+
     pub fn bin_dirs(&self) -> Vec<PathBuf> {
         vec![self.bin_dir()]
     }
@@ -156,9 +254,9 @@ impl RubyRuntime {
         vec![self.lib_dir()]
     }
 
-    /// Create a GemRuntime based on ~/.gem/ruby/version pattern
+    /// Create a GemRuntime based on ~/.gem/engine/version pattern
     ///
-    /// This creates a GemRuntime pointing to ~/.gem/ruby/<full.version>
+    /// This creates a GemRuntime pointing to ~/.gem/<engine>/<full.version>
     /// which follows the standard user gem installation pattern.
     pub fn infer_gem_runtime(&self) -> Result<GemRuntime, std::io::Error> {
         debug!(
@@ -167,10 +265,10 @@ impl RubyRuntime {
             self.version
         );
 
-        let home_dir = home::home_dir().ok_or_else(|| {
+        let home_dir = crate::user_home::home_dir().ok_or_else(|| {
             std::io::Error::new(
                 std::io::ErrorKind::NotFound,
-                "Could not determine home directory",
+                crate::user_home::HOME_DIR_UNAVAILABLE,
             )
         })?;
 
@@ -179,7 +277,7 @@ impl RubyRuntime {
         let gem_base = home_dir.join(".gem");
         debug!("Using gem base directory: {}", gem_base.display());
 
-        let gem_runtime = GemRuntime::for_base_dir(&gem_base, &self.version);
+        let gem_runtime = GemRuntime::for_engine(&gem_base, self.kind, &self.version);
         debug!(
             "Created gem runtime - home: {}, bin: {}",
             gem_runtime.gem_home.display(),
@@ -199,13 +297,21 @@ impl RuntimeProvider for RubyRuntime {
         Some(self.lib_dir())
     }
 
+    fn version(&self) -> Option<Version> {
+        Some(self.version.clone())
+    }
+
     fn compose_version_detector(&self) -> crate::ruby::CompositeDetector {
-        use crate::ruby::version_detector::{GemfileDetector, RubyVersionFileDetector};
+        use crate::ruby::version_detector::{
+            GemfileDetector, RtxTomlDetector, RubyVersionFileDetector, ToolVersionsDetector,
+        };
 
-        // Ruby environment: check .ruby-version first, then Gemfile
+        // Ruby environment: check .ruby-version first, then Gemfile, .tool-versions, .rtx.toml
         crate::ruby::CompositeDetector::new(vec![
             Box::new(RubyVersionFileDetector),
             Box::new(GemfileDetector),
+            Box::new(ToolVersionsDetector),
+            Box::new(RtxTomlDetector),
         ])
     }
 
@@ -240,6 +346,46 @@ mod tests {
         assert_eq!(r.version_name(), "CRuby-3.2.1");
     }
 
+    #[test]
+    fn engine_tokens_map_to_ruby_types() {
+        assert_eq!(RubyType::from_engine("ruby"), Some(RubyType::CRuby));
+        assert_eq!(RubyType::from_engine("jruby"), Some(RubyType::JRuby));
+        assert_eq!(
+            RubyType::from_engine("truffleruby"),
+            Some(RubyType::TruffleRuby)
+        );
+        assert_eq!(RubyType::from_engine("mruby"), None);
+    }
+
+    #[test]
+    fn parses_four_part_engine_versions() {
+        assert_eq!(
+            parse_engine_version("9.4.5.0"),
+            Some(Version::parse("9.4.5+0").unwrap())
+        );
+        assert_eq!(parse_engine_version("3.3.4"), Some(Version::new(3, 3, 4)));
+        assert_eq!(
+            parse_engine_version("3.4.0-preview1"),
+            Some(Version::parse("3.4.0-preview1").unwrap())
+        );
+        assert_eq!(parse_engine_version("latest"), None);
+    }
+
+    #[test]
+    fn version_name_includes_variant_label() {
+        let r = rt("3.3.4", "/opt/rubies/ruby-3.3.4-railsexpress").with_variant("railsexpress");
+        assert_eq!(r.version_name(), "CRuby-3.3.4-railsexpress");
+    }
+
+    #[test]
+    fn runtime_provider_version_is_ruby_version() {
+        let r = rt("3.2.1", "/opt/rubies/ruby-3.2.1");
+        assert_eq!(
+            RuntimeProvider::version(&r),
+            Some(Version::parse("3.2.1").unwrap())
+        );
+    }
+
     #[test]
     fn bin_dir_is_root_bin() {
         let root = Path::new("opt").join("rubies").join("ruby-3.4.5");
@@ -268,6 +414,49 @@ mod tests {
         assert!(p.ends_with(&expected_tail));
     }
 
+    #[test]
+    fn lib_gems_path_follows_engine_layout() {
+        let jruby = RubyRuntime::new(
+            RubyType::JRuby,
+            Version::parse("9.4.5+0").unwrap(),
+            "/opt/rubies/jruby-9.4.5.0",
+        );
+        assert!(
+            jruby
+                .lib_dir()
+                .ends_with(Path::new("lib").join("ruby").join("gems").join("shared"))
+        );
+
+        let truffle = RubyRuntime::new(
+            RubyType::TruffleRuby,
+            Version::new(23, 1, 0),
+            "/opt/rubies/truffleruby-23.1.0",
+        );
+        assert!(truffle.lib_dir().ends_with(Path::new("lib").join("gems")));
+    }
+
+    #[test]
+    fn site_ruby_dir_uses_major_minor_zero_on_cruby_only() {
+        let r = rt("3.2.4", "/opt/rubies/ruby-3.2.4");
+        assert_eq!(
+            r.site_ruby_dir(),
+            Some(
+                Path::new("/opt/rubies/ruby-3.2.4")
+                    .join("lib")
+                    .join("ruby")
+                    .join("site_ruby")
+                    .join("3.2.0")
+            )
+        );
+
+        let jruby = RubyRuntime::new(
+            RubyType::JRuby,
+            Version::parse("9.4.5+0").unwrap(),
+            "/opt/rubies/jruby-9.4.5.0",
+        );
+        assert_eq!(jruby.site_ruby_dir(), None);
+    }
+
     #[test]
     fn runtime_provider_returns_bin_and_gem_dir_for_ruby_runtime() {
         let r = rt("3.2.2", "/opt/rubies/ruby-3.2.2");
@@ -297,7 +486,19 @@ mod tests {
         let version_part = gem_runtime.gem_home.file_name().unwrap();
         assert_eq!(version_part, "3.4.5");
     }
+
+    #[test]
+    fn ruby_runtime_round_trips_through_json() {
+        let ruby = rt("3.3.6", "/opt/rubies/ruby-3.3.6");
+
+        let json = serde_json::to_string(&ruby).unwrap();
+        assert!(json.contains("\"root\":\"/opt/rubies/ruby-3.3.6\""));
+        assert!(json.contains("\"version\":\"3.3.6\""));
+
+        let restored: RubyRuntime = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, ruby);
+    }
 }
 
 pub mod detector;
-pub use detector::RubyRuntimeDetector;
+pub use detector::{RubyDiscovery, RubyRuntimeDetector};
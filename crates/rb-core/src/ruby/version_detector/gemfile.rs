@@ -1,84 +1,223 @@
 //! Detector for Gemfile ruby declarations
 
-use super::RubyVersionDetector;
+use super::{RubyVersionDetector, RubyVersionFileDetector};
+use crate::bundler::BundlerRuntimeDetector;
+use crate::ruby::{RubyType, parse_engine_version};
 use log::{debug, warn};
 use semver::Version;
 use std::fs;
 use std::path::Path;
 
-/// Detects Ruby version from Gemfile ruby declaration
+/// Detects Ruby version from the ruby declaration in a Gemfile or gems.rb
 pub struct GemfileDetector;
 
+/// What a Gemfile `ruby` declaration asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GemfileRuby {
+    /// Ruby language version, e.g. `3.3.4`
+    pub version: Version,
+    /// Engine named with `engine:`, if any
+    pub engine: Option<RubyType>,
+    /// Engine release named with `engine_version:`, e.g. JRuby's `9.4.5.0`
+    pub engine_version: Option<Version>,
+}
+
+impl GemfileRuby {
+    /// The version installations are matched against: the engine's own release when given
+    ///
+    /// Rubies other than CRuby are installed under their engine version (`jruby-9.4.5.0`),
+    /// so that is what selection has to look for.
+    pub fn requirement(&self) -> &Version {
+        self.engine_version.as_ref().unwrap_or(&self.version)
+    }
+}
+
 impl RubyVersionDetector for GemfileDetector {
     fn detect(&self, context: &Path) -> Option<Version> {
-        let gemfile_path = context.join("Gemfile");
+        Self::read(context).map(|ruby| ruby.requirement().clone())
+    }
+
+    fn detect_engine(&self, context: &Path) -> Option<RubyType> {
+        Self::read(context).and_then(|ruby| ruby.engine)
+    }
+
+    fn name(&self) -> &'static str {
+        "Gemfile"
+    }
+}
+
+impl GemfileDetector {
+    /// Read the `ruby` declaration from the Gemfile in `context`, engine options included
+    pub fn read(context: &Path) -> Option<GemfileRuby> {
+        let Some(gemfile_path) = BundlerRuntimeDetector::gemfile_in(context) else {
+            debug!("No Gemfile found");
+            return None;
+        };
         debug!(
             "Checking for ruby declaration in Gemfile: {}",
             gemfile_path.display()
         );
 
-        match fs::read_to_string(&gemfile_path) {
-            Ok(content) => {
-                debug!("Reading Gemfile for ruby declaration");
-
-                for line in content.lines() {
-                    let line = line.trim();
-
-                    // Match: ruby '3.2.5' or ruby "3.2.5"
-                    if line.starts_with("ruby ") {
-                        debug!("Found ruby line: '{}'", line);
-
-                        if let Some(version_str) = Self::extract_quoted_version(line) {
-                            debug!("Extracted version string: '{}'", version_str);
-
-                            match Version::parse(&version_str) {
-                                Ok(version) => {
-                                    debug!(
-                                        "Successfully parsed Ruby version from Gemfile: {}",
-                                        version
-                                    );
-                                    return Some(version);
-                                }
-                                Err(e) => {
-                                    warn!(
-                                        "Failed to parse Ruby version '{}' from Gemfile: {}",
-                                        version_str, e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+        let Ok(content) = fs::read_to_string(&gemfile_path) else {
+            debug!("Unable to read {}", gemfile_path.display());
+            return None;
+        };
+        debug!("Reading Gemfile for ruby declaration");
 
-                debug!("No valid ruby declaration found in Gemfile");
-                None
+        for line in content.lines() {
+            let line = line.trim();
+
+            // Match: ruby '3.2.5' or ruby "3.2.5"
+            if !line.starts_with("ruby ") {
+                continue;
             }
-            Err(_) => {
-                debug!("No Gemfile found");
-                None
+            debug!("Found ruby line: '{}'", line);
+
+            if let Some(argument) = Self::file_argument(line) {
+                let Some(file) = Self::leading_quoted(argument) else {
+                    debug!(
+                        "Ruby file argument '{}' is not a literal path, no version required",
+                        argument
+                    );
+                    return None;
+                };
+                return Self::read_referenced_version(context, &file);
+            }
+
+            if let Some(version_str) = Self::extract_quoted_version(line) {
+                debug!("Extracted version string: '{}'", version_str);
+
+                match Version::parse(&version_str) {
+                    Ok(version) => {
+                        debug!("Successfully parsed Ruby version from Gemfile: {}", version);
+                        return Some(GemfileRuby {
+                            version,
+                            engine: Self::extract_engine(line),
+                            engine_version: Self::extract_engine_version(line),
+                        });
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to parse Ruby version '{}' from Gemfile: {}",
+                            version_str, e
+                        );
+                    }
+                }
             }
         }
+
+        debug!("No valid ruby declaration found in Gemfile");
+        None
     }
 
-    fn name(&self) -> &'static str {
-        "Gemfile"
+    /// The engine named by `engine: 'jruby'` (or `:engine => "jruby"`)
+    fn extract_engine(line: &str) -> Option<RubyType> {
+        let engine = Self::option_value(line, "engine")?;
+        let kind = RubyType::from_engine(&engine);
+        if kind.is_none() {
+            warn!("Ignoring unsupported Ruby engine '{}' in Gemfile", engine);
+        }
+        kind
+    }
+
+    /// The release named by `engine_version: '9.4.5.0'` (or `:engine_version => ...`)
+    fn extract_engine_version(line: &str) -> Option<Version> {
+        let version = Self::option_value(line, "engine_version")?;
+        let parsed = parse_engine_version(&version);
+        if parsed.is_none() {
+            warn!("Failed to parse engine version '{}' from Gemfile", version);
+        }
+        parsed
+    }
+
+    /// The quoted value of a `key: '...'` or `:key => '...'` option on a `ruby` line
+    fn option_value(line: &str, key: &str) -> Option<String> {
+        [
+            format!("{}:", key),
+            format!(":{} =>", key),
+            format!(":{}=>", key),
+        ]
+        .iter()
+        .find_map(|marker| {
+            line.match_indices(marker.as_str())
+                // Not the tail of a longer name, e.g. `engine:` inside `my_engine:`
+                .find(|(at, _)| {
+                    line[..*at]
+                        .chars()
+                        .next_back()
+                        .is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+                })
+                .and_then(|(at, _)| Self::leading_quoted(line[at + marker.len()..].trim()))
+        })
     }
-}
 
-impl GemfileDetector {
     fn extract_quoted_version(line: &str) -> Option<String> {
+        Self::leading_quoted(line.strip_prefix("ruby ")?.trim())
+    }
+
+    /// What follows `ruby file:` (or `:file =>`), if the line uses that form
+    ///
+    /// Only a quoted path can be followed; an argument such as a constant is evaluated
+    /// by Bundler and yields no requirement here.
+    fn file_argument(line: &str) -> Option<&str> {
         let rest = line.strip_prefix("ruby ")?.trim();
+        let argument = rest
+            .strip_prefix("file:")
+            .or_else(|| rest.strip_prefix(":file =>"))
+            .or_else(|| rest.strip_prefix(":file=>"))?
+            .trim();
+        Some(argument)
+    }
 
+    fn leading_quoted(text: &str) -> Option<String> {
         for quote in &['\'', '"'] {
-            if rest.starts_with(*quote)
-                && let Some(end_idx) = rest[1..].find(*quote)
+            if text.starts_with(*quote)
+                && let Some(end_idx) = text[1..].find(*quote)
             {
-                return Some(rest[1..=end_idx].to_string());
+                return Some(text[1..=end_idx].to_string());
             }
         }
 
         None
     }
+
+    /// Read the version from a file referenced by the Gemfile, relative to the Gemfile's directory
+    fn read_referenced_version(context: &Path, file: &str) -> Option<GemfileRuby> {
+        let path = context.join(file);
+        debug!("Gemfile defers its ruby version to {}", path.display());
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!(
+                    "Gemfile references {} for its ruby version, but it cannot be read: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+
+        let token = content.split_whitespace().next().unwrap_or_default();
+        match RubyVersionFileDetector::parse_token(token) {
+            Some((engine, version)) => {
+                debug!("Parsed Ruby version {} from {}", version, path.display());
+                Some(GemfileRuby {
+                    version,
+                    engine,
+                    engine_version: None,
+                })
+            }
+            None => {
+                warn!(
+                    "Failed to parse Ruby version '{}' from {}",
+                    token,
+                    path.display()
+                );
+                None
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -136,6 +275,138 @@ mod tests {
         assert!(detector.detect(temp_dir.path()).is_none());
     }
 
+    #[test]
+    fn test_reads_gems_rb() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("gems.rb"),
+            "source 'https://rubygems.org'\nruby '3.2.5'\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            GemfileDetector.detect(temp_dir.path()),
+            Some(Version::new(3, 2, 5))
+        );
+    }
+
+    #[test]
+    fn test_follows_ruby_file_reference() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".ruby-version"), "ruby-3.3.4\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("Gemfile"),
+            "source 'https://rubygems.org'\nruby file: \".ruby-version\"\n",
+        )
+        .unwrap();
+
+        let version = GemfileDetector.detect(temp_dir.path()).unwrap();
+
+        assert_eq!(version, Version::new(3, 3, 4));
+    }
+
+    #[test]
+    fn test_follows_explicit_ruby_file_path() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("config")).unwrap();
+        std::fs::write(temp_dir.path().join("config/ruby-version"), "3.2.5").unwrap();
+        std::fs::write(
+            temp_dir.path().join("Gemfile"),
+            "ruby file: 'config/ruby-version'\ngem 'rails'\n",
+        )
+        .unwrap();
+
+        let version = GemfileDetector.detect(temp_dir.path()).unwrap();
+
+        assert_eq!(version, Version::new(3, 2, 5));
+    }
+
+    #[test]
+    fn test_reads_engine_qualified_declaration() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("Gemfile"),
+            "source 'https://rubygems.org'\nruby '3.3.4', engine: 'jruby', engine_version: '9.4.5.0'\n",
+        )
+        .unwrap();
+
+        let ruby = GemfileDetector::read(temp_dir.path()).unwrap();
+        assert_eq!(ruby.version, Version::new(3, 3, 4));
+        assert_eq!(ruby.engine, Some(RubyType::JRuby));
+        assert_eq!(
+            ruby.engine_version,
+            Some(Version::parse("9.4.5+0").unwrap())
+        );
+
+        let detector = GemfileDetector;
+        assert_eq!(
+            detector.detect(temp_dir.path()),
+            Some(Version::parse("9.4.5+0").unwrap())
+        );
+        assert_eq!(
+            detector.detect_engine(temp_dir.path()),
+            Some(RubyType::JRuby)
+        );
+    }
+
+    #[test]
+    fn test_reads_hash_rocket_engine_options() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("Gemfile"),
+            "ruby \"3.1.4\", :engine => \"truffleruby\", :engine_version => \"24.1.1\"\n",
+        )
+        .unwrap();
+
+        let ruby = GemfileDetector::read(temp_dir.path()).unwrap();
+        assert_eq!(ruby.version, Version::new(3, 1, 4));
+        assert_eq!(ruby.engine, Some(RubyType::TruffleRuby));
+        assert_eq!(ruby.requirement(), &Version::new(24, 1, 1));
+    }
+
+    #[test]
+    fn test_plain_declaration_has_no_engine() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("Gemfile"), "ruby '3.2.5'\n").unwrap();
+
+        let ruby = GemfileDetector::read(temp_dir.path()).unwrap();
+        assert_eq!(ruby.engine, None);
+        assert_eq!(ruby.engine_version, None);
+        assert_eq!(ruby.requirement(), &Version::new(3, 2, 5));
+
+        let detector = GemfileDetector;
+        assert_eq!(detector.detect_engine(temp_dir.path()), None);
+    }
+
+    #[test]
+    fn test_file_argument() {
+        let file_reference =
+            |line| GemfileDetector::file_argument(line).map(GemfileDetector::leading_quoted);
+        assert_eq!(
+            file_reference("ruby file: \".tool-versions\""),
+            Some(Some(".tool-versions".to_string()))
+        );
+        assert_eq!(
+            file_reference("ruby :file => '.ruby-version'"),
+            Some(Some(".ruby-version".to_string()))
+        );
+        assert_eq!(file_reference("ruby file: RUBY_VERSION_FILE"), Some(None));
+        assert_eq!(file_reference("ruby '3.2.5'"), None);
+    }
+
+    #[test]
+    fn test_constant_file_argument_requires_no_version() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".ruby-version"), "3.3.4\n").unwrap();
+        std::fs::write(
+            temp_dir.path().join("Gemfile"),
+            "ruby file: RUBY_VERSION_FILE\ngem 'rails'\n",
+        )
+        .unwrap();
+
+        assert!(GemfileDetector::read(temp_dir.path()).is_none());
+    }
+
     #[test]
     fn test_extract_quoted_version() {
         assert_eq!(
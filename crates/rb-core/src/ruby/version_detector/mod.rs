@@ -2,7 +2,7 @@
 //!
 //! This module provides a **modular, extensible architecture** for detecting
 //! required Ruby versions from various sources like .ruby-version files,
-//! Gemfile declarations, and potentially .tool-versions (asdf/mise).
+//! Gemfile declarations, asdf/mise `.tool-versions` files and legacy rtx `.rtx.toml` files.
 //!
 //! # Architecture
 //!
@@ -49,16 +49,16 @@
 //!
 //! # Adding New Detectors
 //!
-//! To add support for new version sources (e.g., `.tool-versions` for asdf):
+//! To add support for new version sources (e.g., `.mise.toml`):
 //!
 //! 1. Implement the `RubyVersionDetector` trait:
 //!    ```text
-//!    pub struct ToolVersionsDetector;
-//!    impl RubyVersionDetector for ToolVersionsDetector {
+//!    pub struct MiseTomlDetector;
+//!    impl RubyVersionDetector for MiseTomlDetector {
 //!        fn detect(&self, context: &Path) -> Option<Version> {
-//!            // Read .tool-versions, parse "ruby X.Y.Z" line
+//!            // Read .mise.toml, parse the [tools] ruby entry
 //!        }
-//!        fn name(&self) -> &'static str { ".tool-versions" }
+//!        fn name(&self) -> &'static str { ".mise.toml" }
 //!    }
 //!    ```
 //!
@@ -68,20 +68,30 @@
 //!        detectors: vec![
 //!            Box::new(RubyVersionFileDetector),
 //!            Box::new(GemfileDetector),
-//!            Box::new(ToolVersionsDetector),  // <-- Add here
+//!            Box::new(MiseTomlDetector),  // <-- Add here
 //!        ]
 //!    }
 //!    ```
 
+use crate::ruby::RubyType;
 use log::debug;
 use semver::Version;
 use std::path::Path;
 
 pub mod gemfile;
+pub mod pin_file;
+pub mod rtx_toml;
 pub mod ruby_version_file;
+pub mod tool_versions;
 
-pub use gemfile::GemfileDetector;
+pub use gemfile::{GemfileDetector, GemfileRuby};
+pub use pin_file::PinFileDetector;
+pub use rtx_toml::RtxTomlDetector;
 pub use ruby_version_file::RubyVersionFileDetector;
+pub use tool_versions::ToolVersionsDetector;
+
+/// An acceptable Ruby version, with the engine it names when the source says
+pub type RubyCandidate = (Option<RubyType>, Version);
 
 /// Trait for Ruby version detection strategies
 pub trait RubyVersionDetector {
@@ -91,6 +101,22 @@ pub trait RubyVersionDetector {
     /// or `None` if this detector cannot determine a version.
     fn detect(&self, context: &Path) -> Option<Version>;
 
+    /// Every acceptable version in order of preference, each with the engine it names,
+    /// for sources listing fallbacks
+    ///
+    /// Defaults to the single version `detect` finds, on the engine `detect_engine` names.
+    fn detect_candidates(&self, context: &Path) -> Vec<RubyCandidate> {
+        self.detect(context)
+            .map(|version| (self.detect_engine(context), version))
+            .into_iter()
+            .collect()
+    }
+
+    /// The Ruby engine the requirement names, for sources able to say (e.g. `jruby-9.4.5.0`)
+    fn detect_engine(&self, _context: &Path) -> Option<RubyType> {
+        None
+    }
+
     /// Human-readable name of this detector (for logging)
     fn name(&self) -> &'static str;
 }
@@ -107,15 +133,45 @@ impl CompositeDetector {
 
     /// Returns the first version found, or None if no detector succeeds.
     pub fn detect(&self, context: &Path) -> Option<Version> {
+        self.detect_with_source(context).map(|(version, _)| version)
+    }
+
+    /// Like `detect`, also returning the name of the detector that found the version
+    pub fn detect_with_source(&self, context: &Path) -> Option<(Version, &'static str)> {
+        self.detect_requirement(context)
+            .map(|(version, _, source)| (version, source))
+    }
+
+    /// Like `detect_with_source`, also returning the engine the winning source names
+    pub fn detect_requirement(
+        &self,
+        context: &Path,
+    ) -> Option<(Version, Option<RubyType>, &'static str)> {
+        self.detect_candidates(context)
+            .and_then(|(candidates, source)| {
+                candidates
+                    .into_iter()
+                    .next()
+                    .map(|(engine, version)| (version, engine, source))
+            })
+    }
+
+    /// Like `detect_requirement`, with every version the winning source accepts, preferred first
+    pub fn detect_candidates(&self, context: &Path) -> Option<(Vec<RubyCandidate>, &'static str)> {
         for detector in &self.detectors {
             debug!(
                 "Trying detector '{}' in context: {}",
                 detector.name(),
                 context.display()
             );
-            if let Some(version) = detector.detect(context) {
-                debug!("Detector '{}' found version: {}", detector.name(), version);
-                return Some(version);
+            let candidates = detector.detect_candidates(context);
+            if !candidates.is_empty() {
+                debug!(
+                    "Detector '{}' found versions: {:?}",
+                    detector.name(),
+                    candidates
+                );
+                return Some((candidates, detector.name()));
             }
             debug!("Detector '{}' found no version", detector.name());
         }
@@ -170,6 +226,23 @@ mod tests {
         assert_eq!(version, Version::new(2, 7, 8));
     }
 
+    #[test]
+    fn test_composite_detector_reports_source() {
+        let temp_dir = TempDir::new().unwrap();
+
+        std::fs::write(temp_dir.path().join("Gemfile"), "ruby '3.1.0'\n").unwrap();
+
+        let detector = CompositeDetector::new(vec![
+            Box::new(ruby_version_file::RubyVersionFileDetector),
+            Box::new(gemfile::GemfileDetector),
+        ]);
+
+        assert_eq!(
+            detector.detect_with_source(temp_dir.path()),
+            Some((Version::new(3, 1, 0), "Gemfile"))
+        );
+    }
+
     #[test]
     fn test_composite_detector_returns_none_when_nothing_found() {
         let temp_dir = TempDir::new().unwrap();
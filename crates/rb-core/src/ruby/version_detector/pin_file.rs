@@ -0,0 +1,122 @@
+//! Detector for the project-local pin written by `rb pin`
+
+use super::{RubyVersionDetector, RubyVersionFileDetector};
+use crate::ruby::RubyType;
+use log::{debug, warn};
+use semver::Version;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Detects the Ruby version pinned in `.rb/ruby-version`
+///
+/// The pin takes the same tokens as `.ruby-version` and, when present, wins over
+/// every other version source.
+pub struct PinFileDetector;
+
+impl PinFileDetector {
+    /// Where the pin for `project_dir` lives
+    pub fn path(project_dir: &Path) -> PathBuf {
+        project_dir.join(".rb").join("ruby-version")
+    }
+
+    /// Pin `project_dir` to `token` (e.g. `3.2.5` or `jruby-9.4.5.0`), returning the pin file
+    pub fn write(project_dir: &Path, token: &str) -> io::Result<PathBuf> {
+        let token = token.trim();
+        if RubyVersionFileDetector::parse_token(token).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("'{}' is not a Ruby version", token),
+            ));
+        }
+
+        let path = Self::path(project_dir);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, format!("{}\n", token))?;
+        debug!("Pinned Ruby {} in {}", token, path.display());
+        Ok(path)
+    }
+
+    /// Remove the pin from `project_dir`, returning whether there was one
+    pub fn remove(project_dir: &Path) -> io::Result<bool> {
+        match fs::remove_file(Self::path(project_dir)) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn read(context: &Path) -> Option<(Option<RubyType>, Version)> {
+        let path = Self::path(context);
+        let content = fs::read_to_string(&path).ok()?;
+        let token = content.trim();
+        debug!("Found Ruby pin '{}' in {}", token, path.display());
+
+        let parsed = RubyVersionFileDetector::parse_token(token);
+        if parsed.is_none() {
+            warn!(
+                "Ignoring Ruby pin '{}' in {}: not a Ruby version",
+                token,
+                path.display()
+            );
+        }
+        parsed
+    }
+}
+
+impl RubyVersionDetector for PinFileDetector {
+    fn detect(&self, context: &Path) -> Option<Version> {
+        Self::read(context).map(|(_, version)| version)
+    }
+
+    fn detect_engine(&self, context: &Path) -> Option<RubyType> {
+        Self::read(context).and_then(|(engine, _)| engine)
+    }
+
+    fn name(&self) -> &'static str {
+        ".rb/ruby-version"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_then_detect_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let path = PinFileDetector::write(temp_dir.path(), "3.2.5").unwrap();
+        assert_eq!(path, temp_dir.path().join(".rb").join("ruby-version"));
+        assert_eq!(
+            PinFileDetector.detect(temp_dir.path()),
+            Some(Version::new(3, 2, 5))
+        );
+
+        assert!(PinFileDetector::remove(temp_dir.path()).unwrap());
+        assert!(PinFileDetector.detect(temp_dir.path()).is_none());
+        assert!(!PinFileDetector::remove(temp_dir.path()).unwrap());
+    }
+
+    #[test]
+    fn test_rejects_tokens_that_are_not_versions() {
+        let temp_dir = TempDir::new().unwrap();
+
+        assert!(PinFileDetector::write(temp_dir.path(), "latest").is_err());
+        assert!(!PinFileDetector::path(temp_dir.path()).exists());
+    }
+
+    #[test]
+    fn test_pin_names_engine() {
+        let temp_dir = TempDir::new().unwrap();
+        PinFileDetector::write(temp_dir.path(), "jruby-9.4.5.0").unwrap();
+
+        assert_eq!(
+            PinFileDetector.detect_engine(temp_dir.path()),
+            Some(RubyType::JRuby)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Detector for legacy rtx `.rtx.toml` files (mise before its rename)
+
+use super::RubyVersionDetector;
+use log::{debug, warn};
+use semver::Version;
+use std::fs;
+use std::path::Path;
+
+/// Detects Ruby version from the `[tools] ruby` entry of `.rtx.toml`
+pub struct RtxTomlDetector;
+
+impl RubyVersionDetector for RtxTomlDetector {
+    fn detect(&self, context: &Path) -> Option<Version> {
+        let rtx_path = context.join(".rtx.toml");
+        debug!("Checking for .rtx.toml file: {}", rtx_path.display());
+
+        let content = match fs::read_to_string(&rtx_path) {
+            Ok(content) => content,
+            Err(_) => {
+                debug!("No .rtx.toml file found");
+                return None;
+            }
+        };
+
+        let document: toml::Value = match toml::from_str(&content) {
+            Ok(document) => document,
+            Err(e) => {
+                warn!("Failed to parse .rtx.toml: {}", e);
+                return None;
+            }
+        };
+
+        let version_str = Self::ruby_entry(&document)?;
+        debug!("Found .rtx.toml ruby entry: '{}'", version_str);
+
+        match Version::parse(version_str.trim()) {
+            Ok(version) => {
+                debug!(
+                    "Successfully parsed Ruby version from .rtx.toml: {}",
+                    version
+                );
+                Some(version)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to parse Ruby version '{}' from .rtx.toml: {}",
+                    version_str, e
+                );
+                None
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        ".rtx.toml"
+    }
+}
+
+impl RtxTomlDetector {
+    /// Read `tools.ruby` as a string, the first element of an array, or a `{ version = ... }` table
+    fn ruby_entry(document: &toml::Value) -> Option<&str> {
+        let entry = document.get("tools")?.get("ruby")?;
+
+        match entry {
+            toml::Value::String(version) => Some(version),
+            toml::Value::Array(versions) => versions.first().and_then(|v| v.as_str()),
+            toml::Value::Table(table) => table.get("version").and_then(|v| v.as_str()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn detect(content: &str) -> Option<Version> {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".rtx.toml"), content).unwrap();
+        RtxTomlDetector.detect(temp_dir.path())
+    }
+
+    #[test]
+    fn test_detects_string_version() {
+        assert_eq!(
+            detect("[tools]\nruby = \"3.3.4\"\nnode = \"20\"\n"),
+            Some(Version::new(3, 3, 4))
+        );
+    }
+
+    #[test]
+    fn test_array_of_versions_takes_first() {
+        assert_eq!(
+            detect("[tools]\nruby = [\"3.2.5\", \"3.1.6\"]\n"),
+            Some(Version::new(3, 2, 5))
+        );
+    }
+
+    #[test]
+    fn test_table_form_reads_version_key() {
+        assert_eq!(
+            detect("[tools]\nruby = { version = \"3.4.1\" }\n"),
+            Some(Version::new(3, 4, 1))
+        );
+    }
+
+    #[test]
+    fn test_returns_none_without_ruby_tool() {
+        assert_eq!(detect("[tools]\nnode = \"20\"\n"), None);
+        assert_eq!(detect("[env]\nRAILS_ENV = \"test\"\n"), None);
+    }
+
+    #[test]
+    fn test_returns_none_for_invalid_content() {
+        assert_eq!(detect("[tools]\nruby = \"latest\"\n"), None);
+        assert_eq!(detect("not = [valid toml"), None);
+    }
+
+    #[test]
+    fn test_returns_none_when_file_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(RtxTomlDetector.detect(temp_dir.path()), None);
+    }
+}
@@ -1,16 +1,30 @@
 //! Detector for .ruby-version files
 
 use super::RubyVersionDetector;
+use crate::ruby::{RubyType, parse_engine_version};
 use log::{debug, warn};
 use semver::Version;
 use std::fs;
 use std::path::Path;
 
 /// Detects Ruby version from .ruby-version file
+///
+/// Besides a bare version, the file may name the engine the way chruby and rbenv
+/// accept it: `ruby-3.3.4`, `jruby-9.4.5.0` or `truffleruby-23.1.0`.
 pub struct RubyVersionFileDetector;
 
-impl RubyVersionDetector for RubyVersionFileDetector {
-    fn detect(&self, context: &Path) -> Option<Version> {
+impl RubyVersionFileDetector {
+    /// Split a `.ruby-version` token into the engine it names, if any, and its version
+    pub fn parse_token(token: &str) -> Option<(Option<RubyType>, Version)> {
+        if let Some((engine, version)) = token.split_once('-')
+            && let Some(kind) = RubyType::from_engine(engine)
+        {
+            return parse_engine_version(version).map(|version| (Some(kind), version));
+        }
+        parse_engine_version(token).map(|version| (None, version))
+    }
+
+    fn read(context: &Path) -> Option<(Option<RubyType>, Version)> {
         let ruby_version_path = context.join(".ruby-version");
         debug!(
             "Checking for .ruby-version file: {}",
@@ -22,18 +36,18 @@ impl RubyVersionDetector for RubyVersionFileDetector {
                 let version_str = content.trim();
                 debug!("Found .ruby-version content: '{}'", version_str);
 
-                match Version::parse(version_str) {
-                    Ok(version) => {
+                match Self::parse_token(version_str) {
+                    Some((engine, version)) => {
                         debug!(
-                            "Successfully parsed Ruby version from .ruby-version: {}",
-                            version
+                            "Successfully parsed Ruby version from .ruby-version: {} (engine: {:?})",
+                            version, engine
                         );
-                        Some(version)
+                        Some((engine, version))
                     }
-                    Err(e) => {
+                    None => {
                         warn!(
-                            "Failed to parse Ruby version '{}' from .ruby-version: {}",
-                            version_str, e
+                            "Failed to parse Ruby version '{}' from .ruby-version",
+                            version_str
                         );
                         None
                     }
@@ -45,6 +59,16 @@ impl RubyVersionDetector for RubyVersionFileDetector {
             }
         }
     }
+}
+
+impl RubyVersionDetector for RubyVersionFileDetector {
+    fn detect(&self, context: &Path) -> Option<Version> {
+        Self::read(context).map(|(_, version)| version)
+    }
+
+    fn detect_engine(&self, context: &Path) -> Option<RubyType> {
+        Self::read(context).and_then(|(engine, _)| engine)
+    }
 
     fn name(&self) -> &'static str {
         ".ruby-version"
@@ -95,6 +119,49 @@ mod tests {
         assert!(detector.detect(temp_dir.path()).is_none());
     }
 
+    #[test]
+    fn test_detects_engine_qualified_versions() {
+        let cases = [
+            ("ruby-3.3.4", RubyType::CRuby, Version::new(3, 3, 4)),
+            (
+                "jruby-9.4.5.0",
+                RubyType::JRuby,
+                Version::parse("9.4.5+0").unwrap(),
+            ),
+            (
+                "truffleruby-23.1.0",
+                RubyType::TruffleRuby,
+                Version::new(23, 1, 0),
+            ),
+        ];
+
+        for (content, engine, version) in cases {
+            let temp_dir = TempDir::new().unwrap();
+            std::fs::write(
+                temp_dir.path().join(".ruby-version"),
+                format!("{content}\n"),
+            )
+            .unwrap();
+
+            let detector = RubyVersionFileDetector;
+            assert_eq!(detector.detect(temp_dir.path()), Some(version), "{content}");
+            assert_eq!(
+                detector.detect_engine(temp_dir.path()),
+                Some(engine),
+                "{content}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_bare_version_names_no_engine() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".ruby-version"), "3.2.5\n").unwrap();
+
+        assert_eq!(RubyVersionFileDetector.detect_engine(temp_dir.path()), None);
+        assert!(RubyVersionFileDetector::parse_token("mruby-3.2.0").is_none());
+    }
+
     #[test]
     fn test_name() {
         assert_eq!(RubyVersionFileDetector.name(), ".ruby-version");
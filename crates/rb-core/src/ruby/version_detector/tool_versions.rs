@@ -0,0 +1,166 @@
+//! Detector for asdf/mise `.tool-versions` files
+
+use super::{RubyCandidate, RubyVersionDetector, RubyVersionFileDetector};
+use crate::ruby::RubyType;
+use log::debug;
+use semver::Version;
+use std::fs;
+use std::path::Path;
+
+/// Detects Ruby versions from the `ruby` line of `.tool-versions`
+///
+/// The line may list several versions (`ruby 3.3.4 3.2.5`), most preferred first;
+/// asdf falls back to the next when one is not installed, and so does Butler.
+/// Entries Butler cannot select, such as `system` or `ref:...`, are skipped.
+pub struct ToolVersionsDetector;
+
+impl ToolVersionsDetector {
+    /// The Ruby entries of the `ruby` line, in order of preference
+    fn read(context: &Path) -> Vec<RubyCandidate> {
+        let tool_versions_path = context.join(".tool-versions");
+        debug!(
+            "Checking for .tool-versions file: {}",
+            tool_versions_path.display()
+        );
+
+        let Ok(content) = fs::read_to_string(&tool_versions_path) else {
+            debug!("No .tool-versions file found");
+            return Vec::new();
+        };
+
+        Self::parse(&content)
+    }
+
+    /// Parse the `ruby` line of `.tool-versions` content, ignoring comments
+    fn parse(content: &str) -> Vec<RubyCandidate> {
+        let Some(entries) = content.lines().find_map(|line| {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut words = line.split_whitespace();
+            (words.next() == Some("ruby")).then_some(words)
+        }) else {
+            debug!("No ruby entry in .tool-versions");
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| {
+                let parsed = RubyVersionFileDetector::parse_token(entry);
+                if parsed.is_none() {
+                    debug!("Skipping .tool-versions ruby entry '{}'", entry);
+                }
+                parsed
+            })
+            .collect()
+    }
+}
+
+impl RubyVersionDetector for ToolVersionsDetector {
+    fn detect(&self, context: &Path) -> Option<Version> {
+        Self::read(context)
+            .into_iter()
+            .next()
+            .map(|(_, version)| version)
+    }
+
+    /// Every entry keeps its own engine, so `jruby-9.4.5.0 3.3.4` falls back to CRuby 3.3.4
+    fn detect_candidates(&self, context: &Path) -> Vec<RubyCandidate> {
+        let candidates = Self::read(context);
+        debug!("Ruby versions from .tool-versions: {:?}", candidates);
+        candidates
+    }
+
+    /// The engine named by the most preferred entry
+    fn detect_engine(&self, context: &Path) -> Option<RubyType> {
+        Self::read(context)
+            .into_iter()
+            .next()
+            .and_then(|(engine, _)| engine)
+    }
+
+    fn name(&self) -> &'static str {
+        ".tool-versions"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn candidates(content: &str) -> Vec<Version> {
+        entries(content)
+            .into_iter()
+            .map(|(_, version)| version)
+            .collect()
+    }
+
+    fn entries(content: &str) -> Vec<RubyCandidate> {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".tool-versions"), content).unwrap();
+        ToolVersionsDetector.detect_candidates(temp_dir.path())
+    }
+
+    #[test]
+    fn test_lists_every_ruby_version_in_order() {
+        assert_eq!(
+            candidates("nodejs 20.11.0\nruby 3.3.4 3.2.5\n"),
+            vec![Version::new(3, 3, 4), Version::new(3, 2, 5)]
+        );
+    }
+
+    #[test]
+    fn test_detect_returns_the_preferred_version() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(".tool-versions"), "ruby 3.3.4 3.2.5\n").unwrap();
+
+        assert_eq!(
+            ToolVersionsDetector.detect(temp_dir.path()),
+            Some(Version::new(3, 3, 4))
+        );
+    }
+
+    #[test]
+    fn test_skips_entries_butler_cannot_select() {
+        assert_eq!(
+            candidates("ruby system 3.2.5 # fallback\n"),
+            vec![Version::new(3, 2, 5)]
+        );
+    }
+
+    #[test]
+    fn test_reads_engine_of_preferred_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(".tool-versions"),
+            "ruby jruby-9.4.5.0 3.3.4\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            ToolVersionsDetector.detect_engine(temp_dir.path()),
+            Some(RubyType::JRuby)
+        );
+    }
+
+    #[test]
+    fn test_each_entry_keeps_its_engine() {
+        let entries = entries("ruby jruby-9.4.5.0 3.3.4\n");
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, Some(RubyType::JRuby));
+        assert_eq!(entries[1], (None, Version::new(3, 3, 4)));
+    }
+
+    #[test]
+    fn test_returns_nothing_without_ruby_line() {
+        assert!(candidates("# ruby 3.3.4\nnodejs 20.11.0\n").is_empty());
+
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(ToolVersionsDetector.detect(temp_dir.path()), None);
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(ToolVersionsDetector.name(), ".tool-versions");
+    }
+}
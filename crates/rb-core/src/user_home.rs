@@ -0,0 +1,78 @@
+use crate::butler::ButlerError;
+use log::debug;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Environment variable that replaces the home directory for default path computation
+pub const RB_HOME_ENV: &str = "RB_HOME";
+
+/// Explanation given when no home directory can be found, naming the ways around it
+pub const HOME_DIR_UNAVAILABLE: &str = "Could not determine the home directory.\n\nSet RB_HOME to a directory Butler may use for its defaults, or give explicit locations with RB_RUBIES_DIR (-R) and RB_GEM_HOME (-G).";
+
+/// Home directory used for Butler's defaults (~/.rubies, ~/.gem, ~/.config/rb)
+///
+/// `RB_HOME` takes precedence when set and non-empty, so sandboxes and tests can
+/// keep everything away from the real home directory.
+pub fn home_dir() -> Option<PathBuf> {
+    home_dir_with(std::env::var_os(RB_HOME_ENV))
+}
+
+/// Home directory for defaults that cannot do without one
+///
+/// Containers and service accounts may have neither `HOME` nor `RB_HOME`; they get an
+/// error suggesting the overrides instead of a panic.
+pub fn require_home_dir() -> Result<PathBuf, ButlerError> {
+    require_home_dir_from(home_dir())
+}
+
+fn require_home_dir_from(home: Option<PathBuf>) -> Result<PathBuf, ButlerError> {
+    home.ok_or_else(|| ButlerError::General(HOME_DIR_UNAVAILABLE.to_string()))
+}
+
+/// Home directory given the value of `RB_HOME`, for callers reading the environment themselves
+pub fn home_dir_with(rb_home: Option<OsString>) -> Option<PathBuf> {
+    match rb_home.filter(|value| !value.is_empty()) {
+        Some(value) => {
+            let path = PathBuf::from(value);
+            debug!(
+                "Using {} as home directory: {}",
+                RB_HOME_ENV,
+                path.display()
+            );
+            Some(path)
+        }
+        None => home::home_dir(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rb_home_overrides_real_home() {
+        assert_eq!(
+            home_dir_with(Some(OsString::from("/sandbox/home"))),
+            Some(PathBuf::from("/sandbox/home"))
+        );
+    }
+
+    #[test]
+    fn empty_or_missing_rb_home_falls_back_to_real_home() {
+        assert_eq!(home_dir_with(Some(OsString::new())), home::home_dir());
+        assert_eq!(home_dir_with(None), home::home_dir());
+    }
+
+    #[test]
+    fn missing_home_is_an_error_naming_the_overrides() {
+        let error = require_home_dir_from(None).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("RB_HOME"));
+        assert!(message.contains("RB_RUBIES_DIR"));
+
+        assert_eq!(
+            require_home_dir_from(Some(PathBuf::from("/sandbox/home"))).unwrap(),
+            PathBuf::from("/sandbox/home")
+        );
+    }
+}
@@ -290,3 +290,275 @@ fn bundler_runtime_gem_dir_includes_ruby_version() -> io::Result<()> {
 
     Ok(())
 }
+
+#[cfg(unix)]
+#[test]
+fn bundler_synchronize_with_retry_recovers_from_flaky_install() -> io::Result<()> {
+    use rb_core::bundler::SyncResult;
+    use rb_core::butler::ButlerRuntime;
+    use rb_core::ruby::{RubyRuntime, RubyType};
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = BundlerSandbox::new()?;
+    let project_dir = sandbox.add_bundler_project("flaky-app", false)?;
+    let ruby_dir = sandbox.add_dir("rubies/ruby-3.3.7")?;
+    let marker = sandbox.root().join("install-attempted");
+
+    // Fake bundle: never in sync, and `install` fails on its first invocation only
+    let bundle = sandbox.add_file(
+        "rubies/ruby-3.3.7/bin/bundle",
+        format!(
+            "#!/bin/sh\ncase \"$1\" in\n  check) exit 1 ;;\n  install)\n    if [ -f '{marker}' ]; then echo 'Bundle complete!'; exit 0; fi\n    : > '{marker}'\n    echo 'Fetching gems'\n    exit 5 ;;\nesac\nexit 0\n",
+            marker = marker.display()
+        ),
+    )?;
+    std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755))?;
+
+    let ruby = RubyRuntime::new(RubyType::CRuby, Version::new(3, 3, 7), &ruby_dir);
+    let butler = ButlerRuntime::new(ruby, None);
+    let bundler_runtime = BundlerRuntime::new(&project_dir, Version::new(3, 3, 7));
+
+    let mut lines = Vec::new();
+    let result =
+        bundler_runtime.synchronize_with_retry(&butler, 2, |line| lines.push(line.to_string()))?;
+
+    assert_eq!(result, SyncResult::Synchronized);
+    assert_eq!(
+        lines,
+        vec![
+            "Fetching gems".to_string(),
+            "🔁 Retry 1/2: bundle install failed, trying again in 500ms".to_string(),
+            "Bundle complete!".to_string(),
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_synchronize_without_retry_fails_on_flaky_install() -> io::Result<()> {
+    use rb_core::butler::ButlerRuntime;
+    use rb_core::ruby::{RubyRuntime, RubyType};
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = BundlerSandbox::new()?;
+    let project_dir = sandbox.add_bundler_project("failing-app", false)?;
+    let ruby_dir = sandbox.add_dir("rubies/ruby-3.3.7")?;
+    let bundle = sandbox.add_file(
+        "rubies/ruby-3.3.7/bin/bundle",
+        "#!/bin/sh\ncase \"$1\" in\n  check|install) exit 1 ;;\nesac\nexit 0\n",
+    )?;
+    std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755))?;
+
+    let ruby = RubyRuntime::new(RubyType::CRuby, Version::new(3, 3, 7), &ruby_dir);
+    let butler = ButlerRuntime::new(ruby, None);
+    let bundler_runtime = BundlerRuntime::new(&project_dir, Version::new(3, 3, 7));
+
+    assert!(bundler_runtime.synchronize(&butler, |_| {}).is_err());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_vendor_dir_override_reaches_bundle_config() -> io::Result<()> {
+    use rb_core::butler::ButlerRuntime;
+    use rb_core::butler::runtime_provider::RuntimeProvider;
+    use rb_core::ruby::{RubyRuntime, RubyType};
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = BundlerSandbox::new()?;
+    let project_dir = sandbox.add_bundler_project("scratch-app", false)?;
+    let ruby_dir = sandbox.add_dir("rubies/ruby-3.3.7")?;
+    let log = sandbox.root().join("bundle-args.log");
+
+    // Fake bundle: records every invocation and reports the bundle as satisfied
+    let bundle = sandbox.add_file(
+        "rubies/ruby-3.3.7/bin/bundle",
+        format!(
+            "#!/bin/sh\necho \"$@\" >> '{log}'\nexit 0\n",
+            log = log.display()
+        ),
+    )?;
+    std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755))?;
+
+    let scratch = sandbox.root().join("scratch-vendor");
+    let ruby = RubyRuntime::new(RubyType::CRuby, Version::new(3, 3, 7), &ruby_dir);
+    let butler = ButlerRuntime::new(ruby, None);
+    let bundler_runtime =
+        BundlerRuntime::new(&project_dir, Version::new(3, 3, 7)).with_vendor_dir(&scratch);
+
+    bundler_runtime.check_sync(&butler)?;
+
+    let invocations = std::fs::read_to_string(&log)?;
+    assert!(
+        invocations.contains(&format!("config set path --local {}", scratch.display())),
+        "bundle config should receive the override, got: {}",
+        invocations
+    );
+
+    std::fs::create_dir_all(&scratch)?;
+    assert_eq!(
+        RuntimeProvider::bin_dir(&bundler_runtime),
+        Some(scratch.join("ruby").join("3.3.0").join("bin"))
+    );
+    assert_eq!(
+        BundlerRuntime::new(&project_dir, Version::new(3, 3, 7)).vendor_dir(),
+        project_dir.join(".rb").join("vendor").join("bundler")
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_bin_override_is_the_program_invoked() -> io::Result<()> {
+    use rb_core::butler::ButlerRuntime;
+    use rb_core::ruby::{RubyRuntime, RubyType};
+    use std::os::unix::fs::PermissionsExt;
+
+    let sandbox = BundlerSandbox::new()?;
+    let project_dir = sandbox.add_bundler_project("shimmed-app", false)?;
+    let ruby_dir = sandbox.add_dir("rubies/ruby-3.3.7")?;
+    let log = sandbox.root().join("shim-args.log");
+
+    // Only the shim exists, so a plain `bundle` invocation would fail to start
+    let shim = sandbox.add_file(
+        "rubies/ruby-3.3.7/bin/bundle-shim",
+        format!(
+            "#!/bin/sh\necho \"$@\" >> '{log}'\nexit 0\n",
+            log = log.display()
+        ),
+    )?;
+    std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755))?;
+
+    let ruby = RubyRuntime::new(RubyType::CRuby, Version::new(3, 3, 7), &ruby_dir);
+    let butler = ButlerRuntime::new(ruby, None);
+    let bundler_runtime =
+        BundlerRuntime::new(&project_dir, Version::new(3, 3, 7)).with_bundler_bin("bundle-shim");
+
+    assert_eq!(bundler_runtime.bundler_bin(), "bundle-shim");
+    assert!(bundler_runtime.check_sync(&butler)?);
+
+    let invocations = std::fs::read_to_string(&log)?;
+    assert!(invocations.contains("config set path --local"));
+    assert!(invocations.contains("check"));
+
+    Ok(())
+}
+
+/// Sandbox a project whose `bundle` is the given script, returning the runtimes that drive it
+#[cfg(unix)]
+fn with_fake_bundle(
+    sandbox: &BundlerSandbox,
+    script: &str,
+) -> io::Result<(rb_core::butler::ButlerRuntime, BundlerRuntime)> {
+    use rb_core::butler::ButlerRuntime;
+    use rb_core::ruby::{RubyRuntime, RubyType};
+    use std::os::unix::fs::PermissionsExt;
+
+    let project_dir = sandbox.add_bundler_project("app", false)?;
+    let ruby_dir = sandbox.add_dir("rubies/ruby-3.3.7")?;
+    let bundle = sandbox.add_file("rubies/ruby-3.3.7/bin/bundle", script)?;
+    std::fs::set_permissions(&bundle, std::fs::Permissions::from_mode(0o755))?;
+
+    let ruby = RubyRuntime::new(RubyType::CRuby, Version::new(3, 3, 7), &ruby_dir);
+    Ok((
+        ButlerRuntime::new(ruby, None),
+        BundlerRuntime::new(&project_dir, Version::new(3, 3, 7)),
+    ))
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_missing_executable_is_not_installed() -> io::Result<()> {
+    use rb_core::bundler::BundlerError;
+
+    let sandbox = BundlerSandbox::new()?;
+    let (butler, bundler_runtime) = with_fake_bundle(&sandbox, "#!/bin/sh\nexit 0\n")?;
+    let bundler_runtime = bundler_runtime.with_bundler_bin("no-such-bundle-executable");
+
+    assert_eq!(
+        bundler_runtime.check_sync(&butler),
+        Err(BundlerError::NotInstalled)
+    );
+    assert_eq!(
+        bundler_runtime.synchronize(&butler, |_| {}),
+        Err(BundlerError::NotInstalled)
+    );
+    assert_eq!(
+        bundler_runtime.add_platform(&butler, "x86_64-linux"),
+        Err(BundlerError::NotInstalled)
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_config_failure_is_check_failed() -> io::Result<()> {
+    use rb_core::bundler::BundlerError;
+
+    let sandbox = BundlerSandbox::new()?;
+    let (butler, bundler_runtime) = with_fake_bundle(
+        &sandbox,
+        "#!/bin/sh\ncase \"$1\" in\n  config) exit 3 ;;\nesac\nexit 0\n",
+    )?;
+
+    match bundler_runtime.synchronize(&butler, |_| {}) {
+        Err(BundlerError::CheckFailed(msg)) => assert!(msg.contains("exit code: 3"), "{}", msg),
+        other => panic!("Expected CheckFailed, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_install_failure_carries_details() -> io::Result<()> {
+    use rb_core::bundler::BundlerError;
+
+    let sandbox = BundlerSandbox::new()?;
+    let (butler, bundler_runtime) = with_fake_bundle(
+        &sandbox,
+        "#!/bin/sh\ncase \"$1\" in\n  check) exit 1 ;;\n  install) echo 'extconf.rb failed' >&2; exit 5 ;;\nesac\nexit 0\n",
+    )?;
+
+    assert_eq!(
+        bundler_runtime.synchronize(&butler, |_| {}),
+        Err(BundlerError::InstallFailed {
+            exit_code: 5,
+            details: "extconf.rb failed".to_string(),
+        })
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn bundler_lock_failure_is_lock_failed() -> io::Result<()> {
+    use rb_core::bundler::BundlerError;
+
+    let sandbox = BundlerSandbox::new()?;
+    let (butler, bundler_runtime) = with_fake_bundle(
+        &sandbox,
+        "#!/bin/sh\ncase \"$1\" in\n  lock) echo 'Could not find gem' >&2; exit 7 ;;\nesac\nexit 0\n",
+    )?;
+
+    // Already in sync, so only the lockfile refresh can fail
+    match bundler_runtime.synchronize(&butler, |_| {}) {
+        Err(BundlerError::LockFailed(msg)) => assert!(msg.contains("exit code: 7"), "{}", msg),
+        other => panic!("Expected LockFailed, got {:?}", other),
+    }
+    match bundler_runtime.remove_platform(&butler, "java") {
+        Err(BundlerError::LockFailed(msg)) => {
+            assert!(msg.contains("--remove-platform java"), "{}", msg);
+            assert!(msg.contains("Could not find gem"), "{}", msg);
+        }
+        other => panic!("Expected LockFailed, got {:?}", other),
+    }
+
+    Ok(())
+}
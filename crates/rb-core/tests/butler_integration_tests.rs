@@ -1,6 +1,6 @@
-use rb_core::butler::{ButlerError, ButlerRuntime};
+use rb_core::butler::{ButlerError, ButlerRuntime, SelectionOptions, SelectionReason};
 use rb_core::gems::GemRuntime;
-use rb_core::ruby::{RubyRuntime, RubyRuntimeDetector, RubyType};
+use rb_core::ruby::{PinFileDetector, RubyRuntime, RubyRuntimeDetector, RubyType};
 use rb_tests::RubySandbox;
 use semver::Version;
 use std::io;
@@ -339,6 +339,43 @@ fn test_bundler_isolation_excludes_user_gems() -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+#[test]
+fn test_bundler_local_overrides_join_gem_dirs() -> Result<(), Box<dyn std::error::Error>> {
+    use rb_tests::BundlerSandbox;
+
+    let ruby_sandbox = RubySandbox::new()?;
+    ruby_sandbox.add_ruby_dir("3.3.7")?;
+    let bundler_sandbox = BundlerSandbox::new()?;
+    let project_dir = bundler_sandbox.add_bundler_project("app", true)?;
+    let rack_checkout = bundler_sandbox.add_dir("src/rack")?;
+    bundler_sandbox.add_dir("app/.bundle")?;
+    bundler_sandbox.add_file(
+        "app/.bundle/config",
+        format!(
+            "---\nBUNDLE_LOCAL__RACK: \"{}\"\nBUNDLE_LOCAL__GONE: \"/no/such/checkout\"\n",
+            rack_checkout.display()
+        ),
+    )?;
+
+    let runtime = ButlerRuntime::discover_and_compose_with_current_dir(
+        ruby_sandbox.root().to_path_buf(),
+        None,
+        None,
+        false,
+        project_dir,
+    )?;
+
+    let gem_dirs = runtime.gem_dirs();
+    assert!(
+        gem_dirs.contains(&rack_checkout),
+        "local override should be a gem dir: {:?}",
+        gem_dirs
+    );
+    assert!(!gem_dirs.contains(&PathBuf::from("/no/such/checkout")));
+
+    Ok(())
+}
+
 /// Test that with --no-bundler flag, user gems ARE available
 #[test]
 fn test_no_bundler_flag_restores_user_gems() -> Result<(), Box<dyn std::error::Error>> {
@@ -471,3 +508,593 @@ fn test_bundler_bin_paths_include_ruby_version() -> Result<(), Box<dyn std::erro
 
     Ok(())
 }
+
+/// Test that --no-bundler keeps existing bundler binstubs on PATH only when asked to
+#[test]
+fn test_no_bundler_keeps_binstubs_when_requested() -> Result<(), Box<dyn std::error::Error>> {
+    use rb_tests::BundlerSandbox;
+
+    let ruby_sandbox = RubySandbox::new()?;
+    let bundler_sandbox = BundlerSandbox::new()?;
+    ruby_sandbox.add_ruby_dir("3.3.7")?;
+
+    let project_dir = bundler_sandbox.add_bundler_project("binstub-app", true)?;
+    std::fs::write(project_dir.join(".ruby-version"), "3.3.7")?;
+
+    let compose = |keep_binstubs: bool| {
+        ButlerRuntime::discover_and_compose_with_options(
+            ruby_sandbox.root().to_path_buf(),
+            None,
+            None,
+            true,
+            project_dir.clone(),
+            &SelectionOptions {
+                keep_binstubs,
+                ..Default::default()
+            },
+        )
+    };
+    let has_binstubs = |runtime: &ButlerRuntime| {
+        runtime
+            .bin_dirs()
+            .iter()
+            .any(|p| p.to_string_lossy().contains("bundler"))
+    };
+
+    // Nothing to keep until bundler has produced binstubs
+    assert!(!has_binstubs(&compose(true)?));
+
+    let binstub_dir = project_dir.join(".rb/vendor/bundler/ruby/3.3.0/bin");
+    std::fs::create_dir_all(&binstub_dir)?;
+
+    assert!(!has_binstubs(&compose(false)?));
+
+    let runtime = compose(true)?;
+    assert!(runtime.bundler_runtime().is_none());
+    let bin_dirs = runtime.bin_dirs();
+    assert_eq!(bin_dirs.first(), Some(&binstub_dir));
+    assert!(
+        bin_dirs
+            .iter()
+            .any(|p| p.to_string_lossy().contains(".gem")),
+        "User gem bin should remain available"
+    );
+
+    Ok(())
+}
+
+/// Test that the site_ruby directory joins the gem directories only when asked to
+#[test]
+fn test_include_site_ruby_adds_site_ruby_after_ruby_lib() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    let ruby_dir = sandbox.add_ruby_dir("3.3.7")?;
+    let project_dir = sandbox.add_dir("app")?;
+
+    let compose = |include_site_ruby: bool| {
+        ButlerRuntime::discover_and_compose_with_options(
+            sandbox.root().to_path_buf(),
+            None,
+            Some(sandbox.gem_base_dir()),
+            true,
+            project_dir.clone(),
+            &SelectionOptions {
+                include_site_ruby,
+                ..Default::default()
+            },
+        )
+        .expect("Failed to compose runtime")
+    };
+    let site_ruby = ruby_dir
+        .join("lib")
+        .join("ruby")
+        .join("site_ruby")
+        .join("3.3.0");
+
+    assert!(!compose(false).gem_dirs().contains(&site_ruby));
+
+    let gem_dirs = compose(true).gem_dirs();
+    assert_eq!(gem_dirs.last(), Some(&site_ruby));
+    assert_eq!(
+        gem_dirs[gem_dirs.len() - 2],
+        ruby_dir.join("lib").join("ruby").join("gems").join("3.3.0")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_matches_individual_accessors() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(
+        project_dir.join("Gemfile"),
+        "source 'https://rubygems.org'\nruby '3.2.5'\n",
+    )?;
+
+    let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+        sandbox.root().to_path_buf(),
+        None,
+        Some(sandbox.gem_base_dir()),
+        false,
+        project_dir.clone(),
+    )
+    .expect("Failed to compose runtime");
+
+    let snapshot = butler.snapshot().expect("Snapshot should be available");
+    let ruby = butler.selected_ruby().expect("Ruby should be selected");
+
+    assert_eq!(snapshot.ruby_version, ruby.version);
+    assert_eq!(snapshot.ruby_root, ruby.root);
+    assert_eq!(snapshot.gem_home, butler.gem_home());
+    assert_eq!(snapshot.bin_dirs, butler.bin_dirs());
+    assert_eq!(snapshot.gem_dirs, butler.gem_dirs());
+
+    let bundler = snapshot.bundler.expect("Bundler should be detected");
+    let bundler_runtime = butler.bundler_runtime().unwrap();
+    assert_eq!(bundler.root, bundler_runtime.root);
+    assert_eq!(bundler.gemfile, bundler_runtime.gemfile_path());
+    assert_eq!(bundler.app_config_dir, bundler_runtime.app_config_dir());
+    assert_eq!(bundler.vendor_dir, bundler_runtime.vendor_dir());
+    assert_eq!(
+        bundler.required_ruby_version,
+        Some(Version::parse("3.2.5").unwrap())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_fails_without_ruby() {
+    let butler = ButlerRuntime::empty(PathBuf::from("/nonexistent"), PathBuf::from("."));
+
+    assert!(matches!(
+        butler.snapshot(),
+        Err(ButlerError::NoSuitableRuby(_))
+    ));
+}
+
+#[test]
+fn test_discovery_prefers_stable_over_preview_ruby() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    sandbox.add_ruby_dir("3.4.0-preview1")?;
+    sandbox.add_ruby_dir("3.2.5")?;
+
+    let butler = ButlerRuntime::discover_and_compose_with_current_dir(
+        sandbox.root().to_path_buf(),
+        None,
+        None,
+        false,
+        sandbox.root().to_path_buf(),
+    )
+    .expect("Failed to compose runtime");
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::parse("3.3.4").unwrap()
+    );
+
+    let butler = ButlerRuntime::discover_and_compose_with_options(
+        sandbox.root().to_path_buf(),
+        None,
+        None,
+        false,
+        sandbox.root().to_path_buf(),
+        &SelectionOptions {
+            include_prereleases: true,
+            ..Default::default()
+        },
+    )
+    .expect("Failed to compose runtime");
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::parse("3.4.0-preview1").unwrap()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_resolved_version_reports_the_selected_fallback() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.4.1")?;
+
+    let butler = ButlerRuntime::discover_and_compose_with_options(
+        sandbox.root().to_path_buf(),
+        Some("3.3".to_string()),
+        None,
+        false,
+        sandbox.root().to_path_buf(),
+        &SelectionOptions {
+            fallback_rubies: vec!["3.2.5".to_string()],
+            ..Default::default()
+        },
+    )
+    .expect("Failed to compose runtime");
+
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 2, 5)
+    );
+    assert_eq!(butler.requested_ruby_version(), Some("3.3"));
+    assert_eq!(butler.resolved_ruby_version(), Some("3.2.5"));
+    Ok(())
+}
+
+#[test]
+fn test_additional_rubies_dirs_join_discovery() -> io::Result<()> {
+    let home_rubies = RubySandbox::new()?;
+    home_rubies.add_ruby_dir("3.2.5")?;
+    let opt_rubies = RubySandbox::new()?;
+    let opt_ruby = opt_rubies.add_ruby_dir("3.3.4")?;
+
+    let butler = ButlerRuntime::discover_and_compose_with_options(
+        home_rubies.root().to_path_buf(),
+        Some("3.3.4".to_string()),
+        None,
+        true,
+        home_rubies.root().to_path_buf(),
+        &SelectionOptions {
+            additional_rubies_dirs: vec![opt_rubies.root().to_path_buf()],
+            ..Default::default()
+        },
+    )
+    .expect("Failed to compose runtime");
+
+    assert_eq!(butler.ruby_installations().len(), 2);
+    assert_eq!(butler.selected_ruby().unwrap().root, opt_ruby);
+    Ok(())
+}
+
+fn compose_for_reason(
+    sandbox: &RubySandbox,
+    requested: Option<&str>,
+    project_dir: PathBuf,
+) -> ButlerRuntime {
+    ButlerRuntime::discover_and_compose_with_current_dir(
+        sandbox.root().to_path_buf(),
+        requested.map(str::to_string),
+        None,
+        false,
+        project_dir,
+    )
+    .expect("Failed to compose runtime")
+}
+
+#[test]
+fn test_selection_reason_requested_flag() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(project_dir.join("Gemfile"), "ruby '3.3.4'\n")?;
+
+    let butler = compose_for_reason(&sandbox, Some("3.2.5"), project_dir);
+
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::RequestedFlag)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_selection_reason_version_file() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(
+        project_dir.join("Gemfile"),
+        "source 'https://rubygems.org'\n",
+    )?;
+    std::fs::write(project_dir.join(".ruby-version"), "3.2.5\n")?;
+
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::VersionFile)
+    );
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 2, 5)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_pin_overrides_ruby_version_file_until_removed() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(
+        project_dir.join("Gemfile"),
+        "source 'https://rubygems.org'\n",
+    )?;
+    std::fs::write(project_dir.join(".ruby-version"), "3.3.4\n")?;
+    PinFileDetector::write(&project_dir, "3.2.5")?;
+
+    let butler = compose_for_reason(&sandbox, None, project_dir.clone());
+    assert_eq!(butler.selection_reason(), Some(SelectionReason::Pinned));
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 2, 5)
+    );
+
+    PinFileDetector::remove(&project_dir)?;
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::VersionFile)
+    );
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 3, 4)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_pin_applies_outside_bundler_projects() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let plain_dir = sandbox.add_dir("plain")?;
+    PinFileDetector::write(&plain_dir, "3.2.5")?;
+
+    let butler = compose_for_reason(&sandbox, None, plain_dir);
+
+    assert_eq!(butler.selection_reason(), Some(SelectionReason::Pinned));
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 2, 5)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_selection_reason_gemfile() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(project_dir.join("Gemfile"), "ruby '3.2.5'\n")?;
+
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+
+    assert_eq!(butler.selection_reason(), Some(SelectionReason::Gemfile));
+    Ok(())
+}
+
+#[test]
+fn test_selection_reason_rtx_toml() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(
+        project_dir.join("Gemfile"),
+        "source 'https://rubygems.org'\n",
+    )?;
+    std::fs::write(
+        project_dir.join(".rtx.toml"),
+        "[tools]\nruby = [\"3.2.5\", \"3.3.4\"]\n",
+    )?;
+
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::ToolConfig(".rtx.toml"))
+    );
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 2, 5)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tool_versions_falls_back_across_engines() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    sandbox.add_ruby_dir("3.4.1")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(
+        project_dir.join("Gemfile"),
+        "source 'https://rubygems.org'\n",
+    )?;
+
+    // JRuby is preferred but not installed; the CRuby fallback must not be read as JRuby
+    std::fs::write(
+        project_dir.join(".tool-versions"),
+        "ruby jruby-9.4.5.0 3.3.4\n",
+    )?;
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+    let selected = butler.selected_ruby().unwrap();
+    assert_eq!(selected.version_name(), "CRuby-3.3.4");
+    assert_eq!(butler.missing_required_version(), None);
+    Ok(())
+}
+
+#[test]
+fn test_tool_versions_selects_first_installed_candidate() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.1.6")?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.4.1")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(
+        project_dir.join("Gemfile"),
+        "source 'https://rubygems.org'\n",
+    )?;
+
+    // 3.3.4 is preferred but missing, so the next listed version wins over the latest
+    std::fs::write(
+        project_dir.join(".tool-versions"),
+        "ruby 3.3.4 3.2.5 3.1.6\n",
+    )?;
+    let butler = compose_for_reason(&sandbox, None, project_dir.clone());
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::ToolConfig(".tool-versions"))
+    );
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 2, 5)
+    );
+    assert_eq!(butler.missing_required_version(), None);
+
+    // Nothing listed is installed: the preferred version is reported missing
+    std::fs::write(project_dir.join(".tool-versions"), "ruby 3.3.4 3.0.7\n")?;
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 4, 1)
+    );
+    assert_eq!(
+        butler.missing_required_version(),
+        Some(&Version::new(3, 3, 4))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_selection_reason_latest_fallback() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let plain_dir = sandbox.add_dir("plain")?;
+
+    let butler = compose_for_reason(&sandbox, None, plain_dir);
+
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::LatestFallback)
+    );
+    assert_eq!(
+        butler.selected_ruby().unwrap().version,
+        Version::new(3, 3, 4)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_missing_required_version_is_reported() -> io::Result<()> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.3.4")?;
+    let project_dir = sandbox.add_dir("app")?;
+    std::fs::write(project_dir.join("Gemfile"), "ruby '3.2.5'\n")?;
+
+    let butler = compose_for_reason(&sandbox, None, project_dir.clone());
+
+    assert_eq!(
+        butler.missing_required_version(),
+        Some(&Version::new(3, 2, 5))
+    );
+    assert_eq!(
+        butler.selection_reason(),
+        Some(SelectionReason::LatestFallback)
+    );
+
+    sandbox.add_ruby_dir("3.2.5")?;
+    let butler = compose_for_reason(&sandbox, None, project_dir);
+    assert_eq!(butler.missing_required_version(), None);
+    Ok(())
+}
+
+/// Test that a gemset isolates the gem home and puts its bin directory on PATH
+#[test]
+fn test_gemset_suffixes_gem_home_and_path() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.3.6")?;
+    let gem_base = sandbox.gem_base_dir();
+
+    let runtime = ButlerRuntime::discover_and_compose_with_options(
+        sandbox.root().to_path_buf(),
+        None,
+        Some(gem_base),
+        true,
+        sandbox.root().to_path_buf(),
+        &SelectionOptions {
+            gemset: Some("testing".to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    let gem_home = runtime.gem_home().expect("Expected a gem home");
+    assert!(
+        gem_home.ends_with("3.3.6@testing"),
+        "Gem home should carry the gemset suffix: {}",
+        gem_home.display()
+    );
+
+    let env = runtime.env_vars(Some("/usr/bin".to_string()));
+    let gemset_bin = gem_home.join("bin").display().to_string();
+    assert_eq!(env.get("GEM_HOME"), Some(&gem_home.display().to_string()));
+    assert!(
+        env["PATH"].starts_with(&gemset_bin),
+        "PATH should lead with the gemset bin: {}",
+        env["PATH"]
+    );
+    assert!(env["GEM_PATH"].contains("@testing"));
+
+    let invalid = ButlerRuntime::discover_and_compose_with_options(
+        sandbox.root().to_path_buf(),
+        None,
+        None,
+        true,
+        sandbox.root().to_path_buf(),
+        &SelectionOptions {
+            gemset: Some("../escape".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(invalid, Err(ButlerError::General(_))));
+
+    Ok(())
+}
+
+/// Test that a gem base mapped to a version pattern applies only to matching Rubies
+#[test]
+fn test_gem_base_mapping_follows_selected_ruby() -> Result<(), Box<dyn std::error::Error>> {
+    let sandbox = RubySandbox::new()?;
+    sandbox.add_ruby_dir("3.3.6")?;
+    sandbox.add_ruby_dir("3.2.5")?;
+    let default_base = sandbox.gem_base_dir();
+    let fast_base = sandbox.add_dir("fast-ssd/gems")?;
+    let options = SelectionOptions {
+        gem_bases: vec![("3.3.*".to_string(), fast_base.clone())],
+        ..Default::default()
+    };
+
+    let compose = |version: &str| {
+        ButlerRuntime::discover_and_compose_with_options(
+            sandbox.root().to_path_buf(),
+            Some(version.to_string()),
+            Some(default_base.clone()),
+            true,
+            sandbox.root().to_path_buf(),
+            &options,
+        )
+    };
+
+    let mapped = compose("3.3.6")?.gem_home().expect("Expected a gem home");
+    assert!(
+        mapped.starts_with(&fast_base),
+        "Ruby 3.3.6 should use the mapped gem base: {}",
+        mapped.display()
+    );
+
+    let default = compose("3.2.5")?.gem_home().expect("Expected a gem home");
+    assert!(
+        default.starts_with(&default_base),
+        "Ruby 3.2.5 should keep the default gem base: {}",
+        default.display()
+    );
+
+    Ok(())
+}
@@ -8,15 +8,16 @@ fn discovers_only_ruby_xyz_directories() -> std::io::Result<()> {
     // Valid
     sb.add_ruby_dir("3.1.2")?;
     sb.add_ruby_dir("3.3.0")?;
+    sb.add_ruby_dir("3.2.0-rc1")?; // prereleases are discovered too
     // Invalid names (ignored)
-    sb.add_dir("jruby-9.4.5.0")?;
-    sb.add_dir("ruby-3.2.0-rc1")?;
+    sb.add_dir("mruby-3.2.0")?;
+    sb.add_dir("ruby-3.2")?;
     sb.add_file("ruby-3.2.2", b"not a dir")?; // file, not dir
 
     let rubies = RubyRuntimeDetector::discover(sb.root())?;
 
     let names: Vec<_> = rubies.iter().map(|r| r.version_name()).collect();
-    assert_eq!(names, vec!["CRuby-3.3.0", "CRuby-3.1.2"]); // sorted DESC
+    assert_eq!(names, vec!["CRuby-3.3.0", "CRuby-3.2.0-rc1", "CRuby-3.1.2"]); // sorted DESC
 
     // sanity on fields
     let r = rubies
@@ -75,6 +76,60 @@ fn returns_directory_not_found_error_for_nonexistent_path() {
     }
 }
 
+#[test]
+fn discovers_rubies_split_across_several_directories() -> std::io::Result<()> {
+    let home_rubies = RubySandbox::new()?;
+    home_rubies.add_ruby_dir("3.2.5")?;
+    let opt_rubies = RubySandbox::new()?;
+    opt_rubies.add_ruby_dir("3.3.4")?;
+    opt_rubies.add_ruby_dir("3.1.6")?;
+
+    let rubies = RubyRuntimeDetector::discover_in(
+        home_rubies.root(),
+        &[
+            opt_rubies.root().to_path_buf(),
+            // Listing a directory twice must not duplicate its Rubies
+            home_rubies.root().to_path_buf(),
+            PathBuf::from("completely_nonexistent_directory_12345"),
+        ],
+    )?;
+
+    let found: Vec<_> = rubies
+        .iter()
+        .map(|r| {
+            (
+                r.version.to_string(),
+                r.root.parent().unwrap().to_path_buf(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("3.3.4".to_string(), opt_rubies.root().to_path_buf()),
+            ("3.2.5".to_string(), home_rubies.root().to_path_buf()),
+            ("3.1.6".to_string(), opt_rubies.root().to_path_buf()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn discovers_in_additional_directory_when_main_one_is_missing() -> std::io::Result<()> {
+    let opt_rubies = RubySandbox::new()?;
+    opt_rubies.add_ruby_dir("3.3.4")?;
+    let missing = PathBuf::from("completely_nonexistent_directory_12345");
+
+    let rubies = RubyRuntimeDetector::discover_in(&missing, &[opt_rubies.root().to_path_buf()])?;
+    assert_eq!(rubies.len(), 1);
+
+    match RubyRuntimeDetector::discover_in(&missing, &[]) {
+        Err(RubyDiscoveryError::DirectoryNotFound(path)) => assert_eq!(path, missing),
+        other => panic!("Expected DirectoryNotFound, got {:?}", other),
+    }
+    Ok(())
+}
+
 #[test]
 fn converts_to_io_error_for_backwards_compatibility() {
     let nonexistent_path = PathBuf::from("completely_nonexistent_directory_12345");
@@ -86,3 +141,108 @@ fn converts_to_io_error_for_backwards_compatibility() {
     let io_error: std::io::Error = result.unwrap_err().into();
     assert_eq!(io_error.kind(), std::io::ErrorKind::NotFound);
 }
+
+#[test]
+fn discovers_preview_rubies_as_semver_prereleases() -> std::io::Result<()> {
+    let sb = RubySandbox::new()?;
+    sb.add_ruby_dir("3.3.4")?;
+    sb.add_ruby_dir("3.4.0-preview1")?;
+
+    let rubies = RubyRuntimeDetector::discover(sb.root())?;
+
+    let preview = rubies
+        .iter()
+        .find(|r| r.version_name() == "CRuby-3.4.0-preview1")
+        .expect("preview Ruby should be discovered");
+    assert_eq!(preview.version.pre.as_str(), "preview1");
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn discovers_jruby_and_truffleruby_directories() -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sb = RubySandbox::new()?;
+    sb.add_ruby_dir("3.3.4")?;
+    for name in ["jruby-9.4.5.0", "truffleruby-23.1.0"] {
+        sb.add_dir(format!("{name}/bin"))?;
+        let ruby = sb.add_file(format!("{name}/bin/ruby"), "#!/bin/sh\n")?;
+        std::fs::set_permissions(&ruby, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    let rubies = RubyRuntimeDetector::discover(sb.root())?;
+
+    // Listed in the order selection ranks them, so the first entry is the latest
+    let names: Vec<_> = rubies.iter().map(|r| r.version_name()).collect();
+    assert_eq!(
+        names,
+        vec!["CRuby-3.3.4", "TruffleRuby-23.1.0", "JRuby-9.4.5+0"]
+    );
+    let latest = RubyRuntimeDetector::latest(&rubies).expect("some ruby");
+    assert_eq!(latest.version_name(), names[0]);
+    Ok(())
+}
+
+#[test]
+fn strips_variant_suffix_into_label() -> std::io::Result<()> {
+    let sb = RubySandbox::new()?;
+    sb.add_ruby_dir("3.3.4-railsexpress")?;
+    sb.add_ruby_dir("3.2.5-dev")?;
+    sb.add_ruby_dir("3.1.6")?;
+
+    let rubies = RubyRuntimeDetector::discover(sb.root())?;
+
+    let names: Vec<_> = rubies.iter().map(|r| r.version_name()).collect();
+    assert_eq!(
+        names,
+        vec!["CRuby-3.3.4-railsexpress", "CRuby-3.2.5-dev", "CRuby-3.1.6"]
+    );
+    assert_eq!(rubies[0].version, semver::Version::new(3, 3, 4));
+    assert_eq!(rubies[0].variant.as_deref(), Some("railsexpress"));
+    assert!(rubies[0].version.pre.is_empty());
+    assert_eq!(rubies[2].variant, None);
+    Ok(())
+}
+
+#[test]
+fn skips_installations_without_ruby_executable() -> std::io::Result<()> {
+    let sb = RubySandbox::new()?;
+    sb.add_ruby_dir("3.3.0")?;
+    sb.add_broken_ruby_dir("3.4.0")?; // empty directory left by a failed install
+    sb.add_dir("ruby-3.2.0/bin")?; // bin directory but no ruby
+
+    let discovery = RubyRuntimeDetector::discover_all(sb.root())?;
+
+    let names: Vec<_> = discovery
+        .installations
+        .iter()
+        .map(|r| r.version_name())
+        .collect();
+    assert_eq!(names, vec!["CRuby-3.3.0"]);
+    assert_eq!(
+        discovery.broken_installations,
+        vec![sb.root().join("ruby-3.2.0"), sb.root().join("ruby-3.4.0")]
+    );
+
+    let rubies = RubyRuntimeDetector::discover(sb.root())?;
+    assert_eq!(rubies.len(), 1);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn treats_non_executable_ruby_as_broken() -> std::io::Result<()> {
+    let sb = RubySandbox::new()?;
+    sb.add_dir("ruby-3.3.0/bin")?;
+    sb.add_file("ruby-3.3.0/bin/ruby", "#!/bin/sh\n")?; // written without the executable bit
+
+    let discovery = RubyRuntimeDetector::discover_all(sb.root())?;
+
+    assert!(discovery.installations.is_empty());
+    assert_eq!(
+        discovery.broken_installations,
+        vec![sb.root().join("ruby-3.3.0")]
+    );
+    Ok(())
+}
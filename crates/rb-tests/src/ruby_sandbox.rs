@@ -39,8 +39,25 @@ impl RubySandbox {
         Ok(p)
     }
 
-    /// Create `ruby-<ver>` directory (no bin by default).
+    /// Create `ruby-<ver>` directory with a stub `bin/ruby` executable.
     pub fn add_ruby_dir<S: AsRef<str>>(&self, version: S) -> io::Result<PathBuf> {
+        let root = self.add_broken_ruby_dir(version)?;
+        let bin = root.join("bin");
+        fs::create_dir_all(&bin)?;
+
+        let ruby = bin.join(format!("ruby{}", std::env::consts::EXE_SUFFIX));
+        fs::write(&ruby, "#!/bin/sh\n")?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&ruby, fs::Permissions::from_mode(0o755))?;
+        }
+
+        Ok(root)
+    }
+
+    /// Create `ruby-<ver>` directory without any executable, as a failed install leaves it.
+    pub fn add_broken_ruby_dir<S: AsRef<str>>(&self, version: S) -> io::Result<PathBuf> {
         self.add_dir(format!("ruby-{}", version.as_ref()))
     }
 